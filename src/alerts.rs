@@ -0,0 +1,263 @@
+//! Watchlist alerts on the relative value screen.
+//!
+//! An `Alert` is a set of conditions on one underlying's row of `Universe::relative_value`, all
+//! of which must hold for it to fire:
+//!
+//! ```
+//! use options_math::alerts::{Alert, Metric::*};
+//!
+//! let alert = Alert::new("SPX").when(IvRank.gt(80)).and(TermSlope.lt(0.0));
+//! ```
+//!
+//! `Alert::evaluate` checks one snapshot on its own. A `Watchlist` remembers which alerts are
+//! firing, so a monitor fed every snapshot hears once when an alert fires and once when it
+//! clears, rather than on every snapshot in between.
+
+use crate::universe::{RelativeValueRow, RelativeValueTable};
+use chrono::prelude::*;
+use std::collections::BTreeSet;
+
+/**
+ * A measure of `RelativeValueRow` conditions are on, in the row's units except where noted.
+ */
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Metric {
+    Index,
+    /// In points from 0 to 100, as IV rank is usually quoted, rather than the row's fraction.
+    IvRank,
+    TermSlope,
+    Skew25,
+    Vrp,
+    LiquidityScore,
+}
+
+impl Metric {
+    /**
+     * The measure in `row`, or `None` if it could not be computed.
+     */
+    pub fn value(self, row: &RelativeValueRow) -> Option<f64> {
+        return match self {
+            Metric::Index => row.index,
+            Metric::IvRank => row.iv_rank.map(|rank| rank * 100.0),
+            Metric::TermSlope => row.term_slope,
+            Metric::Skew25 => row.skew_25,
+            Metric::Vrp => row.vrp,
+            Metric::LiquidityScore => row.liquidity_score,
+        };
+    }
+
+    /**
+     * Holds when the measure is above `threshold`.
+     */
+    pub fn gt<T: Into<f64>>(self, threshold: T) -> Condition {
+        return Condition::new(self, Comparison::Above, threshold.into());
+    }
+
+    /**
+     * Holds when the measure is below `threshold`.
+     */
+    pub fn lt<T: Into<f64>>(self, threshold: T) -> Condition {
+        return Condition::new(self, Comparison::Below, threshold.into());
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Comparison {
+    Above,
+    Below,
+}
+
+/**
+ * A measure compared with a threshold, strictly.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct Condition {
+    pub metric: Metric,
+    pub comparison: Comparison,
+    pub threshold: f64,
+}
+
+impl Condition {
+    /**
+     * Whether the condition holds for `row`. A measure that could not be computed never
+     * satisfies it.
+     */
+    pub fn holds(&self, row: &RelativeValueRow) -> bool {
+        return match self.metric.value(row) {
+            Some(value) => match self.comparison {
+                Comparison::Above => value > self.threshold,
+                Comparison::Below => value < self.threshold,
+            },
+            None => false,
+        };
+    }
+}
+
+/**
+ * Conditions on one underlying, all of which must hold.
+ */
+#[derive(PartialEq, Clone, Debug)]
+pub struct Alert {
+    pub symbol: String,
+    pub conditions: Vec<Condition>,
+}
+
+/**
+ * A measure as it was when an alert was evaluated.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Reading {
+    pub condition: Condition,
+    pub value: Option<f64>,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AlertState {
+    /// Every condition holds.
+    Firing,
+    /// The alert was firing and no longer is, including because its underlying left the screen.
+    Cleared,
+}
+
+/**
+ * An alert firing or clearing, with the measures behind it.
+ */
+#[derive(PartialEq, Clone, Debug)]
+pub struct AlertEvent {
+    pub symbol: String,
+    pub at: NaiveDateTime,
+    pub state: AlertState,
+    /// One per condition, in the alert's order. Empty when the underlying left the screen.
+    pub readings: Vec<Reading>,
+}
+
+impl Alert {
+    /**
+     * An alert on `symbol` without conditions, which never fires until given some.
+     */
+    pub fn new(symbol: &str) -> Alert {
+        return Alert {
+            symbol: symbol.to_string(),
+            conditions: vec![],
+        };
+    }
+
+    pub fn when(mut self, condition: Condition) -> Alert {
+        self.conditions.push(condition);
+        return self;
+    }
+
+    pub fn and(self, condition: Condition) -> Alert {
+        return self.when(condition);
+    }
+
+    /**
+     * The readings of the conditions against `row`, or `None` if `row` is another underlying's.
+     */
+    pub fn readings(&self, row: &RelativeValueRow) -> Option<Vec<Reading>> {
+        if row.symbol != self.symbol {
+            return None;
+        }
+        return Some(
+            self.conditions
+                .iter()
+                .map(|condition| Reading {
+                    condition: *condition,
+                    value: condition.metric.value(row),
+                })
+                .collect(),
+        );
+    }
+
+    /**
+     * Whether every condition holds for the alert's underlying in `table`. An alert without
+     * conditions, or whose underlying is not in `table`, does not fire.
+     */
+    pub fn fires(&self, table: &RelativeValueTable) -> bool {
+        return match self.row(table) {
+            Some(row) => {
+                !self.conditions.is_empty() && self.conditions.iter().all(|c| c.holds(row))
+            }
+            None => false,
+        };
+    }
+
+    /**
+     * The firing event for `table`, or `None` if the alert does not fire.
+     */
+    pub fn evaluate(&self, table: &RelativeValueTable) -> Option<AlertEvent> {
+        if !self.fires(table) {
+            return None;
+        }
+        return Some(self.event(table, AlertState::Firing));
+    }
+
+    fn row<'a>(&self, table: &'a RelativeValueTable) -> Option<&'a RelativeValueRow> {
+        return table.rows.iter().find(|row| row.symbol == self.symbol);
+    }
+
+    fn event(&self, table: &RelativeValueTable, state: AlertState) -> AlertEvent {
+        return AlertEvent {
+            symbol: self.symbol.clone(),
+            at: table.now,
+            state,
+            readings: self
+                .row(table)
+                .and_then(|row| self.readings(row))
+                .unwrap_or_default(),
+        };
+    }
+}
+
+/**
+ * Alerts evaluated together on each snapshot, reporting only changes.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct Watchlist {
+    alerts: Vec<Alert>,
+    /// Indices into `alerts` of those firing on the last snapshot.
+    firing: BTreeSet<usize>,
+}
+
+impl Watchlist {
+    pub fn new() -> Watchlist {
+        return Watchlist::default();
+    }
+
+    pub fn add(&mut self, alert: Alert) {
+        self.alerts.push(alert);
+    }
+
+    pub fn alerts(&self) -> &[Alert] {
+        return &self.alerts;
+    }
+
+    /**
+     * The alerts firing on the last snapshot observed.
+     */
+    pub fn firing(&self) -> impl Iterator<Item = &Alert> {
+        return self.firing.iter().map(move |&i| &self.alerts[i]);
+    }
+
+    /**
+     * Evaluates every alert on `table`, returning an event for each that started firing or
+     * cleared since the last snapshot, in the order the alerts were added.
+     */
+    pub fn observe(&mut self, table: &RelativeValueTable) -> Vec<AlertEvent> {
+        let mut events = vec![];
+        for (i, alert) in self.alerts.iter().enumerate() {
+            let fires = alert.fires(table);
+            if fires == self.firing.contains(&i) {
+                continue;
+            }
+            if fires {
+                self.firing.insert(i);
+                events.push(alert.event(table, AlertState::Firing));
+            } else {
+                self.firing.remove(&i);
+                events.push(alert.event(table, AlertState::Cleared));
+            }
+        }
+        return events;
+    }
+}
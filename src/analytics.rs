@@ -0,0 +1,368 @@
+//! Whole-chain analytics in a single pass.
+
+use crate::chain::Chain;
+use crate::dividends::DividendSchedule;
+use crate::early_exercise::BINOMIAL_STEPS;
+use crate::greeks::{black_scholes_greeks, Greeks};
+use crate::math::{
+    implied_normal_volatility_with, implied_volatility_with, norm_cdf, SolverConfig, SolverStats,
+};
+use crate::pricing::{Dividends, Lattice, LatticePricer};
+use crate::rates::YieldCurve;
+use crate::{
+    Cents, ExerciseStyle, OptionContract, OptionKind, OptionsByExpiryDate, Percentage, Settlement,
+    VolatilityModel,
+};
+use chrono::prelude::*;
+use std::collections::BTreeMap;
+
+/**
+ * Liquidity of a single quote.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct Liquidity {
+    /// Whether the contract has a nonzero bid.
+    pub quoted: bool,
+    pub spread: Cents,
+    /// Spread as a fraction of the mark.
+    pub relative_spread: f64,
+}
+
+impl Liquidity {
+    /**
+     * Between 0 and 1: zero without a bid, otherwise one less the relative spread, as
+     * `universe::RelativeValueRow::liquidity_score` scores a whole chain.
+     */
+    pub fn score(&self) -> f64 {
+        if !self.quoted || !self.relative_spread.is_finite() {
+            return 0.0;
+        }
+        return (1.0 - self.relative_spread).clamp(0.0, 1.0);
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ContractAnalytics {
+    pub contract: OptionContract,
+    /// Under the contract's volatility model.
+    pub implied_volatility: Option<Percentage>,
+    /// Black–Scholes Greeks, only for lognormal contracts.
+    pub greeks: Option<Greeks>,
+    /// American less European value at the implied volatility, as
+    /// `early_exercise::early_exercise_premiums` estimates it. Only for lognormal contracts, and
+    /// zero for cash-settled ones.
+    pub early_exercise_premium: Option<Cents>,
+    /// Strike over forward price.
+    pub moneyness: f64,
+    pub liquidity: Liquidity,
+}
+
+/**
+ * Analytics of every contract in an expiry, with the inputs shared between them.
+ */
+#[derive(Clone, Debug)]
+pub struct ExpiryAnalytics {
+    pub expires_at: NaiveDateTime,
+    pub time_to_expiration: f64,
+    pub risk_free_rate: f64,
+    pub discount_factor: f64,
+    pub forward_price: Cents,
+    pub dividend_yield: Percentage,
+    pub contracts: Vec<ContractAnalytics>,
+}
+
+/**
+ * How a single volatility per strike is chosen from the call and put quoted there.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SmileMarking {
+    /// The out-of-the-money contract: puts below the forward, calls at or above it.
+    OutOfTheMoney,
+    /// Both volatilities, weighted by how far out of the money each contract is and by the
+    /// inverse square of its spread. Reduces microstructure noise in the wings.
+    Blended,
+}
+
+impl ExpiryAnalytics {
+    /**
+     * Implied volatilities of the out-of-the-money contracts by moneyness: puts below the
+     * forward and calls at or above it, sorted by moneyness. Smiles are lognormal, so contracts
+     * quoted in normal volatility are left out, as they are from the Greeks.
+     */
+    pub fn smile(&self) -> Vec<(f64, Percentage)> {
+        return self.smile_marked(SmileMarking::OutOfTheMoney);
+    }
+
+    /**
+     * One lognormal implied volatility per strike by moneyness, chosen according to `marking`,
+     * sorted by moneyness.
+     */
+    pub fn smile_marked(&self, marking: SmileMarking) -> Vec<(f64, Percentage)> {
+        if marking == SmileMarking::Blended {
+            return self.blended_smile();
+        }
+        let mut points: Vec<(f64, Percentage)> = self
+            .contracts
+            .iter()
+            .filter(|c| c.contract.volatility_model == VolatilityModel::Lognormal)
+            .filter(|c| match c.contract.kind {
+                OptionKind::Call => c.moneyness >= 1.0,
+                OptionKind::Put => c.moneyness < 1.0,
+            })
+            .flat_map(|c| Some((c.moneyness, c.implied_volatility?)))
+            .collect();
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        return points;
+    }
+
+    fn blended_smile(&self) -> Vec<(f64, Percentage)> {
+        // ordered by strike, so ties in moneyness always resolve the same way
+        let mut by_strike: BTreeMap<
+            Cents,
+            (Option<&ContractAnalytics>, Option<&ContractAnalytics>),
+        > = BTreeMap::new();
+        for c in self
+            .contracts
+            .iter()
+            .filter(|c| c.implied_volatility.is_some())
+            .filter(|c| c.contract.volatility_model == VolatilityModel::Lognormal)
+        {
+            let entry = by_strike.entry(c.contract.strike).or_insert((None, None));
+            match c.contract.kind {
+                OptionKind::Call => entry.0 = Some(c),
+                OptionKind::Put => entry.1 = Some(c),
+            }
+        }
+
+        let std_dev = self.time_to_expiration.sqrt();
+        let mut points: Vec<(f64, Percentage)> = by_strike
+            .into_values()
+            .flat_map(|pair| -> Option<(f64, Percentage)> {
+                return match pair {
+                    (Some(call), Some(put)) => {
+                        let (call_vol, put_vol) =
+                            (call.implied_volatility?, put.implied_volatility?);
+                        let vol = (call_vol + put_vol) / 2.0;
+                        // weight towards the call as the strike moves above the forward
+                        let otm = norm_cdf(call.moneyness.ln() / (vol * std_dev));
+                        let call_weight = otm / (call.liquidity.spread.max(1) as f64).powi(2);
+                        let put_weight = (1.0 - otm) / (put.liquidity.spread.max(1) as f64).powi(2);
+                        Some((
+                            call.moneyness,
+                            (call_weight * call_vol + put_weight * put_vol)
+                                / (call_weight + put_weight),
+                        ))
+                    }
+                    // in-the-money volatilities are too noisy to stand on their own
+                    (Some(call), None) if call.moneyness >= 1.0 => {
+                        Some((call.moneyness, call.implied_volatility?))
+                    }
+                    (None, Some(put)) if put.moneyness < 1.0 => {
+                        Some((put.moneyness, put.implied_volatility?))
+                    }
+                    _ => None,
+                };
+            })
+            .collect();
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        return points;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ChainAnalytics {
+    pub spot: Cents,
+    pub now: NaiveDateTime,
+    pub expiries: Vec<ExpiryAnalytics>,
+}
+
+impl ChainAnalytics {
+    /**
+     * The analytics of `contract`, along with those of its expiry.
+     */
+    pub fn find(
+        &self,
+        contract: &OptionContract,
+    ) -> Option<(&ExpiryAnalytics, &ContractAnalytics)> {
+        let expiry = self
+            .expiries
+            .iter()
+            .find(|e| e.expires_at == contract.expires_at)?;
+        let analytics = expiry
+            .contracts
+            .iter()
+            .find(|c| c.contract.strike == contract.strike && c.contract.kind == contract.kind)?;
+        return Some((expiry, analytics));
+    }
+}
+
+impl Chain {
+    /**
+     * Computes implied volatility, Greeks, moneyness, and liquidity for every contract in the
+     * chain. The forward price, discount factor, and dividend yield are computed once per expiry.
+     */
+    pub fn analytics(&self, spot: Cents, rates: &YieldCurve, now: NaiveDateTime) -> ChainAnalytics {
+        return self
+            .analytics_with_solver(spot, rates, now, &SolverConfig::default())
+            .0;
+    }
+
+    /**
+     * Like `analytics`, solving for implied volatilities according to `solver`, and reporting
+     * how the solves went across the chain.
+     */
+    pub fn analytics_with_solver(
+        &self,
+        spot: Cents,
+        rates: &YieldCurve,
+        now: NaiveDateTime,
+        solver: &SolverConfig,
+    ) -> (ChainAnalytics, SolverStats) {
+        return self.chain_analytics(spot, rates, now, solver, None);
+    }
+
+    /**
+     * Like `analytics`, for a single name paying `dividends`: forwards come from the schedule
+     * rather than put-call parity, and Greeks use the equivalent continuous yield.
+     */
+    pub fn analytics_with_dividends(
+        &self,
+        spot: Cents,
+        rates: &YieldCurve,
+        now: NaiveDateTime,
+        dividends: &DividendSchedule,
+    ) -> ChainAnalytics {
+        return self
+            .chain_analytics(spot, rates, now, &SolverConfig::default(), Some(dividends))
+            .0;
+    }
+
+    fn chain_analytics(
+        &self,
+        spot: Cents,
+        rates: &YieldCurve,
+        now: NaiveDateTime,
+        solver: &SolverConfig,
+        dividends: Option<&DividendSchedule>,
+    ) -> (ChainAnalytics, SolverStats) {
+        let mut stats = SolverStats::default();
+        let analytics = ChainAnalytics {
+            spot,
+            now,
+            expiries: self
+                .expiries()
+                .iter()
+                .map(|e| {
+                    let rate = rates.rate_at(e.expires_at, now);
+                    return expiry_analytics(e, spot, rate, now, solver, dividends, &mut stats);
+                })
+                .collect(),
+        };
+        return (analytics, stats);
+    }
+}
+
+fn expiry_analytics(
+    options: &OptionsByExpiryDate,
+    spot: Cents,
+    risk_free_rate: f64,
+    now: NaiveDateTime,
+    solver: &SolverConfig,
+    dividends: Option<&DividendSchedule>,
+    stats: &mut SolverStats,
+) -> ExpiryAnalytics {
+    let t = options.time_to_expiration(now);
+    let discount_factor = (-risk_free_rate * t).exp();
+    let (forward_price, dividend_yield) = match dividends {
+        Some(dividends) => (
+            dividends.forward_price(spot, risk_free_rate, now, options.expires_at),
+            dividends.equivalent_yield(spot, risk_free_rate, now, options.expires_at),
+        ),
+        None => (
+            options.forward_price(risk_free_rate, now),
+            options.implied_dividend_yield(spot, risk_free_rate, now),
+        ),
+    };
+    let lattice = LatticePricer::new(Lattice::Binomial, BINOMIAL_STEPS)
+        .with_exercise(ExerciseStyle::American)
+        .with_dividends(Dividends::Yield(dividend_yield));
+
+    let contracts = options
+        .calls
+        .iter()
+        .chain(options.puts.iter())
+        .map(|o| -> ContractAnalytics {
+            let mark = o.mark();
+            let vol = if o.bid == 0 || forward_price <= 0 {
+                None
+            } else {
+                let solve = match o.volatility_model {
+                    VolatilityModel::Lognormal => implied_volatility_with,
+                    VolatilityModel::Normal => implied_normal_volatility_with,
+                };
+                let report = solve(
+                    o.kind,
+                    mark as f64,
+                    forward_price as f64,
+                    o.strike as f64,
+                    t,
+                    discount_factor,
+                    solver,
+                );
+                stats.record(&report);
+                #[cfg(feature = "metrics")]
+                if report.volatility.is_none() || !report.converged {
+                    crate::metrics::global().solver_failures.inc();
+                }
+                report.volatility
+            };
+            let lognormal_vol = vol.filter(|_| o.volatility_model == VolatilityModel::Lognormal);
+            let greeks = lognormal_vol.map(|vol| {
+                black_scholes_greeks(
+                    o.kind,
+                    spot as f64 / 100.0,
+                    o.strike as f64 / 100.0,
+                    risk_free_rate,
+                    dividend_yield,
+                    vol,
+                    t,
+                )
+            });
+            let early_exercise_premium = lognormal_vol.map(|vol| {
+                if o.settlement == Settlement::Cash {
+                    return 0;
+                }
+                let american =
+                    lattice.value(o.kind, spot as f64, o.strike as f64, risk_free_rate, vol, t);
+                return (american - mark as f64).round() as Cents;
+            });
+            let spread = o.ask - o.bid;
+            return ContractAnalytics {
+                contract: *o,
+                implied_volatility: vol,
+                greeks,
+                early_exercise_premium,
+                moneyness: o.strike as f64 / forward_price as f64,
+                liquidity: Liquidity {
+                    quoted: o.bid != 0,
+                    spread,
+                    relative_spread: if mark > 0 {
+                        spread as f64 / mark as f64
+                    } else {
+                        f64::INFINITY
+                    },
+                },
+            };
+        })
+        .collect();
+
+    return ExpiryAnalytics {
+        expires_at: options.expires_at,
+        time_to_expiration: t,
+        risk_free_rate,
+        discount_factor,
+        forward_price,
+        dividend_yield,
+        contracts,
+    };
+}
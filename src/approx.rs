@@ -0,0 +1,69 @@
+//! Closed-form approximations.
+//!
+//! These are rules of thumb, not prices: each is accurate near the money and for short
+//! expirations, and degrades away from that. They are meant for sanity checks, quick quotes, and
+//! as starting points for the exact solvers in `math`.
+
+use crate::OptionKind;
+use std::f64::consts::PI;
+
+/**
+ * Approximate undiscounted ATM straddle price: `sqrt(2 / pi) * F * vol * sqrt(t)`, i.e. roughly
+ * `0.8 * F * vol * sqrt(t)`.
+ */
+pub fn atm_straddle(forward: f64, volatility: f64, t: f64) -> f64 {
+    return (2.0 / PI).sqrt() * forward * volatility * t.max(0.0).sqrt();
+}
+
+/**
+ * Approximate volatility implied by an undiscounted ATM straddle price; the inverse of
+ * `atm_straddle`.
+ */
+pub fn straddle_volatility(straddle: f64, forward: f64, t: f64) -> Option<f64> {
+    if forward <= 0.0 || t <= 0.0 || straddle <= 0.0 {
+        return None;
+    }
+    return Some(straddle / ((2.0 / PI).sqrt() * forward * t.sqrt()));
+}
+
+/**
+ * Brenner–Subrahmanyam (1988) approximation of the volatility implied by an undiscounted ATM
+ * call or put price: `sqrt(2 pi / t) * price / F`.
+ */
+pub fn brenner_subrahmanyam(price: f64, forward: f64, t: f64) -> Option<f64> {
+    if forward <= 0.0 || t <= 0.0 || price <= 0.0 {
+        return None;
+    }
+    return Some((2.0 * PI / t).sqrt() * price / forward);
+}
+
+/**
+ * Corrado–Miller (1996) approximation of the implied volatility of a discounted option price,
+ * which extends Brenner–Subrahmanyam away from the money. `None` if the approximation breaks
+ * down, as it does deep in or out of the money.
+ */
+pub fn corrado_miller(
+    kind: OptionKind,
+    price: f64,
+    forward: f64,
+    strike: f64,
+    t: f64,
+    discount_factor: f64,
+) -> Option<f64> {
+    if forward <= 0.0 || strike <= 0.0 || t <= 0.0 || discount_factor <= 0.0 {
+        return None;
+    }
+    // undiscounted call price, by put-call parity for puts
+    let call = match kind {
+        OptionKind::Call => price / discount_factor,
+        OptionKind::Put => price / discount_factor + forward - strike,
+    };
+    let half_intrinsic = (forward - strike) / 2.0;
+    let discriminant = (call - half_intrinsic).powi(2) - (forward - strike).powi(2) / PI;
+    let std_dev = (2.0 * PI).sqrt() / (forward + strike)
+        * (call - half_intrinsic + discriminant.max(0.0).sqrt());
+    if !std_dev.is_finite() || std_dev <= 0.0 {
+        return None;
+    }
+    return Some(std_dev / t.sqrt());
+}
@@ -0,0 +1,160 @@
+//! Options on several underlyings, priced by simulation.
+//!
+//! Each underlying follows its own geometric Brownian motion, with the Brownian motions
+//! correlated by a user-supplied matrix (`math::linalg` checks and repairs one). Baskets,
+//! best-ofs and worst-ofs are the building blocks of most structured notes.
+
+use crate::math::linalg::CorrelatedNormals;
+use crate::math::Rng;
+use crate::montecarlo::{MonteCarlo, MonteCarloEstimate};
+use crate::{Cents, OptionKind};
+
+/**
+ * One underlying of a multi-asset simulation.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct Asset {
+    pub spot: Cents,
+    pub dividend_yield: f64,
+    pub volatility: f64,
+}
+
+/**
+ * What a contract on several underlyings pays at expiration given their simulated paths: one
+ * path per underlying, in the order the assets were given, each starting with the spot and
+ * with one price per step after it, all in cents.
+ */
+pub trait MultiAssetPayoff {
+    fn payoff(&self, paths: &[Vec<f64>]) -> f64;
+}
+
+impl<F: Fn(&[Vec<f64>]) -> f64> MultiAssetPayoff for F {
+    fn payoff(&self, paths: &[Vec<f64>]) -> f64 {
+        return self(paths);
+    }
+}
+
+/**
+ * An option on a weighted sum of the final prices, e.g. units of each stock in an index.
+ */
+#[derive(new, PartialEq, Clone, Debug)]
+pub struct Basket {
+    pub kind: OptionKind,
+    pub weights: Vec<f64>,
+    pub strike: Cents,
+}
+
+impl MultiAssetPayoff for Basket {
+    fn payoff(&self, paths: &[Vec<f64>]) -> f64 {
+        let value: f64 = paths
+            .iter()
+            .zip(self.weights.iter())
+            .map(|(path, weight)| weight * path[path.len() - 1])
+            .sum();
+        return intrinsic(self.kind, value, self.strike as f64);
+    }
+}
+
+/**
+ * An option on the best performing underlying, its final price over its spot, struck at
+ * `strike` (e.g. 1.0 at the money) and paying `notional` cents per unit of performance.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct BestOf {
+    pub kind: OptionKind,
+    pub strike: f64,
+    pub notional: Cents,
+}
+
+impl MultiAssetPayoff for BestOf {
+    fn payoff(&self, paths: &[Vec<f64>]) -> f64 {
+        let best = performances(paths).fold(f64::NEG_INFINITY, f64::max);
+        return self.notional as f64 * intrinsic(self.kind, best, self.strike);
+    }
+}
+
+/**
+ * An option on the worst performing underlying, as `BestOf`. A worst-of put is the downside a
+ * typical autocallable or reverse convertible sells.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct WorstOf {
+    pub kind: OptionKind,
+    pub strike: f64,
+    pub notional: Cents,
+}
+
+impl MultiAssetPayoff for WorstOf {
+    fn payoff(&self, paths: &[Vec<f64>]) -> f64 {
+        let worst = performances(paths).fold(f64::INFINITY, f64::min);
+        return self.notional as f64 * intrinsic(self.kind, worst, self.strike);
+    }
+}
+
+fn performances(paths: &[Vec<f64>]) -> impl Iterator<Item = f64> + '_ {
+    return paths.iter().map(|path| path[path.len() - 1] / path[0]);
+}
+
+fn intrinsic(kind: OptionKind, value: f64, strike: f64) -> f64 {
+    return match kind {
+        OptionKind::Call => (value - strike).max(0.0),
+        OptionKind::Put => (strike - value).max(0.0),
+    };
+}
+
+impl MonteCarlo {
+    /**
+     * Simulated price of `payoff` on `assets` whose Brownian motions are correlated by
+     * `correlation`, a symmetric matrix with one row per asset. `t` is in years. `None` if the
+     * matrix does not match the assets or is not positive definite.
+     */
+    pub fn price_multi_asset<P: MultiAssetPayoff + ?Sized>(
+        &self,
+        payoff: &P,
+        assets: &[Asset],
+        correlation: &[Vec<f64>],
+        risk_free_rate: f64,
+        t: f64,
+    ) -> Option<MonteCarloEstimate> {
+        if correlation.len() != assets.len() {
+            return None;
+        }
+        let normals = CorrelatedNormals::new(correlation)?;
+        let t = t.max(0.0);
+        let steps = self.steps.max(1);
+        let dt = t / steps as f64;
+        let discount = (-risk_free_rate * t).exp();
+        let drifts: Vec<f64> = assets
+            .iter()
+            .map(|a| (risk_free_rate - a.dividend_yield - a.volatility * a.volatility / 2.0) * dt)
+            .collect();
+        let diffusions: Vec<f64> = assets.iter().map(|a| a.volatility * dt.sqrt()).collect();
+
+        let mut rng = Rng::new(self.seed);
+        let mut paths: Vec<Vec<f64>> = assets
+            .iter()
+            .map(|a| vec![a.spot as f64; steps + 1])
+            .collect();
+        let mut z = vec![0.0; assets.len()];
+        let (mut sum, mut sum_squares) = (0.0, 0.0);
+        for _ in 0..self.paths {
+            for step in 1..=steps {
+                normals.fill(&mut rng, &mut z);
+                for (i, path) in paths.iter_mut().enumerate() {
+                    path[step] = path[step - 1] * (drifts[i] + diffusions[i] * z[i]).exp();
+                }
+            }
+            let value = payoff.payoff(&paths) * discount;
+            sum += value;
+            sum_squares += value * value;
+        }
+
+        let n = self.paths.max(1) as f64;
+        let mean = sum / n;
+        let variance = (sum_squares / n - mean * mean).max(0.0);
+        return Some(MonteCarloEstimate {
+            price: mean,
+            standard_error: (variance / n).sqrt(),
+        });
+    }
+}
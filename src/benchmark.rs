@@ -0,0 +1,258 @@
+//! Benchmark indices of passive option strategies.
+//!
+//! Cboe's BXM index holds the S&P 500 and sells a one-month at-the-money call against it every
+//! month, and its PUT index sells one-month at-the-money puts fully collateralized by Treasury
+//! bills. These rebuild indices like them from historical chain snapshots, so a strategy can be
+//! compared with what writing options passively earned over the same period, and put a
+//! volatility target on top of any index.
+
+use crate::chain::Chain;
+use crate::pricing::years_until;
+use crate::rates::YieldCurve;
+use crate::realized::TRADING_DAYS;
+use crate::{Cents, OptionContract, OptionKind, Percentage, SameMinuteExpiry};
+use chrono::prelude::*;
+
+/**
+ * Value every index starts at.
+ */
+pub const BASE_VALUE: f64 = 100.0;
+
+/**
+ * The underlying and its chain at one time.
+ */
+#[derive(new, Clone, Copy, Debug)]
+pub struct MarketSnapshot<'a> {
+    pub at: NaiveDateTime,
+    pub spot: Cents,
+    pub chain: &'a Chain,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BenchmarkPoint {
+    pub at: NaiveDateTime,
+    pub value: f64,
+    /// The option short after this point, if any.
+    pub position: Option<OptionContract>,
+}
+
+/**
+ * Selling one option at a time and rolling into the next when it expires. Calls are written
+ * against the underlying (a buy-write) and puts against cash earning the risk-free rate (a
+ * put-write), in both cases one option per unit of underlying so the position is fully covered.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct OptionWrite {
+    pub kind: OptionKind,
+    /// Strike over spot to write at. Calls take the first strike at or above it and puts the
+    /// last one at or below.
+    pub moneyness: f64,
+    /// Calendar days to the expiration to write, taking the nearest listed.
+    pub tenor_days: i64,
+}
+
+impl OptionWrite {
+    /**
+     * The BXM: one-month at-the-money calls against the underlying.
+     */
+    pub fn buy_write() -> OptionWrite {
+        return OptionWrite::new(OptionKind::Call, 1.0, 30);
+    }
+
+    /**
+     * The PUT index: one-month at-the-money puts against cash.
+     */
+    pub fn put_write() -> OptionWrite {
+        return OptionWrite::new(OptionKind::Put, 1.0, 30);
+    }
+
+    /**
+     * The quoted contract this strategy writes as of `now`.
+     */
+    pub fn select(&self, chain: &Chain, spot: Cents, now: NaiveDateTime) -> Option<OptionContract> {
+        let (expiries, _) = chain.unexpired(now, SameMinuteExpiry::Expired);
+        let distance = |expires_at: NaiveDateTime| -> i64 {
+            let days = expires_at.signed_duration_since(now).num_minutes() / (24 * 60);
+            return (days - self.tenor_days).abs();
+        };
+        let expiry = expiries.iter().min_by_key(|e| distance(e.expires_at))?;
+        let target = self.moneyness * spot as f64;
+        let quoted = |o: &&OptionContract| o.bid != 0;
+        return match self.kind {
+            OptionKind::Call => expiry
+                .calls
+                .iter()
+                .filter(quoted)
+                .filter(|o| o.strike as f64 >= target)
+                .min_by_key(|o| o.strike)
+                .cloned(),
+            OptionKind::Put => expiry
+                .puts
+                .iter()
+                .filter(quoted)
+                .filter(|o| o.strike as f64 <= target)
+                .max_by_key(|o| o.strike)
+                .cloned(),
+        };
+    }
+
+    /**
+     * The strategy's index over `snapshots`, sorted by time, starting at `BASE_VALUE`.
+     *
+     * Options are written at their mark and marked to the mark in each snapshot, keeping the last
+     * one while the contract is missing from the chain. An option settles at its intrinsic value
+     * against the spot of the first snapshot at or after its expiration, and the next one is
+     * written in the same snapshot. Cash earns the rate to the expiration of the put it backs.
+     */
+    pub fn index(&self, snapshots: &[MarketSnapshot], rates: &YieldCurve) -> Vec<BenchmarkPoint> {
+        let mut points = vec![];
+        let mut value = BASE_VALUE;
+        let mut held: Option<Written> = None;
+        for snapshot in snapshots.iter() {
+            if let Some(written) = held.as_mut() {
+                let contract = written.contract;
+                let expired = snapshot.at >= contract.expires_at;
+                let option = if expired {
+                    let (spot, strike) = (snapshot.spot as f64, contract.strike as f64);
+                    match contract.kind {
+                        OptionKind::Call => (spot - strike).max(0.0),
+                        OptionKind::Put => (strike - spot).max(0.0),
+                    }
+                } else {
+                    let mark = find(snapshot.chain, &contract).map(|o| o.mark() as f64);
+                    written.mark = mark.unwrap_or(written.mark);
+                    written.mark
+                };
+                value = written.value(option, snapshot);
+                if expired {
+                    held = None;
+                }
+            }
+            if held.is_none() {
+                held = self
+                    .select(snapshot.chain, snapshot.spot, snapshot.at)
+                    .and_then(|contract| Written::open(contract, value, snapshot, rates));
+            }
+            points.push(BenchmarkPoint {
+                at: snapshot.at,
+                value,
+                position: held.as_ref().map(|w| w.contract),
+            });
+        }
+        return points;
+    }
+}
+
+/**
+ * An option written by `OptionWrite`, with what covers it.
+ */
+struct Written {
+    contract: OptionContract,
+    mark: f64,
+    /// Options written, and units of underlying held against calls.
+    units: f64,
+    /// Cash held against puts, including their premium, when they were written.
+    cash: f64,
+    rate: f64,
+    written_at: NaiveDateTime,
+}
+
+impl Written {
+    fn open(
+        contract: OptionContract,
+        value: f64,
+        snapshot: &MarketSnapshot,
+        rates: &YieldCurve,
+    ) -> Option<Written> {
+        let mark = contract.mark() as f64;
+        let (units, cash) = match contract.kind {
+            OptionKind::Call if snapshot.spot as f64 > mark => {
+                (value / (snapshot.spot as f64 - mark), 0.0)
+            }
+            OptionKind::Put if contract.strike > 0 => {
+                let units = value / contract.strike as f64;
+                (units, value + units * mark)
+            }
+            _ => return None,
+        };
+        return Some(Written {
+            contract,
+            mark,
+            units,
+            cash,
+            rate: rates.rate_at(contract.expires_at, snapshot.at),
+            written_at: snapshot.at,
+        });
+    }
+
+    fn value(&self, option: f64, snapshot: &MarketSnapshot) -> f64 {
+        return match self.contract.kind {
+            OptionKind::Call => self.units * (snapshot.spot as f64 - option),
+            OptionKind::Put => {
+                let t = years_until(snapshot.at, self.written_at);
+                self.cash * (self.rate * t).exp() - self.units * option
+            }
+        };
+    }
+}
+
+fn find(chain: &Chain, contract: &OptionContract) -> Option<OptionContract> {
+    return chain.find(&contract.key()).filter(|o| o.bid != 0).cloned();
+}
+
+/**
+ * A volatility-targeted index at one time.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct VolTargetPoint {
+    pub at: NaiveDateTime,
+    pub value: f64,
+    /// Fraction of the value invested in the underlying index until the next point, the rest
+    /// earning the risk-free rate. Above one is leveraged.
+    pub exposure: f64,
+}
+
+/**
+ * An overlay on an index, given as values by time, that scales its exposure so that its
+ * volatility over the trailing `window` returns would have been `target`, capped at
+ * `max_leverage`. Points are taken to be a trading day apart when annualizing. Starts at
+ * `BASE_VALUE` once the first window is complete.
+ */
+pub fn vol_target(
+    series: &[(NaiveDateTime, f64)],
+    target: Percentage,
+    window: usize,
+    max_leverage: f64,
+    rates: &YieldCurve,
+) -> Vec<VolTargetPoint> {
+    let window = window.max(2);
+    let returns: Vec<f64> = series.windows(2).map(|w| (w[1].1 / w[0].1).ln()).collect();
+    let mut points: Vec<VolTargetPoint> = vec![];
+    for i in window..series.len() {
+        let value = match points.last() {
+            Some(last) => {
+                let t = years_until(series[i].0, series[i - 1].0);
+                let cash = (rates.rate(t * 365.0) * t).exp() - 1.0;
+                let underlying = series[i].1 / series[i - 1].1 - 1.0;
+                last.value * (1.0 + last.exposure * underlying + (1.0 - last.exposure) * cash)
+            }
+            None => BASE_VALUE,
+        };
+        let trailing = &returns[i - window..i];
+        let n = trailing.len() as f64;
+        let mean = trailing.iter().sum::<f64>() / n;
+        let variance = trailing.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        let realized = (variance * TRADING_DAYS).sqrt();
+        let exposure = if realized > 0.0 {
+            (target / realized).min(max_leverage)
+        } else {
+            max_leverage
+        };
+        points.push(VolTargetPoint {
+            at: series[i].0,
+            value,
+            exposure,
+        });
+    }
+    return points;
+}
@@ -0,0 +1,89 @@
+//! Caching of per-strike index contributions between evaluations.
+//!
+//! When the index is published every second from tick data, most quotes are unchanged between
+//! evaluations. The cache keys each strike's contribution by the revision of the quote it was
+//! computed from, so only strikes whose quotes changed are recomputed.
+
+use crate::{Cents, OptionContract, OptionKind};
+use chrono::prelude::*;
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    revision: u64,
+    bid: Cents,
+    ask: Cents,
+    delta_k: Cents,
+    contribution: f64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ContributionCache {
+    entries: HashMap<(NaiveDateTime, Cents, OptionKind), Entry>,
+    hits: u64,
+    misses: u64,
+}
+
+impl ContributionCache {
+    pub fn new() -> ContributionCache {
+        return ContributionCache::default();
+    }
+
+    /**
+     * Number of contributions reused from the cache.
+     */
+    pub fn hits(&self) -> u64 {
+        return self.hits;
+    }
+
+    /**
+     * Number of contributions that had to be computed.
+     */
+    pub fn misses(&self) -> u64 {
+        return self.misses;
+    }
+
+    /**
+     * Drops the contributions of contracts expiring at or before `expires_at`.
+     */
+    pub fn evict_expired(&mut self, expires_at: NaiveDateTime) {
+        self.entries.retain(|key, _| key.0 > expires_at);
+    }
+
+    /**
+     * The cached contribution of `option`, or `compute`'s if the quote or strike interval
+     * changed since it was stored.
+     */
+    pub(crate) fn get_or_insert<F: Fn(&OptionContract, Cents) -> f64>(
+        &mut self,
+        option: &OptionContract,
+        delta_k: Cents,
+        compute: F,
+    ) -> f64 {
+        let key = (option.expires_at(), option.strike(), option.kind());
+        if let Some(entry) = self.entries.get(&key) {
+            // the quote is compared too, for contracts that were replaced without a new revision
+            if entry.revision == option.revision()
+                && entry.bid == option.bid()
+                && entry.ask == option.ask()
+                && entry.delta_k == delta_k
+            {
+                self.hits += 1;
+                return entry.contribution;
+            }
+        }
+        self.misses += 1;
+        let contribution = compute(option, delta_k);
+        self.entries.insert(
+            key,
+            Entry {
+                revision: option.revision(),
+                bid: option.bid(),
+                ask: option.ask(),
+                delta_k,
+                contribution,
+            },
+        );
+        return contribution;
+    }
+}
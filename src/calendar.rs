@@ -0,0 +1,133 @@
+//! Analytics for calendar and diagonal spreads.
+//!
+//! A calendar spread is a position in the term structure of volatility: it is long the forward
+//! volatility between its expiries when long the far leg. These analytics make that exposure
+//! explicit, which a payoff-at-expiration view cannot.
+
+use crate::analytics::ChainAnalytics;
+use crate::math::black_price;
+use crate::skew::atm_vol;
+use crate::strategy::Strategy;
+use chrono::prelude::*;
+
+/**
+ * A change in the implied volatility term structure: every expiry's volatilities move by
+ * `parallel + slope * (days - pivot_days) / 365`.
+ */
+#[derive(new, Clone, Copy, Debug)]
+pub struct TermStructureShift {
+    pub parallel: f64,
+    /// Positive values steepen the term structure, negative values flatten it.
+    pub slope: f64,
+    pub pivot_days: f64,
+}
+
+impl TermStructureShift {
+    pub fn change(&self, days: f64) -> f64 {
+        return self.parallel + self.slope * (days - self.pivot_days) / 365.0;
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ScenarioPnl {
+    pub shift: TermStructureShift,
+    /// Change in the strategy's value, in cents, with everything but volatility held fixed.
+    pub pnl: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct CalendarAnalytics {
+    /// Net vega (dollars per unit of volatility) of the legs in each expiry, sorted by expiry.
+    pub vega_by_expiry: Vec<(NaiveDateTime, f64)>,
+    pub net_vega: f64,
+    /// Forward volatility between the nearest and furthest expiries, from their ATM volatilities.
+    pub forward_volatility: Option<f64>,
+    /// Vega of the furthest expiry: positive when buying the forward volatility, negative when
+    /// selling it.
+    pub forward_vega: f64,
+    pub scenarios: Vec<ScenarioPnl>,
+}
+
+/**
+ * Analyzes a strategy across expiries against the chain's analytics. `None` if any leg is
+ * missing from the chain or has no implied volatility.
+ */
+pub fn calendar_analytics(
+    strategy: &Strategy,
+    analytics: &ChainAnalytics,
+    scenarios: &[TermStructureShift],
+) -> Option<CalendarAnalytics> {
+    let mut legs = vec![];
+    for leg in strategy.legs.iter() {
+        let (expiry, contract) = analytics.find(&leg.contract)?;
+        legs.push((
+            leg.quantity as f64,
+            expiry,
+            contract,
+            contract.implied_volatility?,
+        ));
+    }
+
+    let mut vega_by_expiry: Vec<(NaiveDateTime, f64)> = vec![];
+    for (quantity, expiry, contract, _) in legs.iter() {
+        let vega = quantity * contract.greeks?.vega;
+        match vega_by_expiry.iter_mut().find(|v| v.0 == expiry.expires_at) {
+            Some(v) => v.1 += vega,
+            None => vega_by_expiry.push((expiry.expires_at, vega)),
+        }
+    }
+    vega_by_expiry.sort_by_key(|v| v.0);
+    let net_vega = vega_by_expiry.iter().map(|v| v.1).sum();
+    let forward_vega = vega_by_expiry.last().map(|v| v.1).unwrap_or(0.0);
+
+    let near = legs.iter().min_by_key(|l| l.1.expires_at)?.1;
+    let far = legs.iter().max_by_key(|l| l.1.expires_at)?.1;
+    let forward_volatility = if near.expires_at < far.expires_at {
+        let near_variance = atm_vol(near)?.powi(2) * near.time_to_expiration;
+        let far_variance = atm_vol(far)?.powi(2) * far.time_to_expiration;
+        let forward_variance =
+            (far_variance - near_variance) / (far.time_to_expiration - near.time_to_expiration);
+        if forward_variance >= 0.0 {
+            Some(forward_variance.sqrt())
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    let value = |shift: Option<&TermStructureShift>| -> f64 {
+        return legs
+            .iter()
+            .map(|(quantity, expiry, contract, vol)| {
+                let days = expiry.time_to_expiration * 365.0;
+                let vol = vol + shift.map(|s| s.change(days)).unwrap_or(0.0);
+                return quantity
+                    * black_price(
+                        contract.contract.kind(),
+                        expiry.forward_price as f64,
+                        contract.contract.strike() as f64,
+                        vol.max(0.0),
+                        expiry.time_to_expiration,
+                        expiry.discount_factor,
+                    );
+            })
+            .sum();
+    };
+    let base = value(None);
+    let scenarios = scenarios
+        .iter()
+        .map(|shift| ScenarioPnl {
+            shift: *shift,
+            pnl: value(Some(shift)) - base,
+        })
+        .collect();
+
+    return Some(CalendarAnalytics {
+        vega_by_expiry,
+        net_vega,
+        forward_volatility,
+        forward_vega,
+        scenarios,
+    });
+}
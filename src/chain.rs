@@ -0,0 +1,293 @@
+//! A full option chain: every expiry of a single underlying.
+
+use crate::currency::Currency;
+use crate::holidays::{Cboe, HolidayCalendar};
+use crate::schedule::{classify, ExpirationCycle};
+use crate::validation::{resolve_duplicates, DuplicatePolicy, Issue};
+use crate::{
+    group_options_by_expiry, Cents, ContractKey, ExerciseStyle, OptionContract, OptionKind,
+    OptionsByExpiryDate, SameMinuteExpiry, Settlement, VolatilityModel,
+};
+use chrono::prelude::*;
+use std::sync::{Arc, Mutex, RwLock};
+
+/**
+ * A new quote for a contract.
+ */
+#[derive(new, Clone, Copy, Debug)]
+pub struct QuoteUpdate {
+    pub expires_at: NaiveDateTime,
+    pub strike: Cents,
+    pub kind: OptionKind,
+    pub bid: Cents,
+    pub ask: Cents,
+}
+
+#[derive(Clone, Debug)]
+pub struct Chain {
+    expiries: Vec<OptionsByExpiryDate>,
+}
+
+impl Chain {
+    /**
+     * Builds a chain from contracts in any order. Duplicate contracts are kept, and computations
+     * use whichever came first; see `with_duplicate_policy` to resolve them instead.
+     */
+    pub fn new(options: &[OptionContract]) -> Chain {
+        let mut expiries: Vec<OptionsByExpiryDate> =
+            group_options_by_expiry(options).into_values().collect();
+        expiries.sort_unstable_by_key(|e| e.expires_at);
+        return Chain { expiries };
+    }
+
+    /**
+     * Builds a chain from contracts in any order, resolving duplicate contracts according to
+     * `policy`.
+     */
+    pub fn with_duplicate_policy(
+        options: &[OptionContract],
+        policy: DuplicatePolicy,
+    ) -> Result<Chain, Vec<Issue>> {
+        return Ok(Chain::new(&resolve_duplicates(options, policy)?));
+    }
+
+    /**
+     * The same chain with every contract exercisable as `exercise_style`.
+     */
+    pub fn with_exercise_style(&self, exercise_style: ExerciseStyle) -> Chain {
+        return self.map_contracts(|o| o.with_exercise_style(exercise_style));
+    }
+
+    /**
+     * The same chain with every contract settled as `settlement`.
+     */
+    pub fn with_settlement(&self, settlement: Settlement) -> Chain {
+        return self.map_contracts(|o| o.with_settlement(settlement));
+    }
+
+    /**
+     * The same chain with every contract quoted in `currency`.
+     */
+    pub fn with_currency(&self, currency: Currency) -> Chain {
+        return self.map_contracts(|o| o.with_currency(currency));
+    }
+
+    /**
+     * The same chain with every contract's volatility quoted under `volatility_model`.
+     */
+    pub fn with_volatility_model(&self, volatility_model: VolatilityModel) -> Chain {
+        return self.map_contracts(|o| o.with_volatility_model(volatility_model));
+    }
+
+    fn map_contracts<F: Fn(OptionContract) -> OptionContract>(&self, f: F) -> Chain {
+        let set = |options: &[OptionContract]| -> Vec<OptionContract> {
+            return options.iter().map(|o| f(*o)).collect();
+        };
+        return Chain {
+            expiries: self
+                .expiries
+                .iter()
+                .map(|e| OptionsByExpiryDate {
+                    expires_at: e.expires_at,
+                    calls: set(&e.calls),
+                    puts: set(&e.puts),
+                })
+                .collect(),
+        };
+    }
+
+    /**
+     * All expiries, sorted by expiration date.
+     */
+    pub fn expiries(&self) -> &[OptionsByExpiryDate] {
+        return &self.expiries;
+    }
+
+    /**
+     * The options expiring at `expires_at`, if any.
+     */
+    pub fn get(&self, expires_at: NaiveDateTime) -> Option<&OptionsByExpiryDate> {
+        return self.expiries.iter().find(|e| e.expires_at == expires_at);
+    }
+
+    /**
+     * The contract listed under `key`, with its latest quote.
+     */
+    pub fn find(&self, key: &ContractKey) -> Option<&OptionContract> {
+        let expiry = self.get(key.expires_at)?;
+        let contracts = match key.kind {
+            OptionKind::Call => &expiry.calls,
+            OptionKind::Put => &expiry.puts,
+        };
+        let index = contracts.partition_point(|o| o.strike < key.strike);
+        return contracts.get(index).filter(|o| o.strike == key.strike);
+    }
+
+    /**
+     * The expiries that have not expired as of `now`, along with an `Issue::Expired` for each
+     * one that was left out.
+     */
+    pub fn unexpired(
+        &self,
+        now: NaiveDateTime,
+        same_minute: SameMinuteExpiry,
+    ) -> (&[OptionsByExpiryDate], Vec<Issue>) {
+        let start = self
+            .expiries
+            .partition_point(|e| e.is_expired(now, same_minute));
+        let excluded = self.expiries[..start]
+            .iter()
+            .map(|e| Issue::Expired {
+                expires_at: e.expires_at,
+            })
+            .collect();
+        return (&self.expiries[start..], excluded);
+    }
+
+    /**
+     * Expiries between `min` and `max` calendar days to expiration (inclusive) as of `now`.
+     */
+    pub fn expiries_between_dte(
+        &self,
+        min: i64,
+        max: i64,
+        now: NaiveDateTime,
+    ) -> &[OptionsByExpiryDate] {
+        let dte = |e: &OptionsByExpiryDate| -> i64 {
+            return e
+                .expires_at
+                .date()
+                .signed_duration_since(now.date())
+                .num_days();
+        };
+        let start = self.expiries.partition_point(|e| dte(e) < min);
+        let end = self.expiries.partition_point(|e| dte(e) <= max);
+        return &self.expiries[start..end.max(start)];
+    }
+
+    /**
+     * The first monthly expiry that has not yet expired as of `now`, using the Cboe calendar.
+     */
+    pub fn front_month(&self, now: NaiveDateTime) -> Option<&OptionsByExpiryDate> {
+        return self.front_month_in(now, &Cboe);
+    }
+
+    /**
+     * Like `front_month`, with holiday adjustments from `calendar`.
+     */
+    pub fn front_month_in<C: HolidayCalendar + ?Sized>(
+        &self,
+        now: NaiveDateTime,
+        calendar: &C,
+    ) -> Option<&OptionsByExpiryDate> {
+        return self.expiries.iter().find(|e| {
+            return e.expires_at > now
+                && classify(e.expires_at.date(), calendar) == Some(ExpirationCycle::Monthly);
+        });
+    }
+
+    /**
+     * Only the monthly (third Friday) expiries, using the Cboe calendar.
+     */
+    pub fn monthlies_only(&self) -> impl Iterator<Item = &OptionsByExpiryDate> {
+        return self.monthlies_only_in(&Cboe);
+    }
+
+    /**
+     * Only the monthly expiries, with holiday adjustments from `calendar`.
+     */
+    pub fn monthlies_only_in<'a, C: HolidayCalendar + ?Sized>(
+        &'a self,
+        calendar: &'a C,
+    ) -> impl Iterator<Item = &'a OptionsByExpiryDate> + 'a {
+        return self.expiries.iter().filter(move |e| {
+            return classify(e.expires_at.date(), calendar) == Some(ExpirationCycle::Monthly);
+        });
+    }
+
+    /**
+     * Applies quote updates, bumping the revision of each updated contract. Updates for
+     * contracts not yet in the chain list them.
+     */
+    pub fn apply(&mut self, updates: &[QuoteUpdate]) {
+        for u in updates.iter() {
+            let index = match self
+                .expiries
+                .binary_search_by_key(&u.expires_at, |e| e.expires_at)
+            {
+                Ok(index) => index,
+                Err(index) => {
+                    self.expiries.insert(
+                        index,
+                        OptionsByExpiryDate {
+                            expires_at: u.expires_at,
+                            calls: vec![],
+                            puts: vec![],
+                        },
+                    );
+                    index
+                }
+            };
+            let expiry = &mut self.expiries[index];
+            let contracts = match u.kind {
+                OptionKind::Call => &mut expiry.calls,
+                OptionKind::Put => &mut expiry.puts,
+            };
+            match contracts.iter_mut().find(|o| o.strike == u.strike) {
+                Some(o) => *o = o.with_quote(u.bid, u.ask),
+                None => contracts.insert(
+                    contracts.partition_point(|o| o.strike < u.strike),
+                    OptionContract::new(u.expires_at, u.strike, u.kind, u.bid, u.ask),
+                ),
+            }
+        }
+    }
+}
+
+/**
+ * A chain shared between one writer applying quote updates and any number of readers.
+ *
+ * Readers take an immutable snapshot, which is only a reference count increment, and can compute
+ * on it for as long as they like without blocking the writer. The writer builds the next version
+ * on the side and swaps it in, so readers never see a partially applied batch.
+ */
+#[derive(Debug)]
+pub struct SharedChain {
+    current: RwLock<Arc<Chain>>,
+    writer: Mutex<()>,
+}
+
+impl SharedChain {
+    pub fn new(chain: Chain) -> SharedChain {
+        return SharedChain {
+            current: RwLock::new(Arc::new(chain)),
+            writer: Mutex::new(()),
+        };
+    }
+
+    /**
+     * The latest version of the chain.
+     */
+    pub fn snapshot(&self) -> Arc<Chain> {
+        return self
+            .current
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+    }
+
+    /**
+     * Applies a batch of quote updates and publishes the result. Concurrent calls are
+     * serialized.
+     */
+    pub fn apply(&self, updates: &[QuoteUpdate]) {
+        let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        #[cfg(feature = "metrics")]
+        crate::metrics::global()
+            .quote_updates
+            .add(updates.len() as u64);
+        let mut next = (*self.snapshot()).clone();
+        next.apply(updates);
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(next);
+    }
+}
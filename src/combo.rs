@@ -0,0 +1,198 @@
+//! Quotes on multi-leg combinations from an exchange's complex order book.
+//!
+//! Spreads and straddles are often quoted as a single market that is tighter than the legs'
+//! markets added up, since the combo trades without leg risk. A `ComboBook` keeps those quotes
+//! so a `Strategy` can be priced at its own combo market where one exists, rather than at the
+//! sum of its legs' mids.
+
+use crate::strategy::Strategy;
+use crate::{Cents, OptionKind};
+use chrono::prelude::*;
+use std::collections::HashMap;
+
+/**
+ * One leg of a combo: a contract and how many of it the combo holds per unit, negative if sold.
+ */
+#[derive(new, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct ComboLeg {
+    pub expires_at: NaiveDateTime,
+    pub strike: Cents,
+    pub kind: OptionKind,
+    pub ratio: i64,
+}
+
+/**
+ * A quoted combo market. `bid` and `ask` are net prices per unit of the legs' ratios: what is
+ * paid to buy it, negative for a credit.
+ */
+#[derive(new, PartialEq, Clone, Debug)]
+pub struct ComboQuote {
+    pub legs: Vec<ComboLeg>,
+    pub bid: Cents,
+    pub ask: Cents,
+    #[new(default)]
+    pub quoted_at: Option<NaiveDateTime>,
+}
+
+impl ComboQuote {
+    pub fn with_quoted_at(self, quoted_at: NaiveDateTime) -> ComboQuote {
+        return ComboQuote {
+            quoted_at: Some(quoted_at),
+            ..self
+        };
+    }
+
+    pub fn mark(&self) -> Cents {
+        return (self.bid + self.ask) / 2;
+    }
+}
+
+/**
+ * A market for a position, as net prices to buy (`ask`) and sell (`bid`) it.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct ComboMarket {
+    pub bid: Cents,
+    pub ask: Cents,
+}
+
+impl ComboMarket {
+    pub fn mark(&self) -> Cents {
+        return (self.bid + self.ask) / 2;
+    }
+
+    pub fn width(&self) -> Cents {
+        return self.ask - self.bid;
+    }
+}
+
+/**
+ * Legs in a canonical order with their ratios in lowest terms, the first one long, and the
+ * number of those units the legs amount to (negative if they are the canonical combo sold).
+ */
+type ComboKey = Vec<(NaiveDateTime, Cents, bool, i64)>;
+
+fn canonical(legs: &[ComboLeg]) -> Option<(ComboKey, i64)> {
+    let mut merged: HashMap<(NaiveDateTime, Cents, OptionKind), i64> = HashMap::new();
+    for leg in legs.iter() {
+        *merged
+            .entry((leg.expires_at, leg.strike, leg.kind))
+            .or_insert(0) += leg.ratio;
+    }
+    let mut key: ComboKey = merged
+        .into_iter()
+        .filter(|(_, ratio)| *ratio != 0)
+        .map(|((expires_at, strike, kind), ratio)| {
+            return (expires_at, strike, kind == OptionKind::Put, ratio);
+        })
+        .collect();
+    key.sort();
+    let first = key.first()?.3;
+    let units = key.iter().fold(0, |g, leg| gcd(g, leg.3.abs())) * first.signum();
+    for leg in key.iter_mut() {
+        leg.3 /= units;
+    }
+    return Some((key, units));
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    return if b == 0 { a } else { gcd(b, a % b) };
+}
+
+/**
+ * The latest combo quote for each combination of legs.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct ComboBook {
+    quotes: HashMap<ComboKey, (ComboQuote, i64)>,
+}
+
+impl ComboBook {
+    pub fn new() -> ComboBook {
+        return ComboBook::default();
+    }
+
+    /**
+     * Adds a quote, replacing any for the same combination unless that one was captured later.
+     * The same combination quoted in the opposite direction or at a multiple of the ratios
+     * counts as the same. Quotes without legs are ignored.
+     */
+    pub fn insert(&mut self, quote: ComboQuote) {
+        let (key, units) = match canonical(&quote.legs) {
+            Some(canonical) => canonical,
+            None => return,
+        };
+        if let Some((existing, _)) = self.quotes.get(&key) {
+            if let (Some(existing), Some(quoted_at)) = (existing.quoted_at, quote.quoted_at) {
+                if existing > quoted_at {
+                    return;
+                }
+            }
+        }
+        self.quotes.insert(key, (quote, units));
+    }
+
+    pub fn len(&self) -> usize {
+        return self.quotes.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.quotes.is_empty();
+    }
+
+    /**
+     * The combo market for the strategy's legs, scaled to its quantities and in its direction,
+     * or `None` if that combination is not quoted.
+     */
+    pub fn market(&self, strategy: &Strategy) -> Option<ComboMarket> {
+        let legs: Vec<ComboLeg> = strategy
+            .legs
+            .iter()
+            .map(|l| {
+                let c = l.contract;
+                return ComboLeg::new(c.expires_at(), c.strike(), c.kind(), l.quantity);
+            })
+            .collect();
+        let (key, units) = canonical(&legs)?;
+        let (quote, quote_units) = self.quotes.get(&key)?;
+        let scale = units as f64 / *quote_units as f64;
+        let (bid, ask) = (quote.bid as f64 * scale, quote.ask as f64 * scale);
+        // selling the quoted combo buys the reverse at the negated bid
+        let (bid, ask) = if scale < 0.0 { (ask, bid) } else { (bid, ask) };
+        return Some(ComboMarket {
+            bid: bid.round() as Cents,
+            ask: ask.round() as Cents,
+        });
+    }
+}
+
+impl Strategy {
+    /**
+     * The market from the legs' own quotes: buying pays the asks of long legs and receives the
+     * bids of short ones.
+     */
+    pub fn leg_market(&self) -> ComboMarket {
+        let (mut bid, mut ask) = (0, 0);
+        for leg in self.legs.iter() {
+            let (buy, sell) = if leg.quantity > 0 {
+                (leg.contract.ask(), leg.contract.bid())
+            } else {
+                (leg.contract.bid(), leg.contract.ask())
+            };
+            ask += leg.quantity * buy;
+            bid += leg.quantity * sell;
+        }
+        return ComboMarket { bid, ask };
+    }
+
+    /**
+     * Net premium at the mark of the strategy's combo market in `book` if it is quoted, and
+     * otherwise at the legs' marks as `premium` does.
+     */
+    pub fn premium_in(&self, book: &ComboBook) -> Cents {
+        return book
+            .market(self)
+            .map(|market| market.mark())
+            .unwrap_or_else(|| self.premium());
+    }
+}
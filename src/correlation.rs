@@ -0,0 +1,214 @@
+//! Beta and correlation between an underlying and an index.
+//!
+//! The correlation implied by options compares the variance of an index with the variances of
+//! its constituents: if the index is a weighted sum of stocks, its variance is their weighted
+//! variances plus every pairwise covariance, and assuming one correlation for every pair backs it
+//! out. Dispersion trades are bets on that number against the correlation later realized.
+
+use crate::analytics::ChainAnalytics;
+use crate::math::linear_interpolate_flat;
+use crate::realized::TRADING_DAYS;
+use crate::returns::LogReturn;
+use crate::skew::atm_term_structure;
+use crate::Percentage;
+use chrono::prelude::*;
+
+/**
+ * How an underlying moves with an index.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Comovement {
+    pub beta: f64,
+    pub correlation: f64,
+    /// Annualized volatility of the underlying.
+    pub asset_volatility: Percentage,
+    /// Annualized volatility of the index.
+    pub index_volatility: Percentage,
+}
+
+impl Comovement {
+    fn from_moments(
+        asset_variance: f64,
+        index_variance: f64,
+        covariance: f64,
+    ) -> Option<Comovement> {
+        if asset_variance <= 0.0 || index_variance <= 0.0 {
+            return None;
+        }
+        return Some(Comovement {
+            beta: covariance / index_variance,
+            correlation: covariance / (asset_variance * index_variance).sqrt(),
+            asset_volatility: (asset_variance * TRADING_DAYS).sqrt(),
+            index_volatility: (index_variance * TRADING_DAYS).sqrt(),
+        });
+    }
+}
+
+/**
+ * Returns of the underlying and the index on the dates both have one, each sorted by date.
+ */
+pub fn align(asset: &[LogReturn], index: &[LogReturn]) -> Vec<(NaiveDate, f64, f64)> {
+    let mut aligned = vec![];
+    let mut j = 0;
+    for a in asset.iter() {
+        while j < index.len() && index[j].date < a.date {
+            j += 1;
+        }
+        if j < index.len() && index[j].date == a.date {
+            aligned.push((a.date, a.value, index[j].value));
+        }
+    }
+    return aligned;
+}
+
+fn sample_comovement(pairs: &[(NaiveDate, f64, f64)]) -> Option<Comovement> {
+    if pairs.len() < 2 {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let mean_asset = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+    let mean_index = pairs.iter().map(|p| p.2).sum::<f64>() / n;
+    let (mut asset_variance, mut index_variance, mut covariance) = (0.0, 0.0, 0.0);
+    for (_, a, i) in pairs.iter() {
+        asset_variance += (a - mean_asset).powi(2);
+        index_variance += (i - mean_index).powi(2);
+        covariance += (a - mean_asset) * (i - mean_index);
+    }
+    return Comovement::from_moments(
+        asset_variance / (n - 1.0),
+        index_variance / (n - 1.0),
+        covariance / (n - 1.0),
+    );
+}
+
+/**
+ * Beta and correlation over the whole history.
+ */
+pub fn realized_comovement(asset: &[LogReturn], index: &[LogReturn]) -> Option<Comovement> {
+    return sample_comovement(&align(asset, index));
+}
+
+/**
+ * Beta and correlation over each trailing window of `window` common dates, by the last date of
+ * the window.
+ */
+pub fn rolling_comovement(
+    asset: &[LogReturn],
+    index: &[LogReturn],
+    window: usize,
+) -> Vec<(NaiveDate, Comovement)> {
+    return align(asset, index)
+        .windows(window.max(2))
+        .flat_map(|w| Some((w[w.len() - 1].0, sample_comovement(w)?)))
+        .collect();
+}
+
+/**
+ * Exponentially weighted beta and correlation (RiskMetrics, zero mean) after each common date,
+ * with decay `lambda` per observation, e.g. 0.94 for daily returns.
+ */
+pub fn ewma_comovement(
+    asset: &[LogReturn],
+    index: &[LogReturn],
+    lambda: f64,
+) -> Vec<(NaiveDate, Comovement)> {
+    let mut moments: Option<(f64, f64, f64)> = None;
+    return align(asset, index)
+        .into_iter()
+        .flat_map(|(date, a, i)| -> Option<(NaiveDate, Comovement)> {
+            let (asset_variance, index_variance, covariance) = match moments {
+                Some((av, iv, c)) => (
+                    lambda * av + (1.0 - lambda) * a * a,
+                    lambda * iv + (1.0 - lambda) * i * i,
+                    lambda * c + (1.0 - lambda) * a * i,
+                ),
+                None => (a * a, i * i, a * i),
+            };
+            moments = Some((asset_variance, index_variance, covariance));
+            return Some((
+                date,
+                Comovement::from_moments(asset_variance, index_variance, covariance)?,
+            ));
+        })
+        .collect();
+}
+
+/**
+ * Beta implied by a correlation and the implied volatilities of the underlying and the index,
+ * for comparison against the realized beta.
+ */
+pub fn implied_beta(
+    correlation: f64,
+    asset_volatility: Percentage,
+    index_volatility: Percentage,
+) -> f64 {
+    return correlation * asset_volatility / index_volatility;
+}
+
+/**
+ * The average pairwise correlation under which constituents with these weights and volatilities
+ * make up an index with `index_volatility`:
+ * `(σ_I² - Σ w_i² σ_i²) / ((Σ w_i σ_i)² - Σ w_i² σ_i²)`. Not clamped to `[-1, 1]`; an index
+ * richer than its constituents implies a correlation above one. `None` for fewer than two
+ * constituents with volatility.
+ */
+pub fn implied_correlation(
+    index_volatility: Percentage,
+    constituents: &[(f64, Percentage)],
+) -> Option<f64> {
+    let own: f64 = constituents.iter().map(|(w, vol)| (w * vol).powi(2)).sum();
+    let total: f64 = constituents.iter().map(|(w, vol)| w * vol).sum();
+    let cross = total * total - own;
+    if cross <= 0.0 {
+        return None;
+    }
+    return Some((index_volatility * index_volatility - own) / cross);
+}
+
+/**
+ * Implied correlation at one tenor.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct ImpliedCorrelation {
+    /// Days to expiration.
+    pub tenor: f64,
+    pub index_volatility: Percentage,
+    /// Weighted average ATM volatility of the constituents, the index volatility at a
+    /// correlation of one.
+    pub constituent_volatility: Percentage,
+    pub correlation: f64,
+}
+
+/**
+ * Implied correlation at each of `tenors` (in days) from the ATM volatilities of the index and
+ * its `constituents`, given as weight and surface, interpolated linearly in tenor between
+ * expiries and flat beyond them. Weights are the constituents' shares of the index value.
+ * Tenors the index or any constituent has no expiries for are omitted.
+ */
+pub fn implied_correlation_term_structure(
+    index: &ChainAnalytics,
+    constituents: &[(f64, &ChainAnalytics)],
+    tenors: &[f64],
+) -> Vec<ImpliedCorrelation> {
+    let index = atm_term_structure(index);
+    let constituents: Vec<(f64, Vec<(f64, Percentage)>)> = constituents
+        .iter()
+        .map(|(weight, analytics)| (*weight, atm_term_structure(analytics)))
+        .collect();
+    return tenors
+        .iter()
+        .flat_map(|tenor| -> Option<ImpliedCorrelation> {
+            let index_volatility = linear_interpolate_flat(&index, *tenor)?;
+            let vols = constituents
+                .iter()
+                .map(|(weight, term)| Some((*weight, linear_interpolate_flat(term, *tenor)?)))
+                .collect::<Option<Vec<(f64, Percentage)>>>()?;
+            return Some(ImpliedCorrelation {
+                tenor: *tenor,
+                index_volatility,
+                constituent_volatility: vols.iter().map(|(w, vol)| w * vol).sum(),
+                correlation: implied_correlation(index_volatility, &vols)?,
+            });
+        })
+        .collect();
+}
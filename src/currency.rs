@@ -0,0 +1,175 @@
+//! Currencies and conversion between them.
+//!
+//! Prices within a contract or chain are all in the contract's currency; converting only
+//! happens when amounts from different contracts are aggregated for reporting.
+
+use crate::greeks::Greeks;
+use std::collections::HashMap;
+use std::fmt;
+
+/**
+ * An ISO 4217 currency code.
+ */
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    pub const USD: Currency = Currency(*b"USD");
+    pub const EUR: Currency = Currency(*b"EUR");
+    pub const GBP: Currency = Currency(*b"GBP");
+    pub const JPY: Currency = Currency(*b"JPY");
+    pub const CHF: Currency = Currency(*b"CHF");
+    pub const KRW: Currency = Currency(*b"KRW");
+
+    /**
+     * A currency from its three-letter code, or `None` if `code` is not three ASCII letters.
+     */
+    pub fn new(code: &str) -> Option<Currency> {
+        let bytes = code.as_bytes();
+        if bytes.len() != 3 || !bytes.iter().all(|b| b.is_ascii_alphabetic()) {
+            return None;
+        }
+        return Some(Currency([
+            bytes[0].to_ascii_uppercase(),
+            bytes[1].to_ascii_uppercase(),
+            bytes[2].to_ascii_uppercase(),
+        ]));
+    }
+
+    pub fn code(&self) -> &str {
+        return std::str::from_utf8(&self.0).unwrap_or("???");
+    }
+
+    /**
+     * Decimal places of the minor unit per ISO 4217: 2 for cents, 0 for currencies like the yen
+     * that have no minor unit in use, 3 for the dinars divided into fils.
+     */
+    pub fn minor_unit_exponent(&self) -> i32 {
+        return match self.code() {
+            "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF"
+            | "UGX" | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            _ => 2,
+        };
+    }
+}
+
+impl fmt::Debug for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "{}", self.code());
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "{}", self.code());
+    }
+}
+
+/**
+ * An amount in minor units of a currency, e.g. cents of a dollar but whole yen.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct Money {
+    pub amount: f64,
+    pub currency: Currency,
+}
+
+/**
+ * Source of exchange rates, between major units (e.g. dollars, not cents).
+ */
+pub trait FxRates {
+    /**
+     * Units of `to` per unit of `from`, or `None` if unknown.
+     */
+    fn rate(&self, from: Currency, to: Currency) -> Option<f64>;
+}
+
+/**
+ * Fixed table of exchange rates. Inverse rates are derived automatically.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct FxTable {
+    rates: HashMap<(Currency, Currency), f64>,
+}
+
+impl FxTable {
+    pub fn new() -> FxTable {
+        return FxTable::default();
+    }
+
+    /**
+     * Sets the rate as units of `to` per unit of `from`.
+     */
+    pub fn set(&mut self, from: Currency, to: Currency, rate: f64) {
+        self.rates.insert((from, to), rate);
+    }
+}
+
+impl FxRates for FxTable {
+    fn rate(&self, from: Currency, to: Currency) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        if let Some(rate) = self.rates.get(&(from, to)) {
+            return Some(*rate);
+        }
+        return self.rates.get(&(to, from)).map(|r| 1.0 / r);
+    }
+}
+
+impl Money {
+    /**
+     * The amount converted to `currency`, or `None` if the rate is unknown.
+     */
+    pub fn convert<R: FxRates>(&self, currency: Currency, fx: &R) -> Option<Money> {
+        // rates are between major units
+        let rate = fx.rate(self.currency, currency)?;
+        let exponent = currency.minor_unit_exponent() - self.currency.minor_unit_exponent();
+        return Some(Money::new(
+            self.amount * rate * 10f64.powi(exponent),
+            currency,
+        ));
+    }
+}
+
+/**
+ * Sum of amounts in any currencies, in `currency`. `None` if any rate is unknown.
+ */
+pub fn total<R: FxRates>(amounts: &[Money], currency: Currency, fx: &R) -> Option<Money> {
+    let mut sum = 0.0;
+    for amount in amounts.iter() {
+        sum += amount.convert(currency, fx)?.amount;
+    }
+    return Some(Money::new(sum, currency));
+}
+
+/**
+ * Sum of position Greeks on underlyings priced in different currencies, in `currency`. Each
+ * position's Greeks are paired with its underlying's spot price. `None` if any rate is unknown.
+ *
+ * Greeks are in the units `greeks::black_scholes_greeks` computes them in from a contract's
+ * prices, hundredths of its currency's minor unit (dollars for a USD contract), and so is the
+ * sum. Delta and gamma are not values, so they are summed as cash delta (delta times spot) and
+ * cash gamma (gamma times spot squared), which are.
+ */
+pub fn total_greeks<R: FxRates>(
+    positions: &[(Greeks, Money)],
+    currency: Currency,
+    fx: &R,
+) -> Option<Greeks> {
+    let mut sum = Greeks::default();
+    for (g, spot) in positions.iter() {
+        // conversion is linear, so hundredths of minor units convert as minor units do
+        let value = |v: f64| -> Option<f64> {
+            return Some(Money::new(v, spot.currency).convert(currency, fx)?.amount);
+        };
+        let s = spot.amount / 100.0;
+        sum.delta += value(g.delta * s)?;
+        sum.gamma += value(g.gamma * s * s)?;
+        sum.theta += value(g.theta)?;
+        sum.vega += value(g.vega)?;
+        sum.rho += value(g.rho)?;
+    }
+    return Some(sum);
+}
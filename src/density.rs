@@ -0,0 +1,108 @@
+//! The risk-neutral density implied by an expiry's smile.
+
+use crate::analytics::ExpiryAnalytics;
+use crate::math::{black_price, linear_interpolate_flat, Rng};
+use crate::OptionKind;
+use chrono::prelude::*;
+
+/**
+ * Number of points the distribution is tabulated at.
+ */
+const GRID_POINTS: usize = 2001;
+
+/**
+ * Width of the tabulated range either side of the forward, in ATM standard deviations.
+ */
+const GRID_WIDTH: f64 = 8.0;
+
+/**
+ * Risk-neutral distribution of the underlying at expiration, tabulated from the smile via
+ * Breeden–Litzenberger: `P(S_T <= K) = 1 + e^{rT} dC/dK`.
+ *
+ * Beyond the quoted strikes the smile is extrapolated flat, which gives the tails the shape of
+ * a lognormal at the wing volatilities.
+ */
+#[derive(Clone, Debug)]
+pub struct RiskNeutralDensity {
+    expires_at: NaiveDateTime,
+    /// `(price in cents, cumulative probability)`, both ascending.
+    cdf: Vec<(f64, f64)>,
+    /// The inverse of `cdf`, keeping only points where the probability increases.
+    quantiles: Vec<(f64, f64)>,
+}
+
+impl RiskNeutralDensity {
+    /**
+     * Extracts the density of an expiry, or `None` if it has no usable implied volatilities.
+     */
+    pub fn from_expiry(expiry: &ExpiryAnalytics) -> Option<RiskNeutralDensity> {
+        let smile = expiry.smile();
+        let forward = expiry.forward_price as f64;
+        let t = expiry.time_to_expiration;
+        if smile.is_empty() || forward <= 0.0 || t <= 0.0 {
+            return None;
+        }
+
+        let atm_vol = linear_interpolate_flat(&smile, 1.0)?;
+        let width = GRID_WIDTH * atm_vol * t.sqrt();
+        let step = 2.0 * width / (GRID_POINTS - 1) as f64;
+        let call = |price: f64| -> f64 {
+            let vol = linear_interpolate_flat(&smile, price / forward).unwrap_or(atm_vol);
+            return black_price(
+                OptionKind::Call,
+                forward,
+                price,
+                vol,
+                t,
+                expiry.discount_factor,
+            );
+        };
+
+        let prices: Vec<f64> = (0..GRID_POINTS)
+            .map(|i| forward * (-width + i as f64 * step).exp())
+            .collect();
+        let mut cdf: Vec<(f64, f64)> = Vec::with_capacity(GRID_POINTS);
+        let mut quantiles: Vec<(f64, f64)> = vec![];
+        let mut running_max: f64 = 0.0;
+        for price in prices.into_iter() {
+            let h = price * step / 2.0;
+            let slope = (call(price + h) - call(price - h)) / (2.0 * h);
+            running_max = running_max.max((1.0 + slope / expiry.discount_factor).clamp(0.0, 1.0));
+            cdf.push((price, running_max));
+            if quantiles.last().map(|q| running_max > q.0).unwrap_or(true) {
+                quantiles.push((running_max, price));
+            }
+        }
+
+        return Some(RiskNeutralDensity {
+            expires_at: expiry.expires_at,
+            cdf,
+            quantiles,
+        });
+    }
+
+    pub fn expires_at(&self) -> NaiveDateTime {
+        return self.expires_at;
+    }
+
+    /**
+     * Probability that the underlying expires at or below `price` (in cents).
+     */
+    pub fn cdf(&self, price: f64) -> f64 {
+        return linear_interpolate_flat(&self.cdf, price).unwrap_or(0.0);
+    }
+
+    /**
+     * Price (in cents) below which the underlying expires with probability `p`.
+     */
+    pub fn quantile(&self, p: f64) -> f64 {
+        return linear_interpolate_flat(&self.quantiles, p).unwrap_or(0.0);
+    }
+
+    /**
+     * Draws `n` terminal prices (in cents) by inverse transform sampling.
+     */
+    pub fn sample(&self, n: usize, rng: &mut Rng) -> Vec<f64> {
+        return (0..n).map(|_| self.quantile(rng.next_f64())).collect();
+    }
+}
@@ -0,0 +1,179 @@
+//! Binary (digital) options.
+//!
+//! A cash-or-nothing option pays a fixed amount if it expires in the money and an
+//! asset-or-nothing option pays the underlying itself. A vanilla call is an asset-or-nothing call
+//! less a cash-or-nothing call paying the strike. Listed digitals are rare, so a digital is
+//! usually replicated, and marked, with the tightest vanilla spread around its strike.
+
+use crate::greeks::Greeks;
+use crate::math::{norm_cdf, norm_pdf};
+use crate::{Cents, OptionContract, OptionKind, OptionsByExpiryDate};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum DigitalPayout {
+    /// Pays this many cents.
+    Cash(Cents),
+    /// Pays the underlying's price at expiration.
+    Asset,
+}
+
+/**
+ * A European digital option.
+ */
+#[derive(new, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct DigitalOption {
+    pub kind: OptionKind,
+    pub strike: Cents,
+    pub payout: DigitalPayout,
+}
+
+impl DigitalOption {
+    /**
+     * Black–Scholes price in cents with a continuous dividend yield. `t` is in years; at or past
+     * expiration the price is the payout if in the money.
+     */
+    pub fn price(
+        &self,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        t: f64,
+    ) -> f64 {
+        let (s, k) = (spot as f64, self.strike as f64);
+        let t = t.max(0.0);
+        let std_dev = volatility * t.sqrt();
+        let phi = self.sign();
+        let (d1, d2) = if std_dev > 0.0 {
+            let d1 =
+                ((s / k).ln() + (risk_free_rate - dividend_yield) * t) / std_dev + std_dev / 2.0;
+            (d1, d1 - std_dev)
+        } else {
+            // the limit of the probabilities as volatility vanishes
+            let moneyness = (s / k).ln() + (risk_free_rate - dividend_yield) * t;
+            let d = if moneyness > 0.0 {
+                f64::INFINITY
+            } else {
+                f64::NEG_INFINITY
+            };
+            (d, d)
+        };
+        return match self.payout {
+            DigitalPayout::Cash(amount) => {
+                amount as f64 * (-risk_free_rate * t).exp() * norm_cdf(phi * d2)
+            }
+            DigitalPayout::Asset => s * (-dividend_yield * t).exp() * norm_cdf(phi * d1),
+        };
+    }
+
+    /**
+     * Black–Scholes Greeks, with prices in dollars as `black_scholes_greeks` gives them.
+     * Expired or volatility-free options have none.
+     */
+    pub fn greeks(
+        &self,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        t: f64,
+    ) -> Greeks {
+        let (s, k) = (spot as f64 / 100.0, self.strike as f64 / 100.0);
+        if t <= 0.0 || volatility <= 0.0 || s <= 0.0 || k <= 0.0 {
+            return Greeks::default();
+        }
+        let (r, q) = (risk_free_rate, dividend_yield);
+        let sqrt_t = t.sqrt();
+        let std_dev = volatility * sqrt_t;
+        let d1 = ((s / k).ln() + (r - q) * t) / std_dev + std_dev / 2.0;
+        let d2 = d1 - std_dev;
+        let phi = self.sign();
+
+        return match self.payout {
+            DigitalPayout::Cash(amount) => {
+                let a = amount as f64 / 100.0 * (-r * t).exp();
+                let price = a * norm_cdf(phi * d2);
+                let density = phi * a * norm_pdf(d2);
+                let dd2_dt = (r - q - volatility * volatility / 2.0) / std_dev - d2 / (2.0 * t);
+                Greeks {
+                    delta: density / (s * std_dev),
+                    gamma: -density * d1 / (s * s * std_dev * std_dev),
+                    theta: r * price - density * dd2_dt,
+                    vega: -density * d1 / volatility,
+                    rho: -t * price + density * sqrt_t / volatility,
+                }
+            }
+            DigitalPayout::Asset => {
+                let carry = (-q * t).exp();
+                let price = s * carry * norm_cdf(phi * d1);
+                let density = phi * s * carry * norm_pdf(d1);
+                let dd1_dt = (r - q + volatility * volatility / 2.0) / std_dev - d1 / (2.0 * t);
+                Greeks {
+                    delta: carry * norm_cdf(phi * d1) + density / (s * std_dev),
+                    gamma: -density * d2 / (s * s * std_dev * std_dev),
+                    theta: q * price - density * dd1_dt,
+                    vega: -density * d2 / volatility,
+                    rho: density * sqrt_t / volatility,
+                }
+            }
+        };
+    }
+
+    fn sign(&self) -> f64 {
+        return match self.kind {
+            OptionKind::Call => 1.0,
+            OptionKind::Put => -1.0,
+        };
+    }
+}
+
+/**
+ * A cash-or-nothing digital replicated by a vertical spread: `quantity` of `long` bought and of
+ * `short` sold, so that the spread pays the digital's payout beyond both strikes.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct DigitalReplication {
+    pub long: OptionContract,
+    pub short: OptionContract,
+    pub quantity: f64,
+    /// The spread's value at the contracts' marks, in cents.
+    pub price: f64,
+}
+
+impl OptionsByExpiryDate {
+    /**
+     * Replicates a cash-or-nothing digital with the tightest spread of quoted strikes straddling
+     * its strike: a call spread for a call, a put spread for a put. The spread's payoff ramps
+     * across the two strikes rather than jumping at one, which is what a dealer hedging the
+     * digital would hold. `None` for asset-or-nothing digitals, or without a quoted strike
+     * either side of the digital's.
+     */
+    pub fn replicate_digital(&self, digital: &DigitalOption) -> Option<DigitalReplication> {
+        let amount = match digital.payout {
+            DigitalPayout::Cash(amount) => amount,
+            DigitalPayout::Asset => return None,
+        };
+        let contracts = match digital.kind {
+            OptionKind::Call => self.calls(),
+            OptionKind::Put => self.puts(),
+        };
+        let quoted = || contracts.iter().filter(|o| o.bid > 0 || o.ask > 0);
+        let low = quoted()
+            .filter(|o| o.strike < digital.strike)
+            .max_by_key(|o| o.strike)?;
+        let high = quoted()
+            .filter(|o| o.strike > digital.strike)
+            .min_by_key(|o| o.strike)?;
+        let quantity = amount as f64 / (high.strike - low.strike) as f64;
+        let (long, short) = match digital.kind {
+            OptionKind::Call => (*low, *high),
+            OptionKind::Put => (*high, *low),
+        };
+        return Some(DigitalReplication {
+            long,
+            short,
+            quantity,
+            price: quantity * (long.mark() - short.mark()) as f64,
+        });
+    }
+}
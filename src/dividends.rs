@@ -0,0 +1,122 @@
+//! Known cash dividends of a single name.
+//!
+//! Index forwards come from put-call parity, which prices in whatever dividends the market
+//! expects. Single names pay a few discrete dividends that are usually announced, and pricing
+//! with the schedule is more accurate than a continuous yield, especially across an ex-date.
+
+use crate::pricing::{years_until, Dividends};
+use crate::{Cents, OptionsByExpiryDate};
+use chrono::prelude::*;
+
+/**
+ * Cash dividends by ex-date. The holder of the stock at the start of the ex-date is paid.
+ */
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct DividendSchedule {
+    dividends: Vec<(NaiveDateTime, Cents)>,
+}
+
+impl DividendSchedule {
+    pub fn new(dividends: &[(NaiveDateTime, Cents)]) -> DividendSchedule {
+        let mut dividends = dividends.to_vec();
+        dividends.sort();
+        return DividendSchedule { dividends };
+    }
+
+    /**
+     * Every dividend, by ex-date.
+     */
+    pub fn dividends(&self) -> &[(NaiveDateTime, Cents)] {
+        return &self.dividends;
+    }
+
+    /**
+     * Dividends going ex after `now` and at or before `expires_at`.
+     */
+    pub fn between(
+        &self,
+        now: NaiveDateTime,
+        expires_at: NaiveDateTime,
+    ) -> impl Iterator<Item = &(NaiveDateTime, Cents)> {
+        return self
+            .dividends
+            .iter()
+            .filter(move |(ex_date, _)| *ex_date > now && *ex_date <= expires_at);
+    }
+
+    /**
+     * Present value in cents of the dividends going ex before expiration.
+     */
+    pub fn present_value(
+        &self,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+        expires_at: NaiveDateTime,
+    ) -> f64 {
+        return self
+            .between(now, expires_at)
+            .map(|(ex_date, amount)| {
+                return *amount as f64 * (-risk_free_rate * years_until(*ex_date, now)).exp();
+            })
+            .sum();
+    }
+
+    /**
+     * Forward price of the underlying for `expires_at`: spot less the dividends' present
+     * value, carried at the risk-free rate.
+     */
+    pub fn forward_price(
+        &self,
+        spot: Cents,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+        expires_at: NaiveDateTime,
+    ) -> Cents {
+        let net = spot as f64 - self.present_value(risk_free_rate, now, expires_at);
+        return (net * (risk_free_rate * years_until(expires_at, now)).exp()).round() as Cents;
+    }
+
+    /**
+     * The continuous yield giving the same forward, for models that only take a yield.
+     */
+    pub fn equivalent_yield(
+        &self,
+        spot: Cents,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+        expires_at: NaiveDateTime,
+    ) -> f64 {
+        let t = years_until(expires_at, now);
+        if t <= 0.0 {
+            return 0.0;
+        }
+        let net = spot as f64 - self.present_value(risk_free_rate, now, expires_at);
+        return -(net / spot as f64).ln() / t;
+    }
+
+    /**
+     * The dividends before expiration for a `LatticePricer`, timed in years from `now`.
+     */
+    pub fn lattice_dividends(&self, now: NaiveDateTime, expires_at: NaiveDateTime) -> Dividends {
+        return Dividends::Cash(
+            self.between(now, expires_at)
+                .map(|(ex_date, amount)| (years_until(*ex_date, now), *amount))
+                .collect(),
+        );
+    }
+}
+
+impl OptionsByExpiryDate {
+    /**
+     * Forward price from the dividend schedule rather than put-call parity.
+     */
+    pub fn forward_price_with_dividends(
+        &self,
+        spot: Cents,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+        dividends: &DividendSchedule,
+    ) -> Cents {
+        return dividends.forward_price(spot, risk_free_rate, now, self.expires_at);
+    }
+}
@@ -0,0 +1,146 @@
+//! Early-exercise premium estimates for American-style chains.
+//!
+//! The VIX machinery treats every quote as European. For American chains that is only safe where
+//! the early-exercise premium is small relative to the mark, which these estimates make visible.
+//! Contracts already past their exercise boundary are the ones a short holder should expect to
+//! be assigned on.
+
+use crate::math::implied_volatility;
+use crate::pricing::{Dividends, Lattice, LatticePricer};
+use crate::{Cents, ExerciseStyle, OptionContract, OptionsByExpiryDate, Percentage, Settlement};
+use chrono::prelude::*;
+
+/**
+ * Number of binomial steps used to value the American contract.
+ */
+pub(crate) const BINOMIAL_STEPS: usize = 200;
+
+/**
+ * Early-exercise premium of a single contract.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct EarlyExercisePremium {
+    pub contract: OptionContract,
+    /// Volatility implied by the mark, treating the contract as European.
+    pub implied_volatility: Percentage,
+    /// European value at the implied volatility; this is the mark itself.
+    pub european: f64,
+    /// American value at the implied volatility, in cents.
+    pub american: f64,
+    /// `american - european`, in cents.
+    pub premium: f64,
+}
+
+/**
+ * Estimates the early-exercise premium of every quoted contract in the expiry.
+ *
+ * Cash-settled contracts are index options, which are European-style, so they carry no premium.
+ *
+ * The dividend yield is backed out of the implied forward price and `spot`, so the estimate
+ * is consistent with the same forward the index uses. Contracts whose marks do not imply a
+ * volatility are omitted.
+ */
+pub fn early_exercise_premiums(
+    options: &OptionsByExpiryDate,
+    spot: Cents,
+    risk_free_rate: f64,
+    now: NaiveDateTime,
+) -> Vec<EarlyExercisePremium> {
+    let t = options.time_to_expiration(now);
+    let forward = options.forward_price(risk_free_rate, now) as f64;
+    if t <= 0.0 || forward <= 0.0 || spot <= 0 {
+        return vec![];
+    }
+    let discount = (-risk_free_rate * t).exp();
+    let dividend_yield = options.implied_dividend_yield(spot, risk_free_rate, now);
+    let lattice = LatticePricer::new(Lattice::Binomial, BINOMIAL_STEPS)
+        .with_exercise(ExerciseStyle::American)
+        .with_dividends(Dividends::Yield(dividend_yield));
+
+    return options
+        .calls
+        .iter()
+        .chain(options.puts.iter())
+        .filter(|o| o.bid != 0)
+        .flat_map(|o| -> Option<EarlyExercisePremium> {
+            let european = o.mark() as f64;
+            let vol = implied_volatility(o.kind, european, forward, o.strike as f64, t, discount)?;
+            if o.settlement == Settlement::Cash {
+                return Some(EarlyExercisePremium {
+                    contract: *o,
+                    implied_volatility: vol,
+                    european,
+                    american: european,
+                    premium: 0.0,
+                });
+            }
+            let american =
+                lattice.value(o.kind, spot as f64, o.strike as f64, risk_free_rate, vol, t);
+            return Some(EarlyExercisePremium {
+                contract: *o,
+                implied_volatility: vol,
+                european,
+                american,
+                premium: american - european,
+            });
+        })
+        .collect();
+}
+
+/**
+ * A contract that its holder would do better to exercise now than to hold.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct EarlyAssignmentCandidate {
+    pub contract: OptionContract,
+    /// Volatility implied by the mark, treating the contract as European.
+    pub implied_volatility: Percentage,
+    /// Price beyond which exercising now is optimal, in cents: the spot is at or above it for a
+    /// call and at or below it for a put.
+    pub critical_price: f64,
+}
+
+/**
+ * The quoted contracts in the expiry whose spot is past their early-exercise boundary now, by
+ * a binomial tree at the volatility implied by each mark.
+ *
+ * As with `early_exercise_premiums`, cash-settled contracts are skipped and the dividend yield
+ * is backed out of the implied forward price.
+ */
+pub fn early_assignment_candidates(
+    options: &OptionsByExpiryDate,
+    spot: Cents,
+    risk_free_rate: f64,
+    now: NaiveDateTime,
+) -> Vec<EarlyAssignmentCandidate> {
+    let t = options.time_to_expiration(now);
+    let forward = options.forward_price(risk_free_rate, now) as f64;
+    if t <= 0.0 || forward <= 0.0 || spot <= 0 {
+        return vec![];
+    }
+    let discount = (-risk_free_rate * t).exp();
+    let dividend_yield = options.implied_dividend_yield(spot, risk_free_rate, now);
+    let lattice = LatticePricer::new(Lattice::Binomial, BINOMIAL_STEPS)
+        .with_dividends(Dividends::Yield(dividend_yield));
+
+    return options
+        .calls
+        .iter()
+        .chain(options.puts.iter())
+        .filter(|o| o.bid != 0 && o.settlement != Settlement::Cash)
+        .flat_map(|o| -> Option<EarlyAssignmentCandidate> {
+            let mark = o.mark() as f64;
+            let vol = implied_volatility(o.kind, mark, forward, o.strike as f64, t, discount)?;
+            let boundary =
+                lattice.exercise_boundary(o.kind, spot, o.strike, risk_free_rate, vol, t);
+            if !boundary.should_exercise(spot, 0.0) {
+                return None;
+            }
+            return Some(EarlyAssignmentCandidate {
+                contract: *o,
+                implied_volatility: vol,
+                critical_price: boundary.critical_price(0.0)?,
+            });
+        })
+        .collect();
+}
@@ -0,0 +1,112 @@
+//! Implied probabilities of binary events.
+//!
+//! Ahead of a binary event (an FDA decision, an election) the risk-neutral density is bimodal.
+//! Fitting it as a mixture of two lognormals recovers the implied probability of each outcome
+//! and the move the market expects on each.
+
+use crate::analytics::ExpiryAnalytics;
+use crate::math::{black_price, nelder_mead};
+use crate::skew::atm_vol;
+use crate::OptionKind;
+
+/**
+ * The two outcomes of a binary event, as implied by an expiry's quotes.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct BinaryEvent {
+    /// Probability of the up outcome; the down outcome has `1 - probability`.
+    pub probability: f64,
+    /// Expected return of each outcome relative to the forward price.
+    pub up_move: f64,
+    pub down_move: f64,
+    /// Volatility around each outcome.
+    pub up_volatility: f64,
+    pub down_volatility: f64,
+    /// Root mean squared pricing error of the fit, in spreads.
+    pub error: f64,
+}
+
+/**
+ * Fits a two-lognormal mixture to the out-of-the-money quotes of an expiry that spans the
+ * event. The mixture is constrained to the expiry's forward price, so only the split between
+ * the outcomes is free. `None` if there are too few quotes to fit.
+ */
+pub fn fit_binary_event(expiry: &ExpiryAnalytics) -> Option<BinaryEvent> {
+    let forward = expiry.forward_price as f64;
+    let t = expiry.time_to_expiration;
+    let quotes: Vec<(OptionKind, f64, f64, f64)> = expiry
+        .contracts
+        .iter()
+        .filter(|c| c.liquidity.quoted)
+        .filter(|c| match c.contract.kind() {
+            OptionKind::Call => c.moneyness >= 1.0,
+            OptionKind::Put => c.moneyness < 1.0,
+        })
+        .map(|c| {
+            (
+                c.contract.kind(),
+                c.contract.strike() as f64,
+                c.contract.mark() as f64,
+                c.liquidity.spread.max(1) as f64,
+            )
+        })
+        .collect();
+    if quotes.len() < 5 || forward <= 0.0 || t <= 0.0 {
+        return None;
+    }
+
+    // x = [logit(p), ln(F_up / F), ln(vol_up), ln(vol_down)]
+    let unpack = |x: &[f64]| -> Option<(f64, f64, f64, f64, f64)> {
+        let p = 1.0 / (1.0 + (-x[0]).exp());
+        let up = forward * x[1].exp();
+        let down = (forward - p * up) / (1.0 - p);
+        if down <= 0.0 || p <= 0.0 || p >= 1.0 {
+            return None;
+        }
+        return Some((p, up, down, x[2].exp(), x[3].exp()));
+    };
+    let objective = |x: &[f64]| -> f64 {
+        let (p, up, down, up_vol, down_vol) = match unpack(x) {
+            Some(params) => params,
+            None => return f64::MAX,
+        };
+        return quotes
+            .iter()
+            .map(|(kind, strike, mark, spread)| {
+                let price = p * black_price(*kind, up, *strike, up_vol, t, expiry.discount_factor)
+                    + (1.0 - p)
+                        * black_price(*kind, down, *strike, down_vol, t, expiry.discount_factor);
+                return ((price - mark) / spread).powi(2);
+            })
+            .sum::<f64>();
+    };
+
+    let vol = atm_vol(expiry).unwrap_or(0.5);
+    let (best, fit) = [-1.0, 0.0, 1.0]
+        .iter()
+        .map(|logit| {
+            let x0 = [*logit, vol * t.sqrt(), (vol / 2.0).ln(), (vol / 2.0).ln()];
+            let x = nelder_mead(objective, &x0, 0.1, 2000, 1e-12);
+            let fit = objective(&x);
+            return (x, fit);
+        })
+        .filter(|(_, fit)| fit.is_finite())
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))?;
+
+    let (p, up, down, up_vol, down_vol) = unpack(&best)?;
+    let error = (fit / quotes.len() as f64).sqrt();
+    // label the outcome with the higher forward as "up"
+    let (probability, up, down, up_volatility, down_volatility) = if up >= down {
+        (p, up, down, up_vol, down_vol)
+    } else {
+        (1.0 - p, down, up, down_vol, up_vol)
+    };
+    return Some(BinaryEvent {
+        probability,
+        up_move: up / forward - 1.0,
+        down_move: down / forward - 1.0,
+        up_volatility,
+        down_volatility,
+        error,
+    });
+}
@@ -0,0 +1,113 @@
+//! Exercise and assignment of positions held into expiration.
+
+use crate::strategy::{Leg, Strategy};
+use crate::{Cents, OptionKind, Settlement};
+use chrono::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    /// A long contract finished in the money and was exercised.
+    Exercised,
+    /// A short contract finished in the money and was assigned.
+    Assigned,
+    /// The contract finished out of the money.
+    Expired,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct LegExpiration {
+    pub leg: Leg,
+    pub outcome: Outcome,
+    /// Cash received (negative if paid) on settlement, in cents.
+    pub cash: f64,
+    /// Shares of the underlying received (negative if delivered).
+    pub shares: i64,
+    /// Settlement value less the premium paid to open the leg, in cents.
+    pub realized_pnl: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExpirationResult {
+    pub legs: Vec<LegExpiration>,
+    /// Legs that have not expired yet.
+    pub open_legs: Vec<Leg>,
+    pub cash: f64,
+    pub shares: i64,
+    pub realized_pnl: f64,
+}
+
+/**
+ * Settles every leg of `strategy` expiring at or before `now` against `settlement_price`,
+ * according to each contract's settlement type.
+ *
+ * Contracts in the money by at least a cent are exercised or assigned, as with the OCC's
+ * exercise by exception. Physically delivered shares are valued at the settlement price in the
+ * realized P&L.
+ */
+pub fn process_expiration(
+    strategy: &Strategy,
+    settlement_price: Cents,
+    now: NaiveDateTime,
+) -> ExpirationResult {
+    let (expiring, open_legs): (Vec<Leg>, Vec<Leg>) = strategy
+        .legs
+        .iter()
+        .partition(|l| l.contract.expires_at() <= now);
+
+    let legs: Vec<LegExpiration> = expiring
+        .into_iter()
+        .map(|leg| settle(leg, settlement_price))
+        .collect();
+
+    return ExpirationResult {
+        cash: legs.iter().map(|l| l.cash).sum(),
+        shares: legs.iter().map(|l| l.shares).sum(),
+        realized_pnl: legs.iter().map(|l| l.realized_pnl).sum(),
+        legs,
+        open_legs,
+    };
+}
+
+fn settle(leg: Leg, settlement_price: Cents) -> LegExpiration {
+    let strike = leg.contract.strike();
+    let intrinsic = match leg.contract.kind() {
+        OptionKind::Call => settlement_price - strike,
+        OptionKind::Put => strike - settlement_price,
+    };
+    let premium = (leg.quantity * leg.contract.mark()) as f64;
+
+    if intrinsic < 1 || leg.quantity == 0 {
+        return LegExpiration {
+            leg,
+            outcome: Outcome::Expired,
+            cash: 0.0,
+            shares: 0,
+            realized_pnl: -premium,
+        };
+    }
+
+    let outcome = if leg.quantity > 0 {
+        Outcome::Exercised
+    } else {
+        Outcome::Assigned
+    };
+    let (cash, shares) = match leg.contract.settlement() {
+        // inverse options pay the same value in the underlying
+        Settlement::Cash | Settlement::Inverse => ((leg.quantity * intrinsic) as f64, 0),
+        Settlement::Physical => {
+            // calls buy the underlying at the strike, puts sell it
+            let bought = match leg.contract.kind() {
+                OptionKind::Call => leg.quantity,
+                OptionKind::Put => -leg.quantity,
+            };
+            (-(bought * strike) as f64, bought)
+        }
+    };
+    return LegExpiration {
+        leg,
+        outcome,
+        cash,
+        shares,
+        realized_pnl: cash + (shares * settlement_price) as f64 - premium,
+    };
+}
@@ -0,0 +1,107 @@
+//! P&L explain of a position between two snapshots.
+//!
+//! The change in the position's marked value is split into what the Greeks at the first
+//! snapshot attribute to the moves actually observed: the underlying, each contract's own
+//! implied volatility, the time that passed, and each expiry's rate. Whatever they miss (higher
+//! order terms, dividend changes, quotes moving within their spread) is left as unexplained.
+
+use crate::analytics::{ChainAnalytics, ContractAnalytics, ExpiryAnalytics};
+use crate::pricing::years_until;
+use crate::strategy::Strategy;
+use crate::OptionKind;
+use chrono::prelude::*;
+
+/**
+ * Change in a position's value by source, in cents.
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PnlExplain {
+    /// Change in the marked value of the position.
+    pub total: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    /// From changes in implied volatility, by expiry in order.
+    pub vega: Vec<(NaiveDateTime, f64)>,
+    pub theta: f64,
+    pub rates: f64,
+    pub unexplained: f64,
+}
+
+impl PnlExplain {
+    pub fn vega_total(&self) -> f64 {
+        return self.vega.iter().map(|(_, pnl)| pnl).sum();
+    }
+
+    /**
+     * Everything but the unexplained residual.
+     */
+    pub fn explained(&self) -> f64 {
+        return self.delta + self.gamma + self.vega_total() + self.theta + self.rates;
+    }
+}
+
+/**
+ * Explains the change in value of `strategy` from `before` to `after`. Contracts that expired in
+ * between are valued at their intrinsic value against the later spot. Legs missing from
+ * `before`, or from `after` before expiring, are left out; a leg without Greeks at `before`
+ * contributes only to the unexplained residual.
+ */
+pub fn explain_pnl(
+    strategy: &Strategy,
+    before: &ChainAnalytics,
+    after: &ChainAnalytics,
+) -> PnlExplain {
+    let mut explain = PnlExplain::default();
+    let spot_move = (after.spot - before.spot) as f64 / 100.0;
+    let elapsed = years_until(after.now, before.now);
+    for leg in strategy.legs.iter() {
+        let quantity = leg.quantity as f64;
+        let (expiry, start) = match before.find(&leg.contract) {
+            Some(found) => found,
+            None => continue,
+        };
+        let end = after.find(&leg.contract);
+        let value_after = match end {
+            Some((_, end)) => end.contract.mark() as f64,
+            None if after.now >= leg.contract.expires_at => {
+                let (spot, strike) = (after.spot as f64, leg.contract.strike as f64);
+                match leg.contract.kind {
+                    OptionKind::Call => (spot - strike).max(0.0),
+                    OptionKind::Put => (strike - spot).max(0.0),
+                }
+            }
+            None => continue,
+        };
+        explain.total += quantity * (value_after - start.contract.mark() as f64);
+
+        let greeks = match start.greeks {
+            Some(greeks) => greeks,
+            None => continue,
+        };
+        // Greeks are in dollars
+        explain.delta += quantity * greeks.delta * spot_move * 100.0;
+        explain.gamma += quantity * 0.5 * greeks.gamma * spot_move * spot_move * 100.0;
+        explain.theta += quantity * greeks.theta * elapsed.min(expiry.time_to_expiration) * 100.0;
+        if let Some((end_expiry, end)) = end {
+            explain.rates +=
+                quantity * greeks.rho * (end_expiry.risk_free_rate - expiry.risk_free_rate) * 100.0;
+            if let Some(vol_move) = vol_move(start, end) {
+                let pnl = quantity * greeks.vega * vol_move * 100.0;
+                add_to_bucket(&mut explain.vega, expiry, pnl);
+            }
+        }
+    }
+    explain.unexplained = explain.total - explain.explained();
+    return explain;
+}
+
+fn vol_move(start: &ContractAnalytics, end: &ContractAnalytics) -> Option<f64> {
+    return Some(end.implied_volatility? - start.implied_volatility?);
+}
+
+fn add_to_bucket(buckets: &mut Vec<(NaiveDateTime, f64)>, expiry: &ExpiryAnalytics, pnl: f64) {
+    match buckets.binary_search_by_key(&expiry.expires_at, |(at, _)| *at) {
+        Ok(i) => buckets[i].1 += pnl,
+        Err(i) => buckets.insert(i, (expiry.expires_at, pnl)),
+    }
+}
@@ -0,0 +1,109 @@
+//! CSV export of chain analytics.
+//!
+//! Writes one row per contract with its quote and everything `Chain::analytics` computed for it,
+//! for pipelines that pass chains around as files. The columns are fixed by `CSV_COLUMNS` and
+//! only ever appended to.
+
+use crate::analytics::{ChainAnalytics, ContractAnalytics, ExpiryAnalytics};
+use crate::greeks::Greeks;
+use crate::{OptionKind, VolatilityModel};
+use std::io::{self, Write};
+
+/**
+ * Header of the exported CSV. Prices are in cents, rates and lognormal volatilities are
+ * fractions, normal volatilities are in cents per square root year, and expirations are
+ * `YYYY-MM-DDTHH:MM:SS`. `quoted` is `true` or `false`, `liquidity_score` is
+ * `Liquidity::score`, and `early_exercise_premium` is in cents. Fields that could not be computed are empty.
+ */
+pub const CSV_COLUMNS: [&str; 22] = [
+    "expiration",
+    "time_to_expiration",
+    "kind",
+    "strike",
+    "bid",
+    "ask",
+    "mark",
+    "forward_price",
+    "risk_free_rate",
+    "moneyness",
+    "implied_volatility",
+    "delta",
+    "gamma",
+    "theta",
+    "vega",
+    "rho",
+    "spread",
+    "relative_spread",
+    "volatility_model",
+    "quoted",
+    "liquidity_score",
+    "early_exercise_premium",
+];
+
+impl ChainAnalytics {
+    /**
+     * Writes the analytics of every contract as CSV, with a header, ordered by expiration and then
+     * as the contracts appear in each expiry.
+     */
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{}", CSV_COLUMNS.join(","))?;
+        for expiry in self.expiries.iter() {
+            for contract in expiry.contracts.iter() {
+                writeln!(writer, "{}", csv_row(expiry, contract).join(","))?;
+            }
+        }
+        return Ok(());
+    }
+
+    /**
+     * The CSV written by `write_csv`, as a string.
+     */
+    pub fn to_csv(&self) -> String {
+        let mut buf = Vec::new();
+        self.write_csv(&mut buf)
+            .expect("writing to a Vec cannot fail");
+        return String::from_utf8(buf).expect("the CSV is ASCII");
+    }
+}
+
+fn csv_row(expiry: &ExpiryAnalytics, c: &ContractAnalytics) -> Vec<String> {
+    let optional = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+    let greek = |f: fn(&Greeks) -> f64| optional(c.greeks.as_ref().map(f));
+    let contract = &c.contract;
+    return vec![
+        expiry.expires_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        expiry.time_to_expiration.to_string(),
+        match contract.kind {
+            OptionKind::Call => "call".to_string(),
+            OptionKind::Put => "put".to_string(),
+        },
+        contract.strike.to_string(),
+        contract.bid.to_string(),
+        contract.ask.to_string(),
+        contract.mark().to_string(),
+        expiry.forward_price.to_string(),
+        expiry.risk_free_rate.to_string(),
+        c.moneyness.to_string(),
+        optional(c.implied_volatility),
+        greek(|g| g.delta),
+        greek(|g| g.gamma),
+        greek(|g| g.theta),
+        greek(|g| g.vega),
+        greek(|g| g.rho),
+        c.liquidity.spread.to_string(),
+        if c.liquidity.relative_spread.is_finite() {
+            c.liquidity.relative_spread.to_string()
+        } else {
+            String::new()
+        },
+        match contract.volatility_model {
+            VolatilityModel::Lognormal => "lognormal".to_string(),
+            VolatilityModel::Normal => "normal".to_string(),
+        },
+        c.liquidity.quoted.to_string(),
+        c.liquidity.score().to_string(),
+        c.early_exercise_premium
+            .map(|premium| premium.to_string())
+            .unwrap_or_default(),
+    ];
+}
@@ -0,0 +1,124 @@
+//! Anonymized fixtures from real chains.
+//!
+//! Vendor data usually cannot be redistributed, which keeps real chains out of bug reports and
+//! out of this crate's tests. An `Anonymizer` disguises a chain while keeping what the math
+//! cares about: every price and strike is scaled by the same factor, so moneyness and implied
+//! volatilities are unchanged; dates move by whole weeks, so expirations keep their weekday and
+//! times to expiration are unchanged; and quotes get a little noise, so the prices are no longer
+//! the vendor's.
+
+use crate::chain::Chain;
+use crate::io::jsonl::Record;
+use crate::math::Rng;
+use crate::{Cents, OptionContract};
+use chrono::prelude::*;
+use chrono::Duration;
+
+/**
+ * A disguised chain and underlying.
+ */
+#[derive(Clone, Debug)]
+pub struct Fixture {
+    pub at: NaiveDateTime,
+    pub spot: Cents,
+    pub chain: Chain,
+}
+
+impl Fixture {
+    /**
+     * The fixture as a JSON Lines snapshot, for `io::jsonl`.
+     */
+    pub fn record(&self) -> Record {
+        return Record::Snapshot {
+            at: self.at,
+            chain: self.chain.clone(),
+        };
+    }
+}
+
+/**
+ * How a chain is disguised.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Anonymizer {
+    /// Every price and strike is multiplied by this.
+    pub scale: f64,
+    /// Weeks every date is moved by, negative for earlier.
+    pub shift_weeks: i64,
+    /// Half-width of the uniform noise applied to each mark, as a fraction of it. Spreads are
+    /// kept, and unquoted bids stay zero.
+    pub noise: f64,
+    /// Quotes are rounded to multiples of this, in cents after scaling.
+    pub tick: Cents,
+    pub seed: u64,
+}
+
+impl Anonymizer {
+    /**
+     * An anonymizer with a scale between one half and two and a shift between one and ten years
+     * back, both drawn from `seed`, and noise of half a percent.
+     */
+    pub fn random(seed: u64) -> Anonymizer {
+        let mut rng = Rng::new(seed);
+        let scale = 0.5 * 4f64.powf(rng.next_f64());
+        let shift_weeks = -(52 + (rng.next_u64() % (52 * 9)) as i64);
+        return Anonymizer {
+            scale,
+            shift_weeks,
+            noise: 0.005,
+            tick: 1,
+            seed,
+        };
+    }
+
+    pub fn price(&self, price: Cents) -> Cents {
+        return (price as f64 * self.scale).round() as Cents;
+    }
+
+    pub fn time(&self, at: NaiveDateTime) -> NaiveDateTime {
+        return at + Duration::weeks(self.shift_weeks);
+    }
+
+    /**
+     * `options`, quoted as of `at` against `spot`, disguised. The same anonymizer always gives
+     * the same fixture.
+     */
+    pub fn anonymize(&self, options: &[OptionContract], spot: Cents, at: NaiveDateTime) -> Fixture {
+        let mut rng = Rng::new(self.seed);
+        let anonymized: Vec<OptionContract> = options
+            .iter()
+            .map(|o| self.contract(*o, &mut rng))
+            .collect();
+        return Fixture {
+            at: self.time(at),
+            spot: self.price(spot),
+            chain: Chain::new(&anonymized),
+        };
+    }
+
+    fn contract(&self, contract: OptionContract, rng: &mut Rng) -> OptionContract {
+        let tick = self.tick.max(1) as f64;
+        let round = |price: f64| -> Cents { (price / tick).round() as Cents * tick as Cents };
+        let jitter = 1.0 + self.noise * (2.0 * rng.next_f64() - 1.0);
+        let half_spread = (contract.ask - contract.bid).max(0) as f64 * self.scale / 2.0;
+        let mark = contract.mark() as f64 * self.scale * jitter;
+        let (bid, ask) = if contract.bid == 0 {
+            (0, round(contract.ask as f64 * self.scale * jitter))
+        } else {
+            // quoted bids stay quoted
+            let bid = round(mark - half_spread).max(tick as Cents);
+            (bid, round(mark + half_spread).max(bid))
+        };
+        let anonymized = OptionContract {
+            expires_at: self.time(contract.expires_at),
+            strike: self.price(contract.strike),
+            bid,
+            ask,
+            ..contract
+        };
+        return match contract.quoted_at {
+            Some(quoted_at) => anonymized.with_quoted_at(self.time(quoted_at)),
+            None => anonymized,
+        };
+    }
+}
@@ -0,0 +1,337 @@
+//! Black–Scholes Greeks.
+
+use crate::math::{norm_cdf, norm_pdf};
+use crate::pricing::years_until;
+use crate::{Cents, OptionContract, OptionKind, OptionsByExpiryDate};
+use chrono::prelude::*;
+
+/**
+ * First-order sensitivities of an option's price.
+ *
+ * Prices are in dollars, so `gamma` is per dollar move of the underlying, `vega` and `rho` are
+ * per unit (100 percentage points) change in volatility and rate, and `theta` is per year.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Greeks {
+    pub delta: f64,
+    pub gamma: f64,
+    pub theta: f64,
+    pub vega: f64,
+    pub rho: f64,
+}
+
+/**
+ * Black–Scholes Greeks with a continuous dividend yield. Prices are in dollars and `t` is in
+ * years.
+ */
+pub fn black_scholes_greeks(
+    kind: OptionKind,
+    spot: f64,
+    strike: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    t: f64,
+) -> Greeks {
+    if t <= 0.0 || volatility <= 0.0 || spot <= 0.0 || strike <= 0.0 {
+        let in_the_money = match kind {
+            OptionKind::Call => spot > strike,
+            OptionKind::Put => spot < strike,
+        };
+        let delta = match (kind, in_the_money) {
+            (OptionKind::Call, true) => 1.0,
+            (OptionKind::Put, true) => -1.0,
+            _ => 0.0,
+        };
+        return Greeks {
+            delta,
+            ..Greeks::default()
+        };
+    }
+
+    let sqrt_t = t.sqrt();
+    let d1 = ((spot / strike).ln()
+        + (risk_free_rate - dividend_yield + 0.5 * volatility * volatility) * t)
+        / (volatility * sqrt_t);
+    let d2 = d1 - volatility * sqrt_t;
+    let dividend_discount = (-dividend_yield * t).exp();
+    let discount = (-risk_free_rate * t).exp();
+
+    let gamma = dividend_discount * norm_pdf(d1) / (spot * volatility * sqrt_t);
+    let vega = spot * dividend_discount * norm_pdf(d1) * sqrt_t;
+    let decay = -spot * dividend_discount * norm_pdf(d1) * volatility / (2.0 * sqrt_t);
+
+    return match kind {
+        OptionKind::Call => Greeks {
+            delta: dividend_discount * norm_cdf(d1),
+            gamma,
+            theta: decay - risk_free_rate * strike * discount * norm_cdf(d2)
+                + dividend_yield * spot * dividend_discount * norm_cdf(d1),
+            vega,
+            rho: strike * t * discount * norm_cdf(d2),
+        },
+        OptionKind::Put => Greeks {
+            delta: -dividend_discount * norm_cdf(-d1),
+            gamma,
+            theta: decay + risk_free_rate * strike * discount * norm_cdf(-d2)
+                - dividend_yield * spot * dividend_discount * norm_cdf(-d1),
+            vega,
+            rho: -strike * t * discount * norm_cdf(-d2),
+        },
+    };
+}
+
+/**
+ * Second-order sensitivities, for hedging skew and volatility of volatility.
+ *
+ * In the same units as `Greeks`: `vanna` is the change in delta per unit change in volatility
+ * (equally, in vega per dollar move of the underlying), `volga` the change in vega per unit
+ * change in volatility, and `charm` the change in delta per year as time passes.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SecondOrderGreeks {
+    pub vanna: f64,
+    pub volga: f64,
+    pub charm: f64,
+}
+
+/**
+ * Black–Scholes second-order Greeks with a continuous dividend yield. Prices are in dollars and
+ * `t` is in years.
+ */
+pub fn black_scholes_second_order_greeks(
+    kind: OptionKind,
+    spot: f64,
+    strike: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    t: f64,
+) -> SecondOrderGreeks {
+    if t <= 0.0 || volatility <= 0.0 || spot <= 0.0 || strike <= 0.0 {
+        return SecondOrderGreeks::default();
+    }
+
+    let sqrt_t = t.sqrt();
+    let d1 = ((spot / strike).ln()
+        + (risk_free_rate - dividend_yield + 0.5 * volatility * volatility) * t)
+        / (volatility * sqrt_t);
+    let d2 = d1 - volatility * sqrt_t;
+    let dividend_discount = (-dividend_yield * t).exp();
+    let density = dividend_discount * norm_pdf(d1);
+
+    let vega = spot * density * sqrt_t;
+    let drift = density * (2.0 * (risk_free_rate - dividend_yield) * t - d2 * volatility * sqrt_t)
+        / (2.0 * t * volatility * sqrt_t);
+    return SecondOrderGreeks {
+        vanna: -density * d2 / volatility,
+        volga: vega * d1 * d2 / volatility,
+        charm: match kind {
+            OptionKind::Call => dividend_yield * dividend_discount * norm_cdf(d1) - drift,
+            OptionKind::Put => -dividend_yield * dividend_discount * norm_cdf(-d1) - drift,
+        },
+    };
+}
+
+/**
+ * Third-order sensitivities, for market-making risk reports.
+ *
+ * In the same units as `Greeks`: `speed` is the change in gamma per dollar move of the
+ * underlying, `zomma` the change in gamma per unit change in volatility, `color` the change in
+ * gamma per year as time passes, and `ultima` the change in volga per unit change in
+ * volatility.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ThirdOrderGreeks {
+    pub speed: f64,
+    pub zomma: f64,
+    pub color: f64,
+    pub ultima: f64,
+}
+
+/**
+ * How many orders of Greeks to compute. Each order costs more than the last and most users only
+ * need the first.
+ */
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Default)]
+pub enum GreeksDepth {
+    #[default]
+    First,
+    Second,
+    Third,
+}
+
+/**
+ * Greeks up to some `GreeksDepth`; orders beyond it are `None`.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GreeksReport {
+    pub first: Greeks,
+    pub second: Option<SecondOrderGreeks>,
+    pub third: Option<ThirdOrderGreeks>,
+}
+
+/**
+ * Black–Scholes third-order Greeks with a continuous dividend yield. Prices are in dollars and
+ * `t` is in years. The same for calls and puts.
+ */
+pub fn black_scholes_third_order_greeks(
+    spot: f64,
+    strike: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    t: f64,
+) -> ThirdOrderGreeks {
+    if t <= 0.0 || volatility <= 0.0 || spot <= 0.0 || strike <= 0.0 {
+        return ThirdOrderGreeks::default();
+    }
+
+    let sqrt_t = t.sqrt();
+    let vol_sqrt_t = volatility * sqrt_t;
+    let d1 = ((spot / strike).ln()
+        + (risk_free_rate - dividend_yield + 0.5 * volatility * volatility) * t)
+        / vol_sqrt_t;
+    let d2 = d1 - vol_sqrt_t;
+    let density = (-dividend_yield * t).exp() * norm_pdf(d1);
+
+    let gamma = density / (spot * vol_sqrt_t);
+    let vega = spot * density * sqrt_t;
+    let drift = (2.0 * (risk_free_rate - dividend_yield) * t - d2 * vol_sqrt_t) / vol_sqrt_t;
+    return ThirdOrderGreeks {
+        speed: -gamma / spot * (d1 / vol_sqrt_t + 1.0),
+        zomma: gamma * (d1 * d2 - 1.0) / volatility,
+        color: density / (2.0 * spot * t * vol_sqrt_t)
+            * (2.0 * dividend_yield * t + 1.0 + drift * d1),
+        ultima: -vega / (volatility * volatility) * (d1 * d2 * (1.0 - d1 * d2) + d1 * d1 + d2 * d2),
+    };
+}
+
+/**
+ * Black–Scholes Greeks up to `depth`. Prices are in dollars and `t` is in years.
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn black_scholes_greeks_to(
+    depth: GreeksDepth,
+    kind: OptionKind,
+    spot: f64,
+    strike: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    t: f64,
+) -> GreeksReport {
+    let (r, q) = (risk_free_rate, dividend_yield);
+    return GreeksReport {
+        first: black_scholes_greeks(kind, spot, strike, r, q, volatility, t),
+        second: if depth >= GreeksDepth::Second {
+            Some(black_scholes_second_order_greeks(
+                kind, spot, strike, r, q, volatility, t,
+            ))
+        } else {
+            None
+        },
+        third: if depth >= GreeksDepth::Third {
+            Some(black_scholes_third_order_greeks(
+                spot, strike, r, q, volatility, t,
+            ))
+        } else {
+            None
+        },
+    };
+}
+
+impl OptionContract {
+    /**
+     * Black–Scholes Greeks of the contract as of `now`, treating it as European.
+     */
+    pub fn greeks(
+        self,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        now: NaiveDateTime,
+    ) -> Greeks {
+        return black_scholes_greeks(
+            self.kind,
+            spot as f64 / 100.0,
+            self.strike as f64 / 100.0,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            years_until(self.expires_at, now),
+        );
+    }
+
+    /**
+     * Black–Scholes Greeks of the contract up to `depth` as of `now`, treating it as European.
+     */
+    pub fn greeks_to(
+        self,
+        depth: GreeksDepth,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        now: NaiveDateTime,
+    ) -> GreeksReport {
+        return black_scholes_greeks_to(
+            depth,
+            self.kind,
+            spot as f64 / 100.0,
+            self.strike as f64 / 100.0,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            years_until(self.expires_at, now),
+        );
+    }
+
+    /**
+     * Black–Scholes second-order Greeks of the contract as of `now`, treating it as European.
+     */
+    pub fn second_order_greeks(
+        self,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        now: NaiveDateTime,
+    ) -> SecondOrderGreeks {
+        return black_scholes_second_order_greeks(
+            self.kind,
+            spot as f64 / 100.0,
+            self.strike as f64 / 100.0,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            years_until(self.expires_at, now),
+        );
+    }
+}
+
+impl OptionsByExpiryDate {
+    /**
+     * Black–Scholes Greeks of every call and then every put of the expiry at a single
+     * `volatility`. `Chain::analytics` gives them at each contract's own implied volatility.
+     */
+    pub fn greeks(
+        &self,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        now: NaiveDateTime,
+    ) -> Vec<(OptionContract, Greeks)> {
+        return self
+            .calls
+            .iter()
+            .chain(self.puts.iter())
+            .map(|o| {
+                let greeks = o.greeks(spot, risk_free_rate, dividend_yield, volatility, now);
+                return (*o, greeks);
+            })
+            .collect();
+    }
+}
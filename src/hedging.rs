@@ -0,0 +1,87 @@
+//! Minimum-variance hedge ratios.
+//!
+//! Black–Scholes delta assumes volatility does not move with the underlying. In practice implied
+//! volatility tends to fall as the underlying rises, so the delta that minimizes the variance of
+//! a hedged position includes the vega exposure to that expected volatility move (Hull and White,
+//! "Optimal delta hedging for options", 2017).
+
+use crate::analytics::ExpiryAnalytics;
+use crate::greeks::Greeks;
+use crate::returns::simple_log_returns;
+use crate::OptionContract;
+
+/**
+ * Expected change in implied volatility per unit log return of the underlying.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpotVolSensitivity(pub f64);
+
+impl SpotVolSensitivity {
+    /**
+     * From the correlation between the underlying's returns and changes in implied volatility,
+     * the volatility of implied volatility, and the volatility of the underlying (all annualized).
+     */
+    pub fn from_correlation(
+        correlation: f64,
+        vol_of_vol: f64,
+        spot_vol: f64,
+    ) -> SpotVolSensitivity {
+        return SpotVolSensitivity(correlation * vol_of_vol / spot_vol);
+    }
+
+    /**
+     * Estimates the sensitivity by regressing changes in implied volatility on log returns, from
+     * aligned histories of the underlying price and an implied volatility (e.g. ATM). `None` if
+     * there are fewer than three observations or the underlying never moves.
+     */
+    pub fn estimate(spots: &[f64], implied_vols: &[f64]) -> Option<SpotVolSensitivity> {
+        let changes: Vec<(f64, f64)> = simple_log_returns(spots)
+            .into_iter()
+            .zip(implied_vols.windows(2))
+            .flat_map(|(r, v)| Some((r?, v[1] - v[0])))
+            .collect();
+        if changes.len() < 2 {
+            return None;
+        }
+        let n = changes.len() as f64;
+        let mean_return = changes.iter().map(|c| c.0).sum::<f64>() / n;
+        let mean_change = changes.iter().map(|c| c.1).sum::<f64>() / n;
+        let covariance: f64 = changes
+            .iter()
+            .map(|(r, v)| (r - mean_return) * (v - mean_change))
+            .sum();
+        let variance: f64 = changes.iter().map(|(r, _)| (r - mean_return).powi(2)).sum();
+        if variance <= 0.0 {
+            return None;
+        }
+        return Some(SpotVolSensitivity(covariance / variance));
+    }
+}
+
+/**
+ * Minimum-variance delta: `delta + vega * sensitivity / spot`, with `spot` in dollars to match
+ * the units of `greeks`.
+ */
+pub fn minimum_variance_delta(greeks: &Greeks, spot: f64, sensitivity: SpotVolSensitivity) -> f64 {
+    return greeks.delta + greeks.vega * sensitivity.0 / spot;
+}
+
+/**
+ * Minimum-variance deltas of every contract in the expiry that has Greeks.
+ */
+pub fn minimum_variance_deltas(
+    expiry: &ExpiryAnalytics,
+    spot: f64,
+    sensitivity: SpotVolSensitivity,
+) -> Vec<(OptionContract, f64)> {
+    return expiry
+        .contracts
+        .iter()
+        .flat_map(|c| {
+            Some((
+                c.contract,
+                minimum_variance_delta(&c.greeks?, spot, sensitivity),
+            ))
+        })
+        .collect();
+}
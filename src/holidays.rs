@@ -0,0 +1,477 @@
+//! Exchange holiday calendars.
+//!
+//! Calendars decide which days an exchange trades and for how long, which in turn decides when
+//! listed options expire and how much trading time is left until they do.
+
+use chrono::prelude::*;
+use chrono::Duration;
+use std::collections::{HashMap, HashSet};
+
+/**
+ * Trading days and hours of an exchange. Implementors only need to say which weekdays are
+ * holidays; everything else has defaults for a 9:30–16:00 session.
+ */
+pub trait HolidayCalendar {
+    /**
+     * Whether the exchange is closed on a weekday it would otherwise trade.
+     */
+    fn is_holiday(&self, date: NaiveDate) -> bool;
+
+    /**
+     * Opening time of the regular session.
+     */
+    fn open(&self, _date: NaiveDate) -> NaiveTime {
+        return NaiveTime::from_hms_opt(9, 30, 0).unwrap();
+    }
+
+    /**
+     * Closing time of the regular session, including early closes.
+     */
+    fn close(&self, _date: NaiveDate) -> NaiveTime {
+        return NaiveTime::from_hms_opt(16, 0, 0).unwrap();
+    }
+
+    /**
+     * Minutes of trading in a full year, used to annualize trading time.
+     */
+    fn trading_minutes_per_year(&self) -> f64 {
+        return 252.0 * 390.0;
+    }
+
+    fn is_trading_day(&self, date: NaiveDate) -> bool {
+        return !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.is_holiday(date);
+    }
+
+    /**
+     * The last trading day on or before `date`.
+     */
+    fn previous_trading_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut date = date;
+        while !self.is_trading_day(date) {
+            date -= Duration::days(1);
+        }
+        return date;
+    }
+
+    /**
+     * Expiration date of the first weekly expiration on or after `date`: that week's Friday, or
+     * the trading day before it if the Friday is a holiday.
+     */
+    fn next_expiration_friday(&self, date: NaiveDate) -> NaiveDate {
+        let mut friday = date
+            + Duration::days(
+                (7 + Weekday::Fri.num_days_from_monday() as i64
+                    - date.weekday().num_days_from_monday() as i64)
+                    % 7,
+            );
+        loop {
+            let expiration = self.previous_trading_day(friday);
+            if expiration >= date {
+                return expiration;
+            }
+            friday += Duration::days(7);
+        }
+    }
+
+    /**
+     * Minutes the exchange is open between `from` and `to`, or zero if `to` is not after `from`.
+     */
+    fn trading_minutes_between(&self, from: NaiveDateTime, to: NaiveDateTime) -> i64 {
+        let mut minutes = 0;
+        let mut date = from.date();
+        while date <= to.date() {
+            if self.is_trading_day(date) {
+                let open = date.and_time(self.open(date)).max(from);
+                let close = date.and_time(self.close(date)).min(to);
+                if close > open {
+                    minutes += close.signed_duration_since(open).num_minutes();
+                }
+            }
+            date += Duration::days(1);
+        }
+        return minutes;
+    }
+}
+
+/**
+ * Date of Easter Sunday in the Gregorian calendar (anonymous Gregorian algorithm).
+ */
+fn easter(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    return NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap();
+}
+
+/**
+ * The `n`th `weekday` of a month, counting from 1; negative `n` counts from the end.
+ */
+pub(crate) fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: i32) -> NaiveDate {
+    if n > 0 {
+        return NaiveDate::from_weekday_of_month_opt(year, month, weekday, n as u8).unwrap();
+    }
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    let mut date = NaiveDate::from_ymd_opt(next_year, next_month, 1).unwrap() - Duration::days(1);
+    while date.weekday() != weekday {
+        date -= Duration::days(1);
+    }
+    return date + Duration::weeks((n + 1) as i64);
+}
+
+/**
+ * Weekday a fixed-date holiday is observed on: Saturdays move to Friday and Sundays to Monday.
+ */
+fn observed(date: NaiveDate) -> NaiveDate {
+    return match date.weekday() {
+        Weekday::Sat => date - Duration::days(1),
+        Weekday::Sun => date + Duration::days(1),
+        _ => date,
+    };
+}
+
+/**
+ * New York Stock Exchange holidays and hours, including the 13:00 early closes.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Nyse;
+
+impl Nyse {
+    /**
+     * Full-day closures in `year`.
+     */
+    pub fn holidays(year: i32) -> Vec<NaiveDate> {
+        let date = |month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let mut holidays = vec![
+            nth_weekday(year, 1, Weekday::Mon, 3),
+            nth_weekday(year, 2, Weekday::Mon, 3),
+            easter(year) - Duration::days(2),
+            nth_weekday(year, 5, Weekday::Mon, -1),
+            observed(date(7, 4)),
+            nth_weekday(year, 9, Weekday::Mon, 1),
+            nth_weekday(year, 11, Weekday::Thu, 4),
+            observed(date(12, 25)),
+        ];
+        // a Saturday New Year's Day is not observed on the Friday before
+        if date(1, 1).weekday() != Weekday::Sat {
+            holidays.push(observed(date(1, 1)));
+        }
+        if year >= 2022 {
+            holidays.push(observed(date(6, 19)));
+        }
+        holidays.sort();
+        return holidays;
+    }
+
+    fn is_early_close(date: NaiveDate) -> bool {
+        let year = date.year();
+        let day = |month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let day_after_thanksgiving = nth_weekday(year, 11, Weekday::Thu, 4) + Duration::days(1);
+        return date == day_after_thanksgiving
+            || (date == day(12, 24) && date.weekday() != Weekday::Fri)
+            || (date == day(7, 3) && !matches!(date.weekday(), Weekday::Fri | Weekday::Sat));
+    }
+}
+
+impl HolidayCalendar for Nyse {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        return Nyse::holidays(date.year()).contains(&date);
+    }
+
+    fn close(&self, date: NaiveDate) -> NaiveTime {
+        if Nyse::is_early_close(date) {
+            return NaiveTime::from_hms_opt(13, 0, 0).unwrap();
+        }
+        return NaiveTime::from_hms_opt(16, 0, 0).unwrap();
+    }
+}
+
+/**
+ * Cboe Options Exchange index options: NYSE holidays, but trading until 16:15 (13:15 on early
+ * closes).
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Cboe;
+
+impl HolidayCalendar for Cboe {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        return Nyse.is_holiday(date);
+    }
+
+    fn close(&self, date: NaiveDate) -> NaiveTime {
+        return Nyse.close(date) + Duration::minutes(15);
+    }
+
+    fn trading_minutes_per_year(&self) -> f64 {
+        return 252.0 * 405.0;
+    }
+}
+
+/**
+ * Eurex: the Frankfurt exchange calendar, trading EURO STOXX 50 options from 9:00 to 17:30 CET.
+ * Holidays falling on a weekend are not moved.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Eurex;
+
+impl Eurex {
+    /**
+     * Full-day closures in `year`.
+     */
+    pub fn holidays(year: i32) -> Vec<NaiveDate> {
+        let date = |month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let easter = easter(year);
+        return vec![
+            date(1, 1),
+            easter - Duration::days(2),
+            easter + Duration::days(1),
+            date(5, 1),
+            date(12, 24),
+            date(12, 25),
+            date(12, 26),
+            date(12, 31),
+        ];
+    }
+}
+
+impl HolidayCalendar for Eurex {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        return Eurex::holidays(date.year()).contains(&date);
+    }
+
+    fn open(&self, _date: NaiveDate) -> NaiveTime {
+        return NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    }
+
+    fn close(&self, _date: NaiveDate) -> NaiveTime {
+        return NaiveTime::from_hms_opt(17, 30, 0).unwrap();
+    }
+
+    fn trading_minutes_per_year(&self) -> f64 {
+        return 252.0 * 510.0;
+    }
+}
+
+/**
+ * Japan Exchange Group (Tokyo and Osaka), trading from 9:00 to 15:30 JST. National holidays
+ * follow the rules in effect since 2020; one-off moves such as those for the Tokyo Olympics
+ * are not included.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Jpx;
+
+impl Jpx {
+    /**
+     * Full-day closures in `year`, including substitute holidays for those falling on a Sunday
+     * and weekdays between two holidays.
+     */
+    pub fn holidays(year: i32) -> Vec<NaiveDate> {
+        let date = |month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        // equinoxes by the approximation valid for 1980–2099
+        let drift = 0.242194 * (year - 1980) as f64 - ((year - 1980) / 4) as f64;
+        let vernal = (20.8431 + drift).floor() as u32;
+        let autumnal = (23.2488 + drift).floor() as u32;
+        let mut national = vec![
+            date(1, 1),
+            nth_weekday(year, 1, Weekday::Mon, 2),
+            date(2, 11),
+            date(2, 23),
+            date(3, vernal),
+            date(4, 29),
+            date(5, 3),
+            date(5, 4),
+            date(5, 5),
+            nth_weekday(year, 7, Weekday::Mon, 3),
+            date(8, 11),
+            nth_weekday(year, 9, Weekday::Mon, 3),
+            date(9, autumnal),
+            nth_weekday(year, 10, Weekday::Mon, 2),
+            date(11, 3),
+            date(11, 23),
+        ];
+        national.sort();
+        let mut holidays = national.clone();
+        for holiday in national.iter() {
+            if holiday.weekday() == Weekday::Sun {
+                let mut substitute = *holiday + Duration::days(1);
+                while holidays.contains(&substitute) {
+                    substitute += Duration::days(1);
+                }
+                holidays.push(substitute);
+            }
+        }
+        for w in national.windows(2) {
+            let between = w[0] + Duration::days(1);
+            if w[1] == w[0] + Duration::days(2) && !holidays.contains(&between) {
+                holidays.push(between);
+            }
+        }
+        // the exchange closes over the new year
+        holidays.extend([date(1, 2), date(1, 3), date(12, 31)].iter());
+        holidays.sort();
+        return holidays;
+    }
+}
+
+impl HolidayCalendar for Jpx {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        return Jpx::holidays(date.year()).contains(&date);
+    }
+
+    fn open(&self, _date: NaiveDate) -> NaiveTime {
+        return NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    }
+
+    fn close(&self, _date: NaiveDate) -> NaiveTime {
+        return NaiveTime::from_hms_opt(15, 30, 0).unwrap();
+    }
+
+    fn trading_minutes_per_year(&self) -> f64 {
+        return 245.0 * 390.0;
+    }
+}
+
+/**
+ * Korea Exchange, trading from 9:00 to 15:30 KST. Fixed-date holidays are built in, but the
+ * lunar holidays (Seollal, Buddha's Birthday, Chuseok), substitute holidays, and election days
+ * change every year and must be supplied.
+ */
+#[derive(Clone, Debug)]
+pub struct Krx {
+    listed: HashSet<NaiveDate>,
+}
+
+impl Krx {
+    pub fn new(listed: &[NaiveDate]) -> Krx {
+        return Krx {
+            listed: listed.iter().copied().collect(),
+        };
+    }
+}
+
+impl HolidayCalendar for Krx {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        let fixed = matches!(
+            (date.month(), date.day()),
+            (1, 1)
+                | (3, 1)
+                | (5, 1)
+                | (5, 5)
+                | (6, 6)
+                | (8, 15)
+                | (10, 3)
+                | (10, 9)
+                | (12, 25)
+                | (12, 31)
+        );
+        return fixed || self.listed.contains(&date);
+    }
+
+    fn open(&self, _date: NaiveDate) -> NaiveTime {
+        return NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    }
+
+    fn close(&self, _date: NaiveDate) -> NaiveTime {
+        return NaiveTime::from_hms_opt(15, 30, 0).unwrap();
+    }
+
+    fn trading_minutes_per_year(&self) -> f64 {
+        return 248.0 * 390.0;
+    }
+}
+
+/**
+ * A calendar given by an explicit list of holidays and fixed session hours, for markets without
+ * a bundled calendar.
+ */
+#[derive(Clone, Debug)]
+pub struct ListedHolidays {
+    holidays: HashSet<NaiveDate>,
+    open: NaiveTime,
+    close: NaiveTime,
+}
+
+impl ListedHolidays {
+    pub fn new(holidays: &[NaiveDate], open: NaiveTime, close: NaiveTime) -> ListedHolidays {
+        return ListedHolidays {
+            holidays: holidays.iter().copied().collect(),
+            open,
+            close,
+        };
+    }
+}
+
+impl HolidayCalendar for ListedHolidays {
+    fn is_holiday(&self, date: NaiveDate) -> bool {
+        return self.holidays.contains(&date);
+    }
+
+    fn open(&self, _date: NaiveDate) -> NaiveTime {
+        return self.open;
+    }
+
+    fn close(&self, _date: NaiveDate) -> NaiveTime {
+        return self.close;
+    }
+
+    fn trading_minutes_per_year(&self) -> f64 {
+        return 252.0 * self.close.signed_duration_since(self.open).num_minutes() as f64;
+    }
+}
+
+/**
+ * Calendars by exchange name. `CalendarRegistry::default()` comes with `NYSE`, `CBOE`,
+ * `EUREX`, and `JPX`. `KRX` needs its lunar holidays and so has to be registered.
+ */
+pub struct CalendarRegistry {
+    calendars: HashMap<String, Box<dyn HolidayCalendar + Send + Sync>>,
+}
+
+impl Default for CalendarRegistry {
+    fn default() -> CalendarRegistry {
+        let mut registry = CalendarRegistry::empty();
+        registry.register("NYSE", Nyse);
+        registry.register("CBOE", Cboe);
+        registry.register("EUREX", Eurex);
+        registry.register("JPX", Jpx);
+        return registry;
+    }
+}
+
+impl CalendarRegistry {
+    pub fn empty() -> CalendarRegistry {
+        return CalendarRegistry {
+            calendars: HashMap::new(),
+        };
+    }
+
+    /**
+     * Adds a calendar, replacing any already registered under `exchange`.
+     */
+    pub fn register<C: HolidayCalendar + Send + Sync + 'static>(
+        &mut self,
+        exchange: &str,
+        calendar: C,
+    ) {
+        self.calendars
+            .insert(exchange.to_string(), Box::new(calendar));
+    }
+
+    pub fn get(&self, exchange: &str) -> Option<&(dyn HolidayCalendar + Send + Sync)> {
+        return self.calendars.get(exchange).map(|c| c.as_ref());
+    }
+}
@@ -0,0 +1,107 @@
+//! Weights interpolating the near- and next-term variances to the target maturity.
+//!
+//! The VIX methodology weights each term's total variance by how close its expiration is to the
+//! target in calendar minutes. Some replication studies measure that distance differently, e.g.
+//! in trading time; `Weighting::Custom` lets them plug in their own scheme while everything else
+//! about the index stays the same.
+
+use crate::holidays::HolidayCalendar;
+use chrono::prelude::*;
+use chrono::Duration;
+
+/**
+ * Weights of the near- and next-term total variances. They sum to one; one of them is negative
+ * when the target is outside the two terms.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct InterpolationWeights {
+    pub near: f64,
+    pub next: f64,
+}
+
+impl InterpolationWeights {
+    /**
+     * Linear weights from the distances of the terms to the target, in any unit of time.
+     */
+    pub fn linear(near: f64, next: f64, target: f64) -> InterpolationWeights {
+        return InterpolationWeights {
+            near: (next - target) / (next - near),
+            next: (target - near) / (next - near),
+        };
+    }
+
+    /**
+     * The VIX weights: linear in calendar minutes to expiration.
+     */
+    pub fn calendar_minutes(inputs: &WeightingInputs) -> InterpolationWeights {
+        return InterpolationWeights::linear(
+            inputs.near_minutes,
+            inputs.next_minutes,
+            inputs.target_minutes,
+        );
+    }
+
+    /**
+     * Linear in the minutes `calendar` trades between `now` and each expiration and the target.
+     */
+    pub fn trading_minutes<C: HolidayCalendar + ?Sized>(
+        calendar: &C,
+        inputs: &WeightingInputs,
+    ) -> InterpolationWeights {
+        let minutes = |to: NaiveDateTime| calendar.trading_minutes_between(inputs.now, to) as f64;
+        let target = inputs.now + Duration::seconds((inputs.target_minutes * 60.0).round() as i64);
+        return InterpolationWeights::linear(
+            minutes(inputs.near_expires_at),
+            minutes(inputs.next_expires_at),
+            minutes(target),
+        );
+    }
+}
+
+/**
+ * What a weighting scheme can base the weights on.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct WeightingInputs {
+    pub now: NaiveDateTime,
+    pub near_expires_at: NaiveDateTime,
+    pub next_expires_at: NaiveDateTime,
+    /// Minutes to each expiration as the index measures them, including the settlement
+    /// adjustments of `SameMinuteExpiry`.
+    pub near_minutes: f64,
+    pub next_minutes: f64,
+    /// Calendar minutes from `now` to the target maturity.
+    pub target_minutes: f64,
+}
+
+/**
+ * How the interpolation weights are computed.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Weighting {
+    /// Linear in calendar minutes, as VIX does.
+    #[default]
+    CalendarMinutes,
+    /// Any other scheme, e.g. `InterpolationWeights::trading_minutes` on an exchange calendar.
+    Custom(fn(&WeightingInputs) -> InterpolationWeights),
+}
+
+/// Custom weightings are equal if they are the same function, as far as addresses tell.
+impl PartialEq for Weighting {
+    fn eq(&self, other: &Weighting) -> bool {
+        return match (self, other) {
+            (Weighting::CalendarMinutes, Weighting::CalendarMinutes) => true,
+            (Weighting::Custom(f), Weighting::Custom(g)) => *f as usize == *g as usize,
+            _ => false,
+        };
+    }
+}
+
+impl Weighting {
+    pub fn weights(&self, inputs: &WeightingInputs) -> InterpolationWeights {
+        return match self {
+            Weighting::CalendarMinutes => InterpolationWeights::calendar_minutes(inputs),
+            Weighting::Custom(f) => f(inputs),
+        };
+    }
+}
@@ -0,0 +1,164 @@
+//! No-arbitrage and sanity invariants over an expiry's quotes.
+//!
+//! Every check is phrased against executable prices (bids and asks), so a reported violation is
+//! one that could actually be traded against rather than noise inside the spread. The checks
+//! return their violations instead of failing, which makes them usable both as a data-quality
+//! gate and as a fuzzing oracle.
+
+use crate::{Cents, OptionContract, OptionKind, OptionsByExpiryDate, Percentage};
+use chrono::prelude::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    /**
+     * A contract can be bought for less than a contract further in the money can be sold for.
+     */
+    NotMonotone {
+        kind: OptionKind,
+        lower_strike: Cents,
+        higher_strike: Cents,
+    },
+    /**
+     * Call minus put is outside the range implied by the forward, by `deviation` cents.
+     */
+    ParityOutsideSpread { strike: Cents, deviation: Cents },
+    /**
+     * The butterfly centered on `strike` can be bought for a negative price (in cents per unit
+     * of the center option).
+     */
+    NegativeButterfly {
+        kind: OptionKind,
+        strike: Cents,
+        price: f64,
+    },
+    /**
+     * The model-free variance of the expiry is negative.
+     */
+    NegativeVariance { variance: Percentage },
+}
+
+/**
+ * Runs every check against an expiry.
+ */
+pub fn check(
+    options: &OptionsByExpiryDate,
+    risk_free_rate: f64,
+    now: NaiveDateTime,
+) -> Vec<Violation> {
+    return check_monotonicity(options)
+        .into_iter()
+        .chain(check_put_call_parity(options, risk_free_rate, now))
+        .chain(check_butterflies(options))
+        .chain(check_variance(options, risk_free_rate, now))
+        .collect();
+}
+
+/**
+ * Quoted contracts of one kind, sorted by strike.
+ */
+fn quoted(options: &OptionsByExpiryDate, kind: OptionKind) -> Vec<OptionContract> {
+    let contracts = match kind {
+        OptionKind::Call => &options.calls,
+        OptionKind::Put => &options.puts,
+    };
+    let mut quoted: Vec<OptionContract> =
+        contracts.iter().filter(|o| o.bid != 0).copied().collect();
+    quoted.sort_unstable_by_key(|o| o.strike);
+    return quoted;
+}
+
+/**
+ * Calls must not get more expensive as the strike rises, and puts must not get cheaper.
+ */
+pub fn check_monotonicity(options: &OptionsByExpiryDate) -> Vec<Violation> {
+    let mut violations = vec![];
+    for kind in [OptionKind::Call, OptionKind::Put].iter() {
+        for w in quoted(options, *kind).windows(2) {
+            let (lower, higher) = (w[0], w[1]);
+            let violated = match kind {
+                OptionKind::Call => lower.ask < higher.bid,
+                OptionKind::Put => higher.ask < lower.bid,
+            };
+            if violated {
+                violations.push(Violation::NotMonotone {
+                    kind: *kind,
+                    lower_strike: lower.strike,
+                    higher_strike: higher.strike,
+                });
+            }
+        }
+    }
+    return violations;
+}
+
+/**
+ * At every strike, `C - P` must be within the spread of `e^{-rT} (F - K)`, with `F` the implied
+ * forward price of the expiry.
+ */
+pub fn check_put_call_parity(
+    options: &OptionsByExpiryDate,
+    risk_free_rate: f64,
+    now: NaiveDateTime,
+) -> Vec<Violation> {
+    let discount = (-risk_free_rate * options.time_to_expiration(now)).exp();
+    let forward = options.forward_price(risk_free_rate, now);
+
+    return options
+        .strikes()
+        .flat_map(|s| -> Option<Violation> {
+            let parity = (discount * (forward - s.price) as f64).round() as Cents;
+            let low = s.call.bid - s.put.ask;
+            let high = s.call.ask - s.put.bid;
+            let deviation = if parity < low {
+                low - parity
+            } else if parity > high {
+                parity - high
+            } else {
+                return None;
+            };
+            return Some(Violation::ParityOutsideSpread {
+                strike: s.price,
+                deviation,
+            });
+        })
+        .collect();
+}
+
+/**
+ * Prices must be convex in strike: buying the wings and selling the body of any three
+ * consecutive strikes must cost something.
+ */
+pub fn check_butterflies(options: &OptionsByExpiryDate) -> Vec<Violation> {
+    let mut violations = vec![];
+    for kind in [OptionKind::Call, OptionKind::Put].iter() {
+        for w in quoted(options, *kind).windows(3) {
+            let (low, mid, high) = (w[0], w[1], w[2]);
+            let width = (high.strike - low.strike) as f64;
+            let price = low.ask as f64 * (high.strike - mid.strike) as f64 / width - mid.bid as f64
+                + high.ask as f64 * (mid.strike - low.strike) as f64 / width;
+            if price < 0.0 {
+                violations.push(Violation::NegativeButterfly {
+                    kind: *kind,
+                    strike: mid.strike,
+                    price,
+                });
+            }
+        }
+    }
+    return violations;
+}
+
+/**
+ * The model-free variance must not be negative.
+ */
+pub fn check_variance(
+    options: &OptionsByExpiryDate,
+    risk_free_rate: f64,
+    now: NaiveDateTime,
+) -> Vec<Violation> {
+    let variance = options.variance(risk_free_rate, now);
+    if variance < 0.0 {
+        return vec![Violation::NegativeVariance { variance }];
+    }
+    return vec![];
+}
@@ -0,0 +1,123 @@
+//! Inverse options, quoted and settled in the underlying.
+//!
+//! Crypto venues like Deribit list bitcoin options whose premium is quoted in bitcoin and whose
+//! intrinsic value is paid in bitcoin at the settlement price: a call pays
+//! `max(S - K, 0) / S` coins, worth exactly the dollar payoff of the ordinary option. So in
+//! dollars an inverse option is the ordinary one, and the variance and index math applies once
+//! the quotes are converted to cents at the price of the underlying, as DVOL does. What changes is
+//! everything measured in the underlying: quotes, payoffs, and the delta.
+
+use crate::chain::Chain;
+use crate::methodology::Preset;
+use crate::rates::YieldCurve;
+use crate::{Cents, OptionContract, OptionKind, Percentage, Settlement};
+use chrono::prelude::*;
+
+/**
+ * An option quoted in units of its underlying, e.g. 0.0235 BTC.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct InverseOptionContract {
+    pub expires_at: NaiveDateTime,
+    /// In cents of the quote currency, like any other strike.
+    pub strike: Cents,
+    pub kind: OptionKind,
+    pub bid: f64,
+    pub ask: f64,
+}
+
+impl InverseOptionContract {
+    /**
+     * The quote of `contract`, in cents, in units of the underlying at the price `underlying`.
+     */
+    pub fn from_linear(contract: OptionContract, underlying: Cents) -> InverseOptionContract {
+        let price = underlying as f64;
+        return InverseOptionContract {
+            expires_at: contract.expires_at,
+            strike: contract.strike,
+            kind: contract.kind,
+            bid: contract.bid as f64 / price,
+            ask: contract.ask as f64 / price,
+        };
+    }
+
+    pub fn mark(&self) -> f64 {
+        return (self.bid + self.ask) / 2.0;
+    }
+
+    /**
+     * The contract quoted in cents at the price `underlying`, settled in the underlying.
+     *
+     * Deribit options are on the futures of their expiry, and converting at that futures price
+     * gives the forward value of the premium, which is what the variance replication with zero
+     * rates expects.
+     */
+    pub fn to_linear(self, underlying: Cents) -> OptionContract {
+        let price = underlying as f64;
+        return OptionContract::new(
+            self.expires_at,
+            self.strike,
+            self.kind,
+            (self.bid * price).round() as Cents,
+            (self.ask * price).round() as Cents,
+        )
+        .with_settlement(Settlement::Inverse);
+    }
+
+    /**
+     * Units of underlying paid at expiration against `settlement_price`.
+     */
+    pub fn payoff(&self, settlement_price: Cents) -> f64 {
+        if settlement_price <= 0 {
+            return 0.0;
+        }
+        let intrinsic = match self.kind {
+            OptionKind::Call => settlement_price - self.strike,
+            OptionKind::Put => self.strike - settlement_price,
+        };
+        return intrinsic.max(0) as f64 / settlement_price as f64;
+    }
+
+    /**
+     * Delta in units of underlying, given `linear_delta`, the delta of the ordinary option.
+     *
+     * The premium is itself held in the underlying, so the exposure it adds, the mark, is taken
+     * off: a deep in-the-money call, with a linear delta near one and a mark near `(S - K) / S`,
+     * has a delta of about `K / S`. This is the delta inverse venues report.
+     */
+    pub fn delta(&self, linear_delta: f64) -> f64 {
+        return linear_delta - self.mark();
+    }
+}
+
+/**
+ * `options` converted to cents at the price of the underlying of each expiry, given as
+ * `(expires_at, price)` pairs. Contracts of expiries without a price are left out.
+ */
+pub fn linear_contracts(
+    options: &[InverseOptionContract],
+    underlying: &[(NaiveDateTime, Cents)],
+) -> Vec<OptionContract> {
+    return options
+        .iter()
+        .filter_map(|o| {
+            let (_, price) = underlying.iter().find(|(at, _)| *at == o.expires_at)?;
+            return Some(o.to_linear(*price));
+        })
+        .collect();
+}
+
+/**
+ * A DVOL-style index of `options`: the Deribit methodology on the chain converted with
+ * `linear_contracts`. `None` if there are no expiries either side of thirty days.
+ */
+pub fn dvol(
+    options: &[InverseOptionContract],
+    underlying: &[(NaiveDateTime, Cents)],
+    now: NaiveDateTime,
+) -> Option<Percentage> {
+    let chain = Chain::new(&linear_contracts(options, underlying));
+    return Preset::DeribitDvol
+        .methodology()
+        .compute(&chain, &YieldCurve::flat(0.0), now);
+}
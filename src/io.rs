@@ -0,0 +1,3 @@
+//! Reading and writing chains and indices to files.
+
+pub mod jsonl;
@@ -0,0 +1,534 @@
+//! JSON Lines snapshots of chains and computed indices.
+//!
+//! Each line is one self-contained JSON object, so a collector can append to the same file for
+//! as long as it runs and a crash loses at most the line being written. Lines look like
+//!
+//! ```text
+//! {"type":"snapshot","at":"2020-01-02T09:30:00","contracts":[{"expires_at":"2020-01-17T09:30:00","strike":320000,"kind":"call","bid":410,"ask":420,"settlement":"cash","currency":"USD","volatility_model":"lognormal","exercise_style":"european","revision":0,"quoted_at":"2020-01-02T09:29:58"}]}
+//! {"type":"index","at":"2020-01-02T09:30:00","name":"VIX","value":13.2}
+//! ```
+//!
+//! Prices are in cents and times are local, as everywhere else in the crate.
+
+use crate::chain::Chain;
+use crate::currency::Currency;
+use crate::{ExerciseStyle, OptionContract, OptionKind, Percentage, Settlement, VolatilityModel};
+use chrono::prelude::*;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/**
+ * A single line of a JSON Lines file.
+ */
+#[derive(Clone, Debug)]
+pub enum Record {
+    /// Every quote of a chain as of `at`.
+    Snapshot { at: NaiveDateTime, chain: Chain },
+    /// A computed index value as of `at`.
+    Index {
+        at: NaiveDateTime,
+        name: String,
+        value: Percentage,
+    },
+}
+
+impl Record {
+    pub fn at(&self) -> NaiveDateTime {
+        return match self {
+            Record::Snapshot { at, .. } => *at,
+            Record::Index { at, .. } => *at,
+        };
+    }
+
+    /**
+     * The record as a single line of JSON, without the trailing newline.
+     */
+    pub fn to_json(&self) -> String {
+        return match self {
+            Record::Snapshot { at, chain } => {
+                let contracts: Vec<String> = chain
+                    .expiries()
+                    .iter()
+                    .flat_map(|e| e.calls.iter().chain(e.puts.iter()))
+                    .map(contract_json)
+                    .collect();
+                format!(
+                    "{{\"type\":\"snapshot\",\"at\":\"{}\",\"contracts\":[{}]}}",
+                    at.format(TIME_FORMAT),
+                    contracts.join(",")
+                )
+            }
+            Record::Index { at, name, value } => format!(
+                "{{\"type\":\"index\",\"at\":\"{}\",\"name\":{},\"value\":{}}}",
+                at.format(TIME_FORMAT),
+                quote(name),
+                number(*value)
+            ),
+        };
+    }
+
+    /**
+     * Parses a line written by `to_json`.
+     */
+    pub fn from_json(line: &str) -> io::Result<Record> {
+        let mut parser = Parser {
+            input: line,
+            position: 0,
+        };
+        let value = parser.value()?;
+        parser.whitespace();
+        if parser.position != parser.input.len() {
+            return Err(invalid("trailing characters"));
+        }
+        let at = time(value.field("at")?)?;
+        return match value.field("type")?.string()? {
+            "snapshot" => {
+                let contracts = value
+                    .field("contracts")?
+                    .array()?
+                    .iter()
+                    .map(contract)
+                    .collect::<io::Result<Vec<OptionContract>>>()?;
+                Ok(Record::Snapshot {
+                    at,
+                    chain: Chain::new(&contracts),
+                })
+            }
+            "index" => Ok(Record::Index {
+                at,
+                name: value.field("name")?.string()?.to_string(),
+                value: value.field("value")?.number()?,
+            }),
+            other => Err(invalid(&format!("unknown record type {}", other))),
+        };
+    }
+}
+
+/**
+ * Appends records to a JSON Lines stream, one per line.
+ */
+#[derive(Debug)]
+pub struct JsonlWriter<W: Write> {
+    writer: W,
+}
+
+impl JsonlWriter<File> {
+    /**
+     * A writer appending to the file at `path`, creating it if needed.
+     */
+    pub fn append<P: AsRef<Path>>(path: P) -> io::Result<JsonlWriter<File>> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        return Ok(JsonlWriter::new(file));
+    }
+}
+
+impl<W: Write> JsonlWriter<W> {
+    pub fn new(writer: W) -> JsonlWriter<W> {
+        return JsonlWriter { writer };
+    }
+
+    /**
+     * Writes the record and flushes it, so a reader never sees half a line once this returns.
+     */
+    pub fn write(&mut self, record: &Record) -> io::Result<()> {
+        let mut line = record.to_json();
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())?;
+        return self.writer.flush();
+    }
+
+    pub fn write_snapshot(&mut self, at: NaiveDateTime, chain: &Chain) -> io::Result<()> {
+        return self.write(&Record::Snapshot {
+            at,
+            chain: chain.clone(),
+        });
+    }
+
+    pub fn write_index(
+        &mut self,
+        at: NaiveDateTime,
+        name: &str,
+        value: Percentage,
+    ) -> io::Result<()> {
+        return self.write(&Record::Index {
+            at,
+            name: name.to_string(),
+            value,
+        });
+    }
+
+    pub fn into_inner(self) -> W {
+        return self.writer;
+    }
+}
+
+/**
+ * Reads records back from a JSON Lines stream, skipping blank lines.
+ */
+#[derive(Debug)]
+pub struct JsonlReader<R: BufRead> {
+    reader: R,
+}
+
+impl JsonlReader<BufReader<File>> {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<JsonlReader<BufReader<File>>> {
+        return Ok(JsonlReader::new(BufReader::new(File::open(path)?)));
+    }
+}
+
+impl<R: BufRead> JsonlReader<R> {
+    pub fn new(reader: R) -> JsonlReader<R> {
+        return JsonlReader { reader };
+    }
+}
+
+impl<R: BufRead> Iterator for JsonlReader<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<io::Result<Record>> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            match self.reader.read_line(&mut line) {
+                Ok(0) => return None,
+                Ok(_) if line.trim().is_empty() => continue,
+                Ok(_) => return Some(Record::from_json(line.trim_end())),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+fn contract_json(o: &OptionContract) -> String {
+    return format!(
+        "{{\"expires_at\":\"{}\",\"strike\":{},\"kind\":\"{}\",\"bid\":{},\"ask\":{},\"settlement\":\"{}\",\"currency\":\"{}\",\"volatility_model\":\"{}\",\"exercise_style\":\"{}\",\"revision\":{}{}}}",
+        o.expires_at.format(TIME_FORMAT),
+        o.strike,
+        match o.kind {
+            OptionKind::Call => "call",
+            OptionKind::Put => "put",
+        },
+        o.bid,
+        o.ask,
+        match o.settlement {
+            Settlement::Cash => "cash",
+            Settlement::Physical => "physical",
+            Settlement::Inverse => "inverse",
+        },
+        o.currency.code(),
+        match o.volatility_model {
+            VolatilityModel::Lognormal => "lognormal",
+            VolatilityModel::Normal => "normal",
+        },
+        match o.exercise_style {
+            ExerciseStyle::European => "european",
+            ExerciseStyle::American => "american",
+        },
+        o.revision,
+        match o.quoted_at {
+            Some(quoted_at) => format!(",\"quoted_at\":\"{}\"", quoted_at.format(TIME_FORMAT)),
+            None => String::new(),
+        }
+    );
+}
+
+fn contract(value: &Json) -> io::Result<OptionContract> {
+    let cents = |name: &str| -> io::Result<i64> {
+        let n = value.field(name)?.number()?;
+        if n.fract() != 0.0 {
+            return Err(invalid(&format!("{} is not a whole number of cents", name)));
+        }
+        return Ok(n as i64);
+    };
+    let kind = match value.field("kind")?.string()? {
+        "call" => OptionKind::Call,
+        "put" => OptionKind::Put,
+        other => return Err(invalid(&format!("unknown kind {}", other))),
+    };
+    let settlement = match value.field("settlement")?.string()? {
+        "cash" => Settlement::Cash,
+        "physical" => Settlement::Physical,
+        "inverse" => Settlement::Inverse,
+        other => return Err(invalid(&format!("unknown settlement {}", other))),
+    };
+    // absent from lines written before normal volatilities were supported
+    let volatility_model = match value.field("volatility_model") {
+        Err(_) => VolatilityModel::Lognormal,
+        Ok(model) => match model.string()? {
+            "lognormal" => VolatilityModel::Lognormal,
+            "normal" => VolatilityModel::Normal,
+            other => return Err(invalid(&format!("unknown volatility model {}", other))),
+        },
+    };
+    // absent from lines written before exercise styles were recorded
+    let exercise_style = match value.field("exercise_style") {
+        Err(_) => ExerciseStyle::European,
+        Ok(style) => match style.string()? {
+            "european" => ExerciseStyle::European,
+            "american" => ExerciseStyle::American,
+            other => return Err(invalid(&format!("unknown exercise style {}", other))),
+        },
+    };
+    // absent from lines written before quote times and revisions were recorded
+    let revision = match value.field("revision") {
+        Err(_) => 0,
+        Ok(revision) => {
+            let n = revision.number()?;
+            if n < 0.0 || n.fract() != 0.0 {
+                return Err(invalid("revision is not a whole number"));
+            }
+            n as u64
+        }
+    };
+    let quoted_at = match value.field("quoted_at") {
+        Err(_) => None,
+        Ok(quoted_at) => Some(time(quoted_at)?),
+    };
+    let code = value.field("currency")?.string()?;
+    let currency =
+        Currency::new(code).ok_or_else(|| invalid(&format!("unknown currency {}", code)))?;
+    let contract = OptionContract::new(
+        time(value.field("expires_at")?)?,
+        cents("strike")?,
+        kind,
+        cents("bid")?,
+        cents("ask")?,
+    )
+    .with_settlement(settlement)
+    .with_currency(currency)
+    .with_volatility_model(volatility_model)
+    .with_exercise_style(exercise_style);
+    return Ok(OptionContract {
+        revision,
+        quoted_at,
+        ..contract
+    });
+}
+
+fn time(value: &Json) -> io::Result<NaiveDateTime> {
+    let s = value.string()?;
+    return NaiveDateTime::parse_from_str(s, TIME_FORMAT)
+        .map_err(|_| invalid(&format!("bad time {}", s)));
+}
+
+pub(crate) fn number(value: f64) -> String {
+    // JSON has no NaN or infinities
+    if value.is_finite() {
+        return value.to_string();
+    }
+    return "null".to_string();
+}
+
+pub(crate) fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    return out;
+}
+
+fn invalid(message: &str) -> io::Error {
+    return io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+}
+
+/**
+ * Just enough of JSON to read back what this module writes.
+ */
+#[derive(Debug)]
+enum Json {
+    Null,
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn field(&self, name: &str) -> io::Result<&Json> {
+        if let Json::Object(fields) = self {
+            if let Some((_, value)) = fields.iter().find(|(key, _)| key == name) {
+                return Ok(value);
+            }
+        }
+        return Err(invalid(&format!("missing field {}", name)));
+    }
+
+    fn string(&self) -> io::Result<&str> {
+        return match self {
+            Json::String(s) => Ok(s),
+            _ => Err(invalid("expected a string")),
+        };
+    }
+
+    fn number(&self) -> io::Result<f64> {
+        return match self {
+            Json::Number(n) => Ok(*n),
+            Json::Null => Ok(f64::NAN),
+            _ => Err(invalid("expected a number")),
+        };
+    }
+
+    fn array(&self) -> io::Result<&[Json]> {
+        return match self {
+            Json::Array(values) => Ok(values),
+            _ => Err(invalid("expected an array")),
+        };
+    }
+}
+
+/**
+ * A parser over a line that is already valid UTF-8, so strings are decoded as they are scanned.
+ */
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn whitespace(&mut self) {
+        let bytes = self.input.as_bytes();
+        while self.position < bytes.len() && bytes[self.position].is_ascii_whitespace() {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&mut self) -> io::Result<u8> {
+        self.whitespace();
+        return self
+            .input
+            .as_bytes()
+            .get(self.position)
+            .copied()
+            .ok_or_else(|| invalid("unexpected end of line"));
+    }
+
+    fn expect(&mut self, byte: u8) -> io::Result<()> {
+        if self.peek()? != byte {
+            return Err(invalid(&format!("expected {}", byte as char)));
+        }
+        self.position += 1;
+        return Ok(());
+    }
+
+    fn literal(&mut self, text: &str, value: Json) -> io::Result<Json> {
+        if !self.input[self.position..].starts_with(text) {
+            return Err(invalid("unexpected token"));
+        }
+        self.position += text.len();
+        return Ok(value);
+    }
+
+    fn value(&mut self) -> io::Result<Json> {
+        return match self.peek()? {
+            b'{' => self.object(),
+            b'[' => self.array(),
+            b'"' => Ok(Json::String(self.string()?)),
+            b'n' => self.literal("null", Json::Null),
+            _ => self.number(),
+        };
+    }
+
+    fn object(&mut self) -> io::Result<Json> {
+        self.expect(b'{')?;
+        let mut fields = vec![];
+        if self.peek()? == b'}' {
+            self.position += 1;
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.peek()?;
+            let key = self.string()?;
+            self.expect(b':')?;
+            fields.push((key, self.value()?));
+            match self.peek()? {
+                b',' => self.position += 1,
+                b'}' => {
+                    self.position += 1;
+                    return Ok(Json::Object(fields));
+                }
+                _ => return Err(invalid("expected , or }")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> io::Result<Json> {
+        self.expect(b'[')?;
+        let mut values = vec![];
+        if self.peek()? == b']' {
+            self.position += 1;
+            return Ok(Json::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            match self.peek()? {
+                b',' => self.position += 1,
+                b']' => {
+                    self.position += 1;
+                    return Ok(Json::Array(values));
+                }
+                _ => return Err(invalid("expected , or ]")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        self.expect(b'"')?;
+        let mut out = String::new();
+        // every position the parser stops at follows an ASCII byte, so is a char boundary
+        let mut chars = self.input[self.position..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.position += i + 1;
+                    return Ok(out);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('"') => out.push('"'),
+                    Some('\\') => out.push('\\'),
+                    Some('/') => out.push('/'),
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('r') => out.push('\r'),
+                    Some('u') => {
+                        let hex: String =
+                            (0..4).flat_map(|_| chars.next().map(|(_, c)| c)).collect();
+                        let code =
+                            u32::from_str_radix(&hex, 16).map_err(|_| invalid("bad escape"))?;
+                        out.push(std::char::from_u32(code).ok_or_else(|| invalid("bad escape"))?);
+                    }
+                    _ => return Err(invalid("bad escape")),
+                },
+                c => out.push(c),
+            }
+        }
+        return Err(invalid("unterminated string"));
+    }
+
+    fn number(&mut self) -> io::Result<Json> {
+        let start = self.position;
+        let bytes = self.input.as_bytes();
+        while self.position < bytes.len()
+            && matches!(
+                bytes[self.position],
+                b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'
+            )
+        {
+            self.position += 1;
+        }
+        let text = &self.input[start..self.position];
+        return text
+            .parse()
+            .map(Json::Number)
+            .map_err(|_| invalid(&format!("bad number {}", text)));
+    }
+}
@@ -1,30 +1,272 @@
 #[macro_use]
 extern crate derive_new;
 
+use cache::ContributionCache;
 use chrono::prelude::*;
-use itertools::Itertools;
+use currency::Currency;
+use holidays::HolidayCalendar;
+use interpolation::{InterpolationWeights, Weighting, WeightingInputs};
+use math::Summation;
+use quality::{IndexEstimate, SpreadAccumulator, VarianceEstimate};
+use sparse::SparseChain;
 use std::collections::HashMap;
 
-#[derive(PartialEq, Clone, Copy, Debug)]
+pub mod alerts;
+pub mod analytics;
+pub mod approx;
+pub mod asian;
+pub mod barrier;
+pub mod basket;
+pub mod benchmark;
+pub mod cache;
+pub mod calendar;
+pub mod chain;
+pub mod combo;
+pub mod correlation;
+pub mod currency;
+pub mod density;
+pub mod digital;
+pub mod dividends;
+pub mod early_exercise;
+pub mod event;
+pub mod expiration;
+pub mod explain;
+pub mod export;
+pub mod fixture;
+pub mod fourier;
+pub mod greeks;
+pub mod hedging;
+pub mod holidays;
+pub mod interpolation;
+pub mod invariants;
+pub mod inverse;
+pub mod io;
+pub mod localvol;
+pub mod lookback;
+pub mod lsm;
+pub mod math;
+pub mod methodology;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod models;
+pub mod montecarlo;
+pub mod pairs;
+pub mod pricer;
+pub mod pricing;
+pub mod publish;
+pub mod quality;
+pub mod rates;
+pub mod realized;
+pub mod replication;
+pub mod resample;
+pub mod returns;
+pub mod schedule;
+pub mod series;
+pub mod sessions;
+pub mod skew;
+pub mod sparse;
+pub mod strategy;
+pub mod synthetic;
+pub mod units;
+pub mod universe;
+pub mod validation;
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum OptionKind {
     Call,
     Put,
 }
 
+/**
+ * How exercised contracts settle.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Settlement {
+    /// The intrinsic value is paid in cash (index options).
+    Cash,
+    /// The underlying is delivered against payment of the strike (equity options).
+    Physical,
+    /// The intrinsic value is paid in the underlying, worth it at the settlement price
+    /// (coin-settled crypto options). Prices are still in cents, converted from the quote in the
+    /// underlying by `inverse::InverseOptionContract::to_linear`.
+    Inverse,
+}
+
+/**
+ * When a contract can be exercised.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum ExerciseStyle {
+    /// Only at expiration (index options).
+    #[default]
+    European,
+    /// At any time up to expiration (equity options).
+    American,
+}
+
+/**
+ * How a contract's price maps to a volatility.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum VolatilityModel {
+    /// Black's lognormal model, with volatilities as fractions per square root year.
+    #[default]
+    Lognormal,
+    /// Bachelier's normal model, with volatilities in cents per square root year. Conventional
+    /// for rates and for futures that can trade near or below zero.
+    Normal,
+}
+
 pub type Cents = i64;
 
 pub type Percentage = f64;
 
-#[derive(new, Clone, Copy, Debug)]
+/**
+ * What identifies a listed contract, whatever its quote.
+ */
+#[derive(new, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct ContractKey {
+    pub expires_at: NaiveDateTime,
+    pub strike: Cents,
+    pub kind: OptionKind,
+}
+
+#[derive(new, PartialEq, Clone, Copy, Debug)]
 pub struct OptionContract {
     expires_at: NaiveDateTime,
     strike: Cents,
     kind: OptionKind,
     bid: Cents,
     ask: Cents,
+    #[new(value = "Settlement::Physical")]
+    settlement: Settlement,
+    #[new(value = "Currency::USD")]
+    currency: Currency,
+    #[new(value = "VolatilityModel::Lognormal")]
+    volatility_model: VolatilityModel,
+    #[new(value = "ExerciseStyle::European")]
+    exercise_style: ExerciseStyle,
+    #[new(value = "0")]
+    revision: u64,
+    #[new(value = "None")]
+    quoted_at: Option<NaiveDateTime>,
 }
 
 impl OptionContract {
+    pub fn expires_at(self) -> NaiveDateTime {
+        return self.expires_at;
+    }
+
+    pub fn strike(self) -> Cents {
+        return self.strike;
+    }
+
+    pub fn kind(self) -> OptionKind {
+        return self.kind;
+    }
+
+    pub fn key(self) -> ContractKey {
+        return ContractKey::new(self.expires_at, self.strike, self.kind);
+    }
+
+    pub fn bid(self) -> Cents {
+        return self.bid;
+    }
+
+    pub fn ask(self) -> Cents {
+        return self.ask;
+    }
+
+    pub fn settlement(self) -> Settlement {
+        return self.settlement;
+    }
+
+    /**
+     * Currency the contract is quoted and settled in.
+     */
+    pub fn currency(self) -> Currency {
+        return self.currency;
+    }
+
+    /**
+     * The same contract quoted in a different currency. Contracts are in US dollars unless set
+     * otherwise.
+     */
+    pub fn with_currency(self, currency: Currency) -> OptionContract {
+        return OptionContract { currency, ..self };
+    }
+
+    /**
+     * The same contract with a different settlement type. Contracts are physically settled
+     * unless set otherwise.
+     */
+    pub fn with_settlement(self, settlement: Settlement) -> OptionContract {
+        return OptionContract { settlement, ..self };
+    }
+
+    pub fn volatility_model(self) -> VolatilityModel {
+        return self.volatility_model;
+    }
+
+    /**
+     * The same contract with its volatility quoted under a different model. Contracts are
+     * lognormal unless set otherwise.
+     */
+    pub fn with_volatility_model(self, volatility_model: VolatilityModel) -> OptionContract {
+        return OptionContract {
+            volatility_model,
+            ..self
+        };
+    }
+
+    pub fn exercise_style(self) -> ExerciseStyle {
+        return self.exercise_style;
+    }
+
+    /**
+     * The same contract with a different exercise style. Contracts are European unless set
+     * otherwise.
+     */
+    pub fn with_exercise_style(self, exercise_style: ExerciseStyle) -> OptionContract {
+        return OptionContract {
+            exercise_style,
+            ..self
+        };
+    }
+
+    /**
+     * Number of times the quote has been updated with `with_quote`.
+     */
+    pub fn revision(self) -> u64 {
+        return self.revision;
+    }
+
+    /**
+     * When the quote was taken, if known.
+     */
+    pub fn quoted_at(self) -> Option<NaiveDateTime> {
+        return self.quoted_at;
+    }
+
+    pub fn with_quoted_at(self, quoted_at: NaiveDateTime) -> OptionContract {
+        return OptionContract {
+            quoted_at: Some(quoted_at),
+            ..self
+        };
+    }
+
+    /**
+     * The same contract with a new quote and the next revision.
+     */
+    pub fn with_quote(self, bid: Cents, ask: Cents) -> OptionContract {
+        return OptionContract {
+            bid,
+            ask,
+            revision: self.revision + 1,
+            ..self
+        };
+    }
+
     /**
      * Mark price
      */
@@ -50,6 +292,78 @@ impl OptionStrike {
     }
 }
 
+/**
+ * Iterator over the strikes of an expiry that have a quoted call and put, pairing the calls and
+ * puts by walking both strike-sorted lists side by side.
+ */
+struct Strikes<'a> {
+    calls: &'a [OptionContract],
+    puts: &'a [OptionContract],
+    zero_bids: bool,
+    call_index: usize,
+    put_index: usize,
+    last_paired: Option<Cents>,
+    previous: Option<Cents>,
+    pending: Option<(Cents, OptionContract, OptionContract)>,
+    started: bool,
+}
+
+impl<'a> Strikes<'a> {
+    /**
+     * The next strike with a quoted call and put. Only the first of duplicate contracts is used.
+     */
+    fn next_pair(&mut self) -> Option<(Cents, OptionContract, OptionContract)> {
+        while self.call_index < self.calls.len() && self.put_index < self.puts.len() {
+            let call = self.calls[self.call_index];
+            let put = self.puts[self.put_index];
+            let quoted = |o: OptionContract| o.bid > 0 || (self.zero_bids && o.ask > 0);
+            if !quoted(call) || self.last_paired.is_some_and(|s| call.strike <= s) {
+                self.call_index += 1;
+            } else if !quoted(put) || put.strike < call.strike {
+                self.put_index += 1;
+            } else if put.strike > call.strike {
+                self.call_index += 1;
+            } else {
+                self.call_index += 1;
+                self.put_index += 1;
+                self.last_paired = Some(call.strike);
+                return Some((call.strike, call, put));
+            }
+        }
+        return None;
+    }
+}
+
+impl<'a> Iterator for Strikes<'a> {
+    type Item = OptionStrike;
+
+    fn next(&mut self) -> Option<OptionStrike> {
+        let (price, call, put) = if self.started {
+            self.pending.take()?
+        } else {
+            self.started = true;
+            self.next_pair()?
+        };
+        self.pending = self.next_pair();
+
+        // Interval between strike prices – half the difference between the strike on either side of Ki:
+        let delta_k = match (self.previous, self.pending) {
+            (Some(prev), Some((next, _, _))) => (next - prev) / 2,
+            _ => 0,
+        };
+        self.previous = Some(price);
+        return Some(OptionStrike {
+            price,
+            call,
+            put,
+            delta_k,
+        });
+    }
+}
+
+/**
+ * The options of a single expiry. Calls and puts are each kept sorted by strike.
+ */
 #[derive(Clone, Debug)]
 pub struct OptionsByExpiryDate {
     expires_at: NaiveDateTime,
@@ -57,68 +371,177 @@ pub struct OptionsByExpiryDate {
     puts: Vec<OptionContract>,
 }
 
+/**
+ * Options controlling how the index is computed from the quotes.
+ */
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub struct IndexConfig {
+    /// Clamp marks to their no-arbitrage bounds before computing the variance.
+    pub clamp_to_bounds: bool,
+    /// How the per-strike contributions are added up.
+    pub summation: Summation,
+    /// How expiries less than a minute away are treated.
+    pub same_minute: SameMinuteExpiry,
+    /// Which out-of-the-money strikes are included.
+    pub truncation: Truncation,
+    /// Which contracts with no bid but an ask are included.
+    pub zero_bids: ZeroBidPolicy,
+    /// How the interval each strike's contribution is weighted by is measured.
+    pub intervals: StrikeIntervals,
+    /// Which strikes the intervals at the ends of the sum are measured against.
+    pub end_intervals: EndIntervals,
+    /// What to do when an expiry has too few strikes for a meaningful variance.
+    pub sparse: Option<SparseChain>,
+    /// What to do when there is no usable expiry on one side of the target maturity.
+    pub missing_term: MissingTerm,
+    /// How the near- and next-term variances are weighted towards the target maturity.
+    pub weighting: Weighting,
+}
+
+/**
+ * How the index is computed without a usable expiry on both sides of the target maturity, e.g.
+ * when the next-term expiry has no quotes or has not been listed yet.
+ */
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum MissingTerm {
+    /// Require both terms; the index is not computed (or is NaN) without them.
+    #[default]
+    Require,
+    /// Use the variance of the single usable expiry nearest the target as the index.
+    SingleExpiry,
+    /// Extrapolate linearly in total variance from the two usable expiries nearest the target,
+    /// falling back to a single expiry if there is only one.
+    Extrapolate,
+}
+
+/**
+ * How `ΔK`, the interval a strike's contribution is weighted by, is measured. Both handle grids
+ * that mix strike intervals; they differ where strikes are missing from the sum.
+ */
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum StrikeIntervals {
+    /// Half the distance between the neighbouring strikes in the sum, so strikes either side of
+    /// an unquoted one cover its interval between them. Zero at the ends.
+    #[default]
+    Span,
+    /// Half the distance between the neighbouring listed strikes, quoted or not, so an unquoted
+    /// strike leaves its interval out of the sum instead of it being spread over its neighbours.
+    ListedGrid,
+}
+
+/**
+ * Which strikes `ΔK` is measured against where truncation or a missing bid leaves strikes out
+ * of the sum.
+ */
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum EndIntervals {
+    /// The strikes with a quoted call and put, whether summed or not, as `StrikeIntervals`
+    /// describes.
+    #[default]
+    Quoted,
+    /// Only the strikes summed: their neighbours in the sum for `Span`, and at the ends of the
+    /// sum the distance to the only neighbour in it, as in the Cboe VIX methodology.
+    Summed,
+}
+
+/**
+ * The interval a strike's contribution is weighted by.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct StrikeInterval {
+    pub strike: Cents,
+    pub delta_k: Cents,
+    /// Listed strikes between this one and its neighbours in the sum, left out for lack of a
+    /// quoted call and put.
+    pub skipped: usize,
+}
+
+/**
+ * Which out-of-the-money strikes contribute to the variance.
+ */
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum Truncation {
+    /// Every strike `zero_bids` admits: by default, where both the call and put have a bid.
+    #[default]
+    ZeroBids,
+    /// As `ZeroBids`, but moving away from `K_0` no strikes are included past two consecutive
+    /// zero bids, as in the Cboe VIX methodology.
+    ConsecutiveZeroBids,
+}
+
+/**
+ * What happens to contracts with no bid but an ask. Thin chains often have wings offered but
+ * not bid, which some methodologies keep rather than discard.
+ */
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum ZeroBidPolicy {
+    /// Left out, as if they were not quoted at all.
+    #[default]
+    Drop,
+    /// Kept, marked at half the ask.
+    AskOnlyMark,
+    /// Kept, marked at half the ask, only strictly inside the strikes where two consecutive
+    /// zero bids are first found either side of `K_0`.
+    InsideTruncation,
+}
+
+/**
+ * How an expiry less than a minute away is treated, since the time to expiration is counted in
+ * whole minutes.
+ */
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum SameMinuteExpiry {
+    /// As already expired.
+    #[default]
+    Expired,
+    /// As one minute to expiration.
+    OneMinute,
+}
+
+/**
+ * A quote whose mark was outside its no-arbitrage bounds, and the mark it was clamped to.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct ClampedQuote {
+    pub contract: OptionContract,
+    pub clamped_mark: Cents,
+}
+
 impl OptionsByExpiryDate {
+    pub fn expires_at(&self) -> NaiveDateTime {
+        return self.expires_at;
+    }
+
+    pub fn calls(&self) -> &[OptionContract] {
+        return &self.calls;
+    }
+
+    pub fn puts(&self) -> &[OptionContract] {
+        return &self.puts;
+    }
+
     /**
-     * Gets options grouped and sorted by their strike price.
+     * Strikes quoted on both sides, in ascending order, without allocating.
      */
-    fn get_strikes(&self) -> Vec<OptionStrike> {
-        let mut all_options: Vec<OptionContract> = self
-            .calls
-            .clone()
-            .into_iter()
-            .chain(self.puts.clone().into_iter())
-            // filter out zero bids
-            .filter(|o| o.bid != 0)
-            .collect();
-        all_options.sort_unstable_by_key(|o| o.strike);
-
-        let mut options_by_strike: Vec<OptionStrike> = all_options
-            .into_iter()
-            .group_by(|o| o.strike)
-            .into_iter()
-            .flat_map(|(strike, options)| -> Option<OptionStrike> {
-                let options_for_strike: Vec<OptionContract> = options.collect();
-                let call = options_for_strike
-                    .clone()
-                    .into_iter()
-                    .find(|o| o.kind == OptionKind::Call);
-                let put = options_for_strike
-                    .clone()
-                    .into_iter()
-                    .find(|o| o.kind == OptionKind::Put);
-
-                return match (call, put) {
-                    (Some(c), Some(p)) => Some(OptionStrike {
-                        price: strike,
-                        call: c,
-                        put: p,
-                        delta_k: 0,
-                    }),
-                    _ => None,
-                };
-            })
-            .collect();
-        options_by_strike.sort_unstable_by_key(|s| s.price);
-
-        let mut delta_ks: HashMap<Cents, Cents> = HashMap::new();
-        for w in options_by_strike.windows(3) {
-            match (w.get(0), w.get(1), w.get(2)) {
-                (Some(prev), Some(curr), Some(next)) => {
-                    // Interval between strike prices – half the difference between the strike on either side of Ki:
-                    let delta_k = (next.price - prev.price) / 2;
-                    delta_ks.insert(curr.price, delta_k);
-                }
-                _ => {}
-            };
-        }
+    fn strikes(&self) -> Strikes<'_> {
+        return self.strikes_with(false);
+    }
 
-        return options_by_strike
-            .into_iter()
-            .map(|mut s| -> OptionStrike {
-                s.delta_k = *delta_ks.get(&s.price).unwrap_or(&0);
-                return s;
-            })
-            .collect();
+    /**
+     * Like `strikes`, also pairing contracts with no bid but an ask if `zero_bids`.
+     */
+    fn strikes_with(&self, zero_bids: bool) -> Strikes<'_> {
+        return Strikes {
+            calls: &self.calls,
+            puts: &self.puts,
+            zero_bids,
+            call_index: 0,
+            put_index: 0,
+            last_paired: None,
+            previous: None,
+            pending: None,
+            started: false,
+        };
     }
 
     /**
@@ -128,6 +551,33 @@ impl OptionsByExpiryDate {
         return self.expires_at.signed_duration_since(now).num_minutes() as f64;
     }
 
+    /**
+     * Computes the number of minutes until the option's expiration, or `None` if it has expired
+     * as of `now` under `same_minute`.
+     */
+    pub fn minutes_to_expiration_with(
+        &self,
+        now: NaiveDateTime,
+        same_minute: SameMinuteExpiry,
+    ) -> Option<Percentage> {
+        let remaining = self.expires_at.signed_duration_since(now);
+        if remaining.num_seconds() <= 0 {
+            return None;
+        }
+        return match (remaining.num_minutes(), same_minute) {
+            (0, SameMinuteExpiry::Expired) => None,
+            (0, SameMinuteExpiry::OneMinute) => Some(1.0),
+            (minutes, _) => Some(minutes as f64),
+        };
+    }
+
+    /**
+     * Whether the options have expired as of `now` under `same_minute`.
+     */
+    pub fn is_expired(&self, now: NaiveDateTime, same_minute: SameMinuteExpiry) -> bool {
+        return self.minutes_to_expiration_with(now, same_minute).is_none();
+    }
+
     /**
      * Computes the time to the option's expiration as a percentage of the remaining year.
      */
@@ -135,15 +585,54 @@ impl OptionsByExpiryDate {
         return self.minutes_to_expiration(now) / 525600.0;
     }
 
+    /**
+     * Computes the number of minutes the exchange is open until the option's expiration.
+     */
+    pub fn trading_minutes_to_expiration<C: HolidayCalendar + ?Sized>(
+        &self,
+        now: NaiveDateTime,
+        calendar: &C,
+    ) -> Percentage {
+        return calendar.trading_minutes_between(now, self.expires_at) as f64;
+    }
+
+    /**
+     * Computes the trading time to the option's expiration as a percentage of the trading year,
+     * so that weekends and holidays carry no time.
+     */
+    pub fn trading_time_to_expiration<C: HolidayCalendar + ?Sized>(
+        &self,
+        now: NaiveDateTime,
+        calendar: &C,
+    ) -> Percentage {
+        return self.trading_minutes_to_expiration(now, calendar)
+            / calendar.trading_minutes_per_year();
+    }
+
+    /**
+     * Continuously compounded dividend yield implied by the forward price and `spot`.
+     */
+    pub fn implied_dividend_yield(
+        &self,
+        spot: Cents,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+    ) -> Percentage {
+        let t = self.time_to_expiration(now);
+        let fp = self.forward_price(risk_free_rate, now);
+        if t <= 0.0 || fp <= 0 || spot <= 0 {
+            return 0.0;
+        }
+        return risk_free_rate - (fp as f64 / spot as f64).ln() / t;
+    }
+
     /**
      * Computes the implied forward price.
      */
     pub fn forward_price(&self, risk_free_rate: f64, now: NaiveDateTime) -> Cents {
         let interest = (risk_free_rate * self.time_to_expiration(now)).exp();
-        let mut strikes = self.get_strikes();
         // we want to find the ATM option
-        strikes.sort_unstable_by_key(|k| k.call_put_difference().abs());
-        let atm = strikes.first();
+        let atm = self.strikes().min_by_key(|k| k.call_put_difference().abs());
         return atm
             .map(|strike| -> Cents {
                 strike.price + (interest * strike.call_put_difference() as f64) as Cents
@@ -151,68 +640,434 @@ impl OptionsByExpiryDate {
             .unwrap_or(0);
     }
 
+    /**
+     * Clamps every quoted mark to its no-arbitrage bounds, given the implied forward price:
+     * `e^{-rT} max(F - K, 0) <= call <= e^{-rT} F` and `e^{-rT} max(K - F, 0) <= put <= e^{-rT} K`.
+     *
+     * Clamped contracts are quoted at their bound on both sides, and are returned alongside the
+     * clamped options.
+     */
+    pub fn clamp_to_bounds(
+        &self,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+    ) -> (OptionsByExpiryDate, Vec<ClampedQuote>) {
+        let discount = (-risk_free_rate * self.time_to_expiration(now)).exp();
+        let fp = self.forward_price(risk_free_rate, now) as f64;
+        let mut clamped_quotes = vec![];
+
+        let mut clamp = |options: &[OptionContract]| -> Vec<OptionContract> {
+            return options
+                .iter()
+                .map(|o| -> OptionContract {
+                    if o.bid == 0 {
+                        return *o;
+                    }
+                    let strike = o.strike as f64;
+                    let (lower, upper) = match o.kind {
+                        OptionKind::Call => ((fp - strike).max(0.0), fp),
+                        OptionKind::Put => ((strike - fp).max(0.0), strike),
+                    };
+                    let lower = (discount * lower).ceil() as Cents;
+                    let upper = (discount * upper).floor() as Cents;
+                    let mark = o.mark();
+                    let clamped_mark = if mark < lower {
+                        lower
+                    } else if mark > upper {
+                        upper
+                    } else {
+                        return *o;
+                    };
+                    clamped_quotes.push(ClampedQuote {
+                        contract: *o,
+                        clamped_mark,
+                    });
+                    return OptionContract {
+                        bid: clamped_mark,
+                        ask: clamped_mark,
+                        ..*o
+                    };
+                })
+                .collect();
+        };
+
+        let clamped = OptionsByExpiryDate {
+            expires_at: self.expires_at,
+            calls: clamp(&self.calls),
+            puts: clamp(&self.puts),
+        };
+        return (clamped, clamped_quotes);
+    }
+
     /**
      * \sigma^2 from the VIX whitepaper
+     *
+     * Contributions are summed in ascending strike order, so the result is reproducible
+     * bit-for-bit regardless of the order the quotes were given in.
      */
     pub fn variance(&self, risk_free_rate: f64, now: NaiveDateTime) -> Percentage {
+        return self
+            .variance_with(
+                risk_free_rate,
+                now,
+                self.time_to_expiration(now),
+                &IndexConfig::default(),
+                strike_contribution,
+            )
+            .variance;
+    }
+
+    /**
+     * \sigma^2 from the VIX whitepaper, reusing the contributions of strikes whose quotes have
+     * not changed since they were stored in `cache`.
+     */
+    pub fn variance_cached(
+        &self,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+        cache: &mut ContributionCache,
+    ) -> Percentage {
         let t = self.time_to_expiration(now);
+        return self
+            .variance_with(
+                risk_free_rate,
+                now,
+                t,
+                &IndexConfig::default(),
+                |option, delta_k| {
+                    return cache.get_or_insert(option, delta_k, strike_contribution);
+                },
+            )
+            .variance;
+    }
+
+    /**
+     * Gamma swap variance: the fair strike, in variance terms, of a swap on realized variance
+     * weighted by the underlying's price relative to the forward. It is the whitepaper's sum
+     * with each strike weighted by `ΔK / (K F)` rather than `ΔK / K²`, so it leans less on the
+     * downside puts than `variance` does.
+     */
+    pub fn gamma_variance(&self, risk_free_rate: f64, now: NaiveDateTime) -> Percentage {
+        let fp = self.forward_price(risk_free_rate, now) as f64;
+        return self
+            .variance_with(
+                risk_free_rate,
+                now,
+                self.time_to_expiration(now),
+                &IndexConfig::default(),
+                |option, delta_k| {
+                    return strike_contribution(option, delta_k) * option.strike as f64 / fp;
+                },
+            )
+            .variance;
+    }
+
+    fn variance_with<F: FnMut(&OptionContract, Cents) -> f64>(
+        &self,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+        t: f64,
+        config: &IndexConfig,
+        mut contribution: F,
+    ) -> VarianceEstimate {
         let risk_free_interest = (risk_free_rate * t).exp();
-        let strikes = self.get_strikes();
         let fp = self.forward_price(risk_free_rate, now);
+        let zero_bids = config.zero_bids != ZeroBidPolicy::Drop;
 
-        let (mut below_and_k, above): (Vec<OptionStrike>, Vec<OptionStrike>) =
-            strikes.into_iter().partition(|x| (*x).price < fp);
-
-        // The highest below the forward price is K
-        below_and_k.sort_unstable_by_key(|k| -k.price);
-        let k = below_and_k.get(0);
-        let k_0 = k.map(|s| s.price).unwrap_or(0);
+        // The highest strike below the forward price is K_0
+        let k_0 = self
+            .strikes_with(zero_bids)
+            .map(|s| s.price)
+            .take_while(|price| *price < fp)
+            .last()
+            .unwrap_or(0);
+        let (lowest, highest) = match config.truncation {
+            Truncation::ZeroBids => (Cents::MIN, Cents::MAX),
+            Truncation::ConsecutiveZeroBids => self.truncation_bounds(k_0),
+        };
+        let (zero_bid_lowest, zero_bid_highest) = match config.zero_bids {
+            ZeroBidPolicy::InsideTruncation => self.truncation_bounds(k_0),
+            _ => (Cents::MIN, Cents::MAX),
+        };
 
-        let below = below_and_k.get(1..).unwrap_or(&[]);
+        let listed = match config.intervals {
+            StrikeIntervals::Span => vec![],
+            StrikeIntervals::ListedGrid => self.listed_strikes(),
+        };
 
-        // find all out of the money options + the atm option
-        let selected_options = below
-            .into_iter()
-            .map(|s| (s.put, s.delta_k))
-            .chain(above.into_iter().map(|s| (s.call, s.delta_k)))
-            .chain(
-                k.into_iter()
-                    .flat_map(|s| vec![(s.call, s.delta_k), (s.put, s.delta_k)]),
-            )
-            .collect::<Vec<(OptionContract, Cents)>>();
-
-        let contributions: f64 = selected_options
-            .into_iter()
-            .map(|(option, delta_k)| -> f64 {
-                let strike_dollars = option.strike as f64 / 100.0;
-                return (delta_k as f64 / 100.0) / (strike_dollars * strike_dollars)
-                    * (option.mark() as f64 / 100.0)
-                    * risk_free_interest;
+        // out of the money puts below K_0, calls above it, and both at K_0
+        let mut estimate = VarianceEstimate::new(0.0, 0);
+        let mut spreads = SpreadAccumulator::default();
+        let mut dropped = 0;
+        let mut strikes = self
+            .strikes_with(zero_bids)
+            .filter(|s| {
+                // by the bids of the contracts summed: the out-of-the-money one, or both at K_0
+                let bid = (s.price > k_0 || s.put.bid > 0) && (s.price < k_0 || s.call.bid > 0);
+                let included = s.price > lowest
+                    && s.price < highest
+                    && (bid || (s.price > zero_bid_lowest && s.price < zero_bid_highest));
+                if !included {
+                    dropped += 1;
+                }
+                return included;
             })
-            .sum();
+            .peekable();
+        // under `EndIntervals::Summed` the intervals stop at the strikes summed rather than
+        // reaching over excluded ones
+        let mut previous: Option<Cents> = None;
+        let intervals = std::iter::from_fn(|| {
+            let s = strikes.next()?;
+            let next = strikes.peek().map(|n| n.price);
+            let delta_k = match (config.end_intervals, previous, next) {
+                (EndIntervals::Summed, Some(low), Some(high))
+                    if config.intervals == StrikeIntervals::Span =>
+                {
+                    (high - low) / 2
+                }
+                (EndIntervals::Summed, None, Some(high)) => high - s.price,
+                (EndIntervals::Summed, Some(low), None) => s.price - low,
+                (EndIntervals::Summed, None, None) => 0,
+                _ => match config.intervals {
+                    StrikeIntervals::Span => s.delta_k,
+                    StrikeIntervals::ListedGrid => listed_interval(&listed, s.price),
+                },
+            };
+            previous = Some(s.price);
+            return Some((s, delta_k));
+        });
+        let contributions = config.summation.sum(intervals.flat_map(|(s, delta_k)| {
+            estimate.strikes += 1;
+            estimate.lowest_strike.get_or_insert(s.price);
+            estimate.highest_strike = Some(s.price);
+            let put = if s.price <= k_0 {
+                spreads.record(&s.put);
+                Some(contribution(&s.put, delta_k) * risk_free_interest)
+            } else {
+                None
+            };
+            let call = if s.price >= k_0 {
+                spreads.record(&s.call);
+                Some(contribution(&s.call, delta_k) * risk_free_interest)
+            } else {
+                None
+            };
+            return put.into_iter().chain(call);
+        }));
 
         let a = fp as f64 / k_0 as f64 - 1.0;
-        return (2.0 * contributions - a * a) / t;
+        estimate.variance = (2.0 * contributions - a * a) / t;
+        estimate.spreads = spreads.finish();
+        estimate.strikes_dropped = dropped;
+        return estimate;
+    }
+
+    /**
+     * Every strike listed for either kind, quoted or not, ascending.
+     */
+    fn listed_strikes(&self) -> Vec<Cents> {
+        let mut listed: Vec<Cents> = self
+            .calls
+            .iter()
+            .chain(self.puts.iter())
+            .map(|o| o.strike)
+            .collect();
+        listed.sort_unstable();
+        listed.dedup();
+        return listed;
+    }
+
+    /**
+     * The interval each strike with a quoted call and put is weighted by under `intervals`, and
+     * how many listed strikes its neighbours in the sum skip over, for auditing irregular or
+     * gappy strike grids.
+     */
+    pub fn strike_intervals(&self, intervals: StrikeIntervals) -> Vec<StrikeInterval> {
+        let listed = self.listed_strikes();
+        let between = |low: Cents, high: Cents| -> usize {
+            return listed.iter().filter(|k| **k > low && **k < high).count();
+        };
+        let used: Vec<OptionStrike> = self.strikes().collect();
+        return used
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let low = if i > 0 { used[i - 1].price } else { s.price };
+                let high = used.get(i + 1).map(|n| n.price).unwrap_or(s.price);
+                return StrikeInterval {
+                    strike: s.price,
+                    delta_k: match intervals {
+                        StrikeIntervals::Span => s.delta_k,
+                        StrikeIntervals::ListedGrid => listed_interval(&listed, s.price),
+                    },
+                    skipped: between(low, s.price) + between(s.price, high),
+                };
+            })
+            .collect();
+    }
+
+    /**
+     * The strikes at which two consecutive zero bids are first found, walking down the puts and
+     * up the calls from `k_0`. Strikes at or beyond them are excluded.
+     */
+    fn truncation_bounds(&self, k_0: Cents) -> (Cents, Cents) {
+        let first_after_two_zero_bids = |contracts: &mut dyn Iterator<Item = &OptionContract>| {
+            let mut previous_zero = false;
+            for o in contracts {
+                let zero = o.bid == 0;
+                if zero && previous_zero {
+                    return Some(o.strike);
+                }
+                previous_zero = zero;
+            }
+            return None;
+        };
+        let lowest =
+            first_after_two_zero_bids(&mut self.puts.iter().rev().filter(|o| o.strike < k_0));
+        let highest = first_after_two_zero_bids(&mut self.calls.iter().filter(|o| o.strike > k_0));
+        return (lowest.unwrap_or(Cents::MIN), highest.unwrap_or(Cents::MAX));
     }
+
+    /**
+     * \sigma^2 from the VIX whitepaper, computed according to `config`.
+     *
+     * Expired options have no variance left to measure, so for those the result is NaN rather
+     * than the infinities that dividing by a non-positive time would give.
+     */
+    pub fn variance_with_config(
+        &self,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+        config: &IndexConfig,
+    ) -> Percentage {
+        return self
+            .variance_configured(risk_free_rate, now, config, None)
+            .variance;
+    }
+
+    /**
+     * Like `variance_with_config`, reusing unchanged contributions stored in `cache`.
+     */
+    pub fn variance_cached_with_config(
+        &self,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+        config: &IndexConfig,
+        cache: &mut ContributionCache,
+    ) -> Percentage {
+        return self
+            .variance_configured(risk_free_rate, now, config, Some(cache))
+            .variance;
+    }
+
+    /**
+     * \sigma^2 from the VIX whitepaper computed according to `config`, with the strikes and
+     * quotes it was computed from and any sparse-chain fallback that was applied.
+     */
+    pub fn variance_estimate(
+        &self,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+        config: &IndexConfig,
+    ) -> VarianceEstimate {
+        return self.variance_configured(risk_free_rate, now, config, None);
+    }
+
+    fn variance_configured(
+        &self,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+        config: &IndexConfig,
+        mut cache: Option<&mut ContributionCache>,
+    ) -> VarianceEstimate {
+        let t = match self.minutes_to_expiration_with(now, config.same_minute) {
+            Some(minutes) => minutes / 525600.0,
+            None => return VarianceEstimate::new(f64::NAN, 0),
+        };
+        let estimate = self.variance_at(risk_free_rate, now, t, config, cache.as_deref_mut());
+        return match config.sparse {
+            Some(sparse) if estimate.strikes < sparse.min_strikes => {
+                sparse.apply(self, risk_free_rate, now, t, config, estimate, cache)
+            }
+            _ => estimate,
+        };
+    }
+
+    fn variance_at(
+        &self,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+        t: f64,
+        config: &IndexConfig,
+        mut cache: Option<&mut ContributionCache>,
+    ) -> VarianceEstimate {
+        let contribution = |option: &OptionContract, delta_k: Cents| -> f64 {
+            return match cache.as_mut() {
+                Some(cache) => cache.get_or_insert(option, delta_k, strike_contribution),
+                None => strike_contribution(option, delta_k),
+            };
+        };
+        if config.clamp_to_bounds {
+            let (clamped, _) = self.clamp_to_bounds(risk_free_rate, now);
+            return clamped.variance_with(risk_free_rate, now, t, config, contribution);
+        }
+        return self.variance_with(risk_free_rate, now, t, config, contribution);
+    }
+}
+
+/**
+ * Half the distance between the listed strikes either side of `strike`, or the distance to the
+ * only neighbour at the ends of the listing, as in the VIX whitepaper.
+ */
+fn listed_interval(listed: &[Cents], strike: Cents) -> Cents {
+    let i = match listed.binary_search(&strike) {
+        Ok(i) => i,
+        Err(_) => return 0,
+    };
+    let lower = if i > 0 { Some(listed[i - 1]) } else { None };
+    return match (lower, listed.get(i + 1)) {
+        (Some(lower), Some(upper)) => (upper - lower) / 2,
+        (Some(lower), None) => strike - lower,
+        (None, Some(upper)) => upper - strike,
+        (None, None) => 0,
+    };
 }
 
+/**
+ * Contribution of a single option to the variance before discounting: `ΔK / K^2 * Q(K)`.
+ */
+fn strike_contribution(option: &OptionContract, delta_k: Cents) -> f64 {
+    let strike_dollars = option.strike as f64 / 100.0;
+    return (delta_k as f64 / 100.0) / (strike_dollars * strike_dollars)
+        * (option.mark() as f64 / 100.0);
+}
+
+/**
+ * Groups contracts by expiration date. The result does not depend on the order of `options`,
+ * other than which of several duplicate contracts comes first.
+ */
 pub fn group_options_by_expiry(
     options: &[OptionContract],
 ) -> HashMap<NaiveDateTime, OptionsByExpiryDate> {
     let mut options_by_expiry: HashMap<NaiveDateTime, OptionsByExpiryDate> = HashMap::new();
 
-    for (expires_at, options_for_expiry) in
-        options.into_iter().group_by(|o| o.expires_at).into_iter()
-    {
-        let (calls, puts) = options_for_expiry.partition(|o| o.kind == OptionKind::Call);
-        options_by_expiry.insert(
-            expires_at,
-            OptionsByExpiryDate {
-                expires_at: expires_at,
-                calls: calls,
-                puts: puts,
-            },
-        );
+    for o in options.iter() {
+        let options_for_expiry =
+            options_by_expiry
+                .entry(o.expires_at)
+                .or_insert_with(|| OptionsByExpiryDate {
+                    expires_at: o.expires_at,
+                    calls: vec![],
+                    puts: vec![],
+                });
+        match o.kind {
+            OptionKind::Call => options_for_expiry.calls.push(*o),
+            OptionKind::Put => options_for_expiry.puts.push(*o),
+        }
+    }
+    for options_for_expiry in options_by_expiry.values_mut() {
+        options_for_expiry.calls.sort_by_key(|o| o.strike);
+        options_for_expiry.puts.sort_by_key(|o| o.strike);
     }
     return options_by_expiry;
 }
@@ -224,23 +1079,213 @@ pub fn compute_vix(
     next_term_risk_free_rate: f64,
     now: NaiveDateTime,
 ) -> Percentage {
-    let t1 = near_term.time_to_expiration(now);
-    let n_t1 = near_term.minutes_to_expiration(now);
-    let s1_sq = near_term.variance(near_term_risk_free_rate, now);
-    let t2 = next_term.time_to_expiration(now);
-    let n_t2 = next_term.minutes_to_expiration(now);
-    let s2_sq = next_term.variance(next_term_risk_free_rate, now);
-    let n_30 = (30 * 24 * 60) as f64;
+    return compute_vix_with_config(
+        near_term,
+        next_term,
+        near_term_risk_free_rate,
+        next_term_risk_free_rate,
+        now,
+        &IndexConfig::default(),
+    );
+}
+
+pub fn compute_vix_with_config(
+    near_term: &OptionsByExpiryDate,
+    next_term: &OptionsByExpiryDate,
+    near_term_risk_free_rate: f64,
+    next_term_risk_free_rate: f64,
+    now: NaiveDateTime,
+    config: &IndexConfig,
+) -> Percentage {
+    return compute_vix_estimate(
+        near_term,
+        next_term,
+        near_term_risk_free_rate,
+        next_term_risk_free_rate,
+        now,
+        config,
+    )
+    .value;
+}
+
+/**
+ * Like `compute_vix_with_config`, with the quality of each term's variance.
+ */
+pub fn compute_vix_estimate(
+    near_term: &OptionsByExpiryDate,
+    next_term: &OptionsByExpiryDate,
+    near_term_risk_free_rate: f64,
+    next_term_risk_free_rate: f64,
+    now: NaiveDateTime,
+    config: &IndexConfig,
+) -> IndexEstimate {
+    #[cfg(feature = "metrics")]
+    let _timer = metrics::Timer::start(&metrics::global().computation_seconds);
+    let n_t1 = near_term.minutes_to_expiration_with(now, config.same_minute);
+    let s1 = near_term.variance_estimate(near_term_risk_free_rate, now, config);
+    let n_t2 = next_term.minutes_to_expiration_with(now, config.same_minute);
+    let s2 = next_term.variance_estimate(next_term_risk_free_rate, now, config);
+    let estimate = combine_terms(
+        Term::new(near_term, n_t1, s1),
+        Term::new(next_term, n_t2, s2),
+        now,
+        (30 * 24 * 60) as f64,
+        config,
+    );
+    #[cfg(feature = "metrics")]
+    metrics::global().record_index(&estimate);
+    return estimate;
+}
+
+/**
+ * One side of the interpolation: an expiry, its minutes to expiration, and its variance.
+ */
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Term {
+    pub(crate) expires_at: NaiveDateTime,
+    pub(crate) minutes: Option<f64>,
+    pub(crate) variance: VarianceEstimate,
+}
+
+impl Term {
+    pub(crate) fn new(
+        expiry: &OptionsByExpiryDate,
+        minutes: Option<f64>,
+        variance: VarianceEstimate,
+    ) -> Term {
+        return Term {
+            expires_at: expiry.expires_at,
+            minutes,
+            variance,
+        };
+    }
+
+    fn usable(&self) -> bool {
+        return self.minutes.is_some()
+            && self.variance.strikes > 0
+            && self.variance.variance.is_finite();
+    }
+}
+
+/**
+ * The index at `n_target` minutes from two terms, weighted as `config.weighting` says.
+ *
+ * Unless `config.missing_term` requires both, a term without minutes, strikes, or a finite
+ * variance is replaced by the other. The estimate records when the value came from a single
+ * expiry or was extrapolated beyond the terms, and the weights when it came from both.
+ */
+pub(crate) fn combine_terms(
+    near: Term,
+    next: Term,
+    now: NaiveDateTime,
+    n_target: f64,
+    config: &IndexConfig,
+) -> IndexEstimate {
+    let (first, second) = match (config.missing_term, near.usable(), next.usable()) {
+        (MissingTerm::Require, _, _) => (near, next),
+        (_, true, false) => (near, near),
+        (_, false, true) => (next, next),
+        _ => (near, next),
+    };
+    let (n_t1, n_t2) = (
+        first.minutes.unwrap_or(f64::NAN),
+        second.minutes.unwrap_or(f64::NAN),
+    );
+
+    let (value, term_fallback, weights) = if n_t1 == n_t2 {
+        (
+            first.variance.variance.sqrt() * 100.0,
+            Some(MissingTerm::SingleExpiry),
+            None,
+        )
+    } else {
+        let weights = config.weighting.weights(&WeightingInputs {
+            now,
+            near_expires_at: first.expires_at,
+            next_expires_at: second.expires_at,
+            near_minutes: n_t1,
+            next_minutes: n_t2,
+            target_minutes: n_target,
+        });
+        let extrapolated = n_target < n_t1.min(n_t2) || n_target > n_t1.max(n_t2);
+        let interpolate = constant_maturity_index;
+        #[cfg(feature = "high-precision")]
+        let interpolate = if config.summation == Summation::Extended {
+            constant_maturity_index_extended
+        } else {
+            interpolate
+        };
+        (
+            interpolate(
+                n_t1,
+                first.variance.variance,
+                n_t2,
+                second.variance.variance,
+                n_target,
+                weights,
+            ),
+            if extrapolated {
+                Some(MissingTerm::Extrapolate)
+            } else {
+                None
+            },
+            Some(weights),
+        )
+    };
+    return IndexEstimate {
+        value,
+        near_term: near.variance,
+        next_term: next.variance,
+        term_fallback,
+        weights,
+    };
+}
+
+/**
+ * Weights the near- and next-term total variances to `n_target` minutes and annualizes the
+ * result, as a volatility in percent. NaN if the weighted variance is negative, which
+ * extrapolating can make it.
+ */
+pub(crate) fn constant_maturity_index(
+    n_t1: f64,
+    s1_sq: f64,
+    n_t2: f64,
+    s2_sq: f64,
+    n_target: f64,
+    weights: InterpolationWeights,
+) -> Percentage {
+    let t1 = n_t1 / 525600.0;
+    let t2 = n_t2 / 525600.0;
     let n_365 = (365 * 24 * 60) as f64;
 
-    return ((t1 * s1_sq * (n_t2 - n_30) / (n_t2 - n_t1)
-        + t2 * s2_sq * (n_30 - n_t1) / (n_t2 - n_t1))
-        * n_365
-        / n_30)
-        .powf(0.5)
+    return ((t1 * s1_sq * weights.near + t2 * s2_sq * weights.next) * n_365 / n_target).powf(0.5)
         * 100.0;
 }
 
+/**
+ * `constant_maturity_index` in extended precision.
+ */
+#[cfg(feature = "high-precision")]
+pub(crate) fn constant_maturity_index_extended(
+    n_t1: f64,
+    s1_sq: f64,
+    n_t2: f64,
+    s2_sq: f64,
+    n_target: f64,
+    weights: InterpolationWeights,
+) -> Percentage {
+    use math::extended::Extended;
+
+    let minutes_per_year = (365 * 24 * 60) as f64;
+    let near = Extended::new(n_t1) / minutes_per_year * s1_sq * weights.near;
+    let next = Extended::new(n_t2) / minutes_per_year * s2_sq * weights.next;
+    let variance = (near + next) * minutes_per_year / n_target;
+    if variance.hi < 0.0 {
+        return f64::NAN;
+    }
+    return (variance.sqrt() * 100.0).to_f64();
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -0,0 +1,170 @@
+//! Dupire local volatility from the implied volatility surface.
+//!
+//! Differentiating quoted prices directly amplifies every bid/ask error, so each expiry's smile
+//! is first smoothed with SVI. Total implied variance is then interpolated linearly in time at
+//! fixed log moneyness, and Dupire's formula is applied in Gatheral's total variance form:
+//!
+//! `σ_loc² = (∂w/∂T) / (1 - k/w ∂w/∂k + (-1/4 - 1/w + k²/w²) (∂w/∂k)² / 4 + ∂²w/∂k² / 2)`
+
+use crate::analytics::ChainAnalytics;
+use crate::models::Svi;
+use crate::Cents;
+
+/**
+ * Smallest denominator of Dupire's formula, where butterfly arbitrage in the fitted smile would
+ * otherwise make it vanish or turn negative.
+ */
+const MIN_DENOMINATOR: f64 = 1e-4;
+
+/**
+ * One expiry of the surface: its SVI fit and forward.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct SurfaceSlice {
+    pub time_to_expiration: f64,
+    pub forward_price: f64,
+    pub svi: Svi,
+}
+
+/**
+ * Implied total variance by log moneyness and time, from SVI fits of each expiry.
+ */
+#[derive(PartialEq, Clone, Debug)]
+pub struct ImpliedSurface {
+    pub spot: f64,
+    /// Sorted by time to expiration.
+    pub slices: Vec<SurfaceSlice>,
+}
+
+impl ImpliedSurface {
+    /**
+     * Fits SVI to the out-of-the-money smile of every unexpired expiry. `None` if no expiry
+     * could be fitted.
+     */
+    pub fn from_analytics(analytics: &ChainAnalytics) -> Option<ImpliedSurface> {
+        let mut slices: Vec<SurfaceSlice> = analytics
+            .expiries
+            .iter()
+            .filter(|e| e.time_to_expiration > 0.0 && e.forward_price > 0)
+            .flat_map(|e| {
+                let svi = Svi::calibrate(&e.smile(), e.time_to_expiration)?;
+                return Some(SurfaceSlice {
+                    time_to_expiration: e.time_to_expiration,
+                    forward_price: e.forward_price as f64,
+                    svi,
+                });
+            })
+            .collect();
+        if slices.is_empty() {
+            return None;
+        }
+        slices.sort_by(|a, b| {
+            a.time_to_expiration
+                .partial_cmp(&b.time_to_expiration)
+                .unwrap()
+        });
+        return Some(ImpliedSurface {
+            spot: analytics.spot as f64,
+            slices,
+        });
+    }
+
+    /**
+     * The slices either side of `t`, with the weight of the later one. Before the first slice
+     * and after the last the nearest is used for both, scaled in time, which keeps implied
+     * volatility flat there.
+     */
+    fn bracket(&self, t: f64) -> (&SurfaceSlice, &SurfaceSlice, f64) {
+        let first = &self.slices[0];
+        let last = &self.slices[self.slices.len() - 1];
+        if t <= first.time_to_expiration {
+            return (first, first, 0.0);
+        }
+        if t >= last.time_to_expiration {
+            return (last, last, 0.0);
+        }
+        let i = self.slices.partition_point(|s| s.time_to_expiration <= t);
+        let (before, after) = (&self.slices[i - 1], &self.slices[i]);
+        let weight = (t - before.time_to_expiration)
+            / (after.time_to_expiration - before.time_to_expiration);
+        return (before, after, weight);
+    }
+
+    /**
+     * Total implied variance at log moneyness `k` (against the forward to `t`) and `t` years.
+     */
+    pub fn total_variance(&self, k: f64, t: f64) -> f64 {
+        let (before, after, weight) = self.bracket(t);
+        if std::ptr::eq(before, after) {
+            return before.svi.total_variance(k) * t / before.time_to_expiration;
+        }
+        return (1.0 - weight) * before.svi.total_variance(k)
+            + weight * after.svi.total_variance(k);
+    }
+
+    /**
+     * Forward price to `t` years, interpolated log-linearly from the spot and the slices.
+     */
+    pub fn forward_price(&self, t: f64) -> f64 {
+        let mut previous = (0.0, self.spot);
+        for slice in self.slices.iter() {
+            let (t_1, f_1) = (slice.time_to_expiration, slice.forward_price);
+            if t <= t_1 {
+                let weight = (t - previous.0) / (t_1 - previous.0);
+                return previous.1 * (weight * (f_1 / previous.1).ln()).exp();
+            }
+            previous = (t_1, f_1);
+        }
+        // carry on at the last slice's rate
+        let rate = (previous.1 / self.spot).ln() / previous.0;
+        return self.spot * (rate * t).exp();
+    }
+
+    /**
+     * Implied volatility at `strike` (in cents) for `t` years.
+     */
+    pub fn implied_volatility(&self, strike: Cents, t: f64) -> f64 {
+        let k = (strike as f64 / self.forward_price(t)).ln();
+        return (self.total_variance(k, t).max(0.0) / t).sqrt();
+    }
+
+    /**
+     * Dupire local volatility with the underlying at `price` (in cents) `t` years from now.
+     * Derivatives are central differences of the smooth fits; negative calendar spreads are
+     * floored at zero variance and the denominator at `MIN_DENOMINATOR`.
+     */
+    pub fn local_volatility(&self, price: f64, t: f64) -> f64 {
+        let t = t.max(1e-6);
+        let k = (price / self.forward_price(t)).ln();
+        let (dk, dt) = (1e-3, (1e-3 * t).max(1e-5));
+        let w = self.total_variance(k, t);
+        let w_up = self.total_variance(k + dk, t);
+        let w_down = self.total_variance(k - dk, t);
+        let dw_dk = (w_up - w_down) / (2.0 * dk);
+        let d2w_dk2 = (w_up - 2.0 * w + w_down) / (dk * dk);
+        let dw_dt = (self.total_variance(k, t + dt) - self.total_variance(k, (t - dt).max(0.0)))
+            / (t + dt - (t - dt).max(0.0));
+        if w <= 0.0 {
+            return (dw_dt.max(0.0)).sqrt();
+        }
+        let denominator = 1.0 - k / w * dw_dk
+            + 0.25 * (-0.25 - 1.0 / w + k * k / (w * w)) * dw_dk * dw_dk
+            + 0.5 * d2w_dk2;
+        return (dw_dt.max(0.0) / denominator.max(MIN_DENOMINATOR)).sqrt();
+    }
+
+    /**
+     * Local volatilities on a grid: one row per time, one column per price.
+     */
+    pub fn local_volatility_grid(&self, prices: &[f64], times: &[f64]) -> Vec<Vec<f64>> {
+        return times
+            .iter()
+            .map(|t| {
+                prices
+                    .iter()
+                    .map(|p| self.local_volatility(*p, *t))
+                    .collect()
+            })
+            .collect();
+    }
+}
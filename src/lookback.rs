@@ -0,0 +1,157 @@
+//! Lookback options.
+//!
+//! A floating strike lookback is struck at the best price the underlying reached (its minimum
+//! for a call, maximum for a put), and a fixed strike lookback pays on that best price against
+//! a fixed strike. Under Black–Scholes with continuous monitoring both have closed forms:
+//! Goldman, Sosin and Gatto (1979) for floating strikes and Conze and Viswanathan (1991) for
+//! fixed ones. Discretely monitored lookbacks are worth less; price them with `MonteCarlo`, for
+//! which `Lookback` is a `Payoff`.
+
+use crate::math::norm_cdf;
+use crate::montecarlo::Payoff;
+use crate::pricing::years_until;
+use crate::{Cents, OptionKind};
+use chrono::prelude::*;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum LookbackStrike {
+    /// Struck at the minimum price for a call and the maximum for a put.
+    Floating,
+    /// Pays the maximum price less the strike for a call, and the strike less the minimum
+    /// for a put.
+    Fixed(Cents),
+}
+
+/**
+ * A European lookback option.
+ */
+#[derive(new, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Lookback {
+    pub kind: OptionKind,
+    pub strike: LookbackStrike,
+    pub expires_at: NaiveDateTime,
+}
+
+impl Lookback {
+    /**
+     * Closed-form price as of `now`, with `extreme` the lowest price observed so far for a
+     * floating call or fixed put and the highest for a floating put or fixed call (the spot for
+     * a new option).
+     */
+    pub fn price(
+        &self,
+        spot: Cents,
+        extreme: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        now: NaiveDateTime,
+    ) -> Cents {
+        return lookback_price(
+            self.kind,
+            self.strike,
+            spot,
+            extreme,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            years_until(self.expires_at, now),
+        );
+    }
+}
+
+/**
+ * Monitored at every price of the path, including the spot it starts from.
+ */
+impl Payoff for Lookback {
+    fn payoff(&self, path: &[f64]) -> f64 {
+        let last = path[path.len() - 1];
+        let low = path.iter().cloned().fold(f64::INFINITY, f64::min);
+        let high = path.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        return match (self.kind, self.strike) {
+            (OptionKind::Call, LookbackStrike::Floating) => last - low,
+            (OptionKind::Put, LookbackStrike::Floating) => high - last,
+            (OptionKind::Call, LookbackStrike::Fixed(strike)) => (high - strike as f64).max(0.0),
+            (OptionKind::Put, LookbackStrike::Fixed(strike)) => (strike as f64 - low).max(0.0),
+        };
+    }
+}
+
+/**
+ * Continuously monitored lookback price with a continuous dividend yield, rounded to the
+ * nearest cent. `extreme` is the running minimum or maximum as for `Lookback::price`, and `t`
+ * is in years; at expiration the price is the payoff.
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn lookback_price(
+    kind: OptionKind,
+    strike: LookbackStrike,
+    spot: Cents,
+    extreme: Cents,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    t: f64,
+) -> Cents {
+    let (s, m, r) = (spot as f64, extreme as f64, risk_free_rate);
+    // the running extreme includes the spot
+    let m = match (kind, strike) {
+        (OptionKind::Call, LookbackStrike::Floating)
+        | (OptionKind::Put, LookbackStrike::Fixed(_)) => m.min(s),
+        _ => m.max(s),
+    };
+    let t = t.max(0.0);
+    let std_dev = volatility * t.sqrt();
+    if std_dev <= 0.0 {
+        let price = match (kind, strike) {
+            (OptionKind::Call, LookbackStrike::Floating) => s - m,
+            (OptionKind::Put, LookbackStrike::Floating) => m - s,
+            (OptionKind::Call, LookbackStrike::Fixed(k)) => (m - k as f64).max(0.0),
+            (OptionKind::Put, LookbackStrike::Fixed(k)) => (k as f64 - m).max(0.0),
+        };
+        return price.round() as Cents;
+    }
+
+    // the closed forms divide by the cost of carry
+    let b = match r - dividend_yield {
+        b if b.abs() < 1e-8 => 1e-8,
+        b => b,
+    };
+    let v = volatility * volatility;
+    let carry = ((b - r) * t).exp();
+    let discount = (-r * t).exp();
+    let d = |level: f64| -> f64 { ((s / level).ln() + (b + v / 2.0) * t) / std_dev };
+    let reflection = |level: f64| -> f64 { (s / level).powf(-2.0 * b / v) };
+    let shift = 2.0 * b * t.sqrt() / volatility;
+    let premium = s * discount * v / (2.0 * b);
+
+    // the floating strike options from a running extreme `level`: paying the greater of it and
+    // the maximum to come less S_T, and S_T less the lesser of it and the minimum to come
+    let on_maximum = |level: f64| -> f64 {
+        let d1 = d(level);
+        return level * discount * norm_cdf(-(d1 - std_dev)) - s * carry * norm_cdf(-d1)
+            + premium * (-reflection(level) * norm_cdf(d1 - shift) + (b * t).exp() * norm_cdf(d1));
+    };
+    let on_minimum = |level: f64| -> f64 {
+        let d1 = d(level);
+        return s * carry * norm_cdf(d1) - level * discount * norm_cdf(d1 - std_dev)
+            + premium
+                * (reflection(level) * norm_cdf(-d1 + shift) - (b * t).exp() * norm_cdf(-d1));
+    };
+
+    let price = match (kind, strike) {
+        (OptionKind::Call, LookbackStrike::Floating) => on_minimum(m),
+        (OptionKind::Put, LookbackStrike::Floating) => on_maximum(m),
+        // max(M, K) - K = (max(M, K) - S_T) + (S_T - K)
+        (OptionKind::Call, LookbackStrike::Fixed(k)) => {
+            let k = k as f64;
+            on_maximum(m.max(k)) + s * carry - k * discount
+        }
+        // K - min(m, K) = (S_T - min(m, K)) - (S_T - K)
+        (OptionKind::Put, LookbackStrike::Fixed(k)) => {
+            let k = k as f64;
+            on_minimum(m.min(k)) - s * carry + k * discount
+        }
+    };
+    return price.max(0.0).round() as Cents;
+}
@@ -0,0 +1,165 @@
+//! Early exercise by least-squares Monte Carlo (Longstaff and Schwartz, 2001).
+//!
+//! Lattices handle American options on a single diffusing price, but not stochastic volatility
+//! or path-dependent payoffs. Here the paths are simulated forward under any `Dynamics`, and
+//! then walked back: at each exercise date, the value of continuing on the in-the-money paths is
+//! estimated by regressing their discounted future cash flows on basis functions of the price,
+//! and a path exercises where its payoff beats that estimate. Exercising on an estimate is
+//! suboptimal, so prices are slightly low.
+
+use crate::math::linalg::least_squares;
+use crate::math::Rng;
+use crate::montecarlo::{Dynamics, MonteCarlo, MonteCarloEstimate, Payoff};
+use crate::Cents;
+
+/**
+ * Functions of the price, relative to the spot, that continuation values are regressed on.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Basis {
+    /// `1, x, x², ...` up to the given degree.
+    Polynomial(usize),
+    /// Laguerre polynomials `L_0(x), L_1(x), ...` up to the given degree.
+    Laguerre(usize),
+}
+
+impl Default for Basis {
+    fn default() -> Basis {
+        return Basis::Laguerre(3);
+    }
+}
+
+impl Basis {
+    /**
+     * The basis functions at `x`.
+     */
+    pub fn evaluate(&self, x: f64) -> Vec<f64> {
+        return match *self {
+            Basis::Polynomial(degree) => (0..=degree).map(|n| x.powi(n as i32)).collect(),
+            Basis::Laguerre(degree) => {
+                let mut values = vec![1.0, 1.0 - x];
+                for n in 1..degree {
+                    let n = n as f64;
+                    let next = ((2.0 * n + 1.0 - x) * values[values.len() - 1]
+                        - n * values[values.len() - 2])
+                        / (n + 1.0);
+                    values.push(next);
+                }
+                values.truncate(degree + 1);
+                values
+            }
+        };
+    }
+}
+
+/**
+ * When the holder may exercise before expiration. Exercise at expiration is always allowed.
+ */
+#[derive(PartialEq, Clone, Debug)]
+pub enum ExerciseSchedule {
+    /// At every simulation step.
+    American,
+    /// At the steps nearest these times, in years from now.
+    Bermudan(Vec<f64>),
+}
+
+/**
+ * Least-squares Monte Carlo settings.
+ */
+#[derive(new, PartialEq, Clone, Debug)]
+pub struct LeastSquares {
+    pub basis: Basis,
+    pub schedule: ExerciseSchedule,
+}
+
+impl Default for LeastSquares {
+    fn default() -> LeastSquares {
+        return LeastSquares::new(Basis::default(), ExerciseSchedule::American);
+    }
+}
+
+impl MonteCarlo {
+    /**
+     * Simulated price of `payoff`, which the holder may exercise early as `lsm` allows, on an
+     * underlying following `dynamics`. Exercising at a step pays `payoff` on the path up to it,
+     * so `Vanilla` gives an American option. `t` is in years.
+     */
+    #[allow(clippy::too_many_arguments)]
+    pub fn price_early_exercise<P: Payoff + ?Sized, D: Dynamics + ?Sized>(
+        &self,
+        payoff: &P,
+        dynamics: &D,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        t: f64,
+        lsm: &LeastSquares,
+    ) -> MonteCarloEstimate {
+        let t = t.max(0.0);
+        let steps = self.steps.max(1);
+        let dt = t / steps as f64;
+        let step_discount = (-risk_free_rate * dt).exp();
+
+        let mut rng = Rng::new(self.seed);
+        let paths: Vec<Vec<f64>> = (0..self.paths)
+            .map(|_| {
+                let mut path = vec![spot as f64; steps + 1];
+                dynamics.simulate(&mut rng, &mut path, risk_free_rate, dividend_yield, dt);
+                return path;
+            })
+            .collect();
+        let exercisable = |step: usize| -> bool {
+            return match &lsm.schedule {
+                ExerciseSchedule::American => true,
+                ExerciseSchedule::Bermudan(times) => times
+                    .iter()
+                    .any(|time| dt > 0.0 && (time / dt).round() as usize == step),
+            };
+        };
+
+        // cash flow of each path, discounted to the step being considered
+        let mut values: Vec<f64> = paths.iter().map(|path| payoff.payoff(path)).collect();
+        for step in (1..steps).rev() {
+            for value in values.iter_mut() {
+                *value *= step_discount;
+            }
+            if !exercisable(step) {
+                continue;
+            }
+            let exercise: Vec<f64> = paths.iter().map(|p| payoff.payoff(&p[..=step])).collect();
+            let in_the_money: Vec<usize> =
+                (0..paths.len()).filter(|i| exercise[*i] > 0.0).collect();
+            let rows: Vec<Vec<f64>> = in_the_money
+                .iter()
+                .map(|i| lsm.basis.evaluate(paths[*i][step] / spot as f64))
+                .collect();
+            let continued: Vec<f64> = in_the_money.iter().map(|i| values[*i]).collect();
+            let coefficients = match least_squares(&rows, &continued) {
+                Some(coefficients) => coefficients,
+                None => continue,
+            };
+            for (i, row) in in_the_money.iter().zip(rows.iter()) {
+                let continuation: f64 = row
+                    .iter()
+                    .zip(coefficients.iter())
+                    .map(|(x, b)| x * b)
+                    .sum();
+                if exercise[*i] > continuation {
+                    values[*i] = exercise[*i];
+                }
+            }
+        }
+        let values = values.into_iter().map(|value| value * step_discount);
+
+        // every path is at the spot now, so exercising now is all or nothing
+        let held = MonteCarloEstimate::of(values);
+        let now = payoff.payoff(&[spot as f64]);
+        if exercisable(0) && now > held.price {
+            return MonteCarloEstimate {
+                price: now,
+                standard_error: 0.0,
+            };
+        }
+        return held;
+    }
+}
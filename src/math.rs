@@ -0,0 +1,721 @@
+use crate::approx;
+use crate::OptionKind;
+
+pub mod complex;
+#[cfg(feature = "high-precision")]
+pub mod extended;
+pub mod linalg;
+pub mod quadrature;
+pub mod solve;
+
+/**
+ * Standard normal probability density function.
+ */
+pub fn norm_pdf(x: f64) -> f64 {
+    return (-0.5 * x * x).exp() / (2.0 * std::f64::consts::PI).sqrt();
+}
+
+/**
+ * Standard normal cumulative distribution function.
+ *
+ * Uses Hart's double precision algorithm as described by West, "Better approximations to
+ * cumulative normal functions" (2005).
+ */
+pub fn norm_cdf(x: f64) -> f64 {
+    let x_abs = x.abs();
+    let tail = if x_abs > 37.0 {
+        0.0
+    } else if x_abs < 7.071_067_811_865_47 {
+        let e = (-x_abs * x_abs / 2.0).exp();
+        let mut n = 3.526_249_659_989_11e-2 * x_abs + 0.700_383_064_443_688;
+        n = n * x_abs + 6.373_962_203_531_65;
+        n = n * x_abs + 33.912_866_078_383;
+        n = n * x_abs + 112.079_291_497_871;
+        n = n * x_abs + 221.213_596_169_931;
+        n = n * x_abs + 220.206_867_912_376;
+        let mut d = 8.838_834_764_831_84e-2 * x_abs + 1.755_667_163_182_64;
+        d = d * x_abs + 16.064_177_579_207;
+        d = d * x_abs + 86.780_732_202_946_1;
+        d = d * x_abs + 296.564_248_779_674;
+        d = d * x_abs + 637.333_633_378_831;
+        d = d * x_abs + 793.826_512_519_948;
+        d = d * x_abs + 440.413_735_824_752;
+        e * n / d
+    } else {
+        let e = (-x_abs * x_abs / 2.0).exp();
+        let mut d = x_abs + 0.65;
+        d = x_abs + 4.0 / d;
+        d = x_abs + 3.0 / d;
+        d = x_abs + 2.0 / d;
+        d = x_abs + 1.0 / d;
+        e / d / 2.506_628_274_631
+    };
+    return if x > 0.0 { 1.0 - tail } else { tail };
+}
+
+/**
+ * Standard bivariate normal cumulative distribution function: the probability that `X < x` and
+ * `Y < y` when `X` and `Y` are standard normal with correlation `rho`.
+ *
+ * Uses Genz's (2004) algorithm, as described by West (2005), accurate to about 1e-15.
+ */
+pub fn bivariate_norm_cdf(x: f64, y: f64, rho: f64) -> f64 {
+    const NODES: [&[f64]; 3] = [
+        &[
+            -0.932_469_514_203_152,
+            -0.661_209_386_466_265,
+            -0.238_619_186_083_197,
+        ],
+        &[
+            -0.981_560_634_246_719,
+            -0.904_117_256_370_475,
+            -0.769_902_674_194_305,
+            -0.587_317_954_286_617,
+            -0.367_831_498_998_180,
+            -0.125_233_408_511_469,
+        ],
+        &[
+            -0.993_128_599_185_095,
+            -0.963_971_927_277_914,
+            -0.912_234_428_251_326,
+            -0.839_116_971_822_219,
+            -0.746_331_906_460_151,
+            -0.636_053_680_726_515,
+            -0.510_867_001_950_827,
+            -0.373_706_088_715_420,
+            -0.227_785_851_141_645,
+            -0.076_526_521_133_497,
+        ],
+    ];
+    const WEIGHTS: [&[f64]; 3] = [
+        &[
+            0.171_324_492_379_170,
+            0.360_761_573_048_138,
+            0.467_913_934_572_690,
+        ],
+        &[
+            0.047_175_336_386_512,
+            0.106_939_325_995_318,
+            0.160_078_328_543_346,
+            0.203_167_426_723_066,
+            0.233_492_536_538_355,
+            0.249_147_045_813_403,
+        ],
+        &[
+            0.017_614_007_139_152,
+            0.040_601_429_800_387,
+            0.062_672_048_334_109,
+            0.083_276_741_576_705,
+            0.101_930_119_817_240,
+            0.118_194_531_961_518,
+            0.131_688_638_449_177,
+            0.142_096_109_318_382,
+            0.149_172_986_472_604,
+            0.152_753_387_130_726,
+        ],
+    ];
+    let two_pi = 2.0 * std::f64::consts::PI;
+    let order = if rho.abs() < 0.3 {
+        0
+    } else if rho.abs() < 0.75 {
+        1
+    } else {
+        2
+    };
+    let (nodes, weights) = (NODES[order], WEIGHTS[order]);
+
+    // Genz computes the upper tail P(X > h, Y > k)
+    let h = -x;
+    let mut k = -y;
+    let mut hk = h * k;
+    let mut bvn = 0.0;
+    if rho.abs() < 0.925 {
+        let hs = (h * h + k * k) / 2.0;
+        let asr = rho.asin();
+        for (node, weight) in nodes.iter().zip(weights.iter()) {
+            for sign in [-1.0, 1.0].iter() {
+                let sn = (asr * (sign * node + 1.0) / 2.0).sin();
+                bvn += weight * ((sn * hk - hs) / (1.0 - sn * sn)).exp();
+            }
+        }
+        return bvn * asr / (2.0 * two_pi) + norm_cdf(-h) * norm_cdf(-k);
+    }
+
+    if rho < 0.0 {
+        k = -k;
+        hk = -hk;
+    }
+    if rho.abs() < 1.0 {
+        let a_s = (1.0 - rho) * (1.0 + rho);
+        let mut a = a_s.sqrt();
+        let bs = (h - k) * (h - k);
+        let c = (4.0 - hk) / 8.0;
+        let d = (12.0 - hk) / 16.0;
+        bvn = a
+            * (-(bs / a_s + hk) / 2.0).exp()
+            * (1.0 - c * (bs - a_s) * (1.0 - d * bs / 5.0) / 3.0 + c * d * a_s * a_s / 5.0);
+        if hk > -160.0 {
+            let b = bs.sqrt();
+            bvn -= (-hk / 2.0).exp()
+                * two_pi.sqrt()
+                * norm_cdf(-b / a)
+                * b
+                * (1.0 - c * bs * (1.0 - d * bs / 5.0) / 3.0);
+        }
+        a /= 2.0;
+        for (node, weight) in nodes.iter().zip(weights.iter()) {
+            let xs = (a * (node + 1.0)).powi(2);
+            let rs = (1.0 - xs).sqrt();
+            bvn += a
+                * weight
+                * ((-bs / (2.0 * xs) - hk / (1.0 + rs)).exp() / rs
+                    - (-(bs / xs + hk) / 2.0).exp() * (1.0 + c * xs * (1.0 + d * xs)));
+            let xs = a_s * (1.0 - node).powi(2) / 4.0;
+            let rs = (1.0 - xs).sqrt();
+            bvn += a
+                * weight
+                * (-(bs / xs + hk) / 2.0).exp()
+                * ((-hk * (1.0 - rs) / (2.0 * (1.0 + rs))).exp() / rs
+                    - (1.0 + c * xs * (1.0 + d * xs)));
+        }
+        bvn = -bvn / two_pi;
+    }
+    if rho > 0.0 {
+        return bvn + norm_cdf(-h.max(k));
+    }
+    return -bvn + (norm_cdf(-h) - norm_cdf(-k)).max(0.0);
+}
+
+/**
+ * Black's formula for the discounted price of a European option on a forward.
+ *
+ * All prices are in the same units as `forward` and `strike`; `t` is in years and
+ * `discount_factor` is the price today of one unit paid at expiration.
+ */
+pub fn black_price(
+    kind: OptionKind,
+    forward: f64,
+    strike: f64,
+    volatility: f64,
+    t: f64,
+    discount_factor: f64,
+) -> f64 {
+    let intrinsic = match kind {
+        OptionKind::Call => (forward - strike).max(0.0),
+        OptionKind::Put => (strike - forward).max(0.0),
+    };
+    let std_dev = volatility * t.max(0.0).sqrt();
+    if std_dev <= 0.0 {
+        return discount_factor * intrinsic;
+    }
+    let d1 = ((forward / strike).ln() + 0.5 * std_dev * std_dev) / std_dev;
+    let d2 = d1 - std_dev;
+    return match kind {
+        OptionKind::Call => discount_factor * (forward * norm_cdf(d1) - strike * norm_cdf(d2)),
+        OptionKind::Put => discount_factor * (strike * norm_cdf(-d2) - forward * norm_cdf(-d1)),
+    };
+}
+
+/**
+ * Bachelier's formula for the discounted price of a European option on a forward that moves
+ * normally rather than lognormally.
+ *
+ * `volatility` is in price units per square root year, the same units as `forward` and
+ * `strike`, which may be zero or negative.
+ */
+pub fn bachelier_price(
+    kind: OptionKind,
+    forward: f64,
+    strike: f64,
+    volatility: f64,
+    t: f64,
+    discount_factor: f64,
+) -> f64 {
+    let moneyness = match kind {
+        OptionKind::Call => forward - strike,
+        OptionKind::Put => strike - forward,
+    };
+    let std_dev = volatility * t.max(0.0).sqrt();
+    if std_dev <= 0.0 {
+        return discount_factor * moneyness.max(0.0);
+    }
+    let d = moneyness / std_dev;
+    return discount_factor * (moneyness * norm_cdf(d) + std_dev * norm_pdf(d));
+}
+
+/**
+ * Where the implied volatility solver starts from.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum InitialGuess {
+    Fixed(f64),
+    /// The Brenner–Subrahmanyam ATM approximation, which is poor away from the money.
+    BrennerSubrahmanyam,
+    /// The Corrado–Miller approximation, falling back to 0.2 where it breaks down.
+    CorradoMiller,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct SolverConfig {
+    pub initial_guess: InitialGuess,
+    pub max_iterations: usize,
+    /// Price tolerance, relative to the forward price.
+    pub tolerance: f64,
+}
+
+impl Default for SolverConfig {
+    fn default() -> SolverConfig {
+        return SolverConfig {
+            initial_guess: InitialGuess::Fixed(0.2),
+            max_iterations: 100,
+            tolerance: 1e-10,
+        };
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum SolverMethod {
+    /// Every step was a Newton step.
+    Newton,
+    /// At least one Newton step left the bracket and was replaced by bisection.
+    NewtonWithBisection,
+}
+
+/**
+ * Outcome of a single implied volatility solve.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct SolveReport {
+    /// `None` if the price is outside the no-arbitrage bounds.
+    pub volatility: Option<f64>,
+    pub iterations: usize,
+    /// Absolute price error at the returned volatility; NaN if there is none.
+    pub error: f64,
+    pub method: SolverMethod,
+    /// Whether the error is within the tolerance, rather than the iterations running out.
+    pub converged: bool,
+}
+
+/**
+ * Volatility implied by a discounted European option price under Black's formula, or `None` if
+ * the price is outside the no-arbitrage bounds.
+ *
+ * Uses Newton's method, falling back to bisection whenever a step leaves the bracket.
+ */
+pub fn implied_volatility(
+    kind: OptionKind,
+    price: f64,
+    forward: f64,
+    strike: f64,
+    t: f64,
+    discount_factor: f64,
+) -> Option<f64> {
+    return implied_volatility_with(
+        kind,
+        price,
+        forward,
+        strike,
+        t,
+        discount_factor,
+        &SolverConfig::default(),
+    )
+    .volatility;
+}
+
+/**
+ * Like `implied_volatility`, configured by `config` and reporting how the solve went.
+ */
+pub fn implied_volatility_with(
+    kind: OptionKind,
+    price: f64,
+    forward: f64,
+    strike: f64,
+    t: f64,
+    discount_factor: f64,
+    config: &SolverConfig,
+) -> SolveReport {
+    let lower_bound = black_price(kind, forward, strike, 0.0, t, discount_factor);
+    let upper_bound = discount_factor
+        * match kind {
+            OptionKind::Call => forward,
+            OptionKind::Put => strike,
+        };
+    if t <= 0.0 || price <= lower_bound || price >= upper_bound {
+        return SolveReport {
+            volatility: None,
+            iterations: 0,
+            error: f64::NAN,
+            method: SolverMethod::Newton,
+            converged: false,
+        };
+    }
+
+    let sqrt_t = t.sqrt();
+    let (low, high) = (1e-6, 10.0);
+    let guess = match config.initial_guess {
+        InitialGuess::Fixed(vol) => Some(vol),
+        InitialGuess::BrennerSubrahmanyam => {
+            approx::brenner_subrahmanyam(price / discount_factor, forward, t)
+        }
+        InitialGuess::CorradoMiller => {
+            approx::corrado_miller(kind, price, forward, strike, t, discount_factor)
+        }
+    };
+    let root = solve::newton(
+        |vol| {
+            let diff = black_price(kind, forward, strike, vol, t, discount_factor) - price;
+            let d1 = ((forward / strike).ln() + 0.5 * vol * vol * t) / (vol * sqrt_t);
+            let vega = discount_factor * forward * norm_pdf(d1) * sqrt_t;
+            return (diff, vega);
+        },
+        guess.filter(|v| *v > low && *v < high).unwrap_or(0.2),
+        low,
+        high,
+        config.tolerance * forward.max(1.0),
+        config.max_iterations,
+    );
+    return SolveReport {
+        volatility: Some(root.x),
+        iterations: root.iterations,
+        error: (black_price(kind, forward, strike, root.x, t, discount_factor) - price).abs(),
+        method: if root.bisection_steps > 0 {
+            SolverMethod::NewtonWithBisection
+        } else {
+            SolverMethod::Newton
+        },
+        converged: root.converged,
+    };
+}
+
+/**
+ * Normal volatility implied by a discounted European price under Bachelier's formula, in price
+ * units per square root year, or `None` if the price is at or below intrinsic value.
+ */
+pub fn implied_normal_volatility(
+    kind: OptionKind,
+    price: f64,
+    forward: f64,
+    strike: f64,
+    t: f64,
+    discount_factor: f64,
+) -> Option<f64> {
+    return implied_normal_volatility_with(
+        kind,
+        price,
+        forward,
+        strike,
+        t,
+        discount_factor,
+        &SolverConfig::default(),
+    )
+    .volatility;
+}
+
+/**
+ * Like `implied_normal_volatility`, with the iteration limit and tolerance of `config`, reporting
+ * how the solve went. The solve always starts from the at-the-money volatility with the same
+ * price, so `config.initial_guess` is ignored.
+ */
+pub fn implied_normal_volatility_with(
+    kind: OptionKind,
+    price: f64,
+    forward: f64,
+    strike: f64,
+    t: f64,
+    discount_factor: f64,
+    config: &SolverConfig,
+) -> SolveReport {
+    let lower_bound = bachelier_price(kind, forward, strike, 0.0, t, discount_factor);
+    if t <= 0.0 || discount_factor <= 0.0 || price <= lower_bound {
+        return SolveReport {
+            volatility: None,
+            iterations: 0,
+            error: f64::NAN,
+            method: SolverMethod::Newton,
+            converged: false,
+        };
+    }
+
+    let sqrt_t = t.sqrt();
+    // the price grows without bound in the volatility, so widen the bracket until it is enough
+    let low = 0.0;
+    let mut high = (price / discount_factor) * 3.0 / sqrt_t;
+    while bachelier_price(kind, forward, strike, high, t, discount_factor) < price {
+        high *= 2.0;
+    }
+    let guess = (price / discount_factor) * (2.0 * std::f64::consts::PI).sqrt() / sqrt_t;
+    let root = solve::newton(
+        |vol| {
+            let diff = bachelier_price(kind, forward, strike, vol, t, discount_factor) - price;
+            let d = (forward - strike) / (vol * sqrt_t);
+            let vega = discount_factor * sqrt_t * norm_pdf(d);
+            return (diff, vega);
+        },
+        guess.min(high),
+        low,
+        high,
+        config.tolerance * forward.abs().max(1.0),
+        config.max_iterations,
+    );
+    return SolveReport {
+        volatility: Some(root.x),
+        iterations: root.iterations,
+        error: (bachelier_price(kind, forward, strike, root.x, t, discount_factor) - price).abs(),
+        method: if root.bisection_steps > 0 {
+            SolverMethod::NewtonWithBisection
+        } else {
+            SolverMethod::Newton
+        },
+        converged: root.converged,
+    };
+}
+
+/**
+ * Aggregate telemetry over many solves, e.g. a whole chain, to spot systematic convergence
+ * problems.
+ */
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub struct SolverStats {
+    pub solves: usize,
+    /// Prices outside the no-arbitrage bounds.
+    pub out_of_bounds: usize,
+    /// Solves that ran out of iterations.
+    pub not_converged: usize,
+    /// Solves that needed bisection steps.
+    pub bisected: usize,
+    pub total_iterations: usize,
+    pub max_error: f64,
+}
+
+impl SolverStats {
+    pub fn record(&mut self, report: &SolveReport) {
+        self.solves += 1;
+        self.total_iterations += report.iterations;
+        if report.volatility.is_none() {
+            self.out_of_bounds += 1;
+            return;
+        }
+        if !report.converged {
+            self.not_converged += 1;
+        }
+        if report.method == SolverMethod::NewtonWithBisection {
+            self.bisected += 1;
+        }
+        self.max_error = self.max_error.max(report.error);
+    }
+
+    pub fn mean_iterations(&self) -> f64 {
+        if self.solves == 0 {
+            return 0.0;
+        }
+        return self.total_iterations as f64 / self.solves as f64;
+    }
+}
+
+/**
+ * How a sequence of floating-point values is added up.
+ */
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum Summation {
+    /// Left to right, accumulating rounding error with the number of terms.
+    #[default]
+    Naive,
+    /// Compensated (Kahan–Babuška–Neumaier) summation, whose error does not grow with the
+    /// number of terms. Costs a few extra operations per term.
+    Compensated,
+    /// Double-double accumulation, for reproducing published values: the sum adds no rounding
+    /// error of its own, though each term is still computed in `f64`. With an `IndexConfig`, also
+    /// interpolates between terms in extended precision. Without the `high-precision` feature,
+    /// the same as `Compensated`.
+    Extended,
+}
+
+impl Summation {
+    pub fn sum<I: IntoIterator<Item = f64>>(self, values: I) -> f64 {
+        match self {
+            Summation::Naive => return values.into_iter().sum(),
+            #[cfg(feature = "high-precision")]
+            Summation::Extended => return extended::sum(values),
+            _ => {}
+        }
+        let mut sum = 0.0;
+        let mut compensation = 0.0;
+        for value in values {
+            let next = sum + value;
+            compensation += if sum.abs() >= value.abs() {
+                (sum - next) + value
+            } else {
+                (value - next) + sum
+            };
+            sum = next;
+        }
+        return sum + compensation;
+    }
+
+    /**
+     * Arithmetic mean, e.g. of Monte Carlo samples, or `None` if there are no values.
+     */
+    pub fn mean<I: IntoIterator<Item = f64>>(self, values: I) -> Option<f64> {
+        let mut n = 0usize;
+        let sum = self.sum(values.into_iter().inspect(|_| n += 1));
+        if n == 0 {
+            return None;
+        }
+        return Some(sum / n as f64);
+    }
+}
+
+/**
+ * Linearly interpolates `y` at `x` between points sorted by `x`, or `None` if `x` is outside
+ * their range.
+ */
+pub fn linear_interpolate(points: &[(f64, f64)], x: f64) -> Option<f64> {
+    for w in points.windows(2) {
+        let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+        if x >= x0 && x <= x1 {
+            if x1 == x0 {
+                return Some(y0);
+            }
+            return Some(y0 + (y1 - y0) * (x - x0) / (x1 - x0));
+        }
+    }
+    return match points {
+        [(x0, y0)] if *x0 == x => Some(*y0),
+        _ => None,
+    };
+}
+
+/**
+ * Like `linear_interpolate`, but holds `y` flat at the nearest end point outside the range.
+ * `None` only if there are no points.
+ */
+pub fn linear_interpolate_flat(points: &[(f64, f64)], x: f64) -> Option<f64> {
+    let first = points.first()?;
+    let last = points.last()?;
+    let x = x.max(first.0).min(last.0);
+    return linear_interpolate(points, x);
+}
+
+/**
+ * Minimizes `f` with the Nelder–Mead simplex method, starting from `x0` with an initial simplex
+ * of size `step` along each axis. Stops after `max_iterations` or once the function values of
+ * the simplex are within `tolerance` of each other.
+ */
+pub fn nelder_mead<F: Fn(&[f64]) -> f64>(
+    f: F,
+    x0: &[f64],
+    step: f64,
+    max_iterations: usize,
+    tolerance: f64,
+) -> Vec<f64> {
+    let n = x0.len();
+    let mut simplex: Vec<(Vec<f64>, f64)> = (0..=n)
+        .map(|i| {
+            let mut x = x0.to_vec();
+            if i > 0 {
+                x[i - 1] += step;
+            }
+            let value = f(&x);
+            return (x, value);
+        })
+        .collect();
+    let blend = |a: &[f64], b: &[f64], t: f64| -> Vec<f64> {
+        return a.iter().zip(b).map(|(a, b)| a + t * (b - a)).collect();
+    };
+
+    for _ in 0..max_iterations {
+        simplex.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        if (simplex[n].1 - simplex[0].1).abs() <= tolerance {
+            break;
+        }
+
+        let centroid: Vec<f64> = (0..n)
+            .map(|j| simplex[..n].iter().map(|(x, _)| x[j]).sum::<f64>() / n as f64)
+            .collect();
+        let worst = simplex[n].clone();
+
+        let reflected = blend(&centroid, &worst.0, -1.0);
+        let reflected_value = f(&reflected);
+        if reflected_value < simplex[0].1 {
+            let expanded = blend(&centroid, &worst.0, -2.0);
+            let expanded_value = f(&expanded);
+            simplex[n] = if expanded_value < reflected_value {
+                (expanded, expanded_value)
+            } else {
+                (reflected, reflected_value)
+            };
+        } else if reflected_value < simplex[n - 1].1 {
+            simplex[n] = (reflected, reflected_value);
+        } else {
+            let contracted = blend(&centroid, &worst.0, 0.5);
+            let contracted_value = f(&contracted);
+            if contracted_value < worst.1 {
+                simplex[n] = (contracted, contracted_value);
+            } else {
+                let best = simplex[0].0.clone();
+                for vertex in simplex.iter_mut().skip(1) {
+                    let x = blend(&best, &vertex.0, 0.5);
+                    let value = f(&x);
+                    *vertex = (x, value);
+                }
+            }
+        }
+    }
+
+    simplex.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+    return simplex.swap_remove(0).0;
+}
+
+/**
+ * Small, seedable pseudo-random number generator (SplitMix64).
+ *
+ * Used wherever the crate needs reproducible randomness; identical seeds produce identical
+ * streams on every platform.
+ */
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+    spare_normal: Option<f64>,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        return Rng {
+            state: seed,
+            spare_normal: None,
+        };
+    }
+
+    /**
+     * Next raw 64-bit value.
+     */
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        return z ^ (z >> 31);
+    }
+
+    /**
+     * Uniform sample in [0, 1).
+     */
+    pub fn next_f64(&mut self) -> f64 {
+        return (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+    }
+
+    /**
+     * Standard normal sample (Box–Muller).
+     */
+    pub fn next_normal(&mut self) -> f64 {
+        if let Some(z) = self.spare_normal.take() {
+            return z;
+        }
+        let u1 = 1.0 - self.next_f64();
+        let u2 = self.next_f64();
+        let r = (-2.0 * u1.ln()).sqrt();
+        let theta = 2.0 * std::f64::consts::PI * u2;
+        self.spare_normal = Some(r * theta.sin());
+        return r * theta.cos();
+    }
+}
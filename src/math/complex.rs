@@ -0,0 +1,158 @@
+//! Minimal complex arithmetic for characteristic functions.
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub const I: Complex = Complex { re: 0.0, im: 1.0 };
+
+    pub fn new(re: f64, im: f64) -> Complex {
+        return Complex { re, im };
+    }
+
+    pub fn real(re: f64) -> Complex {
+        return Complex { re, im: 0.0 };
+    }
+
+    pub fn norm(self) -> f64 {
+        return self.re.hypot(self.im);
+    }
+
+    pub fn arg(self) -> f64 {
+        return self.im.atan2(self.re);
+    }
+
+    pub fn conj(self) -> Complex {
+        return Complex::new(self.re, -self.im);
+    }
+
+    pub fn exp(self) -> Complex {
+        let r = self.re.exp();
+        return Complex::new(r * self.im.cos(), r * self.im.sin());
+    }
+
+    /**
+     * Principal branch of the logarithm.
+     */
+    pub fn ln(self) -> Complex {
+        return Complex::new(self.norm().ln(), self.arg());
+    }
+
+    /**
+     * Principal square root.
+     */
+    pub fn sqrt(self) -> Complex {
+        let r = self.norm().sqrt();
+        let theta = self.arg() / 2.0;
+        return Complex::new(r * theta.cos(), r * theta.sin());
+    }
+
+    pub fn scale(self, k: f64) -> Complex {
+        return Complex::new(self.re * k, self.im * k);
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+    fn add(self, other: Complex) -> Complex {
+        return Complex::new(self.re + other.re, self.im + other.im);
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+    fn sub(self, other: Complex) -> Complex {
+        return Complex::new(self.re - other.re, self.im - other.im);
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+    fn mul(self, other: Complex) -> Complex {
+        return Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        );
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex;
+    fn div(self, other: Complex) -> Complex {
+        let d = other.re * other.re + other.im * other.im;
+        return Complex::new(
+            (self.re * other.re + self.im * other.im) / d,
+            (self.im * other.re - self.re * other.im) / d,
+        );
+    }
+}
+
+impl Neg for Complex {
+    type Output = Complex;
+    fn neg(self) -> Complex {
+        return Complex::new(-self.re, -self.im);
+    }
+}
+
+impl Add<f64> for Complex {
+    type Output = Complex;
+    fn add(self, other: f64) -> Complex {
+        return Complex::new(self.re + other, self.im);
+    }
+}
+
+impl Sub<f64> for Complex {
+    type Output = Complex;
+    fn sub(self, other: f64) -> Complex {
+        return Complex::new(self.re - other, self.im);
+    }
+}
+
+impl Mul<f64> for Complex {
+    type Output = Complex;
+    fn mul(self, other: f64) -> Complex {
+        return self.scale(other);
+    }
+}
+
+/**
+ * In-place forward discrete Fourier transform, `X_j = Σ_m x_m e^{-2πi jm/N}`, by the iterative
+ * radix-2 Cooley–Tukey algorithm. The length must be a power of two.
+ */
+pub fn fft(values: &mut [Complex]) {
+    let n = values.len();
+    assert!(n.is_power_of_two(), "FFT length must be a power of two");
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f64::consts::PI / len as f64;
+        let root = Complex::new(angle.cos(), angle.sin());
+        for start in (0..n).step_by(len) {
+            let mut w = Complex::real(1.0);
+            for k in 0..len / 2 {
+                let even = values[start + k];
+                let odd = values[start + k + len / 2] * w;
+                values[start + k] = even + odd;
+                values[start + k + len / 2] = even - odd;
+                w = w * root;
+            }
+        }
+        len <<= 1;
+    }
+}
@@ -0,0 +1,181 @@
+//! Double-double arithmetic: a value carried as the unevaluated sum of two `f64`s, giving about
+//! 106 bits of significand instead of 53.
+//!
+//! Published index values are rounded to two decimals, but one computed in `f64` can land on
+//! the other side of a rounding boundary from the one the publisher computed. Accumulating in
+//! extended precision removes the error the sums add, which grows with the number of strikes, so
+//! an audit is left with the much smaller error of each term and can more easily tell a
+//! methodology difference from floating-point noise. Only built with the `high-precision`
+//! feature; it is several times slower than plain `f64`.
+//!
+//! The algorithms are Dekker's and Knuth's error-free transformations, as in Hida, Li and
+//! Bailey, "Library for Double-Double and Quad-Double Arithmetic" (2007).
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/**
+ * A value equal to `hi + lo` exactly, with `|lo|` at most half an ulp of `hi`.
+ */
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub struct Extended {
+    pub hi: f64,
+    pub lo: f64,
+}
+
+impl Extended {
+    pub fn new(value: f64) -> Extended {
+        return Extended { hi: value, lo: 0.0 };
+    }
+
+    /**
+     * The nearest `f64`.
+     */
+    pub fn to_f64(self) -> f64 {
+        return self.hi + self.lo;
+    }
+
+    pub fn abs(self) -> Extended {
+        return if self.hi < 0.0 { -self } else { self };
+    }
+
+    /**
+     * Square root by one Newton step from the `f64` root, which doubles its precision.
+     */
+    pub fn sqrt(self) -> Extended {
+        if self.hi <= 0.0 {
+            return Extended::new(self.hi.sqrt());
+        }
+        let root = self.hi.sqrt();
+        let (square, error) = two_prod(root, root);
+        let residual = (self - Extended::from_parts(square, error)).to_f64();
+        return Extended::from_parts(root, residual / (2.0 * root));
+    }
+
+    /**
+     * Normalizes a pair whose magnitudes may overlap.
+     */
+    fn from_parts(hi: f64, lo: f64) -> Extended {
+        let (hi, lo) = quick_two_sum(hi, lo);
+        return Extended { hi, lo };
+    }
+}
+
+impl From<f64> for Extended {
+    fn from(value: f64) -> Extended {
+        return Extended::new(value);
+    }
+}
+
+impl Add for Extended {
+    type Output = Extended;
+
+    fn add(self, other: Extended) -> Extended {
+        let (hi, error) = two_sum(self.hi, other.hi);
+        let (lo, lo_error) = two_sum(self.lo, other.lo);
+        let (hi, error) = quick_two_sum(hi, error + lo);
+        return Extended::from_parts(hi, error + lo_error);
+    }
+}
+
+impl Add<f64> for Extended {
+    type Output = Extended;
+
+    fn add(self, other: f64) -> Extended {
+        let (hi, error) = two_sum(self.hi, other);
+        return Extended::from_parts(hi, error + self.lo);
+    }
+}
+
+impl Neg for Extended {
+    type Output = Extended;
+
+    fn neg(self) -> Extended {
+        return Extended {
+            hi: -self.hi,
+            lo: -self.lo,
+        };
+    }
+}
+
+impl Sub for Extended {
+    type Output = Extended;
+
+    fn sub(self, other: Extended) -> Extended {
+        return self + -other;
+    }
+}
+
+impl Mul for Extended {
+    type Output = Extended;
+
+    fn mul(self, other: Extended) -> Extended {
+        let (hi, error) = two_prod(self.hi, other.hi);
+        return Extended::from_parts(hi, error + (self.hi * other.lo + self.lo * other.hi));
+    }
+}
+
+impl Mul<f64> for Extended {
+    type Output = Extended;
+
+    fn mul(self, other: f64) -> Extended {
+        let (hi, error) = two_prod(self.hi, other);
+        return Extended::from_parts(hi, error + self.lo * other);
+    }
+}
+
+impl Div for Extended {
+    type Output = Extended;
+
+    fn div(self, other: Extended) -> Extended {
+        // long division: each quotient digit takes another 53 bits
+        let q1 = self.hi / other.hi;
+        let r = self - other * q1;
+        let q2 = r.hi / other.hi;
+        let r = r - other * q2;
+        let q3 = r.hi / other.hi;
+        return Extended::from_parts(q1, q2) + q3;
+    }
+}
+
+impl Div<f64> for Extended {
+    type Output = Extended;
+
+    fn div(self, other: f64) -> Extended {
+        return self / Extended::new(other);
+    }
+}
+
+/**
+ * Sums `values` in extended precision, rounding once at the end.
+ */
+pub fn sum<I: IntoIterator<Item = f64>>(values: I) -> f64 {
+    return values
+        .into_iter()
+        .fold(Extended::default(), |sum, value| sum + value)
+        .to_f64();
+}
+
+/**
+ * `a + b` and its rounding error.
+ */
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    return (s, (a - (s - bb)) + (b - bb));
+}
+
+/**
+ * `a + b` and its rounding error, given `|a| >= |b|`.
+ */
+fn quick_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    return (s, b - (s - a));
+}
+
+/**
+ * `a * b` and its rounding error, exact with a fused multiply-add.
+ */
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    return (p, a.mul_add(b, -p));
+}
@@ -0,0 +1,289 @@
+//! Small dense linear algebra for correlation matrices.
+//!
+//! Matrices are `Vec` rows. Correlations estimated pairwise, stressed by hand, or mixed from
+//! different windows are often not positive semidefinite, which a simulation cannot sample
+//! from; `nearest_correlation` repairs them before `CorrelatedNormals` factors them.
+
+use crate::math::Rng;
+
+/**
+ * Lower triangular `L` with `L Lᵀ = matrix`, for a symmetric positive definite matrix given by
+ * rows. `None` if it is not square or not positive definite.
+ */
+pub fn cholesky(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = matrix.len();
+    if matrix.iter().any(|row| row.len() != n) {
+        return None;
+    }
+    let mut lower = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let dot: f64 = (0..j).map(|k| lower[i][k] * lower[j][k]).sum();
+            if i == j {
+                let pivot = matrix[i][i] - dot;
+                if pivot <= 0.0 {
+                    return None;
+                }
+                lower[i][j] = pivot.sqrt();
+            } else {
+                lower[i][j] = (matrix[i][j] - dot) / lower[j][j];
+            }
+        }
+    }
+    return Some(lower);
+}
+
+/**
+ * Coefficients `b` minimizing `|X b - y|²`, for `X` given by rows, through the normal equations.
+ * A ridge of a trillionth of their trace keeps nearly collinear columns solvable. `None` without
+ * any rows or if the columns are degenerate.
+ */
+pub fn least_squares(rows: &[Vec<f64>], y: &[f64]) -> Option<Vec<f64>> {
+    let m = rows.first()?.len();
+    let mut normal = vec![vec![0.0; m]; m];
+    let mut rhs = vec![0.0; m];
+    for (row, target) in rows.iter().zip(y.iter()) {
+        for i in 0..m {
+            rhs[i] += row[i] * target;
+            for j in 0..m {
+                normal[i][j] += row[i] * row[j];
+            }
+        }
+    }
+    let ridge = 1e-12 * (0..m).map(|i| normal[i][i]).sum::<f64>();
+    for (i, row) in normal.iter_mut().enumerate() {
+        row[i] += ridge;
+    }
+    let lower = cholesky(&normal)?;
+    // L z = rhs, then Lᵀ b = z
+    let mut z = vec![0.0; m];
+    for i in 0..m {
+        z[i] = (rhs[i] - (0..i).map(|k| lower[i][k] * z[k]).sum::<f64>()) / lower[i][i];
+    }
+    let mut b = vec![0.0; m];
+    for i in (0..m).rev() {
+        b[i] = (z[i] - (i + 1..m).map(|k| lower[k][i] * b[k]).sum::<f64>()) / lower[i][i];
+    }
+    return Some(b);
+}
+
+/**
+ * Eigenvalues of a symmetric matrix, ascending, with a unit eigenvector for each (cyclic
+ * Jacobi rotations).
+ */
+pub fn symmetric_eigen(matrix: &[Vec<f64>]) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = matrix.len();
+    let mut a: Vec<Vec<f64>> = matrix.to_vec();
+    let mut vectors: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+    for _ in 0..100 {
+        let off_diagonal: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |j| *j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j])
+            .sum();
+        if off_diagonal < 1e-22 {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (x, y) = (row[p], row[q]);
+                    row[p] = c * x - s * y;
+                    row[q] = s * x + c * y;
+                }
+                let (row_p, row_q) = (a[p].clone(), a[q].clone());
+                let rotated = row_p.iter().zip(row_q.iter());
+                a[p] = rotated.clone().map(|(x, y)| c * x - s * y).collect();
+                a[q] = rotated.map(|(x, y)| s * x + c * y).collect();
+                for row in vectors.iter_mut() {
+                    let (x, y) = (row[p], row[q]);
+                    row[p] = c * x - s * y;
+                    row[q] = s * x + c * y;
+                }
+            }
+        }
+    }
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|i, j| a[*i][*i].partial_cmp(&a[*j][*j]).unwrap());
+    let values = order.iter().map(|i| a[*i][*i]).collect();
+    let vectors = order
+        .iter()
+        .map(|i| vectors.iter().map(|row| row[*i]).collect())
+        .collect();
+    return (values, vectors);
+}
+
+/**
+ * Something that keeps a matrix from being a usable correlation matrix.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum CorrelationIssue {
+    NotSquare,
+    /// The entry differs from its transpose.
+    NotSymmetric {
+        row: usize,
+        column: usize,
+    },
+    DiagonalNotOne {
+        index: usize,
+    },
+    /// The entry is outside `[-1, 1]`, or not a number.
+    OutOfRange {
+        row: usize,
+        column: usize,
+    },
+    /// The matrix has a negative eigenvalue, so no set of variables has these correlations.
+    NotPositiveSemidefinite {
+        min_eigenvalue: f64,
+    },
+}
+
+/**
+ * Every issue with `matrix` as a correlation matrix, with entries compared to within
+ * `tolerance`. Empty if it is one.
+ */
+pub fn validate_correlation(matrix: &[Vec<f64>], tolerance: f64) -> Vec<CorrelationIssue> {
+    let n = matrix.len();
+    if matrix.iter().any(|row| row.len() != n) {
+        return vec![CorrelationIssue::NotSquare];
+    }
+    let mut issues = vec![];
+    for (i, row) in matrix.iter().enumerate() {
+        if (row[i] - 1.0).abs() > tolerance || row[i].is_nan() {
+            issues.push(CorrelationIssue::DiagonalNotOne { index: i });
+        }
+        for (j, value) in row.iter().enumerate() {
+            if value.is_nan() || value.abs() > 1.0 + tolerance {
+                issues.push(CorrelationIssue::OutOfRange { row: i, column: j });
+            }
+            if j > i && (value - matrix[j][i]).abs() > tolerance {
+                issues.push(CorrelationIssue::NotSymmetric { row: i, column: j });
+            }
+        }
+    }
+    if issues.is_empty() && n > 0 {
+        let min_eigenvalue = symmetric_eigen(matrix).0[0];
+        if min_eigenvalue < -tolerance {
+            issues.push(CorrelationIssue::NotPositiveSemidefinite { min_eigenvalue });
+        }
+    }
+    return issues;
+}
+
+/**
+ * The correlation matrix nearest `matrix` in the Frobenius norm (Higham, 2002), with every
+ * eigenvalue at least `min_eigenvalue` so that it can be factored. `matrix` must be square; it
+ * is symmetrized first.
+ */
+pub fn nearest_correlation(matrix: &[Vec<f64>], min_eigenvalue: f64) -> Vec<Vec<f64>> {
+    let n = matrix.len();
+    let symmetrized: Vec<Vec<f64>> = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| (matrix[i][j] + matrix[j][i]) / 2.0)
+                .collect()
+        })
+        .collect();
+    let clip = |m: &[Vec<f64>]| -> Vec<Vec<f64>> {
+        let (values, vectors) = symmetric_eigen(m);
+        return (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| {
+                        return (0..n)
+                            .map(|k| vectors[k][i] * values[k].max(min_eigenvalue) * vectors[k][j])
+                            .sum();
+                    })
+                    .collect()
+            })
+            .collect();
+    };
+
+    // alternating projections with Dykstra's correction
+    let mut y = symmetrized;
+    let mut correction = vec![vec![0.0; n]; n];
+    for _ in 0..200 {
+        let r: Vec<Vec<f64>> = (0..n)
+            .map(|i| (0..n).map(|j| y[i][j] - correction[i][j]).collect())
+            .collect();
+        let x = clip(&r);
+        for i in 0..n {
+            for j in 0..n {
+                correction[i][j] = x[i][j] - r[i][j];
+            }
+        }
+        let mut next = x;
+        for (i, row) in next.iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        let change: f64 = (0..n)
+            .flat_map(|i| (0..n).map(move |j| (i, j)))
+            .map(|(i, j)| (next[i][j] - y[i][j]).powi(2))
+            .sum();
+        y = next;
+        if change.sqrt() < 1e-12 {
+            break;
+        }
+    }
+
+    // the unit diagonal projection can leave it slightly indefinite
+    let x = clip(&y);
+    return (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| x[i][j] / (x[i][i] * x[j][j]).sqrt())
+                .collect()
+        })
+        .collect();
+}
+
+/**
+ * Draws standard normal vectors with a given correlation matrix.
+ */
+#[derive(Clone, Debug)]
+pub struct CorrelatedNormals {
+    lower: Vec<Vec<f64>>,
+}
+
+impl CorrelatedNormals {
+    /**
+     * `None` if `correlation` is not square and positive definite; repair it with
+     * `nearest_correlation` first.
+     */
+    pub fn new(correlation: &[Vec<f64>]) -> Option<CorrelatedNormals> {
+        return Some(CorrelatedNormals {
+            lower: cholesky(correlation)?,
+        });
+    }
+
+    pub fn dimension(&self) -> usize {
+        return self.lower.len();
+    }
+
+    /**
+     * Fills `out`, one entry per dimension, with a draw. Uses `dimension` normals from `rng`.
+     */
+    pub fn fill(&self, rng: &mut Rng, out: &mut [f64]) {
+        for z in out.iter_mut() {
+            *z = rng.next_normal();
+        }
+        // L is lower triangular, so going from the last row up reads only untouched entries
+        for i in (0..self.lower.len()).rev() {
+            out[i] = (0..=i).map(|k| self.lower[i][k] * out[k]).sum();
+        }
+    }
+
+    pub fn sample(&self, rng: &mut Rng) -> Vec<f64> {
+        let mut out = vec![0.0; self.dimension()];
+        self.fill(rng, &mut out);
+        return out;
+    }
+}
@@ -0,0 +1,193 @@
+//! Numerical integration.
+
+/**
+ * Maximum recursion depth of the adaptive integrator.
+ */
+const MAX_DEPTH: usize = 50;
+
+const ALPHA: f64 = 0.816_496_580_927_726; // sqrt(2/3)
+const BETA: f64 = 0.447_213_595_499_958; // 1/sqrt(5)
+
+/**
+ * Integral of `f` over `[a, b]` by adaptive Gauss–Lobatto quadrature (Gander and Gautschi,
+ * "Adaptive quadrature — revisited", 2000), to about `tolerance` relative to the integral.
+ */
+pub fn gauss_lobatto<F: Fn(f64) -> f64>(f: F, a: f64, b: f64, tolerance: f64) -> f64 {
+    if a == b {
+        return 0.0;
+    }
+    let m = (a + b) / 2.0;
+    let h = (b - a) / 2.0;
+    let (x1, x2, x3) = (
+        0.942_882_415_695_480,
+        0.641_853_342_345_781,
+        0.236_383_199_662_150,
+    );
+    let x = [
+        a,
+        m - x1 * h,
+        m - ALPHA * h,
+        m - x2 * h,
+        m - BETA * h,
+        m - x3 * h,
+        m,
+        m + x3 * h,
+        m + BETA * h,
+        m + x2 * h,
+        m + ALPHA * h,
+        m + x1 * h,
+        b,
+    ];
+    let y: Vec<f64> = x.iter().map(|x| f(*x)).collect();
+
+    // a 13-point Kronrod estimate sets the scale the recursion stops at
+    let i2 = h / 6.0 * (y[0] + y[12] + 5.0 * (y[4] + y[8]));
+    let i1 = h / 1470.0
+        * (77.0 * (y[0] + y[12]) + 432.0 * (y[2] + y[10]) + 625.0 * (y[4] + y[8]) + 672.0 * y[6]);
+    let estimate = h
+        * (0.015_827_191_973_480_2 * (y[0] + y[12])
+            + 0.094_273_840_218_850_0 * (y[1] + y[11])
+            + 0.155_071_987_336_585 * (y[2] + y[10])
+            + 0.188_821_573_960_182 * (y[3] + y[9])
+            + 0.199_773_405_226_859 * (y[4] + y[8])
+            + 0.224_926_465_333_340 * (y[5] + y[7])
+            + 0.242_611_071_901_408 * y[6]);
+    let mut tolerance = tolerance.max(f64::EPSILON);
+    let (error1, error2) = ((i1 - estimate).abs(), (i2 - estimate).abs());
+    let r = if error2 != 0.0 { error1 / error2 } else { 1.0 };
+    if r > 0.0 && r < 1.0 {
+        tolerance /= r;
+    }
+    let sign = if estimate < 0.0 { -1.0 } else { 1.0 };
+    let mut scale = sign * estimate.abs() * tolerance / f64::EPSILON;
+    if scale == 0.0 {
+        scale = b - a;
+    }
+    return lobatto_step(&f, a, b, y[0], y[12], scale, 0);
+}
+
+fn lobatto_step<F: Fn(f64) -> f64>(
+    f: &F,
+    a: f64,
+    b: f64,
+    fa: f64,
+    fb: f64,
+    scale: f64,
+    depth: usize,
+) -> f64 {
+    let h = (b - a) / 2.0;
+    let m = (a + b) / 2.0;
+    let (mll, ml, mr, mrr) = (m - ALPHA * h, m - BETA * h, m + BETA * h, m + ALPHA * h);
+    let (fmll, fml, fm, fmr, fmrr) = (f(mll), f(ml), f(m), f(mr), f(mrr));
+    let i2 = h / 6.0 * (fa + fb + 5.0 * (fml + fmr));
+    let i1 =
+        h / 1470.0 * (77.0 * (fa + fb) + 432.0 * (fmll + fmrr) + 625.0 * (fml + fmr) + 672.0 * fm);
+    if scale + (i1 - i2) == scale || mll <= a || b <= mrr || depth >= MAX_DEPTH {
+        return i1;
+    }
+    let depth = depth + 1;
+    return lobatto_step(f, a, mll, fa, fmll, scale, depth)
+        + lobatto_step(f, mll, ml, fmll, fml, scale, depth)
+        + lobatto_step(f, ml, m, fml, fm, scale, depth)
+        + lobatto_step(f, m, mr, fm, fmr, scale, depth)
+        + lobatto_step(f, mr, mrr, fmr, fmrr, scale, depth)
+        + lobatto_step(f, mrr, b, fmrr, fb, scale, depth);
+}
+
+/**
+ * Integral of `f` over `[a, ∞)` by adaptive Gauss–Lobatto quadrature after substituting
+ * `x = a + t / (1 - t)`. `f` must decay fast enough for the integral to converge.
+ */
+pub fn gauss_lobatto_semi_infinite<F: Fn(f64) -> f64>(f: F, a: f64, tolerance: f64) -> f64 {
+    return gauss_lobatto(
+        |t| -> f64 {
+            if t >= 1.0 {
+                return 0.0;
+            }
+            let value = f(a + t / (1.0 - t)) / ((1.0 - t) * (1.0 - t));
+            return if value.is_finite() { value } else { 0.0 };
+        },
+        0.0,
+        1.0,
+        tolerance,
+    );
+}
+
+/**
+ * Gauss–Laguerre quadrature rule with a fixed number of nodes, for integrals over `[0, ∞)`.
+ * Computing the rule is the expensive part, so it is built once and reused.
+ */
+#[derive(Clone, Debug)]
+pub struct GaussLaguerre {
+    nodes: Vec<f64>,
+    weights: Vec<f64>,
+}
+
+impl GaussLaguerre {
+    /**
+     * The `n`-point rule. Nodes are found by Newton's method on the Laguerre polynomial
+     * (Numerical Recipes, §4.6). Practical up to a few hundred nodes.
+     */
+    pub fn new(n: usize) -> GaussLaguerre {
+        let mut nodes: Vec<f64> = Vec::with_capacity(n);
+        let mut weights = Vec::with_capacity(n);
+        let nf = n as f64;
+        let mut z: f64 = 0.0;
+        for i in 0..n {
+            z = match i {
+                0 => 3.0 / (1.0 + 2.4 * nf),
+                1 => z + 15.0 / (1.0 + 2.5 * nf),
+                _ => {
+                    let ai = (i - 1) as f64;
+                    z + (1.0 + 2.55 * ai) / (1.9 * ai) * (z - nodes[i - 2])
+                }
+            };
+            let (mut p2, mut pp) = (0.0, 1.0);
+            for _ in 0..100 {
+                let mut p1 = 1.0;
+                p2 = 0.0;
+                for j in 1..=n {
+                    let p3 = p2;
+                    p2 = p1;
+                    p1 = ((2.0 * j as f64 - 1.0 - z) * p2 - (j as f64 - 1.0) * p3) / j as f64;
+                }
+                pp = (nf * p1 - nf * p2) / z;
+                let previous = z;
+                z = previous - p1 / pp;
+                if (z - previous).abs() <= 3e-14 * z.abs().max(1.0) {
+                    break;
+                }
+            }
+            nodes.push(z);
+            weights.push(-1.0 / (pp * nf * p2));
+        }
+        return GaussLaguerre { nodes, weights };
+    }
+
+    pub fn nodes(&self) -> &[f64] {
+        return &self.nodes;
+    }
+
+    pub fn weights(&self) -> &[f64] {
+        return &self.weights;
+    }
+
+    /**
+     * Integral of `f(x) e^{-x}` over `[0, ∞)`.
+     */
+    pub fn integrate_weighted<F: Fn(f64) -> f64>(&self, f: F) -> f64 {
+        return self
+            .nodes
+            .iter()
+            .zip(self.weights.iter())
+            .map(|(x, w)| w * f(*x))
+            .sum();
+    }
+
+    /**
+     * Integral of `f(x)` over `[0, ∞)`, for `f` that decays roughly exponentially.
+     */
+    pub fn integrate<F: Fn(f64) -> f64>(&self, f: F) -> f64 {
+        return self.integrate_weighted(|x| f(x) * x.exp());
+    }
+}
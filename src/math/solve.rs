@@ -0,0 +1,253 @@
+//! One-dimensional root finding.
+//!
+//! Every solver in the crate goes through these, so they converge and report the same way.
+
+/**
+ * Result of a root search.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Root {
+    pub x: f64,
+    pub iterations: usize,
+    /// Steps where a safeguarded method fell back to bisection.
+    pub bisection_steps: usize,
+    /// Whether the tolerance was met, rather than the iterations running out.
+    pub converged: bool,
+}
+
+/**
+ * Bisection on `[low, high]`, until the bracket is narrower than `tolerance`. `None` if `f` has
+ * the same sign at both ends.
+ */
+pub fn bisection<F: Fn(f64) -> f64>(
+    f: F,
+    low: f64,
+    high: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> Option<Root> {
+    let (mut low, mut high) = (low, high);
+    let mut f_low = f(low);
+    let f_high = f(high);
+    if f_low == 0.0 {
+        return Some(Root {
+            x: low,
+            iterations: 0,
+            bisection_steps: 0,
+            converged: true,
+        });
+    }
+    if f_high == 0.0 {
+        return Some(Root {
+            x: high,
+            iterations: 0,
+            bisection_steps: 0,
+            converged: true,
+        });
+    }
+    if f_low.signum() == f_high.signum() {
+        return None;
+    }
+    for i in 0..max_iterations {
+        let mid = (low + high) / 2.0;
+        let f_mid = f(mid);
+        if f_mid == 0.0 || (high - low).abs() / 2.0 < tolerance {
+            return Some(Root {
+                x: mid,
+                iterations: i + 1,
+                bisection_steps: i + 1,
+                converged: true,
+            });
+        }
+        if f_mid.signum() == f_low.signum() {
+            low = mid;
+            f_low = f_mid;
+        } else {
+            high = mid;
+        }
+    }
+    return Some(Root {
+        x: (low + high) / 2.0,
+        iterations: max_iterations,
+        bisection_steps: max_iterations,
+        converged: false,
+    });
+}
+
+/**
+ * Brent's method on `[low, high]`, combining inverse quadratic interpolation, secant steps, and
+ * bisection, until the root is located within `tolerance`. `None` if `f` has the same sign at
+ * both ends.
+ */
+pub fn brent<F: Fn(f64) -> f64>(
+    f: F,
+    low: f64,
+    high: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> Option<Root> {
+    let (mut a, mut b) = (low, high);
+    let (mut fa, mut fb) = (f(a), f(b));
+    if fa.signum() == fb.signum() && fa != 0.0 && fb != 0.0 {
+        return None;
+    }
+    let (mut c, mut fc) = (a, fa);
+    let mut d = b - a;
+    let mut e = d;
+    let mut bisection_steps = 0;
+
+    for i in 0..max_iterations {
+        if fb.signum() == fc.signum() && fb != 0.0 {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+        let tol = 2.0 * f64::EPSILON * b.abs() + 0.5 * tolerance;
+        let m = 0.5 * (c - b);
+        if m.abs() <= tol || fb == 0.0 {
+            return Some(Root {
+                x: b,
+                iterations: i,
+                bisection_steps,
+                converged: true,
+            });
+        }
+
+        if e.abs() >= tol && fa.abs() > fb.abs() {
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (2.0 * m * s, 1.0 - s)
+            } else {
+                let q = fa / fc;
+                let r = fb / fc;
+                (
+                    s * (2.0 * m * q * (q - r) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            } else {
+                p = -p;
+            }
+            if 2.0 * p < (3.0 * m * q - (tol * q).abs()).min((e * q).abs()) {
+                e = d;
+                d = p / q;
+            } else {
+                d = m;
+                e = m;
+                bisection_steps += 1;
+            }
+        } else {
+            d = m;
+            e = m;
+            bisection_steps += 1;
+        }
+
+        a = b;
+        fa = fb;
+        b += if d.abs() > tol { d } else { tol.copysign(m) };
+        fb = f(b);
+    }
+    return Some(Root {
+        x: b,
+        iterations: max_iterations,
+        bisection_steps,
+        converged: false,
+    });
+}
+
+/**
+ * Newton's method from `x0`, safeguarded by the bracket `[low, high]`: any step that would leave
+ * the bracket is replaced by bisection, and the bracket shrinks around the root as `f` is
+ * evaluated. `f` returns the function value and its derivative; iteration stops once
+ * `|f(x)| < tolerance`.
+ */
+pub fn newton<F: Fn(f64) -> (f64, f64)>(
+    f: F,
+    x0: f64,
+    low: f64,
+    high: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> Root {
+    let (mut low, mut high) = (low, high);
+    // which side of the root the lower end is on
+    let low_sign = f(low).0.signum();
+    let mut x = if x0 > low && x0 < high {
+        x0
+    } else {
+        (low + high) / 2.0
+    };
+    let mut bisection_steps = 0;
+    for i in 0..max_iterations {
+        let (value, derivative) = f(x);
+        if value.abs() < tolerance {
+            return Root {
+                x,
+                iterations: i + 1,
+                bisection_steps,
+                converged: true,
+            };
+        }
+        if value.signum() == low_sign {
+            low = x;
+        } else {
+            high = x;
+        }
+
+        let next = x - value / derivative;
+        x = if derivative != 0.0 && next > low && next < high {
+            next
+        } else {
+            bisection_steps += 1;
+            (low + high) / 2.0
+        };
+    }
+    return Root {
+        x,
+        iterations: max_iterations,
+        bisection_steps,
+        converged: false,
+    };
+}
+
+/**
+ * Fixed-point iteration `x = g(x)` from `x0`, until successive iterates are within `tolerance`.
+ * Converges when `g` is a contraction near the fixed point.
+ */
+pub fn fixed_point<G: Fn(f64) -> f64>(
+    g: G,
+    x0: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> Root {
+    let mut x = x0;
+    for i in 0..max_iterations {
+        let next = g(x);
+        if (next - x).abs() < tolerance {
+            return Root {
+                x: next,
+                iterations: i + 1,
+                bisection_steps: 0,
+                converged: true,
+            };
+        }
+        x = next;
+    }
+    return Root {
+        x,
+        iterations: max_iterations,
+        bisection_steps: 0,
+        converged: false,
+    };
+}
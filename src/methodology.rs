@@ -0,0 +1,347 @@
+//! Conventions of published implied volatility indices.
+//!
+//! Each index applies the same variance-swap replication with its own horizon, rates, strike
+//! truncation, and expiration times. A `Methodology` bundles those choices so a published index
+//! can be reproduced from a chain without reading its whitepaper.
+
+use crate::chain::Chain;
+use crate::currency::Currency;
+use crate::holidays::{nth_weekday, CalendarRegistry};
+use crate::interpolation::WeightingInputs;
+use crate::quality::IndexEstimate;
+use crate::rates::YieldCurve;
+use crate::series::select_terms_around;
+use crate::{
+    combine_terms, constant_maturity_index, EndIntervals, IndexConfig, MissingTerm,
+    OptionsByExpiryDate, Percentage, SameMinuteExpiry, Term, Truncation,
+};
+use chrono::prelude::*;
+use chrono::Duration;
+
+/**
+ * Where the rate used to discount each term's option prices comes from.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum RateSource {
+    /// The yield curve at each term's expiration.
+    Curve,
+    /// No discounting, for venues that quote options on futures and settle premium upfront in
+    /// the underlying.
+    Zero,
+}
+
+/**
+ * An index methodology.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Methodology {
+    pub name: &'static str,
+    /// Constant maturity the near- and next-term variances are interpolated to.
+    pub horizon_days: u32,
+    pub rates: RateSource,
+    pub index: IndexConfig,
+    /// Local time at which the options used by the index settle.
+    pub settlement_time: NaiveTime,
+    /// Monthly options expire on the `monthly_week`th `monthly_weekday` of the month (negative
+    /// counts from the end), or the trading day before it.
+    pub monthly_weekday: Weekday,
+    pub monthly_week: i32,
+    /// Offset of the exchange's time zone from UTC, for zones without daylight saving time.
+    /// `None` where there is daylight saving time; chain times must then already be local.
+    pub utc_offset: Option<FixedOffset>,
+    /// Exchange calendar name in the `CalendarRegistry`, or `None` for venues that never close.
+    pub calendar: Option<&'static str>,
+    /// Currency the rates and premiums are in.
+    pub currency: Currency,
+}
+
+/**
+ * The volatility of a single expiry, in percent. VSTOXX publishes one of these for each of its
+ * expiries alongside the main index.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct SubIndex {
+    pub expires_at: NaiveDateTime,
+    /// Minutes to expiration when the sub-index was computed.
+    pub minutes: f64,
+    pub value: Percentage,
+}
+
+/**
+ * Published indices this crate can reproduce.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Preset {
+    /// Cboe Volatility Index: 30-day S&P 500 volatility.
+    CboeVix,
+    /// Cboe 9-Day Volatility Index, from PM-settled S&P 500 weeklies.
+    CboeVix9d,
+    /// Deribit DVOL: 30-day bitcoin or ether volatility, trading around the clock.
+    DeribitDvol,
+    /// EURO STOXX 50 volatility, from Eurex options settling at 12:00 CET, discounted with EUR
+    /// rates.
+    Vstoxx,
+    /// Nikkei Stock Average Volatility Index, from Osaka options settling at the open on the
+    /// second Friday.
+    NikkeiVi,
+    /// KOSPI 200 volatility, from Korea Exchange options expiring on the second Thursday.
+    Vkospi,
+}
+
+impl Preset {
+    pub fn methodology(self) -> Methodology {
+        let cboe = IndexConfig {
+            truncation: Truncation::ConsecutiveZeroBids,
+            end_intervals: EndIntervals::Summed,
+            ..IndexConfig::default()
+        };
+        let time = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        return match self {
+            Preset::CboeVix => Methodology {
+                name: "VIX",
+                horizon_days: 30,
+                rates: RateSource::Curve,
+                index: cboe,
+                settlement_time: time(9, 30),
+                calendar: Some("CBOE"),
+                currency: Currency::USD,
+                monthly_weekday: Weekday::Fri,
+                monthly_week: 3,
+                utc_offset: None,
+            },
+            Preset::CboeVix9d => Methodology {
+                name: "VIX9D",
+                horizon_days: 9,
+                rates: RateSource::Curve,
+                index: cboe,
+                settlement_time: time(16, 0),
+                calendar: Some("CBOE"),
+                currency: Currency::USD,
+                monthly_weekday: Weekday::Fri,
+                monthly_week: 3,
+                utc_offset: None,
+            },
+            Preset::DeribitDvol => Methodology {
+                name: "DVOL",
+                horizon_days: 30,
+                rates: RateSource::Zero,
+                index: IndexConfig {
+                    same_minute: SameMinuteExpiry::OneMinute,
+                    ..IndexConfig::default()
+                },
+                settlement_time: time(8, 0),
+                calendar: None,
+                currency: Currency::USD,
+                monthly_weekday: Weekday::Fri,
+                monthly_week: -1,
+                utc_offset: FixedOffset::east_opt(0),
+            },
+            Preset::Vstoxx => Methodology {
+                name: "VSTOXX",
+                horizon_days: 30,
+                rates: RateSource::Curve,
+                index: IndexConfig::default(),
+                settlement_time: time(12, 0),
+                calendar: Some("EUREX"),
+                currency: Currency::EUR,
+                monthly_weekday: Weekday::Fri,
+                monthly_week: 3,
+                utc_offset: None,
+            },
+            Preset::NikkeiVi => Methodology {
+                name: "Nikkei VI",
+                horizon_days: 30,
+                rates: RateSource::Curve,
+                index: IndexConfig::default(),
+                settlement_time: time(9, 0),
+                calendar: Some("JPX"),
+                currency: Currency::JPY,
+                monthly_weekday: Weekday::Fri,
+                monthly_week: 2,
+                utc_offset: FixedOffset::east_opt(9 * 3600),
+            },
+            Preset::Vkospi => Methodology {
+                name: "VKOSPI",
+                horizon_days: 30,
+                rates: RateSource::Curve,
+                index: IndexConfig::default(),
+                settlement_time: time(15, 20),
+                calendar: Some("KRX"),
+                currency: Currency::KRW,
+                monthly_weekday: Weekday::Thu,
+                monthly_week: 2,
+                utc_offset: FixedOffset::east_opt(9 * 3600),
+            },
+        };
+    }
+}
+
+impl Methodology {
+    pub fn horizon_minutes(&self) -> f64 {
+        return (self.horizon_days * 24 * 60) as f64;
+    }
+
+    /**
+     * When options expiring on `date` settle.
+     */
+    pub fn expiration(&self, date: NaiveDate) -> NaiveDateTime {
+        return date.and_time(self.settlement_time);
+    }
+
+    /**
+     * When the monthly options of `year` and `month` settle on the methodology's calendar, or
+     * `None` if `registry` does not have it.
+     */
+    pub fn monthly_expiration(
+        &self,
+        year: i32,
+        month: u32,
+        registry: &CalendarRegistry,
+    ) -> Option<NaiveDateTime> {
+        let mut date = nth_weekday(year, month, self.monthly_weekday, self.monthly_week);
+        if let Some(name) = self.calendar {
+            date = registry.get(name)?.previous_trading_day(date);
+        }
+        return Some(self.expiration(date));
+    }
+
+    /**
+     * `at` in the exchange's local time, or `None` if the zone has daylight saving time.
+     */
+    pub fn local_time(&self, at: DateTime<Utc>) -> Option<NaiveDateTime> {
+        let offset = self.utc_offset?;
+        return Some(at.naive_utc() + Duration::seconds(offset.local_minus_utc() as i64));
+    }
+
+    /**
+     * Like `compute`, as of a UTC time, for chains whose expirations are in local time. `None`
+     * also if the zone has daylight saving time.
+     */
+    pub fn compute_at(
+        &self,
+        chain: &Chain,
+        rates: &YieldCurve,
+        at: DateTime<Utc>,
+    ) -> Option<Percentage> {
+        return self.compute(chain, rates, self.local_time(at)?);
+    }
+
+    /**
+     * The rate discounting the options of `expiry`.
+     */
+    pub fn rate(
+        &self,
+        expiry: &OptionsByExpiryDate,
+        rates: &YieldCurve,
+        now: NaiveDateTime,
+    ) -> f64 {
+        return match self.rates {
+            RateSource::Curve => rates.rate_at(expiry.expires_at(), now),
+            RateSource::Zero => 0.0,
+        };
+    }
+
+    /**
+     * The near- and next-term expiries bracketing the horizon.
+     */
+    pub fn select_terms<'a>(
+        &self,
+        chain: &'a Chain,
+        now: NaiveDateTime,
+    ) -> Option<(&'a OptionsByExpiryDate, &'a OptionsByExpiryDate)> {
+        return select_terms_around(chain, now, self.horizon_minutes(), &self.index);
+    }
+
+    /**
+     * The index as of `now`, or `None` if the chain has no expiries either side of the horizon.
+     */
+    pub fn compute(
+        &self,
+        chain: &Chain,
+        rates: &YieldCurve,
+        now: NaiveDateTime,
+    ) -> Option<Percentage> {
+        return self.estimate(chain, rates, now).map(|e| e.value);
+    }
+
+    /**
+     * Like `compute`, with the quality of each term's variance.
+     */
+    pub fn estimate(
+        &self,
+        chain: &Chain,
+        rates: &YieldCurve,
+        now: NaiveDateTime,
+    ) -> Option<IndexEstimate> {
+        #[cfg(feature = "metrics")]
+        let _timer = crate::metrics::Timer::start(&crate::metrics::global().computation_seconds);
+        let (near, next) = self.select_terms(chain, now)?;
+        let n_t1 = near.minutes_to_expiration_with(now, self.index.same_minute);
+        let n_t2 = next.minutes_to_expiration_with(now, self.index.same_minute);
+        if self.index.missing_term == MissingTerm::Require && (n_t1.is_none() || n_t2.is_none()) {
+            return None;
+        }
+        let s1 = near.variance_estimate(self.rate(near, rates, now), now, &self.index);
+        let s2 = next.variance_estimate(self.rate(next, rates, now), now, &self.index);
+        let estimate = combine_terms(
+            Term::new(near, n_t1, s1),
+            Term::new(next, n_t2, s2),
+            now,
+            self.horizon_minutes(),
+            &self.index,
+        );
+        #[cfg(feature = "metrics")]
+        crate::metrics::global().record_index(&estimate);
+        return Some(estimate);
+    }
+
+    /**
+     * A sub-index for every unexpired expiry of the chain, nearest first.
+     */
+    pub fn sub_indices(
+        &self,
+        chain: &Chain,
+        rates: &YieldCurve,
+        now: NaiveDateTime,
+    ) -> Vec<SubIndex> {
+        let (unexpired, _) = chain.unexpired(now, self.index.same_minute);
+        return unexpired
+            .iter()
+            .flat_map(|expiry| {
+                let minutes = expiry.minutes_to_expiration_with(now, self.index.same_minute)?;
+                let variance =
+                    expiry.variance_with_config(self.rate(expiry, rates, now), now, &self.index);
+                return Some(SubIndex {
+                    expires_at: expiry.expires_at(),
+                    minutes,
+                    value: variance.sqrt() * 100.0,
+                });
+            })
+            .collect();
+    }
+
+    /**
+     * The main index from the sub-indices of the expiries either side of the horizon, which is
+     * how VSTOXX is constructed. Equal to `compute` on the chain they came from.
+     */
+    pub fn interpolate(&self, near: &SubIndex, next: &SubIndex) -> Percentage {
+        let variance = |s: &SubIndex| (s.value / 100.0).powi(2);
+        let now = near.expires_at - Duration::seconds((near.minutes * 60.0).round() as i64);
+        let weights = self.index.weighting.weights(&WeightingInputs {
+            now,
+            near_expires_at: near.expires_at,
+            next_expires_at: next.expires_at,
+            near_minutes: near.minutes,
+            next_minutes: next.minutes,
+            target_minutes: self.horizon_minutes(),
+        });
+        return constant_maturity_index(
+            near.minutes,
+            variance(near),
+            next.minutes,
+            variance(next),
+            self.horizon_minutes(),
+            weights,
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Prometheus metrics for running the crate as a live index service.
+//!
+//! The live path records into a process-wide `Metrics` as it goes: quote batches applied to a
+//! `SharedChain`, index computations and how long they took, strikes left out of the
+//! replication, and implied volatility solves that failed. `Metrics::render` formats them in the
+//! Prometheus text exposition format for a scrape endpoint to serve.
+
+use crate::quality::IndexEstimate;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+/**
+ * Upper bounds of the computation latency buckets, in seconds.
+ */
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 1.0,
+];
+
+/**
+ * A monotonically increasing count.
+ */
+#[derive(Debug, Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    pub fn add(&self, n: u64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        return self.0.load(Ordering::Relaxed);
+    }
+}
+
+/**
+ * Counts of observations at or below each bucket's upper bound, with their sum.
+ */
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: &'static [f64],
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    /// Bits of the `f64` sum, updated by compare-and-swap.
+    sum: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &'static [f64]) -> Histogram {
+        return Histogram {
+            bounds,
+            buckets: bounds.iter().map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum: AtomicU64::new(0f64.to_bits()),
+        };
+    }
+
+    pub fn observe(&self, value: f64) {
+        if let Some(i) = self.bounds.iter().position(|bound| value <= *bound) {
+            self.buckets[i].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        let _ = self
+            .sum
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                return Some((f64::from_bits(bits) + value).to_bits());
+            });
+    }
+
+    pub fn count(&self) -> u64 {
+        return self.count.load(Ordering::Relaxed);
+    }
+
+    pub fn sum(&self) -> f64 {
+        return f64::from_bits(self.sum.load(Ordering::Relaxed));
+    }
+
+    /**
+     * `(upper bound, cumulative count)` for every bucket, ending with `+Inf`.
+     */
+    pub fn cumulative(&self) -> Vec<(f64, u64)> {
+        let mut total = 0;
+        let mut buckets: Vec<(f64, u64)> = self
+            .bounds
+            .iter()
+            .zip(self.buckets.iter())
+            .map(|(bound, count)| {
+                total += count.load(Ordering::Relaxed);
+                return (*bound, total);
+            })
+            .collect();
+        buckets.push((f64::INFINITY, self.count()));
+        return buckets;
+    }
+}
+
+/**
+ * Records the time from `start` until it is dropped into a histogram, in seconds.
+ */
+#[derive(Debug)]
+pub struct Timer<'a> {
+    histogram: &'a Histogram,
+    started: Instant,
+}
+
+impl<'a> Timer<'a> {
+    pub fn start(histogram: &'a Histogram) -> Timer<'a> {
+        return Timer {
+            histogram,
+            started: Instant::now(),
+        };
+    }
+}
+
+impl Drop for Timer<'_> {
+    fn drop(&mut self) {
+        self.histogram.observe(self.started.elapsed().as_secs_f64());
+    }
+}
+
+#[derive(Debug)]
+pub struct Metrics {
+    /// Index values computed from a pair of terms.
+    pub chains_processed: Counter,
+    /// Quote updates applied to shared chains.
+    pub quote_updates: Counter,
+    /// Quoted strikes the truncation or the zero-bid policy left out of a term's replication.
+    pub strikes_dropped: Counter,
+    /// Implied volatility solves with no solution or that did not converge.
+    pub solver_failures: Counter,
+    /// Seconds taken by each index computation.
+    pub computation_seconds: Histogram,
+}
+
+impl Default for Metrics {
+    fn default() -> Metrics {
+        return Metrics {
+            chains_processed: Counter::default(),
+            quote_updates: Counter::default(),
+            strikes_dropped: Counter::default(),
+            solver_failures: Counter::default(),
+            computation_seconds: Histogram::new(&LATENCY_BUCKETS),
+        };
+    }
+}
+
+impl Metrics {
+    /**
+     * Records an index value computed at one of the public entry points, with the strikes its
+     * two terms dropped. Variances computed along the way, e.g. for calibration, are not counted.
+     */
+    pub(crate) fn record_index(&self, estimate: &IndexEstimate) {
+        self.chains_processed.inc();
+        self.strikes_dropped
+            .add((estimate.near_term.strikes_dropped + estimate.next_term.strikes_dropped) as u64);
+    }
+
+    /**
+     * The metrics in the Prometheus text exposition format, each name prefixed with
+     * `options_math_`.
+     */
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let counters = [
+            (
+                "chains_processed_total",
+                "Index values computed.",
+                &self.chains_processed,
+            ),
+            (
+                "quote_updates_total",
+                "Quote updates applied to shared chains.",
+                &self.quote_updates,
+            ),
+            (
+                "strikes_dropped_total",
+                "Quoted strikes left out of the variance replication.",
+                &self.strikes_dropped,
+            ),
+            (
+                "solver_failures_total",
+                "Implied volatility solves that failed.",
+                &self.solver_failures,
+            ),
+        ];
+        for (name, help, counter) in counters.iter() {
+            let _ = writeln!(out, "# HELP options_math_{} {}", name, help);
+            let _ = writeln!(out, "# TYPE options_math_{} counter", name);
+            let _ = writeln!(out, "options_math_{} {}", name, counter.get());
+        }
+
+        let name = "options_math_computation_seconds";
+        let histogram = &self.computation_seconds;
+        let _ = writeln!(out, "# HELP {} Time taken by each index computation.", name);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (bound, count) in histogram.cumulative() {
+            let le = if bound.is_infinite() {
+                "+Inf".to_string()
+            } else {
+                bound.to_string()
+            };
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, le, count);
+        }
+        let _ = writeln!(out, "{}_sum {}", name, histogram.sum());
+        let _ = writeln!(out, "{}_count {}", name, histogram.count());
+        return out;
+    }
+}
+
+/**
+ * The metrics the crate records into.
+ */
+pub fn global() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    return METRICS.get_or_init(Metrics::default);
+}
@@ -0,0 +1,969 @@
+//! Parametric smile models, their calibration to an expiry, and a report comparing them.
+//!
+//! Every model is calibrated to implied volatilities by moneyness (strike over forward), so
+//! prices are in units of the forward and parameters do not depend on the level of the
+//! underlying.
+
+use crate::analytics::ExpiryAnalytics;
+use crate::fourier::{fourier_price, CarrMadan, CharacteristicFunction};
+use crate::math::complex::Complex;
+use crate::math::{black_price, implied_volatility, nelder_mead};
+use crate::{Cents, OptionKind, OptionsByExpiryDate};
+use chrono::prelude::*;
+
+/**
+ * Number of points the density of each fitted model is tabulated at.
+ */
+const DENSITY_POINTS: usize = 101;
+
+/**
+ * Penalty on the squared error of a point the model cannot produce a volatility for.
+ */
+const MISSING_PENALTY: f64 = 1.0;
+
+/**
+ * A model of the implied volatility smile of a single expiry.
+ */
+pub trait SmileModel {
+    fn name(&self) -> &'static str;
+
+    /**
+     * Parameter names and values, in a fixed order.
+     */
+    fn parameters(&self) -> Vec<(&'static str, f64)>;
+
+    /**
+     * Implied volatilities at each moneyness for an expiration `t` years away, or `None` where
+     * the model has none.
+     */
+    fn volatilities(&self, moneyness: &[f64], t: f64) -> Vec<Option<f64>>;
+}
+
+/**
+ * Black–Scholes with a single volatility across strikes.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct FlatVolatility {
+    pub volatility: f64,
+}
+
+impl FlatVolatility {
+    /**
+     * The volatility minimizing squared error to `smile`, i.e. its mean.
+     */
+    pub fn calibrate(smile: &[(f64, f64)]) -> Option<FlatVolatility> {
+        if smile.is_empty() {
+            return None;
+        }
+        let mean = smile.iter().map(|p| p.1).sum::<f64>() / smile.len() as f64;
+        return Some(FlatVolatility::new(mean));
+    }
+}
+
+impl SmileModel for FlatVolatility {
+    fn name(&self) -> &'static str {
+        return "Black-Scholes";
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        return vec![("volatility", self.volatility)];
+    }
+
+    fn volatilities(&self, moneyness: &[f64], _t: f64) -> Vec<Option<f64>> {
+        return moneyness.iter().map(|_| Some(self.volatility)).collect();
+    }
+}
+
+/**
+ * Gatheral's raw SVI parameterization of total implied variance in log moneyness `k`:
+ * `w(k) = a + b (rho (k - m) + sqrt((k - m)^2 + sigma^2))`.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct Svi {
+    pub a: f64,
+    pub b: f64,
+    pub rho: f64,
+    pub m: f64,
+    pub sigma: f64,
+}
+
+impl Svi {
+    pub fn total_variance(&self, k: f64) -> f64 {
+        let x = k - self.m;
+        return self.a + self.b * (self.rho * x + (x * x + self.sigma * self.sigma).sqrt());
+    }
+
+    pub fn calibrate(smile: &[(f64, f64)], t: f64) -> Option<Svi> {
+        let atm = atm_volatility(smile)?;
+        let from = |x: &[f64]| -> Svi {
+            return Svi::new(x[0], x[1].exp(), x[2].tanh(), x[3], x[4].exp());
+        };
+        let x0 = [
+            0.5 * atm * atm * t,
+            (0.1f64).ln(),
+            (-0.3f64).atanh(),
+            0.0,
+            (0.1f64).ln(),
+        ];
+        let x = calibrate(from, smile, t, &x0);
+        return Some(from(&x));
+    }
+}
+
+impl SmileModel for Svi {
+    fn name(&self) -> &'static str {
+        return "SVI";
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        return vec![
+            ("a", self.a),
+            ("b", self.b),
+            ("rho", self.rho),
+            ("m", self.m),
+            ("sigma", self.sigma),
+        ];
+    }
+
+    fn volatilities(&self, moneyness: &[f64], t: f64) -> Vec<Option<f64>> {
+        return moneyness
+            .iter()
+            .map(|m| {
+                let w = self.total_variance(m.ln());
+                return if w > 0.0 { Some((w / t).sqrt()) } else { None };
+            })
+            .collect();
+    }
+}
+
+/**
+ * SABR with Hagan et al.'s (2002) lognormal volatility approximation, with the forward
+ * normalized to one. `beta` is usually fixed rather than calibrated, since `beta` and `rho` both
+ * tilt the smile and a single expiry rarely tells them apart.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct Sabr {
+    pub alpha: f64,
+    pub beta: f64,
+    pub rho: f64,
+    pub nu: f64,
+}
+
+impl Sabr {
+    pub fn volatility(&self, moneyness: f64, t: f64) -> f64 {
+        let (alpha, beta, rho, nu) = (self.alpha, self.beta, self.rho, self.nu);
+        let log_fk = -moneyness.ln();
+        let fk = moneyness.powf((1.0 - beta) / 2.0);
+        let one_minus_beta = (1.0 - beta) * (1.0 - beta);
+        let z = nu / alpha * fk * log_fk;
+        let z_over_x = if z.abs() < 1e-8 {
+            1.0
+        } else {
+            let x = (((1.0 - 2.0 * rho * z + z * z).sqrt() + z - rho) / (1.0 - rho)).ln();
+            z / x
+        };
+        let denominator = fk
+            * (1.0
+                + one_minus_beta / 24.0 * log_fk * log_fk
+                + one_minus_beta * one_minus_beta / 1920.0 * log_fk.powi(4));
+        let correction = 1.0
+            + (one_minus_beta / 24.0 * alpha * alpha / (fk * fk)
+                + rho * beta * nu * alpha / (4.0 * fk)
+                + (2.0 - 3.0 * rho * rho) * nu * nu / 24.0)
+                * t;
+        return alpha / denominator * z_over_x * correction;
+    }
+
+    pub fn calibrate(smile: &[(f64, f64)], t: f64, beta: f64) -> Option<Sabr> {
+        let atm = atm_volatility(smile)?;
+        let from = |x: &[f64]| -> Sabr {
+            return Sabr::new(x[0].exp(), beta, x[1].tanh(), x[2].exp());
+        };
+        let x0 = [atm.ln(), (-0.2f64).atanh(), (0.5f64).ln()];
+        let x = calibrate(from, smile, t, &x0);
+        return Some(from(&x));
+    }
+
+    /**
+     * Like `calibrate`, also fitting `beta` within [0, 1].
+     */
+    pub fn calibrate_free_beta(smile: &[(f64, f64)], t: f64) -> Option<Sabr> {
+        let atm = atm_volatility(smile)?;
+        let from = |x: &[f64]| -> Sabr {
+            return Sabr::new(
+                x[0].exp(),
+                (x[3].tanh() + 1.0) / 2.0,
+                x[1].tanh(),
+                x[2].exp(),
+            );
+        };
+        let x0 = [atm.ln(), (-0.2f64).atanh(), (0.5f64).ln(), 0.0];
+        let x = calibrate(from, smile, t, &x0);
+        return Some(from(&x));
+    }
+}
+
+/**
+ * SABR calibrated to the quotes of one expiry, for volatilities and prices at strikes that are
+ * not listed.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct SabrSlice {
+    pub model: Sabr,
+    pub forward_price: Cents,
+    pub time_to_expiration: f64,
+    pub discount_factor: f64,
+    /// Root mean squared volatility error over the quotes it was fitted to.
+    pub rmse: f64,
+}
+
+impl SabrSlice {
+    /**
+     * Calibrates SABR to the out-of-the-money options of `options` with a bid, with `beta` fixed
+     * or, if `None`, fitted as well. `None` if the expiry has expired or has no quotes to fit.
+     */
+    pub fn calibrate(
+        options: &OptionsByExpiryDate,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+        beta: Option<f64>,
+    ) -> Option<SabrSlice> {
+        let t = options.time_to_expiration(now);
+        if t <= 0.0 {
+            return None;
+        }
+        let forward_price = options.forward_price(risk_free_rate, now);
+        if forward_price <= 0 {
+            return None;
+        }
+        let forward = forward_price as f64;
+        let discount_factor = (-risk_free_rate * t).exp();
+        let mut smile: Vec<(f64, f64)> = options
+            .puts()
+            .iter()
+            .filter(|o| o.strike < forward_price)
+            .chain(options.calls().iter().filter(|o| o.strike >= forward_price))
+            .filter(|o| o.bid > 0)
+            .flat_map(|o| {
+                let strike = o.strike as f64;
+                let vol = implied_volatility(
+                    o.kind,
+                    o.mark() as f64,
+                    forward,
+                    strike,
+                    t,
+                    discount_factor,
+                )?;
+                return Some((strike / forward, vol));
+            })
+            .collect();
+        smile.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        let model = match beta {
+            Some(beta) => Sabr::calibrate(&smile, t, beta)?,
+            None => Sabr::calibrate_free_beta(&smile, t)?,
+        };
+        return Some(SabrSlice {
+            model,
+            forward_price,
+            time_to_expiration: t,
+            discount_factor,
+            rmse: ModelFit::of(&model, &smile, t).rmse,
+        });
+    }
+
+    /**
+     * Implied volatility at `strike`.
+     */
+    pub fn volatility(&self, strike: Cents) -> f64 {
+        return self.model.volatility(
+            strike as f64 / self.forward_price as f64,
+            self.time_to_expiration,
+        );
+    }
+
+    /**
+     * Price of the option at `strike`, rounded to the nearest cent.
+     */
+    pub fn price(&self, kind: OptionKind, strike: Cents) -> Cents {
+        let price = black_price(
+            kind,
+            self.forward_price as f64,
+            strike as f64,
+            self.volatility(strike),
+            self.time_to_expiration,
+            self.discount_factor,
+        );
+        return price.round() as Cents;
+    }
+}
+
+impl SmileModel for Sabr {
+    fn name(&self) -> &'static str {
+        return "SABR";
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        return vec![
+            ("alpha", self.alpha),
+            ("beta", self.beta),
+            ("rho", self.rho),
+            ("nu", self.nu),
+        ];
+    }
+
+    fn volatilities(&self, moneyness: &[f64], t: f64) -> Vec<Option<f64>> {
+        return moneyness
+            .iter()
+            .map(|m| {
+                let vol = self.volatility(*m, t);
+                return if vol.is_finite() && vol > 0.0 {
+                    Some(vol)
+                } else {
+                    None
+                };
+            })
+            .collect();
+    }
+}
+
+/**
+ * Heston's stochastic volatility model: variance starts at `v0` and reverts to `theta` at rate
+ * `kappa`, with volatility of variance `sigma` and correlation `rho` to the underlying.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct Heston {
+    pub v0: f64,
+    pub kappa: f64,
+    pub theta: f64,
+    pub sigma: f64,
+    pub rho: f64,
+}
+
+impl Heston {
+    pub fn calibrate(smile: &[(f64, f64)], t: f64) -> Option<Heston> {
+        let atm = atm_volatility(smile)?;
+        let from = |x: &[f64]| -> Heston {
+            return Heston::new(x[0].exp(), x[1].exp(), x[2].exp(), x[3].exp(), x[4].tanh());
+        };
+        let variance = (atm * atm).ln();
+        let x0 = [
+            variance,
+            (2.0f64).ln(),
+            variance,
+            (0.5f64).ln(),
+            (-0.5f64).atanh(),
+        ];
+        let x = calibrate(from, smile, t, &x0);
+        return Some(from(&x));
+    }
+
+    /**
+     * Discounted European price, by integrating the characteristic function.
+     */
+    pub fn price(
+        &self,
+        kind: OptionKind,
+        forward: f64,
+        strike: f64,
+        t: f64,
+        discount_factor: f64,
+    ) -> f64 {
+        return fourier_price(self, kind, forward, strike, t, discount_factor);
+    }
+
+    /**
+     * Expected average variance over the next `t` years, annualized: the model's fair
+     * variance swap strike, comparable to the model-free variance of an expiry.
+     */
+    pub fn expected_variance(&self, t: f64) -> f64 {
+        let decay = self.kappa * t;
+        if decay < 1e-12 {
+            return self.v0;
+        }
+        return self.theta + (self.v0 - self.theta) * (1.0 - (-decay).exp()) / decay;
+    }
+}
+
+/**
+ * Heston calibrated to an expiry, with the variance it implies beside the model-free variance
+ * the index computes from the same quotes.
+ */
+#[derive(Clone, Debug)]
+pub struct HestonFit {
+    pub model: Heston,
+    pub fit: ModelFit,
+    /// `Heston::expected_variance` to the expiration.
+    pub model_variance: f64,
+    /// Variance-swap replication of the expiry's quotes.
+    pub model_free_variance: f64,
+}
+
+impl HestonFit {
+    /**
+     * Model-free variance less the model's, positive where the quoted wings price in more
+     * variance than the calibrated dynamics.
+     */
+    pub fn variance_spread(&self) -> f64 {
+        return self.model_free_variance - self.model_variance;
+    }
+}
+
+/**
+ * Calibrates Heston to the out-of-the-money smile of `analytics`, the analytics of `options` as
+ * of `now`. `None` if the expiry has no smile or has expired.
+ */
+pub fn fit_heston(
+    options: &OptionsByExpiryDate,
+    analytics: &ExpiryAnalytics,
+    now: NaiveDateTime,
+) -> Option<HestonFit> {
+    let smile = analytics.smile();
+    let t = analytics.time_to_expiration;
+    if t <= 0.0 {
+        return None;
+    }
+    let model = Heston::calibrate(&smile, t)?;
+    return Some(HestonFit {
+        model,
+        fit: ModelFit::of(&model, &smile, t),
+        model_variance: model.expected_variance(t),
+        model_free_variance: options.variance(analytics.risk_free_rate, now),
+    });
+}
+
+impl CharacteristicFunction for Heston {
+    fn characteristic(&self, u: Complex, t: f64) -> Complex {
+        // the "little Heston trap" form of Albrecher et al. (2007), continuous in u
+        let iu = Complex::I * u;
+        let sigma2 = self.sigma * self.sigma;
+        let beta = Complex::real(self.kappa) - iu * (self.rho * self.sigma);
+        let d = (beta * beta + (iu + u * u) * sigma2).sqrt();
+        let g = (beta - d) / (beta + d);
+        let e = (-d * t).exp();
+        let one = Complex::real(1.0);
+        let c = ((beta - d) * t - ((one - g * e) / (one - g)).ln().scale(2.0))
+            .scale(self.kappa * self.theta / sigma2);
+        let d_term = (beta - d) * (one - e) / ((one - g * e) * sigma2);
+        return (c + d_term * self.v0).exp();
+    }
+}
+
+impl SmileModel for Heston {
+    fn name(&self) -> &'static str {
+        return "Heston";
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        return vec![
+            ("v0", self.v0),
+            ("kappa", self.kappa),
+            ("theta", self.theta),
+            ("sigma", self.sigma),
+            ("rho", self.rho),
+        ];
+    }
+
+    fn volatilities(&self, moneyness: &[f64], t: f64) -> Vec<Option<f64>> {
+        return fourier_volatilities(self, moneyness, t);
+    }
+}
+
+/**
+ * Distribution of the log of the factor the underlying jumps by.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum JumpSize {
+    /// Normally distributed, as in Merton (1976).
+    Lognormal { mean: f64, volatility: f64 },
+    /// Kou's (2002) asymmetric double exponential: up with probability `up_probability` by an
+    /// exponential with rate `up_rate`, otherwise down with rate `down_rate`. `up_rate` must
+    /// exceed 1 for the expected jump to be finite.
+    DoubleExponential {
+        up_probability: f64,
+        up_rate: f64,
+        down_rate: f64,
+    },
+}
+
+impl JumpSize {
+    /**
+     * `E[exp(i u J)]` of the log jump `J`.
+     */
+    pub fn characteristic(&self, u: Complex) -> Complex {
+        let iu = Complex::I * u;
+        return match *self {
+            JumpSize::Lognormal { mean, volatility } => {
+                (iu * mean + (u * u).scale(-0.5 * volatility * volatility)).exp()
+            }
+            JumpSize::DoubleExponential {
+                up_probability,
+                up_rate,
+                down_rate,
+            } => {
+                Complex::real(up_probability * up_rate) / (Complex::real(up_rate) - iu)
+                    + Complex::real((1.0 - up_probability) * down_rate)
+                        / (Complex::real(down_rate) + iu)
+            }
+        };
+    }
+
+    /**
+     * Expected relative change in price on a jump, `E[e^J] - 1`.
+     */
+    pub fn mean_jump(&self) -> f64 {
+        return match *self {
+            JumpSize::Lognormal { mean, volatility } => {
+                (mean + volatility * volatility / 2.0).exp() - 1.0
+            }
+            JumpSize::DoubleExponential {
+                up_probability,
+                up_rate,
+                down_rate,
+            } => {
+                up_probability * up_rate / (up_rate - 1.0)
+                    + (1.0 - up_probability) * down_rate / (down_rate + 1.0)
+                    - 1.0
+            }
+        };
+    }
+}
+
+/**
+ * Black–Scholes diffusion with volatility `volatility` plus Poisson jumps arriving `intensity`
+ * times a year on average, compensated so the forward is unchanged. Downward jumps steepen the
+ * short-dated skew in a way pure diffusions cannot, as around earnings.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct JumpDiffusion {
+    pub volatility: f64,
+    pub intensity: f64,
+    pub jumps: JumpSize,
+}
+
+impl JumpDiffusion {
+    /**
+     * Merton's model: normally distributed log jumps.
+     */
+    pub fn merton(
+        volatility: f64,
+        intensity: f64,
+        jump_mean: f64,
+        jump_volatility: f64,
+    ) -> JumpDiffusion {
+        return JumpDiffusion::new(
+            volatility,
+            intensity,
+            JumpSize::Lognormal {
+                mean: jump_mean,
+                volatility: jump_volatility,
+            },
+        );
+    }
+
+    /**
+     * Calibrates Merton's model to the smile.
+     */
+    pub fn calibrate(smile: &[(f64, f64)], t: f64) -> Option<JumpDiffusion> {
+        let atm = atm_volatility(smile)?;
+        let from = |x: &[f64]| -> JumpDiffusion {
+            return JumpDiffusion::merton(x[0].exp(), x[1].exp(), x[2], x[3].exp());
+        };
+        let x0 = [(0.8 * atm).ln(), 0.0, -0.1, (0.15f64).ln()];
+        let x = calibrate(from, smile, t, &x0);
+        return Some(from(&x));
+    }
+
+    /**
+     * Discounted European price, by integrating the characteristic function.
+     */
+    pub fn price(
+        &self,
+        kind: OptionKind,
+        forward: f64,
+        strike: f64,
+        t: f64,
+        discount_factor: f64,
+    ) -> f64 {
+        return fourier_price(self, kind, forward, strike, t, discount_factor);
+    }
+
+    /**
+     * Annualized variance of the log return: diffusion plus the jumps' contribution.
+     */
+    pub fn total_variance_rate(&self) -> f64 {
+        let jump_second_moment = match self.jumps {
+            JumpSize::Lognormal { mean, volatility } => mean * mean + volatility * volatility,
+            JumpSize::DoubleExponential {
+                up_probability,
+                up_rate,
+                down_rate,
+            } => {
+                2.0 * up_probability / (up_rate * up_rate)
+                    + 2.0 * (1.0 - up_probability) / (down_rate * down_rate)
+            }
+        };
+        return self.volatility * self.volatility + self.intensity * jump_second_moment;
+    }
+}
+
+impl CharacteristicFunction for JumpDiffusion {
+    fn characteristic(&self, u: Complex, t: f64) -> Complex {
+        let iu = Complex::I * u;
+        let diffusion = (iu + u * u).scale(-0.5 * self.volatility * self.volatility);
+        let jumps = (self.jumps.characteristic(u) - 1.0 - iu * self.jumps.mean_jump())
+            .scale(self.intensity);
+        return ((diffusion + jumps).scale(t)).exp();
+    }
+}
+
+impl SmileModel for JumpDiffusion {
+    fn name(&self) -> &'static str {
+        return match self.jumps {
+            JumpSize::Lognormal { .. } => "Merton",
+            JumpSize::DoubleExponential { .. } => "Kou",
+        };
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        let mut parameters = vec![
+            ("volatility", self.volatility),
+            ("intensity", self.intensity),
+        ];
+        match self.jumps {
+            JumpSize::Lognormal { mean, volatility } => {
+                parameters.push(("jump_mean", mean));
+                parameters.push(("jump_volatility", volatility));
+            }
+            JumpSize::DoubleExponential {
+                up_probability,
+                up_rate,
+                down_rate,
+            } => {
+                parameters.push(("up_probability", up_probability));
+                parameters.push(("up_rate", up_rate));
+                parameters.push(("down_rate", down_rate));
+            }
+        }
+        return parameters;
+    }
+
+    fn volatilities(&self, moneyness: &[f64], t: f64) -> Vec<Option<f64>> {
+        return fourier_volatilities(self, moneyness, t);
+    }
+}
+
+/**
+ * Madan, Carr and Chang's (1998) variance gamma model: Brownian motion with volatility
+ * `volatility` and drift `theta`, run on a gamma clock whose rate has variance `nu`. A negative
+ * `theta` skews the smile down and `nu` sets how fat its tails are.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct VarianceGamma {
+    pub volatility: f64,
+    pub nu: f64,
+    pub theta: f64,
+}
+
+impl VarianceGamma {
+    pub fn calibrate(smile: &[(f64, f64)], t: f64) -> Option<VarianceGamma> {
+        let atm = atm_volatility(smile)?;
+        let from = |x: &[f64]| -> VarianceGamma {
+            return VarianceGamma::new(x[0].exp(), x[1].exp(), x[2]);
+        };
+        let x0 = [atm.ln(), (0.2f64).ln(), -0.1];
+        let x = calibrate(from, smile, t, &x0);
+        return Some(from(&x));
+    }
+
+    /**
+     * Discounted European price, by integrating the characteristic function.
+     */
+    pub fn price(
+        &self,
+        kind: OptionKind,
+        forward: f64,
+        strike: f64,
+        t: f64,
+        discount_factor: f64,
+    ) -> f64 {
+        return fourier_price(self, kind, forward, strike, t, discount_factor);
+    }
+
+    /**
+     * Annualized variance of the log return, `σ² + ν θ²`.
+     */
+    pub fn variance_rate(&self) -> f64 {
+        return self.volatility * self.volatility + self.nu * self.theta * self.theta;
+    }
+
+    /**
+     * Skewness of the log return over `t` years. Shrinks as `1 / √t`.
+     */
+    pub fn skewness(&self, t: f64) -> f64 {
+        let (v, nu, theta) = (self.volatility * self.volatility, self.nu, self.theta);
+        let third = 2.0 * theta.powi(3) * nu * nu + 3.0 * v * theta * nu;
+        return third * t / (self.variance_rate() * t).powf(1.5);
+    }
+
+    /**
+     * Excess kurtosis of the log return over `t` years, zero for a normal. Shrinks as `1 / t`.
+     */
+    pub fn excess_kurtosis(&self, t: f64) -> f64 {
+        let (v, nu, theta) = (self.volatility * self.volatility, self.nu, self.theta);
+        let fourth = 3.0 * v * v * nu
+            + 12.0 * v * theta * theta * nu * nu
+            + 6.0 * theta.powi(4) * nu.powi(3);
+        return fourth * t / (self.variance_rate() * t).powi(2);
+    }
+}
+
+/**
+ * Variance gamma calibrated to an expiry, with the shape of the distribution it implies.
+ */
+#[derive(Clone, Debug)]
+pub struct VarianceGammaFit {
+    pub model: VarianceGamma,
+    pub fit: ModelFit,
+    /// Of the log return to the expiration.
+    pub skewness: f64,
+    /// Of the log return to the expiration.
+    pub excess_kurtosis: f64,
+}
+
+/**
+ * Calibrates variance gamma to the out-of-the-money smile of `analytics`. Its tails are heavier
+ * than a diffusion's at short expirations, which suits single names whose smiles are steep in
+ * the wings. `None` if the expiry has no smile or has expired.
+ */
+pub fn fit_variance_gamma(analytics: &ExpiryAnalytics) -> Option<VarianceGammaFit> {
+    let smile = analytics.smile();
+    let t = analytics.time_to_expiration;
+    if t <= 0.0 {
+        return None;
+    }
+    let model = VarianceGamma::calibrate(&smile, t)?;
+    return Some(VarianceGammaFit {
+        model,
+        fit: ModelFit::of(&model, &smile, t),
+        skewness: model.skewness(t),
+        excess_kurtosis: model.excess_kurtosis(t),
+    });
+}
+
+impl CharacteristicFunction for VarianceGamma {
+    fn characteristic(&self, u: Complex, t: f64) -> Complex {
+        let iu = Complex::I * u;
+        let v = self.volatility * self.volatility;
+        // the drift that makes the forward the expected terminal price
+        let omega = (1.0 - self.theta * self.nu - v * self.nu / 2.0).ln() / self.nu;
+        let base =
+            Complex::real(1.0) - iu.scale(self.theta * self.nu) + (u * u).scale(v * self.nu / 2.0);
+        return (iu.scale(omega * t) - base.ln().scale(t / self.nu)).exp();
+    }
+}
+
+impl SmileModel for VarianceGamma {
+    fn name(&self) -> &'static str {
+        return "Variance gamma";
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        return vec![
+            ("volatility", self.volatility),
+            ("nu", self.nu),
+            ("theta", self.theta),
+        ];
+    }
+
+    fn volatilities(&self, moneyness: &[f64], t: f64) -> Vec<Option<f64>> {
+        return fourier_volatilities(self, moneyness, t);
+    }
+}
+
+/**
+ * Implied volatilities of a model priced on the Carr–Madan grid.
+ */
+fn fourier_volatilities<M: CharacteristicFunction>(
+    model: &M,
+    moneyness: &[f64],
+    t: f64,
+) -> Vec<Option<f64>> {
+    let pricer = CarrMadan::default();
+    let calls = pricer.prices(model, OptionKind::Call, 1.0, moneyness, t, 1.0);
+    return moneyness
+        .iter()
+        .zip(calls.iter())
+        .map(|(m, call)| {
+            // out-of-the-money prices invert more reliably
+            if *m < 1.0 {
+                return implied_volatility(OptionKind::Put, call - (1.0 - m), 1.0, *m, t, 1.0);
+            }
+            return implied_volatility(OptionKind::Call, *call, 1.0, *m, t, 1.0);
+        })
+        .collect();
+}
+
+fn atm_volatility(smile: &[(f64, f64)]) -> Option<f64> {
+    return smile
+        .iter()
+        .min_by(|a, b| (a.0 - 1.0).abs().partial_cmp(&(b.0 - 1.0).abs()).unwrap())
+        .map(|p| p.1);
+}
+
+fn squared_error<M: SmileModel>(model: &M, smile: &[(f64, f64)], t: f64) -> f64 {
+    let moneyness: Vec<f64> = smile.iter().map(|p| p.0).collect();
+    return model
+        .volatilities(&moneyness, t)
+        .iter()
+        .zip(smile.iter())
+        .map(|(vol, p)| match vol {
+            Some(vol) if vol.is_finite() => (vol - p.1).powi(2),
+            _ => MISSING_PENALTY,
+        })
+        .sum();
+}
+
+/**
+ * Minimizes squared volatility error over unconstrained parameters `x`, restarting once from
+ * the first result since a collapsed simplex often stops short of the minimum.
+ */
+fn calibrate<M: SmileModel, F: Fn(&[f64]) -> M>(
+    model: F,
+    smile: &[(f64, f64)],
+    t: f64,
+    x0: &[f64],
+) -> Vec<f64> {
+    let objective = |x: &[f64]| -> f64 {
+        let error = squared_error(&model(x), smile, t);
+        return if error.is_finite() { error } else { f64::MAX };
+    };
+    let x = nelder_mead(objective, x0, 0.1, 2000, 1e-14);
+    return nelder_mead(objective, &x, 0.05, 2000, 1e-14);
+}
+
+/**
+ * How well a single model fits an expiry.
+ */
+#[derive(Clone, Debug)]
+pub struct ModelFit {
+    pub name: &'static str,
+    pub parameters: Vec<(&'static str, f64)>,
+    /// Root mean squared volatility error over every quoted strike.
+    pub rmse: f64,
+    /// Root mean squared volatility error over strikes more than one ATM standard deviation
+    /// from the forward, or `None` if there are none.
+    pub wing_rmse: Option<f64>,
+    /// `(moneyness, market volatility, model volatility)` at every quoted strike.
+    pub fitted: Vec<(f64, f64, Option<f64>)>,
+    /// `(moneyness, risk-neutral density)` implied by the model across the quoted range, per
+    /// unit of moneyness.
+    pub density: Vec<(f64, f64)>,
+}
+
+impl ModelFit {
+    pub fn of<M: SmileModel>(model: &M, smile: &[(f64, f64)], t: f64) -> ModelFit {
+        let moneyness: Vec<f64> = smile.iter().map(|p| p.0).collect();
+        let vols = model.volatilities(&moneyness, t);
+        let fitted: Vec<(f64, f64, Option<f64>)> = smile
+            .iter()
+            .zip(vols)
+            .map(|(p, vol)| (p.0, p.1, vol))
+            .collect();
+        let rmse = |points: &[&(f64, f64, Option<f64>)]| -> f64 {
+            let sum: f64 = points
+                .iter()
+                .map(|(_, market, vol)| match vol {
+                    Some(vol) => (vol - market).powi(2),
+                    None => MISSING_PENALTY,
+                })
+                .sum();
+            return (sum / points.len() as f64).sqrt();
+        };
+        let all: Vec<&(f64, f64, Option<f64>)> = fitted.iter().collect();
+        let width = atm_volatility(smile).unwrap_or(0.0) * t.sqrt();
+        let wings: Vec<&(f64, f64, Option<f64>)> =
+            fitted.iter().filter(|p| p.0.ln().abs() > width).collect();
+        return ModelFit {
+            name: model.name(),
+            parameters: model.parameters(),
+            rmse: if all.is_empty() { 0.0 } else { rmse(&all) },
+            wing_rmse: if wings.is_empty() {
+                None
+            } else {
+                Some(rmse(&wings))
+            },
+            density: density(model, smile, t),
+            fitted,
+        };
+    }
+}
+
+/**
+ * Breeden–Litzenberger density `d²C/dK²` from the model's volatilities on an even grid.
+ */
+fn density<M: SmileModel>(model: &M, smile: &[(f64, f64)], t: f64) -> Vec<(f64, f64)> {
+    let (low, high) = match (smile.first(), smile.last()) {
+        (Some(low), Some(high)) if high.0 > low.0 => (low.0, high.0),
+        _ => return vec![],
+    };
+    let step = (high - low) / (DENSITY_POINTS - 1) as f64;
+    let grid: Vec<f64> = (0..DENSITY_POINTS).map(|i| low + i as f64 * step).collect();
+    let calls: Vec<Option<f64>> = model
+        .volatilities(&grid, t)
+        .into_iter()
+        .zip(grid.iter())
+        .map(|(vol, m)| Some(black_price(OptionKind::Call, 1.0, *m, vol?, t, 1.0)))
+        .collect();
+    return (1..DENSITY_POINTS - 1)
+        .flat_map(|i| {
+            let (a, b, c) = (calls[i - 1]?, calls[i]?, calls[i + 1]?);
+            return Some((grid[i], (a - 2.0 * b + c) / (step * step)));
+        })
+        .collect();
+}
+
+/**
+ * Fits of every model to the same expiry.
+ */
+#[derive(Clone, Debug)]
+pub struct ModelComparison {
+    pub expires_at: NaiveDateTime,
+    pub time_to_expiration: f64,
+    pub fits: Vec<ModelFit>,
+}
+
+impl ModelComparison {
+    /**
+     * The fit with the lowest RMSE.
+     */
+    pub fn best(&self) -> Option<&ModelFit> {
+        return self
+            .fits
+            .iter()
+            .min_by(|a, b| a.rmse.partial_cmp(&b.rmse).unwrap());
+    }
+}
+
+/**
+ * Calibrates a flat volatility, SVI, SABR (with `sabr_beta`), and Heston to the expiry's
+ * out-of-the-money smile and reports how each fits. `None` if the expiry has no smile.
+ */
+pub fn compare_models(expiry: &ExpiryAnalytics, sabr_beta: f64) -> Option<ModelComparison> {
+    let smile = expiry.smile();
+    let t = expiry.time_to_expiration;
+    if t <= 0.0 {
+        return None;
+    }
+    let fits = vec![
+        ModelFit::of(&FlatVolatility::calibrate(&smile)?, &smile, t),
+        ModelFit::of(&Svi::calibrate(&smile, t)?, &smile, t),
+        ModelFit::of(&Sabr::calibrate(&smile, t, sabr_beta)?, &smile, t),
+        ModelFit::of(&Heston::calibrate(&smile, t)?, &smile, t),
+    ];
+    return Some(ModelComparison {
+        expires_at: expiry.expires_at,
+        time_to_expiration: t,
+        fits,
+    });
+}
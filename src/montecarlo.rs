@@ -0,0 +1,313 @@
+//! Monte Carlo pricing under geometric Brownian motion.
+//!
+//! For payoffs without a closed form, such as path-dependent ones: simulate the underlying,
+//! evaluate the payoff on every path, and discount the average. Paths come from a seeded `Rng`,
+//! so the same configuration always gives the same price. Other dynamics, such as Heston's,
+//! plug in through `Dynamics`.
+
+use crate::math::Rng;
+use crate::models::Heston;
+use crate::pricing::years_until;
+use crate::{Cents, OptionContract, OptionKind};
+use chrono::prelude::*;
+
+/**
+ * What a contract pays at expiration given the simulated path of the underlying. The path
+ * starts with the spot price and has one price per step after it, all in cents.
+ */
+pub trait Payoff {
+    fn payoff(&self, path: &[f64]) -> f64;
+}
+
+impl<F: Fn(&[f64]) -> f64> Payoff for F {
+    fn payoff(&self, path: &[f64]) -> f64 {
+        return self(path);
+    }
+}
+
+/**
+ * A European option: pays on the final price only.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct Vanilla {
+    pub kind: OptionKind,
+    pub strike: Cents,
+}
+
+impl Payoff for Vanilla {
+    fn payoff(&self, path: &[f64]) -> f64 {
+        return intrinsic(self.kind, path[path.len() - 1], self.strike as f64);
+    }
+}
+
+impl From<&OptionContract> for Vanilla {
+    fn from(contract: &OptionContract) -> Vanilla {
+        return Vanilla::new(contract.kind, contract.strike);
+    }
+}
+
+/**
+ * An option on the arithmetic average of the prices at each step, excluding the spot.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct ArithmeticAsian {
+    pub kind: OptionKind,
+    pub strike: Cents,
+}
+
+impl Payoff for ArithmeticAsian {
+    fn payoff(&self, path: &[f64]) -> f64 {
+        let observed = &path[1..];
+        let average = observed.iter().sum::<f64>() / observed.len() as f64;
+        return intrinsic(self.kind, average, self.strike as f64);
+    }
+}
+
+fn intrinsic(kind: OptionKind, price: f64, strike: f64) -> f64 {
+    return match kind {
+        OptionKind::Call => (price - strike).max(0.0),
+        OptionKind::Put => (strike - price).max(0.0),
+    };
+}
+
+/**
+ * How the underlying evolves under the risk-neutral measure.
+ */
+pub trait Dynamics {
+    /**
+     * Fills `path[1..]` with prices `dt` years apart, starting from the spot in `path[0]`.
+     */
+    fn simulate(
+        &self,
+        rng: &mut Rng,
+        path: &mut [f64],
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        dt: f64,
+    );
+}
+
+/**
+ * Geometric Brownian motion with a constant volatility, as `MonteCarlo::price` simulates.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct GeometricBrownianMotion {
+    pub volatility: f64,
+}
+
+impl Dynamics for GeometricBrownianMotion {
+    fn simulate(
+        &self,
+        rng: &mut Rng,
+        path: &mut [f64],
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        dt: f64,
+    ) {
+        let vol = self.volatility;
+        let drift = (risk_free_rate - dividend_yield - vol * vol / 2.0) * dt;
+        let diffusion = vol * dt.sqrt();
+        for i in 1..path.len() {
+            path[i] = path[i - 1] * (drift + diffusion * rng.next_normal()).exp();
+        }
+    }
+}
+
+/**
+ * Euler steps of the log price and the variance, truncating negative variance to zero where it
+ * drives the next step (full truncation, Lord et al. 2010).
+ */
+impl Dynamics for Heston {
+    fn simulate(
+        &self,
+        rng: &mut Rng,
+        path: &mut [f64],
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        dt: f64,
+    ) {
+        let orthogonal = (1.0 - self.rho * self.rho).max(0.0).sqrt();
+        let mut variance = self.v0;
+        for i in 1..path.len() {
+            let (z1, z2) = (rng.next_normal(), rng.next_normal());
+            let v = variance.max(0.0);
+            let drift = (risk_free_rate - dividend_yield - v / 2.0) * dt;
+            path[i] = path[i - 1] * (drift + (v * dt).sqrt() * z1).exp();
+            let shock = self.rho * z1 + orthogonal * z2;
+            variance += self.kappa * (self.theta - v) * dt + self.sigma * (v * dt).sqrt() * shock;
+        }
+    }
+}
+
+/**
+ * Simulation settings.
+ */
+#[derive(new, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct MonteCarlo {
+    pub paths: usize,
+    /// Time steps per path. One suffices for payoffs that only look at the final price.
+    pub steps: usize,
+    pub seed: u64,
+}
+
+impl Default for MonteCarlo {
+    fn default() -> MonteCarlo {
+        return MonteCarlo::new(10_000, 50, 0);
+    }
+}
+
+/**
+ * A simulated price in cents with the standard error of the estimate.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct MonteCarloEstimate {
+    pub price: f64,
+    pub standard_error: f64,
+}
+
+impl MonteCarloEstimate {
+    /**
+     * Mean and standard error of discounted payoffs, one per path.
+     */
+    pub(crate) fn of<I: IntoIterator<Item = f64>>(values: I) -> MonteCarloEstimate {
+        let (mut n, mut sum, mut sum_squares) = (0.0, 0.0, 0.0);
+        for value in values {
+            n += 1.0;
+            sum += value;
+            sum_squares += value * value;
+        }
+        let n = f64::max(n, 1.0);
+        let mean = sum / n;
+        let variance = (sum_squares / n - mean * mean).max(0.0);
+        return MonteCarloEstimate {
+            price: mean,
+            standard_error: (variance / n).sqrt(),
+        };
+    }
+
+    /**
+     * The price rounded to the nearest cent.
+     */
+    pub fn cents(&self) -> Cents {
+        return self.price.round() as Cents;
+    }
+}
+
+impl MonteCarlo {
+    /**
+     * Simulated price of `payoff` on an underlying following geometric Brownian motion with a
+     * continuous dividend yield. `t` is in years.
+     */
+    pub fn price<P: Payoff + ?Sized>(
+        &self,
+        payoff: &P,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        t: f64,
+    ) -> MonteCarloEstimate {
+        return self.price_with_local_volatility(
+            payoff,
+            spot,
+            risk_free_rate,
+            dividend_yield,
+            |_, _| volatility,
+            t,
+        );
+    }
+
+    /**
+     * Like `price`, with the volatility of each step a function of the price (in cents) and
+     * time (in years) it starts from, e.g. `ImpliedSurface::local_volatility`.
+     */
+    pub fn price_with_local_volatility<P: Payoff + ?Sized, V: Fn(f64, f64) -> f64>(
+        &self,
+        payoff: &P,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: V,
+        t: f64,
+    ) -> MonteCarloEstimate {
+        let t = t.max(0.0);
+        let steps = self.steps.max(1);
+        let dt = t / steps as f64;
+        let discount = (-risk_free_rate * t).exp();
+
+        let mut rng = Rng::new(self.seed);
+        let mut path = vec![spot as f64; steps + 1];
+        let (mut sum, mut sum_squares) = (0.0, 0.0);
+        for _ in 0..self.paths {
+            for i in 1..=steps {
+                let vol = volatility(path[i - 1], (i - 1) as f64 * dt);
+                let drift = (risk_free_rate - dividend_yield - vol * vol / 2.0) * dt;
+                let diffusion = vol * dt.sqrt();
+                path[i] = path[i - 1] * (drift + diffusion * rng.next_normal()).exp();
+            }
+            let value = payoff.payoff(&path) * discount;
+            sum += value;
+            sum_squares += value * value;
+        }
+
+        let n = self.paths.max(1) as f64;
+        let mean = sum / n;
+        let variance = (sum_squares / n - mean * mean).max(0.0);
+        return MonteCarloEstimate {
+            price: mean,
+            standard_error: (variance / n).sqrt(),
+        };
+    }
+
+    /**
+     * Simulated price of `payoff` on an underlying following `dynamics`. `t` is in years.
+     */
+    pub fn price_with_dynamics<P: Payoff + ?Sized, D: Dynamics + ?Sized>(
+        &self,
+        payoff: &P,
+        dynamics: &D,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        t: f64,
+    ) -> MonteCarloEstimate {
+        let t = t.max(0.0);
+        let steps = self.steps.max(1);
+        let discount = (-risk_free_rate * t).exp();
+        let mut rng = Rng::new(self.seed);
+        let values = (0..self.paths).map(|_| {
+            let mut path = vec![spot as f64; steps + 1];
+            dynamics.simulate(
+                &mut rng,
+                &mut path,
+                risk_free_rate,
+                dividend_yield,
+                t / steps as f64,
+            );
+            return payoff.payoff(&path) * discount;
+        });
+        return MonteCarloEstimate::of(values);
+    }
+
+    /**
+     * Simulated price of the contract as of `now`, as a European option.
+     */
+    pub fn price_contract(
+        &self,
+        contract: &OptionContract,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        now: NaiveDateTime,
+    ) -> MonteCarloEstimate {
+        return self.price(
+            &Vanilla::from(contract),
+            spot,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            years_until(contract.expires_at, now),
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Volatility of one underlying against another.
+//!
+//! Relative value pairs, like SPY against QQQ or a stock against its sector ETF, are traded on
+//! the spread or ratio between the two volatilities rather than on either level. These compare
+//! two index series point by point, with a rolling z-score of the spread for spotting when it
+//! is stretched, and two surfaces tenor by tenor.
+
+use crate::analytics::ChainAnalytics;
+use crate::math::linear_interpolate_flat;
+use crate::series::IndexPoint;
+use crate::skew::atm_term_structure;
+use crate::Percentage;
+use chrono::prelude::*;
+
+/**
+ * Both indices at one time they both have a value.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct PairPoint {
+    pub at: NaiveDateTime,
+    pub first: Percentage,
+    pub second: Percentage,
+}
+
+impl PairPoint {
+    /**
+     * First less second.
+     */
+    pub fn spread(&self) -> Percentage {
+        return self.first - self.second;
+    }
+
+    /**
+     * First over second.
+     */
+    pub fn ratio(&self) -> f64 {
+        return self.first / self.second;
+    }
+}
+
+/**
+ * The points of two index series, each sorted by time, at the times both have a value.
+ */
+pub fn align_indices(first: &[IndexPoint], second: &[IndexPoint]) -> Vec<PairPoint> {
+    let mut aligned = vec![];
+    let mut j = 0;
+    for a in first.iter() {
+        while j < second.len() && second[j].at < a.at {
+            j += 1;
+        }
+        if j < second.len() && second[j].at == a.at {
+            if let (Some(first), Some(second)) = (a.value, second[j].value) {
+                aligned.push(PairPoint {
+                    at: a.at,
+                    first,
+                    second,
+                });
+            }
+        }
+    }
+    return aligned;
+}
+
+/**
+ * How many standard deviations the spread is from its mean over each trailing window of
+ * `window` points, by the last point of the window. Windows whose spread never moved are
+ * skipped.
+ */
+pub fn rolling_spread_zscore(points: &[PairPoint], window: usize) -> Vec<(NaiveDateTime, f64)> {
+    return points
+        .windows(window.max(2))
+        .flat_map(|w| -> Option<(NaiveDateTime, f64)> {
+            let n = w.len() as f64;
+            let mean = w.iter().map(|p| p.spread()).sum::<f64>() / n;
+            let variance = w.iter().map(|p| (p.spread() - mean).powi(2)).sum::<f64>() / (n - 1.0);
+            if variance <= 0.0 {
+                return None;
+            }
+            let last = w[w.len() - 1];
+            return Some((last.at, (last.spread() - mean) / variance.sqrt()));
+        })
+        .collect();
+}
+
+/**
+ * ATM volatilities of two surfaces at one tenor, in percentage points like the index and
+ * `universe::RelativeValueRow`, so they compare directly with a `PairPoint`.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct TenorSpread {
+    /// Days to expiration.
+    pub tenor: f64,
+    pub first: Percentage,
+    pub second: Percentage,
+}
+
+impl TenorSpread {
+    pub fn spread(&self) -> Percentage {
+        return self.first - self.second;
+    }
+
+    pub fn ratio(&self) -> f64 {
+        return self.first / self.second;
+    }
+}
+
+/**
+ * ATM volatilities of both surfaces at each of `tenors` (in days), interpolated linearly in
+ * tenor between expiries and flat beyond them. Tenors either surface has no expiries for are
+ * omitted.
+ */
+pub fn surface_spread(
+    first: &ChainAnalytics,
+    second: &ChainAnalytics,
+    tenors: &[f64],
+) -> Vec<TenorSpread> {
+    let (first, second) = (atm_term_structure(first), atm_term_structure(second));
+    return tenors
+        .iter()
+        .flat_map(|tenor| {
+            return Some(TenorSpread {
+                tenor: *tenor,
+                first: linear_interpolate_flat(&first, *tenor)? * 100.0,
+                second: linear_interpolate_flat(&second, *tenor)? * 100.0,
+            });
+        })
+        .collect();
+}
@@ -0,0 +1,288 @@
+//! A common interface to the pricing models.
+//!
+//! Each model in `pricing` has its own signature. `Pricer` puts them behind one, taking a
+//! contract and the market it trades in, so code that values positions can be handed any model,
+//! or `ByExerciseStyle` to pick one per contract from its exercise style.
+
+use crate::greeks::{black_scholes_greeks, Greeks};
+use crate::pricing::{
+    black76_price, black_scholes_price, years_until, AmericanApproximation, BinomialTree,
+    Dividends, Lattice, LatticePricer,
+};
+use crate::{Cents, ExerciseStyle, OptionContract};
+use chrono::prelude::*;
+use chrono::Duration;
+
+/**
+ * What a contract is priced against.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct Market {
+    /// Price of the underlying, or of the futures for options on futures.
+    pub spot: Cents,
+    pub risk_free_rate: f64,
+    pub dividend_yield: f64,
+    pub now: NaiveDateTime,
+}
+
+/**
+ * A model pricing a single contract. `volatility` is lognormal, as a fraction.
+ */
+pub trait Pricer {
+    /**
+     * Price of `contract`, rounded to the nearest cent.
+     */
+    fn price(&self, contract: &OptionContract, market: &Market, volatility: f64) -> Cents;
+
+    /**
+     * Greeks of `contract`, in dollars as `Greeks` documents. By default these are central
+     * differences of `price` with the default `Bumps`.
+     */
+    fn greeks(&self, contract: &OptionContract, market: &Market, volatility: f64) -> Greeks {
+        return Bumps::default().greeks(self, contract, market, volatility);
+    }
+}
+
+/**
+ * Sizes of the bumps finite-difference Greeks reprice at, each applied up and down.
+ *
+ * Prices are rounded to the cent, so bumps too small leave only rounding noise, and gamma, a
+ * second difference, needs the largest: a 1% spot bump keeps its error to a few percent for an
+ * underlying in the thousands of dollars, but not for one worth a few dollars.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Bumps {
+    /// Fraction of the spot.
+    pub spot: f64,
+    /// Absolute, in volatility units.
+    pub volatility: f64,
+    /// Absolute, in rate units.
+    pub rate: f64,
+    /// Time moved forward for theta, a one-sided difference since the contract cannot be
+    /// valued before now. Stops at expiration.
+    pub time: Duration,
+}
+
+impl Default for Bumps {
+    /**
+     * 1% of the spot, a point of volatility and of rate, and a day.
+     */
+    fn default() -> Bumps {
+        return Bumps {
+            spot: 0.01,
+            volatility: 0.01,
+            rate: 0.01,
+            time: Duration::days(1),
+        };
+    }
+}
+
+impl Bumps {
+    /**
+     * Greeks of `contract` under any `pricer`, by repricing it with each input bumped.
+     */
+    pub fn greeks<P: Pricer + ?Sized>(
+        &self,
+        pricer: &P,
+        contract: &OptionContract,
+        market: &Market,
+        volatility: f64,
+    ) -> Greeks {
+        let price = |market: &Market, volatility: f64| -> f64 {
+            return pricer.price(contract, market, volatility) as f64 / 100.0;
+        };
+        let base = price(market, volatility);
+
+        let h = ((market.spot as f64 * self.spot).round() as Cents).max(1);
+        let spot = |spot: Cents| Market { spot, ..*market };
+        let up = price(&spot(market.spot + h), volatility);
+        let down = price(&spot(market.spot - h), volatility);
+        let h = h as f64 / 100.0;
+
+        let rate = |risk_free_rate: f64| Market {
+            risk_free_rate,
+            ..*market
+        };
+        let later = Market {
+            now: (market.now + self.time).min(contract.expires_at),
+            ..*market
+        };
+        let elapsed = years_until(later.now, market.now);
+        let (low, high) = (
+            (volatility - self.volatility).max(0.0),
+            volatility + self.volatility,
+        );
+        return Greeks {
+            delta: (up - down) / (2.0 * h),
+            gamma: (up - 2.0 * base + down) / (h * h),
+            theta: if elapsed > 0.0 {
+                (price(&later, volatility) - base) / elapsed
+            } else {
+                0.0
+            },
+            vega: (price(market, high) - price(market, low)) / (high - low),
+            rho: (price(&rate(market.risk_free_rate + self.rate), volatility)
+                - price(&rate(market.risk_free_rate - self.rate), volatility))
+                / (2.0 * self.rate),
+        };
+    }
+}
+
+/**
+ * A pricer whose Greeks are always finite differences with the given bumps, even where it has
+ * analytic ones, e.g. to compare the two or to match a risk system's bump conventions.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct Bumped<P: Pricer> {
+    pub pricer: P,
+    pub bumps: Bumps,
+}
+
+impl<P: Pricer> Pricer for Bumped<P> {
+    fn price(&self, contract: &OptionContract, market: &Market, volatility: f64) -> Cents {
+        return self.pricer.price(contract, market, volatility);
+    }
+
+    fn greeks(&self, contract: &OptionContract, market: &Market, volatility: f64) -> Greeks {
+        return self
+            .bumps
+            .greeks(&self.pricer, contract, market, volatility);
+    }
+}
+
+/**
+ * Black–Scholes, ignoring early exercise.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct BlackScholes;
+
+impl Pricer for BlackScholes {
+    fn price(&self, contract: &OptionContract, market: &Market, volatility: f64) -> Cents {
+        return black_scholes_price(
+            contract.kind,
+            market.spot,
+            contract.strike,
+            market.risk_free_rate,
+            market.dividend_yield,
+            volatility,
+            years_until(contract.expires_at, market.now),
+        );
+    }
+
+    fn greeks(&self, contract: &OptionContract, market: &Market, volatility: f64) -> Greeks {
+        return black_scholes_greeks(
+            contract.kind,
+            market.spot as f64 / 100.0,
+            contract.strike as f64 / 100.0,
+            market.risk_free_rate,
+            market.dividend_yield,
+            volatility,
+            years_until(contract.expires_at, market.now),
+        );
+    }
+}
+
+/**
+ * Black-76, for options on futures: the market's spot is the futures price, and the dividend
+ * yield is ignored.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct Black76;
+
+impl Pricer for Black76 {
+    fn price(&self, contract: &OptionContract, market: &Market, volatility: f64) -> Cents {
+        return black76_price(
+            contract.kind,
+            market.spot,
+            contract.strike,
+            market.risk_free_rate,
+            volatility,
+            years_until(contract.expires_at, market.now),
+        );
+    }
+
+    fn greeks(&self, contract: &OptionContract, market: &Market, volatility: f64) -> Greeks {
+        // a futures price grows at the rate, like a stock yielding it
+        let market = Market {
+            dividend_yield: market.risk_free_rate,
+            ..*market
+        };
+        let greeks = BlackScholes.greeks(contract, &market, volatility);
+        // except that the futures price does not move with the rate
+        let t = years_until(contract.expires_at, market.now).max(0.0);
+        let price = self.price(contract, &market, volatility) as f64 / 100.0;
+        return Greeks {
+            rho: -t * price,
+            ..greeks
+        };
+    }
+}
+
+/**
+ * The binomial tree, with the exercise style of each contract.
+ */
+impl Pricer for BinomialTree {
+    fn price(&self, contract: &OptionContract, market: &Market, volatility: f64) -> Cents {
+        return LatticePricer::new(Lattice::Binomial, self.steps)
+            .with_exercise(contract.exercise_style)
+            .with_dividends(Dividends::Yield(market.dividend_yield))
+            .price_contract(
+                contract,
+                market.spot,
+                market.risk_free_rate,
+                volatility,
+                market.now,
+            );
+    }
+}
+
+/**
+ * The closed-form approximations, valuing every contract as American.
+ */
+impl Pricer for AmericanApproximation {
+    fn price(&self, contract: &OptionContract, market: &Market, volatility: f64) -> Cents {
+        return self.price_contract(
+            contract,
+            market.spot,
+            market.risk_free_rate,
+            market.dividend_yield,
+            volatility,
+            market.now,
+        );
+    }
+}
+
+/**
+ * One model for European contracts and another for American ones, chosen by each contract's
+ * exercise style.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct ByExerciseStyle<E: Pricer, A: Pricer> {
+    pub european: E,
+    pub american: A,
+}
+
+impl Default for ByExerciseStyle<BlackScholes, AmericanApproximation> {
+    /**
+     * Black–Scholes for European contracts and Barone-Adesi–Whaley for American ones.
+     */
+    fn default() -> ByExerciseStyle<BlackScholes, AmericanApproximation> {
+        return ByExerciseStyle::new(BlackScholes, AmericanApproximation::BaroneAdesiWhaley);
+    }
+}
+
+impl<E: Pricer, A: Pricer> Pricer for ByExerciseStyle<E, A> {
+    fn price(&self, contract: &OptionContract, market: &Market, volatility: f64) -> Cents {
+        return match contract.exercise_style {
+            ExerciseStyle::European => self.european.price(contract, market, volatility),
+            ExerciseStyle::American => self.american.price(contract, market, volatility),
+        };
+    }
+
+    fn greeks(&self, contract: &OptionContract, market: &Market, volatility: f64) -> Greeks {
+        return match contract.exercise_style {
+            ExerciseStyle::European => self.european.greeks(contract, market, volatility),
+            ExerciseStyle::American => self.american.greeks(contract, market, volatility),
+        };
+    }
+}
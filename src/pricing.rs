@@ -0,0 +1,977 @@
+//! Theoretical prices of contracts, for comparison with their market marks.
+
+use crate::dividends::DividendSchedule;
+use crate::math::solve::brent;
+use crate::math::{
+    bachelier_price, bivariate_norm_cdf, black_price, linear_interpolate_flat, norm_cdf, norm_pdf,
+};
+use crate::{
+    Cents, ExerciseStyle, OptionContract, OptionKind, OptionsByExpiryDate, VolatilityModel,
+};
+use chrono::prelude::*;
+
+/**
+ * Black–Scholes price of a European option with a continuous dividend yield, rounded to the
+ * nearest cent. `t` is in years; at or past expiration the price is the intrinsic value.
+ */
+pub fn black_scholes_price(
+    kind: OptionKind,
+    spot: Cents,
+    strike: Cents,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    t: f64,
+) -> Cents {
+    let t = t.max(0.0);
+    let forward = spot as f64 * ((risk_free_rate - dividend_yield) * t).exp();
+    let discount_factor = (-risk_free_rate * t).exp();
+    let price = black_price(kind, forward, strike as f64, volatility, t, discount_factor);
+    return price.round() as Cents;
+}
+
+/**
+ * Bachelier price of a European option with a continuous dividend yield, rounded to the nearest
+ * cent. `volatility` is in cents per square root year and `t` is in years.
+ */
+pub fn normal_price(
+    kind: OptionKind,
+    spot: Cents,
+    strike: Cents,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    t: f64,
+) -> Cents {
+    let t = t.max(0.0);
+    let forward = spot as f64 * ((risk_free_rate - dividend_yield) * t).exp();
+    let discount_factor = (-risk_free_rate * t).exp();
+    let price = bachelier_price(kind, forward, strike as f64, volatility, t, discount_factor);
+    return price.round() as Cents;
+}
+
+/**
+ * Black-76 price of a European option on a futures or forward price, rounded to the nearest
+ * cent. `t` is in years; at or past expiration the price is the intrinsic value.
+ */
+pub fn black76_price(
+    kind: OptionKind,
+    forward: Cents,
+    strike: Cents,
+    risk_free_rate: f64,
+    volatility: f64,
+    t: f64,
+) -> Cents {
+    let t = t.max(0.0);
+    let discount_factor = (-risk_free_rate * t).exp();
+    let price = black_price(
+        kind,
+        forward as f64,
+        strike as f64,
+        volatility,
+        t,
+        discount_factor,
+    );
+    return price.round() as Cents;
+}
+
+/**
+ * Garman–Kohlhagen price of a European FX option: Black–Scholes with the foreign interest rate
+ * in place of the dividend yield. Exchange rates are quoted to more precision than cents, so
+ * `spot`, `strike`, and the price are in units of the domestic currency per unit of foreign.
+ */
+pub fn garman_kohlhagen_price(
+    kind: OptionKind,
+    spot: f64,
+    strike: f64,
+    domestic_rate: f64,
+    foreign_rate: f64,
+    volatility: f64,
+    t: f64,
+) -> f64 {
+    return FxMarket::new(spot, domestic_rate, foreign_rate).price(kind, strike, volatility, t);
+}
+
+/**
+ * How FX deltas are quoted. Spot deltas include the foreign discount factor; premium-adjusted
+ * deltas are for pairs whose premium is paid in the foreign currency, and hedge with the
+ * premium's delta taken out.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum DeltaConvention {
+    #[default]
+    Spot,
+    Forward,
+    PremiumAdjustedSpot,
+    PremiumAdjustedForward,
+}
+
+/**
+ * A currency pair: spot in domestic currency per unit of foreign, with both continuously
+ * compounded rates. `t` is in years throughout.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct FxMarket {
+    pub spot: f64,
+    pub domestic_rate: f64,
+    pub foreign_rate: f64,
+}
+
+impl FxMarket {
+    /**
+     * Outright forward, by covered interest parity.
+     */
+    pub fn forward(&self, t: f64) -> f64 {
+        return self.spot * ((self.domestic_rate - self.foreign_rate) * t).exp();
+    }
+
+    /**
+     * Garman–Kohlhagen price; the intrinsic value at or past expiration.
+     */
+    pub fn price(&self, kind: OptionKind, strike: f64, volatility: f64, t: f64) -> f64 {
+        let t = t.max(0.0);
+        let discount_factor = (-self.domestic_rate * t).exp();
+        return black_price(
+            kind,
+            self.forward(t),
+            strike,
+            volatility,
+            t,
+            discount_factor,
+        );
+    }
+
+    /**
+     * Delta of the option under `convention`, per unit of foreign notional.
+     */
+    pub fn delta(
+        &self,
+        kind: OptionKind,
+        strike: f64,
+        volatility: f64,
+        t: f64,
+        convention: DeltaConvention,
+    ) -> f64 {
+        let forward = self.forward(t);
+        let sqrt_t = t.max(0.0).sqrt();
+        let d1 =
+            ((forward / strike).ln() + 0.5 * volatility * volatility * t) / (volatility * sqrt_t);
+        let d2 = d1 - volatility * sqrt_t;
+        let phi = match kind {
+            OptionKind::Call => 1.0,
+            OptionKind::Put => -1.0,
+        };
+        let foreign_discount = (-self.foreign_rate * t).exp();
+        return match convention {
+            DeltaConvention::Spot => phi * foreign_discount * norm_cdf(phi * d1),
+            DeltaConvention::Forward => phi * norm_cdf(phi * d1),
+            DeltaConvention::PremiumAdjustedSpot => {
+                phi * foreign_discount * strike / forward * norm_cdf(phi * d2)
+            }
+            DeltaConvention::PremiumAdjustedForward => phi * strike / forward * norm_cdf(phi * d2),
+        };
+    }
+
+    /**
+     * The strike with the given delta under `convention`, as FX volatility surfaces are quoted
+     * (e.g. a 25-delta put has `delta` -0.25). Premium-adjusted call deltas peak below one, and
+     * the strike above the peak is returned. `None` if no strike has that delta.
+     */
+    pub fn strike_for_delta(
+        &self,
+        kind: OptionKind,
+        delta: f64,
+        volatility: f64,
+        t: f64,
+        convention: DeltaConvention,
+    ) -> Option<f64> {
+        if t <= 0.0 || volatility <= 0.0 {
+            return None;
+        }
+        let forward = self.forward(t);
+        let width = volatility * t.sqrt();
+        let premium_adjusted = matches!(
+            convention,
+            DeltaConvention::PremiumAdjustedSpot | DeltaConvention::PremiumAdjustedForward
+        );
+        // log strikes relative to the forward
+        let mut low = -10.0 * width;
+        if premium_adjusted && kind == OptionKind::Call {
+            // the peak is where N(d2) width = n(d2)
+            let peak = brent(
+                |d2| width * norm_cdf(d2) - norm_pdf(d2),
+                -width,
+                10.0,
+                1e-12,
+                200,
+            )?;
+            low = -peak.x * width - width * width / 2.0;
+        }
+        let root = brent(
+            |k| self.delta(kind, forward * k.exp(), volatility, t, convention) - delta,
+            low,
+            10.0 * width,
+            1e-12,
+            200,
+        )?;
+        return Some(forward * root.x.exp());
+    }
+}
+
+impl OptionsByExpiryDate {
+    /**
+     * Black-76 price of the expiry's `kind` option at `strike` as of `now`, on the forward
+     * implied by its quotes (`forward_price`), as for options on futures.
+     */
+    pub fn black76_price(
+        &self,
+        kind: OptionKind,
+        strike: Cents,
+        risk_free_rate: f64,
+        volatility: f64,
+        now: NaiveDateTime,
+    ) -> Cents {
+        return black76_price(
+            kind,
+            self.forward_price(risk_free_rate, now),
+            strike,
+            risk_free_rate,
+            volatility,
+            years_until(self.expires_at, now),
+        );
+    }
+}
+
+impl OptionContract {
+    /**
+     * Price of the contract as of `now` under its volatility model, treating it as European.
+     * `volatility` is in that model's units.
+     */
+    pub fn model_price(
+        self,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        now: NaiveDateTime,
+    ) -> Cents {
+        let price = match self.volatility_model {
+            VolatilityModel::Lognormal => black_scholes_price,
+            VolatilityModel::Normal => normal_price,
+        };
+        return price(
+            self.kind,
+            spot,
+            self.strike,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            years_until(self.expires_at, now),
+        );
+    }
+
+    /**
+     * Barone-Adesi–Whaley approximation to the price of the contract as of `now`, as an
+     * American option.
+     */
+    pub fn barone_adesi_whaley_price(
+        self,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        now: NaiveDateTime,
+    ) -> Cents {
+        return barone_adesi_whaley_price(
+            self.kind,
+            spot,
+            self.strike,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            years_until(self.expires_at, now),
+        );
+    }
+
+    /**
+     * Black-76 price of the contract as of `now` on the futures or forward price `forward`.
+     */
+    pub fn black76_price(
+        self,
+        forward: Cents,
+        risk_free_rate: f64,
+        volatility: f64,
+        now: NaiveDateTime,
+    ) -> Cents {
+        return black76_price(
+            self.kind,
+            forward,
+            self.strike,
+            risk_free_rate,
+            volatility,
+            years_until(self.expires_at, now),
+        );
+    }
+
+    /**
+     * Black–Scholes price of the contract as of `now`, treating it as European.
+     */
+    pub fn black_scholes_price(
+        self,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        now: NaiveDateTime,
+    ) -> Cents {
+        return black_scholes_price(
+            self.kind,
+            spot,
+            self.strike,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            years_until(self.expires_at, now),
+        );
+    }
+
+    /**
+     * Black–Scholes price of the contract as of `now`, treating it as European, with the
+     * underlying paying `dividends` in cash: the spot net of their present value follows
+     * geometric Brownian motion (the escrowed dividend model).
+     */
+    pub fn black_scholes_price_with_dividends(
+        self,
+        spot: Cents,
+        risk_free_rate: f64,
+        volatility: f64,
+        dividends: &DividendSchedule,
+        now: NaiveDateTime,
+    ) -> Cents {
+        let net = spot as f64 - dividends.present_value(risk_free_rate, now, self.expires_at);
+        let t = years_until(self.expires_at, now).max(0.0);
+        let forward = net * (risk_free_rate * t).exp();
+        let discount_factor = (-risk_free_rate * t).exp();
+        let price = black_price(
+            self.kind,
+            forward,
+            self.strike as f64,
+            volatility,
+            t,
+            discount_factor,
+        );
+        return price.round() as Cents;
+    }
+}
+
+/**
+ * Barone-Adesi–Whaley (1987) quadratic approximation to the price of an American option with a
+ * continuous dividend yield, rounded to the nearest cent. `t` is in years.
+ *
+ * Costs a one-dimensional root solve instead of a tree, and is typically within a few cents per
+ * hundred dollars of the tree price; it is least accurate for long-dated options.
+ */
+pub fn barone_adesi_whaley_price(
+    kind: OptionKind,
+    spot: Cents,
+    strike: Cents,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    t: f64,
+) -> Cents {
+    let (s, k, r, q) = (spot as f64, strike as f64, risk_free_rate, dividend_yield);
+    let european = |s: f64| -> f64 {
+        return black_price(
+            kind,
+            s * ((r - q) * t).exp(),
+            k,
+            volatility,
+            t,
+            (-r * t).exp(),
+        );
+    };
+    let intrinsic = match kind {
+        OptionKind::Call => (s - k).max(0.0),
+        OptionKind::Put => (k - s).max(0.0),
+    };
+    // early exercise only pays for calls when there is a dividend and for puts when there is
+    // interest
+    let early_exercise = match kind {
+        OptionKind::Call => q > 0.0,
+        OptionKind::Put => r > 0.0,
+    };
+    if t <= 0.0 || volatility <= 0.0 || !early_exercise {
+        return european(s)
+            .max(if t <= 0.0 { intrinsic } else { 0.0 })
+            .round() as Cents;
+    }
+
+    let variance = volatility * volatility;
+    let m = 2.0 * r / variance;
+    let n = 2.0 * (r - q) / variance;
+    let h = 1.0 - (-r * t).exp();
+    let root = ((n - 1.0).powi(2) + 4.0 * m / h).sqrt();
+    let (exponent, sign) = match kind {
+        OptionKind::Call => ((-(n - 1.0) + root) / 2.0, 1.0),
+        OptionKind::Put => ((-(n - 1.0) - root) / 2.0, -1.0),
+    };
+    let carry = ((-q) * t).exp();
+    let delta = |s: f64| -> f64 {
+        let d1 = ((s / k).ln() + (r - q + variance / 2.0) * t) / (volatility * t.sqrt());
+        return sign * carry * norm_cdf(sign * d1);
+    };
+    // the early exercise premium is `a * (s / critical)^exponent` for `s` short of `critical`
+    let premium = |s: f64| -> f64 { s / exponent * (sign - delta(s)) };
+    let boundary = |s: f64| -> f64 { sign * (s - k) - european(s) - premium(s) };
+
+    let critical = match kind {
+        OptionKind::Call => {
+            let mut high = 2.0 * k;
+            while boundary(high) < 0.0 && high < 1e6 * k {
+                high *= 2.0;
+            }
+            brent(boundary, k, high, 1e-9 * k, 200)
+        }
+        OptionKind::Put => brent(boundary, 1e-9 * k, k, 1e-9 * k, 200),
+    };
+    let critical = match critical {
+        Some(root) => root.x,
+        None => return european(s).round() as Cents,
+    };
+    let exercised = match kind {
+        OptionKind::Call => s >= critical,
+        OptionKind::Put => s <= critical,
+    };
+    let price = if exercised {
+        intrinsic
+    } else {
+        european(s) + premium(critical) * (s / critical).powf(exponent)
+    };
+    return price.round() as Cents;
+}
+
+/**
+ * Bjerksund–Stensland (2002) closed-form approximation to the price of an American option with
+ * a continuous dividend yield, rounded to the nearest cent. `t` is in years.
+ *
+ * Approximates the exercise boundary with two flat segments, which holds up better than
+ * Barone-Adesi–Whaley for long-dated options, and slightly underprices in general. Puts are
+ * priced as calls by put–call transformation.
+ */
+pub fn bjerksund_stensland_price(
+    kind: OptionKind,
+    spot: Cents,
+    strike: Cents,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    t: f64,
+) -> Cents {
+    let (s, k, r, b) = (
+        spot as f64,
+        strike as f64,
+        risk_free_rate,
+        risk_free_rate - dividend_yield,
+    );
+    let price = match kind {
+        OptionKind::Call => bjerksund_stensland_call(s, k, t, r, b, volatility),
+        OptionKind::Put => bjerksund_stensland_call(k, s, t, r - b, -b, volatility),
+    };
+    return price.round() as Cents;
+}
+
+/**
+ * The call with cost of carry `b`.
+ */
+fn bjerksund_stensland_call(s: f64, k: f64, t: f64, r: f64, b: f64, volatility: f64) -> f64 {
+    let european = || -> f64 {
+        return black_price(
+            OptionKind::Call,
+            s * (b * t).exp(),
+            k,
+            volatility,
+            t,
+            (-r * t).exp(),
+        );
+    };
+    if t <= 0.0 || volatility <= 0.0 || b >= r {
+        return european();
+    }
+
+    let variance = volatility * volatility;
+    let t1 = (5f64.sqrt() - 1.0) / 2.0 * t;
+    let beta = (0.5 - b / variance) + ((b / variance - 0.5).powi(2) + 2.0 * r / variance).sqrt();
+    let b_infinity = beta / (beta - 1.0) * k;
+    let b_0 = k.max(r / (r - b) * k);
+    let trigger = |t: f64| -> f64 {
+        let h = -(b * t + 2.0 * volatility * t.sqrt()) * k * k / ((b_infinity - b_0) * b_0);
+        return b_0 + (b_infinity - b_0) * (1.0 - h.exp());
+    };
+    let (i1, i2) = (trigger(t1), trigger(t));
+    if s >= i2 {
+        return s - k;
+    }
+    let alpha1 = (i1 - k) * i1.powf(-beta);
+    let alpha2 = (i2 - k) * i2.powf(-beta);
+
+    let drift = |gamma: f64| b + (gamma - 0.5) * variance;
+    let lambda = |gamma: f64| -r + gamma * b + 0.5 * gamma * (gamma - 1.0) * variance;
+    let kappa = |gamma: f64| 2.0 * b / variance + 2.0 * gamma - 1.0;
+    let phi = |t: f64, gamma: f64, h: f64, i: f64| -> f64 {
+        let sd = volatility * t.sqrt();
+        let d = -((s / h).ln() + drift(gamma) * t) / sd;
+        return (lambda(gamma) * t).exp()
+            * s.powf(gamma)
+            * (norm_cdf(d) - (i / s).powf(kappa(gamma)) * norm_cdf(d - 2.0 * (i / s).ln() / sd));
+    };
+    let psi = |gamma: f64, h: f64| -> f64 {
+        let (sd1, sd) = (volatility * t1.sqrt(), volatility * t.sqrt());
+        let (m1, m) = (drift(gamma) * t1, drift(gamma) * t);
+        let e1 = ((s / i1).ln() + m1) / sd1;
+        let e2 = ((i2 * i2 / (s * i1)).ln() + m1) / sd1;
+        let e3 = ((s / i1).ln() - m1) / sd1;
+        let e4 = ((i2 * i2 / (s * i1)).ln() - m1) / sd1;
+        let f1 = ((s / h).ln() + m) / sd;
+        let f2 = ((i2 * i2 / (s * h)).ln() + m) / sd;
+        let f3 = ((i1 * i1 / (s * h)).ln() + m) / sd;
+        let f4 = ((s * i1 * i1 / (h * i2 * i2)).ln() + m) / sd;
+        let rho = (t1 / t).sqrt();
+        let kappa = kappa(gamma);
+        return (lambda(gamma) * t).exp()
+            * s.powf(gamma)
+            * (bivariate_norm_cdf(-e1, -f1, rho)
+                - (i2 / s).powf(kappa) * bivariate_norm_cdf(-e2, -f2, rho)
+                - (i1 / s).powf(kappa) * bivariate_norm_cdf(-e3, -f3, -rho)
+                + (i1 / i2).powf(kappa) * bivariate_norm_cdf(-e4, -f4, -rho));
+    };
+
+    return alpha2 * s.powf(beta) - alpha2 * phi(t1, beta, i2, i2) + phi(t1, 1.0, i2, i2)
+        - phi(t1, 1.0, i1, i2)
+        - k * phi(t1, 0.0, i2, i2)
+        + k * phi(t1, 0.0, i1, i2)
+        + alpha1 * phi(t1, beta, i1, i2)
+        - alpha1 * psi(beta, i1)
+        + psi(1.0, i1)
+        - psi(1.0, k)
+        - k * psi(0.0, i1)
+        + k * psi(0.0, k);
+}
+
+/**
+ * Closed-form approximations to American option prices, for valuing large chains without a
+ * tree per contract.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AmericanApproximation {
+    BaroneAdesiWhaley,
+    BjerksundStensland,
+}
+
+impl AmericanApproximation {
+    /**
+     * Price of an American option, rounded to the nearest cent. `t` is in years.
+     */
+    #[allow(clippy::too_many_arguments)]
+    pub fn price(
+        self,
+        kind: OptionKind,
+        spot: Cents,
+        strike: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        t: f64,
+    ) -> Cents {
+        let price = match self {
+            AmericanApproximation::BaroneAdesiWhaley => barone_adesi_whaley_price,
+            AmericanApproximation::BjerksundStensland => bjerksund_stensland_price,
+        };
+        return price(
+            kind,
+            spot,
+            strike,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            t,
+        );
+    }
+
+    /**
+     * Price of the contract as of `now`, as an American option.
+     */
+    pub fn price_contract(
+        self,
+        contract: &OptionContract,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        now: NaiveDateTime,
+    ) -> Cents {
+        return self.price(
+            contract.kind,
+            spot,
+            contract.strike,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            years_until(contract.expires_at, now),
+        );
+    }
+}
+
+/**
+ * Cox–Ross–Rubinstein binomial tree for American options. More steps converge closer to the
+ * continuous-time price at linear cost per step in memory and quadratic in time.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct BinomialTree {
+    pub steps: usize,
+}
+
+impl Default for BinomialTree {
+    fn default() -> BinomialTree {
+        return BinomialTree::new(200);
+    }
+}
+
+impl BinomialTree {
+    /**
+     * Price of an American option, rounded to the nearest cent. `t` is in years.
+     */
+    #[allow(clippy::too_many_arguments)]
+    pub fn price(
+        &self,
+        kind: OptionKind,
+        spot: Cents,
+        strike: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        t: f64,
+    ) -> Cents {
+        let lattice = LatticePricer::new(Lattice::Binomial, self.steps)
+            .with_dividends(Dividends::Yield(dividend_yield));
+        return lattice.price(kind, spot, strike, risk_free_rate, volatility, t);
+    }
+
+    /**
+     * Price of the contract as of `now`, treating it as American.
+     */
+    pub fn price_contract(
+        &self,
+        contract: &OptionContract,
+        spot: Cents,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        now: NaiveDateTime,
+    ) -> Cents {
+        return self.price(
+            contract.kind,
+            spot,
+            contract.strike,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+            years_until(contract.expires_at, now),
+        );
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Lattice {
+    /// Cox–Ross–Rubinstein: the price moves up or down by `e^{σ√Δt}` each step.
+    Binomial,
+    /// The price moves up or down by `e^{σ√(2Δt)}` or stays put each step (Hull's
+    /// parameterization). Converges faster and more smoothly than the binomial tree.
+    Trinomial,
+}
+
+/**
+ * Exercise style a lattice values an option with, whatever its contract says.
+ */
+pub type Exercise = ExerciseStyle;
+
+/**
+ * Dividends paid by the underlying before expiration.
+ */
+#[derive(PartialEq, Clone, Debug)]
+pub enum Dividends {
+    /// A continuously compounded yield.
+    Yield(f64),
+    /// Cash amounts paid a number of years from now. The lattice models the price net of the
+    /// present value of the dividends still to come (the escrowed dividend model), adding it
+    /// back when valuing early exercise.
+    Cash(Vec<(f64, Cents)>),
+}
+
+/**
+ * The early-exercise boundary of an American option: the critical prices beyond which it should
+ * be exercised rather than held, below for a put and above for a call.
+ */
+#[derive(PartialEq, Clone, Debug)]
+pub struct ExerciseBoundary {
+    pub kind: OptionKind,
+    /// Years from now and the critical price at that time in cents, ascending in time. Times
+    /// when early exercise is never optimal are omitted.
+    pub points: Vec<(f64, f64)>,
+}
+
+impl ExerciseBoundary {
+    /**
+     * Critical price `t` years from now, interpolated linearly between points and flat beyond
+     * them. `None` if early exercise is never optimal.
+     */
+    pub fn critical_price(&self, t: f64) -> Option<f64> {
+        return linear_interpolate_flat(&self.points, t);
+    }
+
+    /**
+     * Whether exercising at `spot`, `t` years from now, beats holding the option.
+     */
+    pub fn should_exercise(&self, spot: Cents, t: f64) -> bool {
+        return match (self.kind, self.critical_price(t)) {
+            (OptionKind::Call, Some(critical)) => spot as f64 >= critical,
+            (OptionKind::Put, Some(critical)) => spot as f64 <= critical,
+            (_, None) => false,
+        };
+    }
+}
+
+/**
+ * Configuration shared by the lattice pricers.
+ */
+#[derive(PartialEq, Clone, Debug)]
+pub struct LatticePricer {
+    pub lattice: Lattice,
+    pub steps: usize,
+    pub exercise: Exercise,
+    pub dividends: Dividends,
+}
+
+impl LatticePricer {
+    /**
+     * A pricer for American options on an underlying without dividends.
+     */
+    pub fn new(lattice: Lattice, steps: usize) -> LatticePricer {
+        return LatticePricer {
+            lattice,
+            steps,
+            exercise: Exercise::American,
+            dividends: Dividends::Yield(0.0),
+        };
+    }
+
+    pub fn with_exercise(self, exercise: Exercise) -> LatticePricer {
+        return LatticePricer { exercise, ..self };
+    }
+
+    pub fn with_dividends(self, dividends: Dividends) -> LatticePricer {
+        return LatticePricer { dividends, ..self };
+    }
+
+    /**
+     * Price of an option, rounded to the nearest cent. `t` is in years.
+     */
+    pub fn price(
+        &self,
+        kind: OptionKind,
+        spot: Cents,
+        strike: Cents,
+        risk_free_rate: f64,
+        volatility: f64,
+        t: f64,
+    ) -> Cents {
+        let price = self.value(
+            kind,
+            spot as f64,
+            strike as f64,
+            risk_free_rate,
+            volatility,
+            t,
+        );
+        return price.round() as Cents;
+    }
+
+    /**
+     * Price of the contract as of `now`.
+     */
+    pub fn price_contract(
+        &self,
+        contract: &OptionContract,
+        spot: Cents,
+        risk_free_rate: f64,
+        volatility: f64,
+        now: NaiveDateTime,
+    ) -> Cents {
+        return self.price(
+            contract.kind,
+            spot,
+            contract.strike,
+            risk_free_rate,
+            volatility,
+            years_until(contract.expires_at, now),
+        );
+    }
+
+    /**
+     * Where exercising the option early beats holding it, from the same lattice that prices it:
+     * the highest price a put is exercised at, or the lowest a call is, at each step with any
+     * exercise. Empty for European exercise or when early exercise is never optimal, e.g. a call
+     * without dividends. The first steps have few nodes, so the boundary is coarsest near now.
+     */
+    pub fn exercise_boundary(
+        &self,
+        kind: OptionKind,
+        spot: Cents,
+        strike: Cents,
+        risk_free_rate: f64,
+        volatility: f64,
+        t: f64,
+    ) -> ExerciseBoundary {
+        let mut points = vec![];
+        self.roll_back(
+            kind,
+            spot as f64,
+            strike as f64,
+            risk_free_rate,
+            volatility,
+            t,
+            &mut points,
+        );
+        points.reverse();
+        return ExerciseBoundary { kind, points };
+    }
+
+    /**
+     * Like `price`, in cents without rounding, for spot and strike given in cents.
+     */
+    pub(crate) fn value(
+        &self,
+        kind: OptionKind,
+        spot: f64,
+        strike: f64,
+        risk_free_rate: f64,
+        volatility: f64,
+        t: f64,
+    ) -> f64 {
+        return self.roll_back(
+            kind,
+            spot,
+            strike,
+            risk_free_rate,
+            volatility,
+            t,
+            &mut vec![],
+        );
+    }
+
+    /**
+     * Value of the option at the root, pushing the exercise boundary onto `boundary` from the
+     * last step back to the first.
+     */
+    #[allow(clippy::too_many_arguments)]
+    fn roll_back(
+        &self,
+        kind: OptionKind,
+        spot: f64,
+        strike: f64,
+        risk_free_rate: f64,
+        volatility: f64,
+        t: f64,
+        boundary: &mut Vec<(f64, f64)>,
+    ) -> f64 {
+        let payoff = |s: f64| -> f64 {
+            return match kind {
+                OptionKind::Call => (s - strike).max(0.0),
+                OptionKind::Put => (strike - s).max(0.0),
+            };
+        };
+        let (dividend_yield, cash): (f64, &[(f64, Cents)]) = match &self.dividends {
+            Dividends::Yield(q) => (*q, &[]),
+            Dividends::Cash(cash) => (0.0, cash),
+        };
+        // present value at `from` of the dividends paid after it and before expiration
+        let pending = |from: f64| -> f64 {
+            return cash
+                .iter()
+                .filter(|(at, _)| *at > from && *at <= t)
+                .map(|(at, amount)| *amount as f64 * (-risk_free_rate * (at - from)).exp())
+                .sum();
+        };
+        if t <= 0.0 || volatility <= 0.0 || self.steps == 0 {
+            return payoff(spot);
+        }
+
+        let steps = self.steps;
+        let dt = t / steps as f64;
+        let base = spot - pending(0.0);
+        let discount = (-risk_free_rate * dt).exp();
+        let american = self.exercise == Exercise::American;
+        // the lattice moves by whole powers of `up`; `branches` are the probabilities of each
+        // move, lowest first
+        let (up, branches) = match self.lattice {
+            Lattice::Binomial => {
+                let up = (volatility * dt.sqrt()).exp();
+                let growth = ((risk_free_rate - dividend_yield) * dt).exp();
+                let p = ((growth - 1.0 / up) / (up - 1.0 / up)).clamp(0.0, 1.0);
+                (up, vec![1.0 - p, p])
+            }
+            Lattice::Trinomial => {
+                let half = (volatility * (dt / 2.0).sqrt()).exp();
+                let drift = ((risk_free_rate - dividend_yield) * dt / 2.0).exp();
+                let p_up = ((drift - 1.0 / half) / (half - 1.0 / half)).powi(2);
+                let p_down = ((half - drift) / (half - 1.0 / half)).powi(2);
+                (
+                    (volatility * (2.0 * dt).sqrt()).exp(),
+                    vec![p_down, 1.0 - p_up - p_down, p_up],
+                )
+            }
+        };
+        // node `j` of step `i` sits `level(i, j)` powers of `up` from the base, and moves to nodes
+        // `j`, `j + 1`, ... of the next step
+        let moves = branches.len() - 1;
+        let width = |i: usize| -> usize { moves * i + 1 };
+        let level = |i: usize, j: usize| -> i32 { (2 / moves * j) as i32 - i as i32 };
+        let mut values: Vec<f64> = (0..width(steps))
+            .map(|j| payoff(base * up.powi(level(steps, j)) + pending(t)))
+            .collect();
+        for i in (0..steps).rev() {
+            let time = i as f64 * dt;
+            let mut critical: Option<f64> = None;
+            for j in 0..width(i) {
+                let continuation: f64 = branches
+                    .iter()
+                    .enumerate()
+                    .map(|(n, p)| p * values[j + n])
+                    .sum::<f64>()
+                    * discount;
+                values[j] = continuation;
+                if american {
+                    let price = base * up.powi(level(i, j)) + pending(time);
+                    let exercise = payoff(price);
+                    if exercise > continuation {
+                        values[j] = exercise;
+                        // nodes go up in price, so a put keeps its last exercise and a call its first
+                        critical = match (kind, critical) {
+                            (OptionKind::Call, Some(lowest)) => Some(lowest),
+                            _ => Some(price),
+                        };
+                    }
+                }
+            }
+            // the root is the spot alone, which says nothing about where the boundary lies
+            if let (Some(price), true) = (critical, i > 0) {
+                boundary.push((time, price));
+            }
+        }
+        return values[0];
+    }
+}
+
+pub(crate) fn years_until(expires_at: NaiveDateTime, now: NaiveDateTime) -> f64 {
+    return expires_at.signed_duration_since(now).num_minutes() as f64 / 525600.0;
+}
@@ -0,0 +1,156 @@
+//! Pushing computed indices to downstream consumers.
+//!
+//! A live computation loop calls `IndexPublisher::publish` with every new value. Where it goes is
+//! up to the implementation: `Vec<Publication>` keeps them in memory, and with the `redis`
+//! feature `RedisPublisher` sends them over Redis pub/sub.
+
+use crate::Percentage;
+use chrono::prelude::*;
+use std::collections::BTreeMap;
+use std::io;
+
+/**
+ * Free-form details published alongside a value, such as the terms it was computed from.
+ */
+pub type Metadata = BTreeMap<String, String>;
+
+pub trait IndexPublisher {
+    fn publish(
+        &mut self,
+        symbol: &str,
+        timestamp: NaiveDateTime,
+        value: Percentage,
+        metadata: &Metadata,
+    ) -> io::Result<()>;
+}
+
+/**
+ * A single published value.
+ */
+#[derive(PartialEq, Clone, Debug)]
+pub struct Publication {
+    pub symbol: String,
+    pub timestamp: NaiveDateTime,
+    pub value: Percentage,
+    pub metadata: Metadata,
+}
+
+impl Publication {
+    /**
+     * The publication as a JSON object, with the timestamp as `YYYY-MM-DDTHH:MM:SS`. A value
+     * that is not finite is `null`.
+     */
+    pub fn to_json(&self) -> String {
+        use crate::io::jsonl::{number, quote};
+
+        let metadata: Vec<String> = self
+            .metadata
+            .iter()
+            .map(|(key, value)| format!("{}:{}", quote(key), quote(value)))
+            .collect();
+        return format!(
+            "{{\"symbol\":{},\"timestamp\":\"{}\",\"value\":{},\"metadata\":{{{}}}}}",
+            quote(&self.symbol),
+            self.timestamp.format("%Y-%m-%dT%H:%M:%S"),
+            number(self.value),
+            metadata.join(",")
+        );
+    }
+}
+
+impl IndexPublisher for Vec<Publication> {
+    fn publish(
+        &mut self,
+        symbol: &str,
+        timestamp: NaiveDateTime,
+        value: Percentage,
+        metadata: &Metadata,
+    ) -> io::Result<()> {
+        self.push(Publication {
+            symbol: symbol.to_string(),
+            timestamp,
+            value,
+            metadata: metadata.clone(),
+        });
+        return Ok(());
+    }
+}
+
+#[cfg(feature = "redis")]
+pub use self::redis::RedisPublisher;
+
+#[cfg(feature = "redis")]
+mod redis {
+    use super::{IndexPublisher, Metadata, Publication};
+    use crate::Percentage;
+    use chrono::prelude::*;
+    use std::io::{self, BufRead, BufReader, Write};
+    use std::net::{TcpStream, ToSocketAddrs};
+
+    /**
+     * Publishes each value as JSON (see `Publication::to_json`) to the Redis channel
+     * `{prefix}{symbol}`.
+     */
+    #[derive(Debug)]
+    pub struct RedisPublisher {
+        stream: BufReader<TcpStream>,
+        prefix: String,
+    }
+
+    impl RedisPublisher {
+        pub fn connect<A: ToSocketAddrs>(address: A, prefix: &str) -> io::Result<RedisPublisher> {
+            return Ok(RedisPublisher {
+                stream: BufReader::new(TcpStream::connect(address)?),
+                prefix: prefix.to_string(),
+            });
+        }
+
+        /**
+         * Sends a command and returns its integer reply.
+         */
+        fn command(&mut self, args: &[&str]) -> io::Result<i64> {
+            let mut request = format!("*{}\r\n", args.len());
+            for arg in args {
+                request.push_str(&format!("${}\r\n{}\r\n", arg.len(), arg));
+            }
+            self.stream.get_mut().write_all(request.as_bytes())?;
+
+            let mut reply = String::new();
+            self.stream.read_line(&mut reply)?;
+            let reply = reply.trim_end();
+            if let Some(error) = reply.strip_prefix('-') {
+                return Err(io::Error::other(error.to_string()));
+            }
+            return reply
+                .strip_prefix(':')
+                .and_then(|n| n.parse().ok())
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unexpected reply {}", reply),
+                    )
+                });
+        }
+    }
+
+    impl IndexPublisher for RedisPublisher {
+        fn publish(
+            &mut self,
+            symbol: &str,
+            timestamp: NaiveDateTime,
+            value: Percentage,
+            metadata: &Metadata,
+        ) -> io::Result<()> {
+            let message = Publication {
+                symbol: symbol.to_string(),
+                timestamp,
+                value,
+                metadata: metadata.clone(),
+            }
+            .to_json();
+            let channel = format!("{}{}", self.prefix, symbol);
+            self.command(&["PUBLISH", &channel, &message])?;
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Quality metadata accompanying computed variances and index values, so consumers can filter
+//! or down-weight readings computed from poor quotes.
+
+use crate::interpolation::InterpolationWeights;
+use crate::sparse::Fallback;
+use crate::{Cents, MissingTerm, OptionContract, Percentage};
+
+/**
+ * Bid/ask spreads of the contracts a variance was computed from.
+ */
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub struct SpreadStats {
+    /// Mean spread as a fraction of the mark.
+    pub mean_relative_spread: f64,
+    pub max_spread: Cents,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct SpreadAccumulator {
+    relative_sum: f64,
+    count: usize,
+    max_spread: Cents,
+}
+
+impl SpreadAccumulator {
+    pub(crate) fn record(&mut self, contract: &OptionContract) {
+        let spread = contract.ask - contract.bid;
+        if contract.mark() > 0 {
+            self.relative_sum += spread as f64 / contract.mark() as f64;
+            self.count += 1;
+        }
+        self.max_spread = self.max_spread.max(spread);
+    }
+
+    pub(crate) fn finish(self) -> SpreadStats {
+        return SpreadStats {
+            mean_relative_spread: if self.count > 0 {
+                self.relative_sum / self.count as f64
+            } else {
+                0.0
+            },
+            max_spread: self.max_spread,
+        };
+    }
+}
+
+/**
+ * A variance with the information needed to judge how far to trust it.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct VarianceEstimate {
+    pub variance: Percentage,
+    /// Strikes whose quotes were summed. Extrapolated wing strikes are not counted.
+    pub strikes: usize,
+    /// Lowest strike summed, where the put wing was truncated.
+    #[new(default)]
+    pub lowest_strike: Option<Cents>,
+    /// Highest strike summed, where the call wing was truncated.
+    #[new(default)]
+    pub highest_strike: Option<Cents>,
+    /// Strikes quoted on both sides that the truncation or the zero-bid policy left out.
+    #[new(default)]
+    pub strikes_dropped: usize,
+    #[new(default)]
+    pub spreads: SpreadStats,
+    /// The fallback applied because there were too few strikes, if any.
+    #[new(default)]
+    pub fallback: Option<Fallback>,
+    /// Whether there were still too few strikes after any fallback.
+    #[new(default)]
+    pub low_quality: bool,
+}
+
+/**
+ * An index value with the estimates of the two variances it interpolates between.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct IndexEstimate {
+    pub value: Percentage,
+    pub near_term: VarianceEstimate,
+    pub next_term: VarianceEstimate,
+    /// `SingleExpiry` if the value is the variance of one expiry, `Extrapolate` if the target
+    /// maturity was outside the two terms, `None` if it was interpolated between them.
+    pub term_fallback: Option<MissingTerm>,
+    /// The weights of the two terms' variances, if the value came from both.
+    pub weights: Option<InterpolationWeights>,
+}
+
+impl IndexEstimate {
+    /**
+     * Whether either term had too few strikes, even after fallbacks.
+     */
+    pub fn low_quality(&self) -> bool {
+        return self.near_term.low_quality || self.next_term.low_quality;
+    }
+
+    /**
+     * Whether a sparse-chain fallback was applied to either term.
+     */
+    pub fn fallback_applied(&self) -> bool {
+        return self.near_term.fallback.is_some() || self.next_term.fallback.is_some();
+    }
+
+    /**
+     * Strikes summed across both terms.
+     */
+    pub fn strikes(&self) -> usize {
+        return self.near_term.strikes + self.next_term.strikes;
+    }
+}
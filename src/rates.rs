@@ -0,0 +1,58 @@
+//! Risk-free rate term structures.
+
+use chrono::prelude::*;
+
+/**
+ * Continuously compounded risk-free rates by days to maturity, linearly interpolated between
+ * points and held flat beyond the ends.
+ */
+#[derive(Clone, Debug)]
+pub struct YieldCurve {
+    points: Vec<(f64, f64)>,
+}
+
+impl YieldCurve {
+    /**
+     * Builds a curve from `(days, rate)` points in any order.
+     */
+    pub fn new(points: &[(f64, f64)]) -> YieldCurve {
+        let mut points = points.to_vec();
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        return YieldCurve { points };
+    }
+
+    /**
+     * A curve with the same rate at every maturity.
+     */
+    pub fn flat(rate: f64) -> YieldCurve {
+        return YieldCurve::new(&[(0.0, rate)]);
+    }
+
+    /**
+     * The rate for a maturity `days` away.
+     */
+    pub fn rate(&self, days: f64) -> f64 {
+        let first = match self.points.first() {
+            Some(p) => p,
+            None => return 0.0,
+        };
+        if days <= first.0 {
+            return first.1;
+        }
+        for w in self.points.windows(2) {
+            let ((d0, r0), (d1, r1)) = (w[0], w[1]);
+            if days <= d1 {
+                return r0 + (r1 - r0) * (days - d0) / (d1 - d0);
+            }
+        }
+        return self.points[self.points.len() - 1].1;
+    }
+
+    /**
+     * The rate for an expiration at `expires_at`, as seen from `now`.
+     */
+    pub fn rate_at(&self, expires_at: NaiveDateTime, now: NaiveDateTime) -> f64 {
+        let days = expires_at.signed_duration_since(now).num_minutes() as f64 / (24.0 * 60.0);
+        return self.rate(days);
+    }
+}
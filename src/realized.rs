@@ -0,0 +1,105 @@
+//! Realized volatility of the underlying.
+
+use crate::math::linear_interpolate_flat;
+use crate::returns::LogReturn;
+use crate::Percentage;
+
+/**
+ * Trading days in a year, for annualizing daily returns.
+ */
+pub const TRADING_DAYS: f64 = 252.0;
+
+/**
+ * The horizons, in trading days, of a standard volatility cone.
+ */
+pub const CONE_HORIZONS: [usize; 6] = [5, 10, 21, 63, 126, 252];
+
+/**
+ * Annualized close-to-close volatility of daily `returns`, or `None` with fewer than two.
+ */
+pub fn realized_volatility(returns: &[LogReturn]) -> Option<Percentage> {
+    if returns.len() < 2 {
+        return None;
+    }
+    let n = returns.len() as f64;
+    let mean = returns.iter().map(|r| r.value).sum::<f64>() / n;
+    let variance = returns
+        .iter()
+        .map(|r| (r.value - mean).powi(2))
+        .sum::<f64>()
+        / (n - 1.0);
+    return Some((variance * TRADING_DAYS).sqrt());
+}
+
+/**
+ * Distribution of realized volatility over every window of one horizon.
+ */
+#[derive(Clone, Debug)]
+pub struct ConeBand {
+    /// Window length in trading days.
+    pub horizon: usize,
+    /// Realized volatility over the most recent window.
+    pub current: Percentage,
+    /// `(percentile, volatility)` for each requested percentile.
+    pub percentiles: Vec<(f64, Percentage)>,
+    /// Volatility of every window, ascending.
+    samples: Vec<Percentage>,
+}
+
+impl ConeBand {
+    /**
+     * Volatility at percentile `p` (between 0 and 1) of the windows.
+     */
+    pub fn percentile(&self, p: f64) -> Percentage {
+        let points: Vec<(f64, f64)> = self
+            .samples
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (i as f64 / (self.samples.len() - 1).max(1) as f64, *v))
+            .collect();
+        return linear_interpolate_flat(&points, p).unwrap_or(f64::NAN);
+    }
+
+    /**
+     * Fraction of windows whose realized volatility was below `volatility`, e.g. to tell whether
+     * an implied volatility of this tenor is rich or cheap.
+     */
+    pub fn rank(&self, volatility: Percentage) -> f64 {
+        let below = self.samples.partition_point(|v| *v < volatility);
+        return below as f64 / self.samples.len() as f64;
+    }
+}
+
+/**
+ * Volatility cone: for each horizon with at least one full window of history, the percentiles
+ * of realized volatility over every (overlapping) window of that many daily returns.
+ */
+pub fn vol_cone(returns: &[LogReturn], horizons: &[usize], percentiles: &[f64]) -> Vec<ConeBand> {
+    return horizons
+        .iter()
+        .flat_map(|horizon| -> Option<ConeBand> {
+            let horizon = *horizon;
+            if horizon < 2 || returns.len() < horizon {
+                return None;
+            }
+            let windows: Vec<Percentage> = returns
+                .windows(horizon)
+                .flat_map(realized_volatility)
+                .collect();
+            let current = *windows.last()?;
+            let mut samples = windows;
+            samples.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+            let mut band = ConeBand {
+                horizon,
+                current,
+                percentiles: vec![],
+                samples,
+            };
+            band.percentiles = percentiles
+                .iter()
+                .map(|p| (*p, band.percentile(*p)))
+                .collect();
+            return Some(band);
+        })
+        .collect();
+}
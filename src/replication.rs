@@ -0,0 +1,183 @@
+//! Static replication of European payoffs from the quoted strikes.
+//!
+//! Carr and Madan (1998): any twice-differentiable payoff `f(S_T)` is a bond paying `f(K_0)`,
+//! `f'(K_0)` forwards struck at `K_0`, and a strip of out-of-the-money options weighted by
+//! `f''(K) ΔK`. The variance the index computes is the log contract's price by this route; this
+//! module prices any other payoff the same way, including the power and moment contracts behind
+//! implied skewness and kurtosis.
+
+use crate::{Cents, OptionContract, OptionsByExpiryDate};
+use chrono::prelude::*;
+
+/**
+ * A static portfolio replicating a payoff at expiration, and its price at the marks.
+ */
+#[derive(Clone, Debug)]
+pub struct Replication {
+    /// The strike the payoff is expanded around: the highest quoted strike below the forward.
+    pub expansion_strike: Cents,
+    pub forward_price: Cents,
+    /// `f(K_0)`, paid in cents at expiration.
+    pub cash: f64,
+    /// `f'(K_0)`: forward contracts on the underlying struck at `K_0`.
+    pub forwards: f64,
+    /// Contracts held of each option: puts below `K_0`, calls above it, and half of each at it.
+    pub weights: Vec<(OptionContract, f64)>,
+    /// Present value of the portfolio in cents.
+    pub price: f64,
+}
+
+impl OptionsByExpiryDate {
+    /**
+     * Replicates `payoff`, in cents as a function of the underlying's price at expiration in
+     * cents, with the expiry's quoted strikes. Its derivatives are taken numerically, so it
+     * only needs to be smooth where strikes are quoted. `None` without quoted strikes below and
+     * above the forward.
+     */
+    pub fn replicate<F: Fn(f64) -> f64>(
+        &self,
+        payoff: F,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+    ) -> Option<Replication> {
+        let t = self.time_to_expiration(now);
+        let discount = (-risk_free_rate * t).exp();
+        let forward_price = self.forward_price(risk_free_rate, now);
+        let strikes: Vec<_> = self.strikes().collect();
+        let k_0 = strikes
+            .iter()
+            .map(|s| s.price)
+            .take_while(|price| *price < forward_price)
+            .last()?;
+        if strikes.last()?.price <= k_0 {
+            return None;
+        }
+
+        let derivatives = |k: f64| -> (f64, f64) {
+            let h = (k * 1e-4).max(1.0);
+            let (down, at, up) = (payoff(k - h), payoff(k), payoff(k + h));
+            return ((up - down) / (2.0 * h), (up - 2.0 * at + down) / (h * h));
+        };
+        let mut weights = vec![];
+        for s in strikes.iter() {
+            let weight = derivatives(s.price as f64).1 * s.delta_k as f64;
+            if s.price < k_0 {
+                weights.push((s.put, weight));
+            } else if s.price > k_0 {
+                weights.push((s.call, weight));
+            } else {
+                weights.push((s.put, weight / 2.0));
+                weights.push((s.call, weight / 2.0));
+            }
+        }
+
+        let cash = payoff(k_0 as f64);
+        let forwards = derivatives(k_0 as f64).0;
+        let options: f64 = weights
+            .iter()
+            .map(|(o, weight)| weight * o.mark() as f64)
+            .sum();
+        let price = discount * (cash + forwards * (forward_price - k_0) as f64) + options;
+        return Some(Replication {
+            expansion_strike: k_0,
+            forward_price,
+            cash,
+            forwards,
+            weights,
+            price,
+        });
+    }
+}
+
+/**
+ * Fair strikes of the moment swaps of one expiry: forward prices of contracts paying powers of
+ * the log return `x = ln(S_T / F)`. Together they give the implied skewness and kurtosis of the
+ * return that skew and kurtosis swaps are struck on.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct ImpliedMoments {
+    /// `E[x]`: negative, as a lognormal forward's convexity requires.
+    pub mean: f64,
+    /// `E[x²]`.
+    pub quadratic: f64,
+    /// `E[x³]`.
+    pub cubic: f64,
+    /// `E[x⁴]`.
+    pub quartic: f64,
+}
+
+impl ImpliedMoments {
+    /**
+     * Variance of the log return to expiration, not annualized.
+     */
+    pub fn variance(&self) -> f64 {
+        return self.quadratic - self.mean * self.mean;
+    }
+
+    pub fn skewness(&self) -> f64 {
+        let mu = self.mean;
+        let third = self.cubic - 3.0 * mu * self.quadratic + 2.0 * mu.powi(3);
+        return third / self.variance().powf(1.5);
+    }
+
+    /**
+     * Kurtosis of the log return, 3 for a lognormal underlying.
+     */
+    pub fn kurtosis(&self) -> f64 {
+        let mu = self.mean;
+        let fourth = self.quartic - 4.0 * mu * self.cubic + 6.0 * mu * mu * self.quadratic
+            - 3.0 * mu.powi(4);
+        return fourth / self.variance().powi(2);
+    }
+}
+
+impl OptionsByExpiryDate {
+    /**
+     * Present value of a power contract paying `(S_T / F)^exponent` per unit of notional, where
+     * `F` is the forward price.
+     */
+    pub fn power_price(
+        &self,
+        exponent: f64,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+    ) -> Option<f64> {
+        let forward = self.forward_price(risk_free_rate, now) as f64;
+        return self
+            .replicate(|s| (s / forward).powf(exponent), risk_free_rate, now)
+            .map(|r| r.price);
+    }
+
+    /**
+     * Fair strike of the moment swap of `order`, paying `ln(S_T / F)^order` at expiration
+     * against it: 2 is an uncentered variance swap, 3 the cubic contract and 4 the quartic.
+     */
+    pub fn moment_swap_strike(
+        &self,
+        order: i32,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+    ) -> Option<f64> {
+        let forward = self.forward_price(risk_free_rate, now) as f64;
+        let discount = (-risk_free_rate * self.time_to_expiration(now)).exp();
+        return self
+            .replicate(|s| (s / forward).ln().powi(order), risk_free_rate, now)
+            .map(|r| r.price / discount);
+    }
+
+    /**
+     * The first four moment swaps' fair strikes. `None` if the payoffs can't be replicated.
+     */
+    pub fn implied_moments(
+        &self,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+    ) -> Option<ImpliedMoments> {
+        return Some(ImpliedMoments {
+            mean: self.moment_swap_strike(1, risk_free_rate, now)?,
+            quadratic: self.moment_swap_strike(2, risk_free_rate, now)?,
+            cubic: self.moment_swap_strike(3, risk_free_rate, now)?,
+            quartic: self.moment_swap_strike(4, risk_free_rate, now)?,
+        });
+    }
+}
@@ -0,0 +1,126 @@
+//! Resampling of quoted smiles onto a standard moneyness grid.
+//!
+//! Different underlyings list strikes at different spacings; putting every expiry on the same
+//! grid of strike over forward makes them directly comparable.
+
+use crate::analytics::{ChainAnalytics, ExpiryAnalytics, SmileMarking};
+use crate::math::{black_price, linear_interpolate};
+use crate::{OptionKind, Percentage};
+use chrono::prelude::*;
+
+/**
+ * Evenly spaced moneyness (strike over forward) levels from `low` to `high` inclusive.
+ */
+#[derive(new, Clone, Copy, Debug)]
+pub struct MoneynessGrid {
+    pub low: f64,
+    pub high: f64,
+    pub step: f64,
+}
+
+impl Default for MoneynessGrid {
+    /**
+     * 80% to 120% in 1% steps.
+     */
+    fn default() -> MoneynessGrid {
+        return MoneynessGrid::new(0.8, 1.2, 0.01);
+    }
+}
+
+impl MoneynessGrid {
+    pub fn levels(&self) -> Vec<f64> {
+        let count = ((self.high - self.low) / self.step + 1e-9).floor() as usize;
+        return (0..=count)
+            .map(|i| self.low + i as f64 * self.step)
+            .collect();
+    }
+}
+
+/**
+ * The smile of an expiry at a single grid level. Levels outside the quoted strikes are not
+ * extrapolated and have no volatility or prices.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct GridPoint {
+    pub moneyness: f64,
+    /// Strike at this level, in cents.
+    pub strike: f64,
+    pub implied_volatility: Option<Percentage>,
+    /// Call and put values at the interpolated volatility, in cents.
+    pub call: Option<f64>,
+    pub put: Option<f64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ResampledExpiry {
+    pub expires_at: NaiveDateTime,
+    pub time_to_expiration: f64,
+    pub points: Vec<GridPoint>,
+}
+
+/**
+ * Resamples every expiry of the chain onto `grid`.
+ *
+ * Volatilities are interpolated linearly in moneyness between the out-of-the-money quotes, and
+ * prices are recomputed from the interpolated volatility so they stay consistent with it.
+ */
+pub fn resample(analytics: &ChainAnalytics, grid: &MoneynessGrid) -> Vec<ResampledExpiry> {
+    return resample_marked(analytics, grid, SmileMarking::OutOfTheMoney);
+}
+
+/**
+ * Resamples every expiry of the chain onto `grid`, choosing the volatility at each quoted
+ * strike according to `marking`.
+ */
+pub fn resample_marked(
+    analytics: &ChainAnalytics,
+    grid: &MoneynessGrid,
+    marking: SmileMarking,
+) -> Vec<ResampledExpiry> {
+    return analytics
+        .expiries
+        .iter()
+        .map(|e| resample_expiry(e, grid, marking))
+        .collect();
+}
+
+fn resample_expiry(
+    expiry: &ExpiryAnalytics,
+    grid: &MoneynessGrid,
+    marking: SmileMarking,
+) -> ResampledExpiry {
+    let smile = expiry.smile_marked(marking);
+    let forward = expiry.forward_price as f64;
+    let price = |kind: OptionKind, strike: f64, vol: f64| -> f64 {
+        return black_price(
+            kind,
+            forward,
+            strike,
+            vol,
+            expiry.time_to_expiration,
+            expiry.discount_factor,
+        );
+    };
+
+    let points = grid
+        .levels()
+        .into_iter()
+        .map(|moneyness| -> GridPoint {
+            let strike = moneyness * forward;
+            let vol = linear_interpolate(&smile, moneyness);
+            return GridPoint {
+                moneyness,
+                strike,
+                implied_volatility: vol,
+                call: vol.map(|v| price(OptionKind::Call, strike, v)),
+                put: vol.map(|v| price(OptionKind::Put, strike, v)),
+            };
+        })
+        .collect();
+
+    return ResampledExpiry {
+        expires_at: expiry.expires_at,
+        time_to_expiration: expiry.time_to_expiration,
+        points,
+    };
+}
@@ -0,0 +1,147 @@
+//! Return series of the underlying.
+//!
+//! Every estimator working from price history (realized volatility, correlation, sensitivities)
+//! builds its returns here, so corporate actions and missing observations are treated the same
+//! way everywhere.
+
+use crate::holidays::HolidayCalendar;
+use crate::Cents;
+use chrono::prelude::*;
+
+/**
+ * A closing price of the underlying.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct Observation {
+    pub date: NaiveDate,
+    pub price: Cents,
+}
+
+/**
+ * An event that changes the price of the underlying without changing its value, taking effect
+ * on its ex-date.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum CorporateAction {
+    /// Each share becomes `ratio` shares, e.g. 2.0 for a 2-for-1 split.
+    Split { ex_date: NaiveDate, ratio: f64 },
+    /// A cash dividend per share.
+    Dividend { ex_date: NaiveDate, amount: Cents },
+}
+
+/**
+ * How returns spanning missing trading days are treated.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum GapPolicy {
+    /// Keep the return over the whole gap.
+    Include,
+    /// Drop returns spanning a gap.
+    Skip,
+    /// Keep the return, scaled by `1 / sqrt(periods)` to the size of a one-day return.
+    Scale,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct LogReturn {
+    /// Date of the closing price the return ends at.
+    pub date: NaiveDate,
+    pub value: f64,
+    /// Number of trading days the return spans.
+    pub periods: u32,
+}
+
+/**
+ * Log returns of consecutive prices, without adjustments. `None` for non-positive prices.
+ */
+pub fn simple_log_returns(prices: &[f64]) -> Vec<Option<f64>> {
+    return prices
+        .windows(2)
+        .map(|w| -> Option<f64> {
+            if w[0] <= 0.0 || w[1] <= 0.0 {
+                return None;
+            }
+            return Some((w[1] / w[0]).ln());
+        })
+        .collect();
+}
+
+/**
+ * Daily total log returns of `prices` (sorted by date), adjusted for `actions` so that splits
+ * and dividends do not show up as returns. Returns spanning more than one trading day of
+ * `calendar` are treated according to `gaps`, and non-positive prices are skipped.
+ */
+pub fn log_returns<C: HolidayCalendar + ?Sized>(
+    prices: &[Observation],
+    actions: &[CorporateAction],
+    calendar: &C,
+    gaps: GapPolicy,
+) -> Vec<LogReturn> {
+    return prices
+        .windows(2)
+        .flat_map(|w| -> Option<LogReturn> {
+            let (previous, current) = (w[0], w[1]);
+            if previous.price <= 0 || current.price <= 0 {
+                return None;
+            }
+
+            // the current price in terms of the previous one's shares
+            let mut ratio = 1.0;
+            let mut dividends = 0.0;
+            for action in actions.iter() {
+                match *action {
+                    CorporateAction::Split { ex_date, ratio: r }
+                        if ex_date > previous.date && ex_date <= current.date =>
+                    {
+                        ratio *= r
+                    }
+                    CorporateAction::Dividend { ex_date, amount }
+                        if ex_date > previous.date && ex_date <= current.date =>
+                    {
+                        dividends += amount as f64
+                    }
+                    _ => {}
+                }
+            }
+            let value = ((current.price as f64 + dividends) * ratio / previous.price as f64).ln();
+
+            let periods = trading_days_between(previous.date, current.date, calendar).max(1);
+            if periods == 1 {
+                return Some(LogReturn {
+                    date: current.date,
+                    value,
+                    periods,
+                });
+            }
+            return match gaps {
+                GapPolicy::Include => Some(LogReturn {
+                    date: current.date,
+                    value,
+                    periods,
+                }),
+                GapPolicy::Skip => None,
+                GapPolicy::Scale => Some(LogReturn {
+                    date: current.date,
+                    value: value / (periods as f64).sqrt(),
+                    periods: 1,
+                }),
+            };
+        })
+        .collect();
+}
+
+/**
+ * Number of trading days after `from` up to and including `to`.
+ */
+fn trading_days_between<C: HolidayCalendar + ?Sized>(
+    from: NaiveDate,
+    to: NaiveDate,
+    calendar: &C,
+) -> u32 {
+    return from
+        .iter_days()
+        .skip(1)
+        .take_while(|d| *d <= to)
+        .filter(|d| calendar.is_trading_day(*d))
+        .count() as u32;
+}
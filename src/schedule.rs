@@ -0,0 +1,141 @@
+//! Listed expiration schedules.
+//!
+//! Generates the expiration dates an exchange would list over a range of dates, and classifies
+//! existing dates into their expiration cycle, e.g. to validate vendor data.
+
+use crate::holidays::{nth_weekday, HolidayCalendar};
+use chrono::prelude::*;
+use chrono::Duration;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash)]
+pub enum ExpirationCycle {
+    /// Every Friday that is not otherwise a standard expiration.
+    Weekly,
+    /// The third Friday of every month.
+    Monthly,
+    /// The last trading day of March, June, September, and December.
+    Quarterly,
+    /// January monthly expirations more than a year after listing.
+    Leaps,
+}
+
+#[derive(new, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Expiration {
+    pub date: NaiveDate,
+    pub cycle: ExpirationCycle,
+}
+
+impl Expiration {
+    /**
+     * Expiration time, taken as the close of trading on the expiration date.
+     */
+    pub fn at_close<C: HolidayCalendar + ?Sized>(&self, calendar: &C) -> NaiveDateTime {
+        return self.date.and_time(calendar.close(self.date));
+    }
+}
+
+/**
+ * The monthly expiration of a month: the third Friday, or the trading day before it if the
+ * Friday is a holiday.
+ */
+pub fn monthly_expiration<C: HolidayCalendar + ?Sized>(
+    year: i32,
+    month: u32,
+    calendar: &C,
+) -> NaiveDate {
+    return calendar.previous_trading_day(nth_weekday(year, month, Weekday::Fri, 3));
+}
+
+/**
+ * The quarterly expiration of a quarter-end month: its last trading day.
+ */
+pub fn quarterly_expiration<C: HolidayCalendar + ?Sized>(
+    year: i32,
+    month: u32,
+    calendar: &C,
+) -> NaiveDate {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    let last_day = NaiveDate::from_ymd_opt(next_year, next_month, 1).unwrap() - Duration::days(1);
+    return calendar.previous_trading_day(last_day);
+}
+
+/**
+ * The cycle `date` belongs to, or `None` if it is not a standard expiration date. Monthly and
+ * quarterly expirations take precedence over weeklies; LEAPS depend on the listing date and are
+ * classified as monthlies.
+ */
+pub fn classify<C: HolidayCalendar + ?Sized>(
+    date: NaiveDate,
+    calendar: &C,
+) -> Option<ExpirationCycle> {
+    if date == monthly_expiration(date.year(), date.month(), calendar) {
+        return Some(ExpirationCycle::Monthly);
+    }
+    if matches!(date.month(), 3 | 6 | 9 | 12)
+        && date == quarterly_expiration(date.year(), date.month(), calendar)
+    {
+        return Some(ExpirationCycle::Quarterly);
+    }
+    if calendar.is_trading_day(date) && calendar.next_expiration_friday(date) == date {
+        return Some(ExpirationCycle::Weekly);
+    }
+    return None;
+}
+
+/**
+ * Expirations of the given cycles from `from` through `to`, as listed on `from`, sorted by date.
+ * A date in several cycles appears once, under the first of monthly, quarterly, and weekly that
+ * was requested; January monthlies more than a year after `from` are LEAPS.
+ */
+pub fn expirations<C: HolidayCalendar + ?Sized>(
+    from: NaiveDate,
+    to: NaiveDate,
+    cycles: &[ExpirationCycle],
+    calendar: &C,
+) -> Vec<Expiration> {
+    let leaps_after = from + Duration::days(365);
+    let mut listed: Vec<Expiration> = vec![];
+    let mut push = |date: NaiveDate, cycle: ExpirationCycle| {
+        if date < from || date > to || !cycles.contains(&cycle) {
+            return;
+        }
+        if listed.iter().any(|e| e.date == date) {
+            return;
+        }
+        listed.push(Expiration::new(date, cycle));
+    };
+
+    let (mut year, mut month) = (from.year(), from.month());
+    while NaiveDate::from_ymd_opt(year, month, 1).unwrap() <= to {
+        let monthly = monthly_expiration(year, month, calendar);
+        if month == 1 && monthly > leaps_after {
+            push(monthly, ExpirationCycle::Leaps);
+        }
+        push(monthly, ExpirationCycle::Monthly);
+        if matches!(month, 3 | 6 | 9 | 12) {
+            push(
+                quarterly_expiration(year, month, calendar),
+                ExpirationCycle::Quarterly,
+            );
+        }
+        if month == 12 {
+            year += 1;
+            month = 1;
+        } else {
+            month += 1;
+        }
+    }
+
+    let mut date = calendar.next_expiration_friday(from);
+    while date <= to {
+        push(date, ExpirationCycle::Weekly);
+        date = calendar.next_expiration_friday(date + Duration::days(1));
+    }
+
+    listed.sort_by_key(|e| e.date);
+    return listed;
+}
@@ -0,0 +1,411 @@
+//! The index evaluated over time.
+
+use crate::cache::ContributionCache;
+use crate::chain::Chain;
+use crate::quality::IndexEstimate;
+use crate::rates::YieldCurve;
+use crate::{combine_terms, IndexConfig, MissingTerm, OptionsByExpiryDate, Percentage, Term};
+use chrono::prelude::*;
+use chrono::Duration;
+
+/**
+ * The index at one point in time, or `None` if there were no expiries either side of 30 days.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct IndexPoint {
+    pub at: NaiveDateTime,
+    pub value: Option<Percentage>,
+    /// Quality of the computed value. Smoothing keeps the estimate of the raw reading.
+    pub estimate: Option<IndexEstimate>,
+}
+
+/**
+ * The near- and next-term expiries for the 30-day index as of `now`: the last unexpired expiry
+ * at most 30 days away and the first one further out.
+ */
+pub fn select_terms<'a>(
+    chain: &'a Chain,
+    now: NaiveDateTime,
+    config: &IndexConfig,
+) -> Option<(&'a OptionsByExpiryDate, &'a OptionsByExpiryDate)> {
+    return select_terms_around(chain, now, (30 * 24 * 60) as f64, config);
+}
+
+/**
+ * The near- and next-term expiries bracketing `n_target` minutes from `now`: the last unexpired
+ * expiry at most that far away and the first one further out.
+ *
+ * Without an expiry on one side, `config.missing_term` decides: `SingleExpiry` returns the
+ * expiry nearest the target as both terms, and `Extrapolate` the two nearest it (or the only
+ * one, twice).
+ */
+pub fn select_terms_around<'a>(
+    chain: &'a Chain,
+    now: NaiveDateTime,
+    n_target: f64,
+    config: &IndexConfig,
+) -> Option<(&'a OptionsByExpiryDate, &'a OptionsByExpiryDate)> {
+    let (unexpired, _) = chain.unexpired(now, config.same_minute);
+    let split = unexpired.partition_point(|e| {
+        return e
+            .minutes_to_expiration_with(now, config.same_minute)
+            .is_some_and(|minutes| minutes <= n_target);
+    });
+    if split > 0 && split < unexpired.len() {
+        return Some((&unexpired[split - 1], &unexpired[split]));
+    }
+    if unexpired.is_empty() {
+        return None;
+    }
+    // every expiry is on the same side of the target
+    let nearest = if split == 0 { 0 } else { unexpired.len() - 1 };
+    return match config.missing_term {
+        MissingTerm::Require => None,
+        MissingTerm::SingleExpiry => Some((&unexpired[nearest], &unexpired[nearest])),
+        MissingTerm::Extrapolate if unexpired.len() == 1 => Some((&unexpired[0], &unexpired[0])),
+        MissingTerm::Extrapolate if split == 0 => Some((&unexpired[0], &unexpired[1])),
+        MissingTerm::Extrapolate => Some((&unexpired[nearest - 1], &unexpired[nearest])),
+    };
+}
+
+/**
+ * Evaluates the index on each snapshot of the chain, e.g. every minute of a session.
+ *
+ * Snapshots are evaluated in order with a shared contribution cache, so strikes whose quotes did
+ * not change since the previous snapshot are not recomputed.
+ */
+pub fn compute_vix_series(
+    chains_by_time: &[(NaiveDateTime, &Chain)],
+    rates: &YieldCurve,
+    config: &IndexConfig,
+) -> Vec<IndexPoint> {
+    let mut cache = ContributionCache::new();
+    return chains_by_time
+        .iter()
+        .map(|(now, chain)| -> IndexPoint {
+            let now = *now;
+            #[cfg(feature = "metrics")]
+            let _timer =
+                crate::metrics::Timer::start(&crate::metrics::global().computation_seconds);
+            let estimate = select_terms(chain, now, config).and_then(|(near, next)| {
+                return estimate_terms(near, next, now, rates, config, &mut cache);
+            });
+            #[cfg(feature = "metrics")]
+            if let Some(estimate) = &estimate {
+                crate::metrics::global().record_index(estimate);
+            }
+            return IndexPoint {
+                at: now,
+                value: estimate.map(|e| e.value),
+                estimate,
+            };
+        })
+        .collect();
+}
+
+fn estimate_terms(
+    near: &OptionsByExpiryDate,
+    next: &OptionsByExpiryDate,
+    now: NaiveDateTime,
+    rates: &YieldCurve,
+    config: &IndexConfig,
+    cache: &mut ContributionCache,
+) -> Option<IndexEstimate> {
+    let n_t1 = near.minutes_to_expiration_with(now, config.same_minute);
+    let n_t2 = next.minutes_to_expiration_with(now, config.same_minute);
+    if config.missing_term == MissingTerm::Require && (n_t1.is_none() || n_t2.is_none()) {
+        return None;
+    }
+    let s1 = near.variance_configured(
+        rates.rate_at(near.expires_at(), now),
+        now,
+        config,
+        Some(&mut *cache),
+    );
+    let s2 = next.variance_configured(
+        rates.rate_at(next.expires_at(), now),
+        now,
+        config,
+        Some(cache),
+    );
+    return Some(combine_terms(
+        Term::new(near, n_t1, s1),
+        Term::new(next, n_t2, s2),
+        now,
+        (30 * 24 * 60) as f64,
+        config,
+    ));
+}
+
+/**
+ * The expiries the index interpolates between.
+ */
+#[derive(new, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct TermPair {
+    pub near: NaiveDateTime,
+    pub next: NaiveDateTime,
+}
+
+/**
+ * How a series crosses from one pair of terms to the next.
+ */
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub struct RollConfig {
+    /// Blend from the index on the previous terms to the index on the new ones over this long
+    /// after a roll, linearly in variance. `None` switches at once.
+    pub blend: Option<Duration>,
+}
+
+/**
+ * The series switching to a new pair of terms, e.g. when an expiry comes within 30 days or a
+ * new one is listed between the terms.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct RollEvent {
+    pub at: NaiveDateTime,
+    pub from: TermPair,
+    pub to: TermPair,
+    /// The index on the previous terms at the roll, or `None` if they can no longer be
+    /// computed, e.g. because the near term expired.
+    pub previous_value: Option<Percentage>,
+    /// The index on the new terms at the roll.
+    pub value: Option<Percentage>,
+}
+
+impl RollEvent {
+    /**
+     * How far naive recomputation moves the index at the roll.
+     */
+    pub fn jump(&self) -> Option<Percentage> {
+        return Some(self.value? - self.previous_value?);
+    }
+}
+
+/**
+ * An index series with the rolls between terms it went through.
+ */
+#[derive(PartialEq, Clone, Debug)]
+pub struct RolledSeries {
+    pub points: Vec<IndexPoint>,
+    pub rolls: Vec<RollEvent>,
+}
+
+/**
+ * Like `compute_vix_series`, reporting every change in the pair of terms and, if `rolls`
+ * configures a blend, easing the published value across it. Blended points keep the estimate
+ * on the new terms.
+ */
+pub fn compute_vix_series_with_rolls(
+    chains_by_time: &[(NaiveDateTime, &Chain)],
+    rates: &YieldCurve,
+    config: &IndexConfig,
+    rolls: &RollConfig,
+) -> RolledSeries {
+    let mut cache = ContributionCache::new();
+    let mut series = RolledSeries {
+        points: vec![],
+        rolls: vec![],
+    };
+    let mut current: Option<TermPair> = None;
+    for (now, chain) in chains_by_time.iter() {
+        let now = *now;
+        #[cfg(feature = "metrics")]
+        let _timer = crate::metrics::Timer::start(&crate::metrics::global().computation_seconds);
+        let terms = select_terms(chain, now, config);
+        let pair = terms.map(|(near, next)| TermPair::new(near.expires_at(), next.expires_at()));
+        let estimate = terms.and_then(|(near, next)| {
+            return estimate_terms(near, next, now, rates, config, &mut cache);
+        });
+        #[cfg(feature = "metrics")]
+        if let Some(estimate) = &estimate {
+            crate::metrics::global().record_index(estimate);
+        }
+        let mut value = estimate.map(|e| e.value);
+
+        if let (Some(from), Some(to)) = (current, pair) {
+            if from != to {
+                series.rolls.push(RollEvent {
+                    at: now,
+                    from,
+                    to,
+                    previous_value: estimate_pair(chain, from, now, rates, config, &mut cache)
+                        .map(|e| e.value),
+                    value,
+                });
+            }
+        }
+        current = pair.or(current);
+
+        let blending = match (rolls.blend, series.rolls.last()) {
+            (Some(blend), Some(roll)) if Some(roll.to) == pair && now - roll.at < blend => Some((
+                roll.from,
+                (now - roll.at).num_seconds() as f64 / blend.num_seconds() as f64,
+            )),
+            _ => None,
+        };
+        if let (Some((from, weight)), Some(new)) = (blending, value) {
+            let previous = estimate_pair(chain, from, now, rates, config, &mut cache);
+            if let Some(old) = previous.map(|e| e.value).filter(|v| v.is_finite()) {
+                value = Some(((1.0 - weight) * old * old + weight * new * new).sqrt());
+            }
+        }
+
+        series.points.push(IndexPoint {
+            at: now,
+            value,
+            estimate,
+        });
+    }
+    return series;
+}
+
+/**
+ * The index on `pair` as of `now`, if both its expiries are still listed and unexpired.
+ */
+fn estimate_pair(
+    chain: &Chain,
+    pair: TermPair,
+    now: NaiveDateTime,
+    rates: &YieldCurve,
+    config: &IndexConfig,
+    cache: &mut ContributionCache,
+) -> Option<IndexEstimate> {
+    let (near, next) = (chain.get(pair.near)?, chain.get(pair.next)?);
+    near.minutes_to_expiration_with(now, config.same_minute)?;
+    next.minutes_to_expiration_with(now, config.same_minute)?;
+    return estimate_terms(near, next, now, rates, config, cache);
+}
+
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum Smoothing {
+    /// Publish accepted values as they are.
+    #[default]
+    None,
+    /// The median of the last `window` accepted values.
+    Median { window: usize },
+    /// Exponentially weighted moving average, weighting the latest accepted value by `alpha`.
+    Ewma { alpha: f64 },
+}
+
+/**
+ * Rejects values further than `max_deviation` (as a fraction) from the median of the previous
+ * `window` raw values. Because the reference includes rejected values, a genuine level shift is
+ * accepted once it has persisted for half the window.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct OutlierRejection {
+    pub window: usize,
+    pub max_deviation: f64,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub struct SmoothingConfig {
+    pub smoothing: Smoothing,
+    pub outliers: Option<OutlierRejection>,
+}
+
+/**
+ * Smooths a published index series one value at a time, for live publication.
+ */
+#[derive(Clone, Debug)]
+pub struct IndexSmoother {
+    config: SmoothingConfig,
+    raw: Vec<f64>,
+    accepted: Vec<f64>,
+    ewma: Option<f64>,
+    last: Option<f64>,
+}
+
+pub(crate) fn median(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        return Some((sorted[mid - 1] + sorted[mid]) / 2.0);
+    }
+    return Some(sorted[mid]);
+}
+
+/**
+ * Keeps only the last `n` values.
+ */
+fn keep_last(values: &mut Vec<f64>, n: usize) {
+    if values.len() > n {
+        values.drain(..values.len() - n);
+    }
+}
+
+impl IndexSmoother {
+    pub fn new(config: SmoothingConfig) -> IndexSmoother {
+        return IndexSmoother {
+            config,
+            raw: vec![],
+            accepted: vec![],
+            ewma: None,
+            last: None,
+        };
+    }
+
+    /**
+     * The value to publish for `point`. Missing and rejected values publish the last published
+     * value instead.
+     */
+    pub fn push(&mut self, point: IndexPoint) -> IndexPoint {
+        let value = match point.value {
+            Some(value) if value.is_finite() => value,
+            _ => {
+                return IndexPoint {
+                    value: self.last,
+                    ..point
+                };
+            }
+        };
+
+        let rejected = match self.config.outliers {
+            Some(outliers) => {
+                let reference = median(&self.raw);
+                self.raw.push(value);
+                keep_last(&mut self.raw, outliers.window);
+                reference.is_some_and(|m| (value - m).abs() > outliers.max_deviation * m.abs())
+            }
+            None => false,
+        };
+        if !rejected {
+            self.last = Some(self.smooth(value));
+        }
+        return IndexPoint {
+            value: self.last,
+            ..point
+        };
+    }
+
+    fn smooth(&mut self, value: f64) -> f64 {
+        return match self.config.smoothing {
+            Smoothing::None => value,
+            Smoothing::Median { window } => {
+                self.accepted.push(value);
+                keep_last(&mut self.accepted, window.max(1));
+                median(&self.accepted).unwrap_or(value)
+            }
+            Smoothing::Ewma { alpha } => {
+                let next = match self.ewma {
+                    Some(previous) => alpha * value + (1.0 - alpha) * previous,
+                    None => value,
+                };
+                self.ewma = Some(next);
+                next
+            }
+        };
+    }
+}
+
+/**
+ * Smooths a whole series with a fresh `IndexSmoother`.
+ */
+pub fn smooth(series: &[IndexPoint], config: SmoothingConfig) -> Vec<IndexPoint> {
+    let mut smoother = IndexSmoother::new(config);
+    return series.iter().map(|p| smoother.push(*p)).collect();
+}
@@ -0,0 +1,201 @@
+//! Exchange trading sessions.
+//!
+//! Many products trade outside their regular session (RTH): SPX options have a global trading
+//! hours session overnight, and futures trade nearly around the clock. Quotes from those
+//! extended sessions (ETH) are thinner and are usually treated as indicative rather than firm.
+//! `TradingHours` says which session, if any, a time falls in, with the regular session taken
+//! from the product's holiday calendar so early closes are respected.
+
+use crate::chain::Chain;
+use crate::holidays::{Cboe, HolidayCalendar};
+use crate::OptionContract;
+use chrono::prelude::*;
+use chrono::Duration;
+use std::collections::HashMap;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Session {
+    Regular,
+    Extended,
+}
+
+/**
+ * A session in progress: which one, and when it started and ends.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct SessionWindow {
+    pub session: Session,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl SessionWindow {
+    /**
+     * Minutes from `at` until the session ends.
+     */
+    pub fn minutes_remaining(&self, at: NaiveDateTime) -> i64 {
+        return self.end.signed_duration_since(at).num_minutes().max(0);
+    }
+}
+
+/**
+ * Whether a quote can be relied on as a tradeable market.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum QuoteStatus {
+    /// Captured during the regular session.
+    Firm,
+    /// Captured in an extended session or while the exchange was closed.
+    Indicative,
+}
+
+/**
+ * The sessions a product trades. The regular session is the calendar's; extended sessions are
+ * windows of exchange local time on each trading day, and a window ending at or before its start
+ * runs overnight into the trading day it ends on.
+ */
+pub struct TradingHours {
+    calendar: Box<dyn HolidayCalendar + Send + Sync>,
+    extended: Vec<(NaiveTime, NaiveTime)>,
+}
+
+impl TradingHours {
+    /**
+     * Only the regular session of `calendar`.
+     */
+    pub fn new<C: HolidayCalendar + Send + Sync + 'static>(calendar: C) -> TradingHours {
+        return TradingHours {
+            calendar: Box::new(calendar),
+            extended: vec![],
+        };
+    }
+
+    /**
+     * Adds an extended session from `start` to `end`.
+     */
+    pub fn with_extended(mut self, start: NaiveTime, end: NaiveTime) -> TradingHours {
+        self.extended.push((start, end));
+        return self;
+    }
+
+    /**
+     * SPX options: Cboe's regular session, and global trading hours from 20:15 the evening
+     * before until 9:15.
+     */
+    pub fn spx() -> TradingHours {
+        return TradingHours::new(Cboe).with_extended(
+            NaiveTime::from_hms_opt(20, 15, 0).unwrap(),
+            NaiveTime::from_hms_opt(9, 15, 0).unwrap(),
+        );
+    }
+
+    /**
+     * The session `at` falls in, preferring the regular one where they overlap, or `None` if
+     * the product is not trading.
+     */
+    pub fn session_at(&self, at: NaiveDateTime) -> Option<SessionWindow> {
+        let date = at.date();
+        if self.calendar.is_trading_day(date) {
+            let start = date.and_time(self.calendar.open(date));
+            let end = date.and_time(self.calendar.close(date));
+            if at >= start && at < end {
+                return Some(SessionWindow {
+                    session: Session::Regular,
+                    start,
+                    end,
+                });
+            }
+        }
+        return self
+            .extended
+            .iter()
+            .flat_map(|(start, end)| -> Vec<(NaiveDateTime, NaiveDateTime)> {
+                if end > start {
+                    return vec![(date.and_time(*start), date.and_time(*end))];
+                }
+                // the windows ending today and starting today
+                let yesterday = date - Duration::days(1);
+                let tomorrow = date + Duration::days(1);
+                return vec![
+                    (yesterday.and_time(*start), date.and_time(*end)),
+                    (date.and_time(*start), tomorrow.and_time(*end)),
+                ];
+            })
+            .find(|(start, end)| {
+                return at >= *start && at < *end && self.calendar.is_trading_day(end.date());
+            })
+            .map(|(start, end)| SessionWindow {
+                session: Session::Extended,
+                start,
+                end,
+            });
+    }
+
+    pub fn is_open(&self, at: NaiveDateTime) -> bool {
+        return self.session_at(at).is_some();
+    }
+
+    pub fn is_regular(&self, at: NaiveDateTime) -> bool {
+        return self
+            .session_at(at)
+            .is_some_and(|s| s.session == Session::Regular);
+    }
+
+    /**
+     * Whether the contract's quote is firm, judged by when it was captured, or by `now` if the
+     * capture time is unknown.
+     */
+    pub fn quote_status(&self, contract: &OptionContract, now: NaiveDateTime) -> QuoteStatus {
+        if self.is_regular(contract.quoted_at().unwrap_or(now)) {
+            return QuoteStatus::Firm;
+        }
+        return QuoteStatus::Indicative;
+    }
+
+    /**
+     * Contracts in the chain whose quotes are indicative as of `now`.
+     */
+    pub fn indicative_quotes(&self, chain: &Chain, now: NaiveDateTime) -> Vec<OptionContract> {
+        return chain
+            .expiries()
+            .iter()
+            .flat_map(|expiry| expiry.calls().iter().chain(expiry.puts().iter()))
+            .filter(|o| self.quote_status(o, now) == QuoteStatus::Indicative)
+            .copied()
+            .collect();
+    }
+}
+
+/**
+ * Trading hours by product symbol. `SessionRegistry::default()` comes with `SPX`.
+ */
+pub struct SessionRegistry {
+    products: HashMap<String, TradingHours>,
+}
+
+impl Default for SessionRegistry {
+    fn default() -> SessionRegistry {
+        let mut registry = SessionRegistry::empty();
+        registry.register("SPX", TradingHours::spx());
+        return registry;
+    }
+}
+
+impl SessionRegistry {
+    pub fn empty() -> SessionRegistry {
+        return SessionRegistry {
+            products: HashMap::new(),
+        };
+    }
+
+    /**
+     * Adds a product's hours, replacing any already registered under `product`.
+     */
+    pub fn register(&mut self, product: &str, hours: TradingHours) {
+        self.products.insert(product.to_string(), hours);
+    }
+
+    pub fn get(&self, product: &str) -> Option<&TradingHours> {
+        return self.products.get(product);
+    }
+}
@@ -0,0 +1,138 @@
+//! Smile skew measures quoted in delta space.
+
+use crate::analytics::{ChainAnalytics, ExpiryAnalytics};
+use crate::math::{linear_interpolate, linear_interpolate_flat, norm_cdf, solve};
+use crate::{OptionKind, Percentage};
+use chrono::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SkewMetric {
+    /// 25Δ call volatility minus 25Δ put volatility.
+    RiskReversal25,
+    /// Average of the 25Δ call and put volatilities, minus the ATM volatility.
+    Butterfly25,
+    RiskReversal10,
+    Butterfly10,
+}
+
+/**
+ * A single skew observation, in tidy `(tenor, metric, value)` form.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct SkewRow {
+    pub expires_at: NaiveDateTime,
+    /// Days to expiration.
+    pub tenor: f64,
+    pub metric: SkewMetric,
+    pub value: Percentage,
+}
+
+/**
+ * Implied volatility of the out-of-the-money option of `kind` with the given delta, interpolated
+ * linearly in delta. Put deltas are negative.
+ */
+pub fn vol_at_delta(expiry: &ExpiryAnalytics, kind: OptionKind, delta: f64) -> Option<Percentage> {
+    let mut points: Vec<(f64, f64)> = expiry
+        .contracts
+        .iter()
+        .filter(|c| c.contract.kind() == kind)
+        .filter(|c| match kind {
+            OptionKind::Call => c.moneyness >= 1.0,
+            OptionKind::Put => c.moneyness <= 1.0,
+        })
+        .flat_map(|c| Some((c.greeks?.delta, c.implied_volatility?)))
+        .collect();
+    points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    return linear_interpolate(&points, delta);
+}
+
+/**
+ * Strike (in cents) at which an option of `kind` has the given delta, with volatilities taken
+ * from the smile so each candidate strike is valued at its own volatility. Put deltas are
+ * negative. `None` if the expiry has no smile or no strike has that delta.
+ */
+pub fn strike_at_delta(expiry: &ExpiryAnalytics, kind: OptionKind, delta: f64) -> Option<f64> {
+    let smile = expiry.smile();
+    let forward = expiry.forward_price as f64;
+    let t = expiry.time_to_expiration;
+    let atm = linear_interpolate_flat(&smile, 1.0)?;
+    if forward <= 0.0 || t <= 0.0 {
+        return None;
+    }
+    let dividend_discount = (-expiry.dividend_yield * t).exp();
+    let delta_at = |strike: f64| -> f64 {
+        let vol = linear_interpolate_flat(&smile, strike / forward).unwrap_or(atm);
+        let std_dev = vol * t.sqrt();
+        let d1 = ((forward / strike).ln() + 0.5 * std_dev * std_dev) / std_dev;
+        return match kind {
+            OptionKind::Call => dividend_discount * norm_cdf(d1),
+            OptionKind::Put => dividend_discount * (norm_cdf(d1) - 1.0),
+        };
+    };
+    let width = 8.0 * atm * t.sqrt();
+    let root = solve::brent(
+        |strike| delta_at(strike) - delta,
+        forward * (-width).exp(),
+        forward * width.exp(),
+        1e-6 * forward,
+        100,
+    )?;
+    return Some(root.x);
+}
+
+/**
+ * Implied volatility at the forward price, interpolated linearly in strike between the
+ * out-of-the-money options on either side.
+ */
+pub fn atm_vol(expiry: &ExpiryAnalytics) -> Option<Percentage> {
+    return linear_interpolate(&expiry.smile(), 1.0);
+}
+
+/**
+ * ATM volatility of every expiry by days to expiration, omitting expiries without one.
+ */
+pub fn atm_term_structure(analytics: &ChainAnalytics) -> Vec<(f64, Percentage)> {
+    return analytics
+        .expiries
+        .iter()
+        .flat_map(|e| Some((e.time_to_expiration * 365.0, atm_vol(e)?)))
+        .collect();
+}
+
+/**
+ * 25Δ and 10Δ risk reversals and butterflies for every expiry. Metrics that cannot be computed
+ * because the quoted strikes do not reach the required delta are omitted.
+ */
+pub fn skew_term_structure(analytics: &ChainAnalytics) -> Vec<SkewRow> {
+    let mut rows = vec![];
+    for expiry in analytics.expiries.iter() {
+        let tenor = expiry.time_to_expiration * 365.0;
+        let atm = atm_vol(expiry);
+        for (delta, rr, bf) in [
+            (0.25, SkewMetric::RiskReversal25, SkewMetric::Butterfly25),
+            (0.10, SkewMetric::RiskReversal10, SkewMetric::Butterfly10),
+        ]
+        .iter()
+        {
+            let call = vol_at_delta(expiry, OptionKind::Call, *delta);
+            let put = vol_at_delta(expiry, OptionKind::Put, -*delta);
+            if let (Some(call), Some(put)) = (call, put) {
+                rows.push(SkewRow {
+                    expires_at: expiry.expires_at,
+                    tenor,
+                    metric: *rr,
+                    value: call - put,
+                });
+                if let Some(atm) = atm {
+                    rows.push(SkewRow {
+                        expires_at: expiry.expires_at,
+                        tenor,
+                        metric: *bf,
+                        value: (call + put) / 2.0 - atm,
+                    });
+                }
+            }
+        }
+    }
+    return rows;
+}
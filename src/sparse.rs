@@ -0,0 +1,123 @@
+//! Fallbacks for expiries with too few quoted strikes.
+//!
+//! Thin single-name chains often quote only a handful of strikes around the money. The
+//! replication then misses most of the variance in the wings and the index reads far too low,
+//! or has nothing to compute from at all. These fallbacks recover what they can and record that
+//! they did.
+
+use crate::cache::ContributionCache;
+use crate::math::{black_price, implied_volatility};
+use crate::quality::VarianceEstimate;
+use crate::{IndexConfig, OptionKind, OptionsByExpiryDate, ZeroBidPolicy};
+use chrono::prelude::*;
+
+/**
+ * Width of the extrapolated wings either side of the forward, in standard deviations at the
+ * wing volatility.
+ */
+const WING_WIDTH: f64 = 8.0;
+
+/**
+ * What to do with an expiry that has fewer than `min_strikes` strikes in the sum.
+ */
+#[derive(new, PartialEq, Clone, Copy, Debug)]
+pub struct SparseChain {
+    pub min_strikes: usize,
+    pub fallback: Fallback,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Fallback {
+    /// Also use strikes with no bid, valuing them at half the ask, as `ZeroBidPolicy::AskOnlyMark`
+    /// does. Any truncation still applies.
+    IncludeZeroBids,
+    /// Extend the quoted strikes into the wings at the same spacing, pricing the missing
+    /// options at the implied volatility of the outermost quoted one.
+    ExtrapolateWings,
+    /// Compute as usual and only mark the result as low quality.
+    Flag,
+}
+
+impl SparseChain {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn apply(
+        &self,
+        expiry: &OptionsByExpiryDate,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+        t: f64,
+        config: &IndexConfig,
+        estimate: VarianceEstimate,
+        cache: Option<&mut ContributionCache>,
+    ) -> VarianceEstimate {
+        let mut estimate = match self.fallback {
+            Fallback::IncludeZeroBids => {
+                let config = IndexConfig {
+                    zero_bids: ZeroBidPolicy::AskOnlyMark,
+                    ..*config
+                };
+                expiry.variance_at(risk_free_rate, now, t, &config, cache)
+            }
+            Fallback::ExtrapolateWings => VarianceEstimate {
+                variance: estimate.variance + wing_variance(expiry, risk_free_rate, now, t),
+                ..estimate
+            },
+            Fallback::Flag => estimate,
+        };
+        estimate.fallback = Some(self.fallback);
+        estimate.low_quality = estimate.strikes < self.min_strikes;
+        return estimate;
+    }
+}
+
+/**
+ * Variance contributed by out-of-the-money options beyond the quoted strikes, spaced like the
+ * outermost two strikes and priced at the outermost strike's implied volatility.
+ */
+fn wing_variance(
+    expiry: &OptionsByExpiryDate,
+    risk_free_rate: f64,
+    now: NaiveDateTime,
+    t: f64,
+) -> f64 {
+    let strikes: Vec<_> = expiry.strikes().collect();
+    if strikes.len() < 2 {
+        return 0.0;
+    }
+    let forward = expiry.forward_price(risk_free_rate, now) as f64;
+    let discount_factor = (-risk_free_rate * t).exp();
+    let last = strikes.len() - 1;
+    let wings = [
+        (OptionKind::Put, &strikes[0], &strikes[1], -1.0),
+        (OptionKind::Call, &strikes[last], &strikes[last - 1], 1.0),
+    ];
+
+    let mut contributions = 0.0;
+    for (kind, end, inner, direction) in wings.iter() {
+        let contract = match kind {
+            OptionKind::Put => end.put,
+            OptionKind::Call => end.call,
+        };
+        let strike = end.price as f64;
+        let vol = match implied_volatility(
+            *kind,
+            contract.mark() as f64,
+            forward,
+            strike,
+            t,
+            discount_factor,
+        ) {
+            Some(vol) => vol,
+            None => continue,
+        };
+        let step = (end.price - inner.price).abs() as f64;
+        let limit = forward * (direction * WING_WIDTH * vol * t.sqrt()).exp();
+        let mut k = strike + direction * step;
+        while k > 0.0 && (k - limit) * direction <= 0.0 {
+            let price = black_price(*kind, forward, k, vol, t, discount_factor);
+            contributions += step / (k * k) * price;
+            k += direction * step;
+        }
+    }
+    return 2.0 * contributions * (risk_free_rate * t).exp() / t;
+}
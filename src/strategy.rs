@@ -0,0 +1,309 @@
+//! Multi-leg option strategies held to expiration.
+
+use crate::analytics::ExpiryAnalytics;
+use crate::chain::Chain;
+use crate::density::RiskNeutralDensity;
+use crate::math::norm_cdf;
+use crate::skew::atm_vol;
+use crate::{Cents, ContractKey, OptionContract, OptionKind, Settlement};
+
+/**
+ * A position in a single contract. Positive quantities are long, negative are short.
+ */
+#[derive(new, Clone, Copy, Debug)]
+pub struct Leg {
+    /// The contract as last quoted; `Strategy::remark` updates it.
+    pub contract: OptionContract,
+    pub quantity: i64,
+}
+
+impl Leg {
+    pub fn key(&self) -> ContractKey {
+        return self.contract.key();
+    }
+}
+
+#[derive(new, Clone, Debug)]
+pub struct Strategy {
+    pub legs: Vec<Leg>,
+}
+
+/**
+ * Probability that a strategy expires with a profit, under two views of the terminal
+ * distribution.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct ProbabilityOfProfit {
+    /// Lognormal at the ATM implied volatility.
+    pub lognormal: f64,
+    /// The risk-neutral density implied by the smile.
+    pub smile: f64,
+}
+
+/**
+ * Risk of a strategy at expiration, valid for any leg quantities.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RiskProfile {
+    /// Change in profit per cent rise of the underlying above the highest strike. Negative for
+    /// strategies that are net short calls, e.g. call ratio spreads.
+    pub upside_slope: f64,
+    /// Profit or loss if the underlying goes to zero.
+    pub pnl_at_zero: f64,
+    /// Largest profit, or `None` if unbounded.
+    pub max_profit: Option<f64>,
+    /// Largest loss (a positive number), or `None` if unbounded.
+    pub max_loss: Option<f64>,
+    /// Price above which losses grow without bound, if any.
+    pub unlimited_risk_above: Option<f64>,
+    /// Short contracts not covered by a long contract of the same kind.
+    pub naked_calls: i64,
+    pub naked_puts: i64,
+}
+
+impl Strategy {
+    /**
+     * A strategy of `quantity` of each contract, quoted as in `chain`, or the keys not listed
+     * there.
+     */
+    pub fn from_keys(
+        legs: &[(ContractKey, i64)],
+        chain: &Chain,
+    ) -> Result<Strategy, Vec<ContractKey>> {
+        let mut missing = vec![];
+        let mut resolved = vec![];
+        for (key, quantity) in legs.iter() {
+            match chain.find(key) {
+                Some(contract) => resolved.push(Leg::new(*contract, *quantity)),
+                None => missing.push(*key),
+            }
+        }
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+        return Ok(Strategy::new(resolved));
+    }
+
+    /**
+     * Requotes every leg from `chain`, e.g. the latest `SharedChain` snapshot, so everything
+     * computed from the strategy afterwards uses the current quotes. Legs whose contract is no
+     * longer listed keep their last quote, and their keys are returned.
+     */
+    pub fn remark(&mut self, chain: &Chain) -> Vec<ContractKey> {
+        let mut stale = vec![];
+        for leg in self.legs.iter_mut() {
+            match chain.find(&leg.key()) {
+                Some(contract) => leg.contract = *contract,
+                None => stale.push(leg.key()),
+            }
+        }
+        return stale;
+    }
+
+    /**
+     * Net premium paid to open the strategy at the marks; negative for a credit.
+     */
+    pub fn premium(&self) -> Cents {
+        return self
+            .legs
+            .iter()
+            .map(|l| l.quantity * l.contract.mark())
+            .sum();
+    }
+
+    /**
+     * Value of the legs at expiration with the underlying at `price` (in cents).
+     */
+    pub fn payoff(&self, price: f64) -> f64 {
+        return self
+            .legs
+            .iter()
+            .map(|l| {
+                let strike = l.contract.strike as f64;
+                let intrinsic = match l.contract.kind {
+                    OptionKind::Call => (price - strike).max(0.0),
+                    OptionKind::Put => (strike - price).max(0.0),
+                };
+                return l.quantity as f64 * intrinsic;
+            })
+            .sum();
+    }
+
+    /**
+     * Profit or loss at expiration with the underlying at `price` (in cents).
+     */
+    pub fn pnl(&self, price: f64) -> f64 {
+        return self.payoff(price) - self.premium() as f64;
+    }
+
+    /**
+     * Prices at which the payoff changes slope: zero and every strike, ascending.
+     */
+    fn kinks(&self) -> Vec<f64> {
+        let mut kinks: Vec<f64> = vec![0.0];
+        kinks.extend(self.legs.iter().map(|l| l.contract.strike as f64));
+        kinks.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        kinks.dedup();
+        return kinks;
+    }
+
+    /**
+     * Net quantity of contracts of one kind; negative if net short.
+     */
+    fn net_quantity(&self, kind: OptionKind) -> i64 {
+        return self
+            .legs
+            .iter()
+            .filter(|l| l.contract.kind == kind)
+            .map(|l| l.quantity)
+            .sum();
+    }
+
+    /**
+     * Profile of the strategy's risk at expiration: asymptotes, extremes, and the uncovered
+     * short positions.
+     */
+    pub fn risk_profile(&self) -> RiskProfile {
+        let kinks = self.kinks();
+        let pnls: Vec<f64> = kinks.iter().map(|k| self.pnl(*k)).collect();
+        let upside_slope = self.net_quantity(OptionKind::Call) as f64;
+        let highest = pnls.iter().copied().fold(f64::MIN, f64::max);
+        let lowest = pnls.iter().copied().fold(f64::MAX, f64::min);
+
+        return RiskProfile {
+            upside_slope,
+            pnl_at_zero: pnls[0],
+            max_profit: if upside_slope > 0.0 {
+                None
+            } else {
+                Some(highest)
+            },
+            max_loss: if upside_slope < 0.0 {
+                None
+            } else {
+                Some((-lowest).max(0.0))
+            },
+            unlimited_risk_above: if upside_slope < 0.0 {
+                kinks.last().copied()
+            } else {
+                None
+            },
+            naked_calls: (-self.net_quantity(OptionKind::Call)).max(0),
+            naked_puts: (-self.net_quantity(OptionKind::Put)).max(0),
+        };
+    }
+
+    /**
+     * Margin on the uncovered short contracts, in cents, using the CBOE naked option rule: the
+     * premium plus 20% of the underlying (15% for cash-settled index options) less the
+     * out-of-the-money amount, with a minimum of 10% of the underlying (calls) or strike (puts).
+     *
+     * Shorts are covered by longs of the same kind, with the lowest-strike calls and the
+     * highest-strike puts (the most in the money) treated as the naked ones.
+     */
+    pub fn naked_margin(&self, spot: Cents) -> f64 {
+        let spot = spot as f64;
+        let mut margin = 0.0;
+        for kind in [OptionKind::Call, OptionKind::Put].iter() {
+            let mut naked = (-self.net_quantity(*kind)).max(0);
+            let mut shorts: Vec<&Leg> = self
+                .legs
+                .iter()
+                .filter(|l| l.contract.kind == *kind && l.quantity < 0)
+                .collect();
+            shorts.sort_by_key(|l| match kind {
+                OptionKind::Call => l.contract.strike,
+                OptionKind::Put => -l.contract.strike,
+            });
+            for leg in shorts {
+                if naked == 0 {
+                    break;
+                }
+                let quantity = naked.min(-leg.quantity);
+                naked -= quantity;
+
+                let strike = leg.contract.strike as f64;
+                let (out_of_the_money, minimum) = match kind {
+                    OptionKind::Call => ((strike - spot).max(0.0), 0.1 * spot),
+                    OptionKind::Put => ((spot - strike).max(0.0), 0.1 * strike),
+                };
+                let rate = match leg.contract.settlement {
+                    Settlement::Cash | Settlement::Inverse => 0.15,
+                    Settlement::Physical => 0.2,
+                };
+                let requirement = (rate * spot - out_of_the_money).max(minimum);
+                margin += quantity as f64 * (leg.contract.mark() as f64 + requirement);
+            }
+        }
+        return margin;
+    }
+
+    /**
+     * Ranges of the underlying price at expiration over which the strategy is profitable. The
+     * last range ends at infinity if the strategy profits from an unbounded rally.
+     */
+    pub fn profit_intervals(&self) -> Vec<(f64, f64)> {
+        let mut kinks = self.kinks();
+        let last = kinks[kinks.len() - 1];
+        // beyond the last strike the payoff is linear, so one more point fixes the slope
+        kinks.push(last + 1.0);
+
+        let mut intervals: Vec<(f64, f64)> = vec![];
+        for w in kinks.windows(2) {
+            let (a, b) = (w[0], w[1]);
+            let (pnl_a, pnl_b) = (self.pnl(a), self.pnl(b));
+            let slope = (pnl_b - pnl_a) / (b - a);
+            let root = a - pnl_a / slope;
+            let end = if b > last { f64::INFINITY } else { b };
+            let interval = if pnl_a > 0.0 && pnl_b > 0.0 && (end.is_finite() || slope >= 0.0) {
+                (a, end)
+            } else if pnl_a > 0.0 {
+                (a, root)
+            } else if slope > 0.0 && (pnl_b > 0.0 || end.is_infinite()) {
+                (root, end)
+            } else {
+                continue;
+            };
+            match intervals.last_mut() {
+                Some(previous) if previous.1 >= interval.0 => previous.1 = interval.1,
+                _ => intervals.push(interval),
+            };
+        }
+        return intervals;
+    }
+
+    /**
+     * Probability of expiring with a profit, given the cumulative distribution of the underlying
+     * price at expiration (in cents).
+     */
+    pub fn probability_of_profit_with<F: Fn(f64) -> f64>(&self, cdf: F) -> f64 {
+        return self
+            .profit_intervals()
+            .into_iter()
+            .map(|(start, end)| {
+                let upper = if end.is_finite() { cdf(end) } else { 1.0 };
+                return upper - cdf(start);
+            })
+            .sum();
+    }
+
+    /**
+     * Probability of profit at expiration, under both a flat-volatility lognormal and the
+     * smile-implied distribution of `expiry`, so the effect of skew can be seen directly. `None`
+     * if the expiry has no usable implied volatilities.
+     */
+    pub fn probability_of_profit(&self, expiry: &ExpiryAnalytics) -> Option<ProbabilityOfProfit> {
+        let forward = expiry.forward_price as f64;
+        let std_dev = atm_vol(expiry)? * expiry.time_to_expiration.sqrt();
+        let density = RiskNeutralDensity::from_expiry(expiry)?;
+
+        let lognormal = self.probability_of_profit_with(|price| {
+            if price <= 0.0 {
+                return 0.0;
+            }
+            return norm_cdf(((price / forward).ln() + 0.5 * std_dev * std_dev) / std_dev);
+        });
+        let smile = self.probability_of_profit_with(|price| density.cdf(price));
+        return Some(ProbabilityOfProfit { lognormal, smile });
+    }
+}
@@ -0,0 +1,200 @@
+//! Synthetic option chains generated from a known volatility surface.
+//!
+//! The generated quotes come with their ground truth, so pipelines (including this crate's own
+//! index math) can be property-tested against the surface that produced them.
+
+use crate::math::{black_price, Rng};
+use crate::{Cents, OptionContract, OptionKind, Percentage};
+use chrono::prelude::*;
+
+/**
+ * ATM volatility of a single expiry on the surface.
+ */
+#[derive(new, Clone, Copy, Debug)]
+pub struct SyntheticExpiry {
+    pub days: i64,
+    pub atm_vol: Percentage,
+}
+
+/**
+ * Quadratic smile in log-moneyness `ln(K / F)`, added to the ATM volatility of each expiry.
+ */
+#[derive(new, Clone, Copy, Debug, Default)]
+pub struct Skew {
+    pub slope: f64,
+    pub curvature: f64,
+}
+
+/**
+ * Bid/ask spread as a fixed amount plus a proportion of the theoretical price.
+ */
+#[derive(new, Clone, Copy, Debug)]
+pub struct SpreadModel {
+    pub fixed: Cents,
+    pub proportional: f64,
+}
+
+/**
+ * Everything needed to generate a synthetic chain: the underlying, the surface, and how it is quoted.
+ */
+#[derive(Clone, Debug)]
+pub struct SurfaceSpec {
+    pub spot: Cents,
+    pub risk_free_rate: f64,
+    pub dividend_yield: f64,
+    pub expiries: Vec<SyntheticExpiry>,
+    pub skew: Skew,
+    pub spread: SpreadModel,
+    pub strike_interval: Cents,
+    /// Strikes are listed out to this many ATM standard deviations either side of the forward.
+    pub strike_range: f64,
+    pub tick: Cents,
+    /// Probability that any single contract has no quote at all (zero bid and ask).
+    pub missing_quote_probability: f64,
+}
+
+impl Default for SurfaceSpec {
+    fn default() -> SurfaceSpec {
+        return SurfaceSpec {
+            spot: 300_000,
+            risk_free_rate: 0.01,
+            dividend_yield: 0.0,
+            expiries: vec![SyntheticExpiry::new(23, 0.2), SyntheticExpiry::new(37, 0.2)],
+            skew: Skew::default(),
+            spread: SpreadModel::new(10, 0.02),
+            strike_interval: 500,
+            strike_range: 6.0,
+            tick: 5,
+            missing_quote_probability: 0.0,
+        };
+    }
+}
+
+impl SurfaceSpec {
+    /**
+     * Years to expiration, using the same minute-based convention as the index math.
+     */
+    pub fn time_to_expiration(&self, expiry: &SyntheticExpiry) -> f64 {
+        return (expiry.days * 24 * 60) as f64 / 525600.0;
+    }
+
+    /**
+     * Forward price of the underlying in dollars.
+     */
+    pub fn forward(&self, expiry: &SyntheticExpiry) -> f64 {
+        let t = self.time_to_expiration(expiry);
+        return self.spot as f64 / 100.0 * ((self.risk_free_rate - self.dividend_yield) * t).exp();
+    }
+
+    /**
+     * Volatility of the surface at the given strike (in dollars).
+     */
+    pub fn volatility(&self, expiry: &SyntheticExpiry, strike: f64) -> Percentage {
+        let k = (strike / self.forward(expiry)).ln();
+        let vol = expiry.atm_vol + self.skew.slope * k + self.skew.curvature * k * k;
+        return vol.max(0.01);
+    }
+
+    /**
+     * Theoretical price in dollars of an option on the surface.
+     */
+    pub fn theoretical_price(
+        &self,
+        expiry: &SyntheticExpiry,
+        kind: OptionKind,
+        strike: f64,
+    ) -> f64 {
+        let t = self.time_to_expiration(expiry);
+        return black_price(
+            kind,
+            self.forward(expiry),
+            strike,
+            self.volatility(expiry, strike),
+            t,
+            (-self.risk_free_rate * t).exp(),
+        );
+    }
+
+    /**
+     * Ground truth model-free variance of an expiry: the VIX strip integrated over a continuum
+     * of strikes rather than the listed ones.
+     */
+    pub fn expected_variance(&self, expiry: &SyntheticExpiry) -> Percentage {
+        let t = self.time_to_expiration(expiry);
+        let forward = self.forward(expiry);
+        let limit = 10.0 * expiry.atm_vol.max(0.01) * t.sqrt();
+        let steps = 4000;
+        let h = 2.0 * limit / steps as f64;
+
+        // Simpson's rule in log-strike: dK / K^2 = dx / K
+        let integrand = |x: f64| -> f64 {
+            let strike = forward * x.exp();
+            let kind = if x < 0.0 {
+                OptionKind::Put
+            } else {
+                OptionKind::Call
+            };
+            return self.theoretical_price(expiry, kind, strike) / strike;
+        };
+        let integral: f64 = (0..=steps)
+            .map(|i| {
+                let weight = if i == 0 || i == steps {
+                    1.0
+                } else if i % 2 == 1 {
+                    4.0
+                } else {
+                    2.0
+                };
+                return weight * integrand(-limit + i as f64 * h);
+            })
+            .sum::<f64>()
+            * h
+            / 3.0;
+
+        return 2.0 * (self.risk_free_rate * t).exp() * integral / t;
+    }
+
+    /**
+     * Strikes listed for an expiry, in cents.
+     */
+    fn strikes(&self, expiry: &SyntheticExpiry) -> Vec<Cents> {
+        let forward = self.forward(expiry);
+        let width = self.strike_range * expiry.atm_vol * self.time_to_expiration(expiry).sqrt();
+        let interval = self.strike_interval.max(1);
+        let low = ((forward * (-width).exp() * 100.0) as Cents / interval).max(1) * interval;
+        let high = ((forward * width.exp() * 100.0) as Cents / interval + 1) * interval;
+        return (low..=high).step_by(interval as usize).collect();
+    }
+}
+
+/**
+ * Generates a chain of quotes from the surface, expiring relative to `now`.
+ *
+ * The same `seed` always produces the same chain.
+ */
+pub fn generate_chain(spec: &SurfaceSpec, now: NaiveDateTime, seed: u64) -> Vec<OptionContract> {
+    let mut rng = Rng::new(seed);
+    let tick = spec.tick.max(1);
+    let mut options = vec![];
+
+    for expiry in spec.expiries.iter() {
+        let expires_at = now + chrono::Duration::days(expiry.days);
+        for strike in spec.strikes(expiry) {
+            for kind in [OptionKind::Call, OptionKind::Put].iter() {
+                if rng.next_f64() < spec.missing_quote_probability {
+                    options.push(OptionContract::new(expires_at, strike, *kind, 0, 0));
+                    continue;
+                }
+
+                let theo = 100.0 * spec.theoretical_price(expiry, *kind, strike as f64 / 100.0);
+                let half_spread =
+                    (spec.spread.fixed as f64 + spec.spread.proportional * theo) / 2.0;
+                let bid = ((theo - half_spread) / tick as f64).floor().max(0.0) as Cents * tick;
+                let ask = (((theo + half_spread) / tick as f64).ceil() as Cents * tick).max(tick);
+                options.push(OptionContract::new(expires_at, strike, *kind, bid, ask));
+            }
+        }
+    }
+
+    return options;
+}
@@ -0,0 +1,183 @@
+//! Heuristics for inputs given in the wrong units.
+//!
+//! Prices and strikes are in cents, and volatilities and rates are fractions, but vendor data
+//! comes in dollars and percent. A chain converted to cents twice, or not at all, still computes;
+//! it just computes nonsense. These compare inputs with what they plausibly could be and either
+//! correct an off-by-a-hundred mistake, reporting it, or reject the input.
+
+use crate::pricing::years_until;
+use crate::{Cents, OptionContract, Percentage};
+use chrono::prelude::*;
+
+/**
+ * Volatilities above this are taken to be in percent: 5.0 would be 500%.
+ */
+pub const PERCENT_VOLATILITY_THRESHOLD: f64 = 5.0;
+
+/**
+ * Rates above this in magnitude are taken to be in percent: 0.5 would be 50%. Rates in percent
+ * below it, like 0.25 meaning a quarter percent, cannot be told apart and pass.
+ */
+pub const PERCENT_RATE_THRESHOLD: f64 = 0.5;
+
+/**
+ * An input that looks a hundred times too large or too small.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum UnitIssue {
+    /// Strikes are `factor` times what they should be relative to the spot, e.g. 100 for
+    /// strikes converted to cents twice and 0.01 for strikes left in dollars.
+    Strikes { factor: f64 },
+    /// Bids and asks are `factor` times what they should be relative to the spot.
+    Prices { factor: f64 },
+    /// A volatility given in percent.
+    PercentVolatility { value: f64 },
+    /// A rate given in percent.
+    PercentRate { value: f64 },
+}
+
+/**
+ * What to do with an input in the wrong units.
+ */
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum UnitPolicy {
+    /// Rescale it, reporting the issue as a warning.
+    Correct,
+    /// Fail with the issue.
+    Reject,
+}
+
+/**
+ * How many times too large the strikes of `options` are, judged by their median against the
+ * spot: 100 or 0.01 if they are off by that much, `None` if they look right or the evidence is
+ * ambiguous.
+ */
+pub fn check_strikes(options: &[OptionContract], spot: Cents) -> Option<UnitIssue> {
+    let mut strikes: Vec<Cents> = options.iter().map(|o| o.strike).collect();
+    if strikes.is_empty() || spot <= 0 {
+        return None;
+    }
+    strikes.sort_unstable();
+    let ratio = strikes[strikes.len() / 2] as f64 / spot as f64;
+    return off_by_hundred(ratio).map(|factor| UnitIssue::Strikes { factor });
+}
+
+/**
+ * How many times too large the bids and asks of `options` are, judged by the volatility the
+ * quotes nearest the money imply with Brenner and Subrahmanyam's approximation
+ * `σ ≈ mark / (0.4 S √t)`. Prices a hundred times too small imply a volatility under 1%, and a
+ * hundred times too large over 1,000%.
+ */
+pub fn check_prices(
+    options: &[OptionContract],
+    spot: Cents,
+    now: NaiveDateTime,
+) -> Option<UnitIssue> {
+    if spot <= 0 {
+        return None;
+    }
+    let mut vols: Vec<f64> = options
+        .iter()
+        .filter(|o| o.bid > 0 && o.expires_at > now)
+        .filter(|o| (o.strike - spot).abs() as f64 <= 0.02 * spot as f64)
+        .map(|o| {
+            let t = years_until(o.expires_at, now);
+            return o.mark() as f64 / (0.4 * spot as f64 * t.sqrt());
+        })
+        .collect();
+    if vols.is_empty() {
+        return None;
+    }
+    vols.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let vol = vols[vols.len() / 2];
+    let factor = if vol < 0.01 {
+        0.01
+    } else if vol > 10.0 {
+        100.0
+    } else {
+        return None;
+    };
+    return Some(UnitIssue::Prices { factor });
+}
+
+/**
+ * `options` with strikes and prices rescaled according to `check_strikes` and `check_prices`,
+ * with the issues found. With `UnitPolicy::Reject`, fails with the issues instead.
+ */
+pub fn normalize_chain(
+    options: &[OptionContract],
+    spot: Cents,
+    now: NaiveDateTime,
+    policy: UnitPolicy,
+) -> Result<(Vec<OptionContract>, Vec<UnitIssue>), Vec<UnitIssue>> {
+    let mut issues = vec![];
+    let mut normalized = options.to_vec();
+    if let Some(UnitIssue::Strikes { factor }) = check_strikes(&normalized, spot) {
+        issues.push(UnitIssue::Strikes { factor });
+        for o in normalized.iter_mut() {
+            o.strike = rescale(o.strike, factor);
+        }
+    }
+    if let Some(UnitIssue::Prices { factor }) = check_prices(&normalized, spot, now) {
+        issues.push(UnitIssue::Prices { factor });
+        for o in normalized.iter_mut() {
+            o.bid = rescale(o.bid, factor);
+            o.ask = rescale(o.ask, factor);
+        }
+    }
+    if policy == UnitPolicy::Reject && !issues.is_empty() {
+        return Err(issues);
+    }
+    return Ok((normalized, issues));
+}
+
+/**
+ * A volatility as a fraction, with the issue if it was given in percent.
+ */
+pub fn normalize_volatility(
+    value: Percentage,
+    policy: UnitPolicy,
+) -> Result<(Percentage, Option<UnitIssue>), UnitIssue> {
+    if value <= PERCENT_VOLATILITY_THRESHOLD {
+        return Ok((value, None));
+    }
+    let issue = UnitIssue::PercentVolatility { value };
+    return match policy {
+        UnitPolicy::Correct => Ok((value / 100.0, Some(issue))),
+        UnitPolicy::Reject => Err(issue),
+    };
+}
+
+/**
+ * A rate as a fraction, with the issue if it was given in percent.
+ */
+pub fn normalize_rate(
+    value: f64,
+    policy: UnitPolicy,
+) -> Result<(f64, Option<UnitIssue>), UnitIssue> {
+    if value.abs() <= PERCENT_RATE_THRESHOLD {
+        return Ok((value, None));
+    }
+    let issue = UnitIssue::PercentRate { value };
+    return match policy {
+        UnitPolicy::Correct => Ok((value / 100.0, Some(issue))),
+        UnitPolicy::Reject => Err(issue),
+    };
+}
+
+/**
+ * 100 or 0.01 if `ratio`, which should be near one, is off by about that factor.
+ */
+fn off_by_hundred(ratio: f64) -> Option<f64> {
+    if ratio > 20.0 && ratio < 500.0 {
+        return Some(100.0);
+    }
+    if ratio > 0.002 && ratio < 0.05 {
+        return Some(0.01);
+    }
+    return None;
+}
+
+fn rescale(value: Cents, factor: f64) -> Cents {
+    return (value as f64 / factor).round() as Cents;
+}
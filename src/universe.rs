@@ -0,0 +1,245 @@
+//! Cross-sectional screening of many underlyings.
+//!
+//! A `Universe` holds what is known about each underlying (its chain, price history, and the
+//! history of its 30-day index), and `Universe::relative_value` reduces each to one row of the
+//! measures volatility traders compare across names every day.
+
+use crate::chain::Chain;
+use crate::math::linear_interpolate_flat;
+use crate::rates::YieldCurve;
+use crate::realized::realized_volatility;
+use crate::returns::LogReturn;
+use crate::series::compute_vix_series;
+use crate::skew::{atm_vol, skew_term_structure, SkewMetric};
+use crate::{Cents, IndexConfig, Percentage};
+use chrono::prelude::*;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+/**
+ * Trading days of returns the variance risk premium compares the index against: the 30
+ * calendar days it measures.
+ */
+pub const VRP_WINDOW: usize = 21;
+
+/**
+ * Everything screened about one underlying.
+ */
+#[derive(new, Clone, Debug)]
+pub struct Underlying {
+    pub spot: Cents,
+    pub chain: Chain,
+    /// Daily returns, oldest first.
+    pub returns: Vec<LogReturn>,
+    /// Past values of the 30-day index, e.g. a year of closes, for ranking the current value.
+    pub index_history: Vec<Percentage>,
+}
+
+/**
+ * Underlyings by symbol.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct Universe {
+    underlyings: BTreeMap<String, Underlying>,
+}
+
+/**
+ * One underlying's relative value measures. Volatilities are in the index's units (percentage
+ * points); measures that could not be computed are `None`.
+ */
+#[derive(PartialEq, Clone, Debug)]
+pub struct RelativeValueRow {
+    pub symbol: String,
+    /// 30-day index.
+    pub index: Option<Percentage>,
+    /// Where the index sits between the low (0) and high (1) of its history.
+    pub iv_rank: Option<f64>,
+    /// 90-day less 30-day ATM volatility: positive in contango.
+    pub term_slope: Option<Percentage>,
+    /// 30-day 25Δ risk reversal: call less put volatility, negative when puts are bid.
+    pub skew_25: Option<Percentage>,
+    /// Index less realized volatility over the last `VRP_WINDOW` returns.
+    pub vrp: Option<Percentage>,
+    /// Between 0 and 1: the share of contracts within 60 days that are quoted, discounted by
+    /// their average relative spread.
+    pub liquidity_score: Option<f64>,
+}
+
+/**
+ * Header of the relative value CSV, one column per field of `RelativeValueRow`.
+ */
+pub const RELATIVE_VALUE_COLUMNS: [&str; 7] = [
+    "symbol",
+    "index",
+    "iv_rank",
+    "term_slope",
+    "skew_25",
+    "vrp",
+    "liquidity_score",
+];
+
+/**
+ * The relative value screen of a universe, ordered by symbol.
+ */
+#[derive(PartialEq, Clone, Debug)]
+pub struct RelativeValueTable {
+    pub now: NaiveDateTime,
+    pub rows: Vec<RelativeValueRow>,
+}
+
+impl RelativeValueTable {
+    /**
+     * Writes the table as CSV, with a header. Fields that could not be computed are empty.
+     */
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{}", RELATIVE_VALUE_COLUMNS.join(","))?;
+        let optional = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+        for row in self.rows.iter() {
+            let fields = [
+                row.symbol.clone(),
+                optional(row.index),
+                optional(row.iv_rank),
+                optional(row.term_slope),
+                optional(row.skew_25),
+                optional(row.vrp),
+                optional(row.liquidity_score),
+            ];
+            writeln!(writer, "{}", fields.join(","))?;
+        }
+        return Ok(());
+    }
+
+    /**
+     * The CSV written by `write_csv`, as a string.
+     */
+    pub fn to_csv(&self) -> String {
+        let mut buf = Vec::new();
+        self.write_csv(&mut buf)
+            .expect("writing to a Vec cannot fail");
+        return String::from_utf8(buf).expect("the CSV is UTF-8");
+    }
+}
+
+impl Universe {
+    pub fn new() -> Universe {
+        return Universe::default();
+    }
+
+    /**
+     * Adds an underlying, replacing any already held under `symbol`.
+     */
+    pub fn insert(&mut self, symbol: &str, underlying: Underlying) {
+        self.underlyings.insert(symbol.to_string(), underlying);
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&Underlying> {
+        return self.underlyings.get(symbol);
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        return self.underlyings.keys().map(|s| s.as_str());
+    }
+
+    pub fn len(&self) -> usize {
+        return self.underlyings.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.underlyings.is_empty();
+    }
+
+    /**
+     * The relative value screen of every underlying as of `now`.
+     */
+    pub fn relative_value(&self, rates: &YieldCurve, now: NaiveDateTime) -> RelativeValueTable {
+        return RelativeValueTable {
+            now,
+            rows: self
+                .underlyings
+                .iter()
+                .map(|(symbol, underlying)| underlying.relative_value(symbol, rates, now))
+                .collect(),
+        };
+    }
+}
+
+impl Underlying {
+    fn relative_value(
+        &self,
+        symbol: &str,
+        rates: &YieldCurve,
+        now: NaiveDateTime,
+    ) -> RelativeValueRow {
+        let index = compute_vix_series(&[(now, &self.chain)], rates, &IndexConfig::default())
+            .first()
+            .and_then(|point| point.value)
+            .filter(|value| value.is_finite());
+        let analytics = self.chain.analytics(self.spot, rates, now);
+        let days = |t: f64| t * 365.0;
+
+        let atm: Vec<(f64, f64)> = analytics
+            .expiries
+            .iter()
+            .flat_map(|e| Some((days(e.time_to_expiration), atm_vol(e)? * 100.0)))
+            .collect();
+        let term_slope = match (
+            linear_interpolate_flat(&atm, 90.0),
+            linear_interpolate_flat(&atm, 30.0),
+        ) {
+            (Some(long), Some(short)) if atm.len() > 1 => Some(long - short),
+            _ => None,
+        };
+
+        let risk_reversals: Vec<(f64, f64)> = skew_term_structure(&analytics)
+            .iter()
+            .filter(|row| row.metric == SkewMetric::RiskReversal25)
+            .map(|row| (row.tenor, row.value * 100.0))
+            .collect();
+        let skew_25 = linear_interpolate_flat(&risk_reversals, 30.0);
+
+        let recent = &self.returns[self.returns.len().saturating_sub(VRP_WINDOW)..];
+        let vrp = match (index, realized_volatility(recent)) {
+            (Some(index), Some(realized)) => Some(index - realized * 100.0),
+            _ => None,
+        };
+
+        let iv_rank = index.and_then(|index| {
+            let low = self.index_history.iter().cloned().fold(f64::NAN, f64::min);
+            let high = self.index_history.iter().cloned().fold(f64::NAN, f64::max);
+            // NaN without history
+            if high.is_nan() || high <= low {
+                return None;
+            }
+            return Some(((index - low) / (high - low)).clamp(0.0, 1.0));
+        });
+
+        let contracts: Vec<_> = analytics
+            .expiries
+            .iter()
+            .filter(|e| days(e.time_to_expiration) <= 60.0)
+            .flat_map(|e| e.contracts.iter())
+            .collect();
+        let quoted: Vec<f64> = contracts
+            .iter()
+            .filter(|c| c.liquidity.quoted && c.liquidity.relative_spread.is_finite())
+            .map(|c| c.liquidity.relative_spread)
+            .collect();
+        let liquidity_score = if contracts.is_empty() {
+            None
+        } else {
+            let share = quoted.len() as f64 / contracts.len() as f64;
+            let spread = quoted.iter().sum::<f64>() / quoted.len().max(1) as f64;
+            Some(share * (1.0 - spread).max(0.0))
+        };
+
+        return RelativeValueRow {
+            symbol: symbol.to_string(),
+            index,
+            iv_rank,
+            term_slope,
+            skew_25,
+            vrp,
+            liquidity_score,
+        };
+    }
+}
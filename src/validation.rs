@@ -0,0 +1,279 @@
+//! Structural validation of quotes before computing with them.
+//!
+//! Unlike the invariants, which flag prices that are tradeable against, these checks catch
+//! input that is malformed outright and would make any computation meaningless.
+
+use crate::chain::Chain;
+use crate::series::median;
+use crate::{
+    compute_vix_with_config, Cents, IndexConfig, OptionContract, OptionsByExpiryDate, Percentage,
+    SameMinuteExpiry,
+};
+use chrono::prelude::*;
+use std::collections::HashMap;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Severity {
+    /// Suspicious, but computations still produce a meaningful result.
+    Warning,
+    /// Computations on this input produce meaningless results.
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Issue {
+    /**
+     * The bid or ask is below zero.
+     */
+    NegativePrice { contract: OptionContract },
+    /**
+     * The bid is above the ask.
+     */
+    CrossedQuote { contract: OptionContract },
+    /**
+     * The same expiry, strike, and kind appears more than once. Only the first is used.
+     */
+    DuplicateContract { contract: OptionContract },
+    /**
+     * The expiry is at or before the time of the computation, or within the same minute.
+     */
+    Expired { expires_at: NaiveDateTime },
+    /**
+     * The strike is zero or negative.
+     */
+    NonPositiveStrike { contract: OptionContract },
+    /**
+     * The forwards implied by each strike's call and put disagree by more than allowed, as
+     * stale quotes or a wrong rate or dividend make them.
+     */
+    InconsistentForwards {
+        expires_at: NaiveDateTime,
+        dispersion: f64,
+    },
+}
+
+/**
+ * The forward implied by put-call parity at every strike of an expiry, `K + e^{rT}(C - P)`.
+ */
+#[derive(PartialEq, Clone, Debug)]
+pub struct ForwardDispersion {
+    /// Strike and the forward implied there, both in cents, ascending by strike.
+    pub forwards: Vec<(Cents, f64)>,
+    pub median: f64,
+    /// Median absolute deviation from `median`, as a fraction of it. Robust to a few wide wing
+    /// quotes, so a large value means most strikes disagree.
+    pub dispersion: f64,
+}
+
+/**
+ * Which contract to keep when the same expiry, strike, and kind is quoted more than once, as
+ * happens with concatenated vendor files.
+ */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum DuplicatePolicy {
+    /// Reject the input.
+    Error,
+    /// Keep the first in input order.
+    KeepFirst,
+    /// Keep the latest quote, by `quoted_at` and then by revision. Ties keep the first.
+    KeepFreshest,
+    /// Keep the quote with the narrowest spread. Ties keep the first.
+    KeepTightestSpread,
+}
+
+impl Issue {
+    pub fn severity(&self) -> Severity {
+        return match self {
+            Issue::DuplicateContract { .. } => Severity::Warning,
+            _ => Severity::Error,
+        };
+    }
+}
+
+impl OptionsByExpiryDate {
+    /**
+     * Every structural issue with the expiry's quotes as of `now`.
+     */
+    pub fn validate(&self, now: NaiveDateTime) -> Vec<Issue> {
+        return self.validate_with(now, SameMinuteExpiry::Expired);
+    }
+
+    /**
+     * Like `validate`, treating expiries less than a minute away according to `same_minute`.
+     */
+    pub fn validate_with(&self, now: NaiveDateTime, same_minute: SameMinuteExpiry) -> Vec<Issue> {
+        let mut issues = vec![];
+        if self.is_expired(now, same_minute) {
+            issues.push(Issue::Expired {
+                expires_at: self.expires_at,
+            });
+        }
+        for contracts in [&self.calls, &self.puts].iter() {
+            for (i, o) in contracts.iter().enumerate() {
+                if o.bid < 0 || o.ask < 0 {
+                    issues.push(Issue::NegativePrice { contract: *o });
+                }
+                if o.bid > o.ask {
+                    issues.push(Issue::CrossedQuote { contract: *o });
+                }
+                if o.strike <= 0 {
+                    issues.push(Issue::NonPositiveStrike { contract: *o });
+                }
+                // contracts are sorted by strike, so duplicates are adjacent
+                if i > 0 && contracts[i - 1].strike == o.strike {
+                    issues.push(Issue::DuplicateContract { contract: *o });
+                }
+            }
+        }
+        return issues;
+    }
+}
+
+impl OptionsByExpiryDate {
+    /**
+     * The forward implied at each strike with a quoted call and put, or `None` if there are
+     * none.
+     */
+    pub fn forward_dispersion(
+        &self,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+    ) -> Option<ForwardDispersion> {
+        let interest = (risk_free_rate * self.time_to_expiration(now)).exp();
+        let forwards: Vec<(Cents, f64)> = self
+            .strikes()
+            .map(|s| {
+                (
+                    s.price,
+                    s.price as f64 + interest * s.call_put_difference() as f64,
+                )
+            })
+            .collect();
+        let values: Vec<f64> = forwards.iter().map(|(_, f)| *f).collect();
+        let middle = median(&values)?;
+        let deviations: Vec<f64> = values.iter().map(|f| (f - middle).abs()).collect();
+        let deviation = median(&deviations)?;
+        return Some(ForwardDispersion {
+            forwards,
+            median: middle,
+            dispersion: deviation / middle,
+        });
+    }
+
+    /**
+     * An `Issue::InconsistentForwards` if the dispersion of the forwards implied at each strike
+     * is above `max_dispersion`.
+     */
+    pub fn check_forwards(
+        &self,
+        risk_free_rate: f64,
+        now: NaiveDateTime,
+        max_dispersion: f64,
+    ) -> Option<Issue> {
+        let dispersion = self.forward_dispersion(risk_free_rate, now)?.dispersion;
+        if dispersion <= max_dispersion {
+            return None;
+        }
+        return Some(Issue::InconsistentForwards {
+            expires_at: self.expires_at,
+            dispersion,
+        });
+    }
+}
+
+impl Chain {
+    /**
+     * Every structural issue with the chain's quotes as of `now`, by expiry.
+     */
+    pub fn validate(&self, now: NaiveDateTime) -> Vec<Issue> {
+        return self
+            .expiries()
+            .iter()
+            .flat_map(|e| e.validate(now))
+            .collect();
+    }
+}
+
+/**
+ * Removes duplicate contracts from `options` according to `policy`, keeping the order in which
+ * each contract first appears. With `DuplicatePolicy::Error`, fails with every duplicate found.
+ */
+pub fn resolve_duplicates(
+    options: &[OptionContract],
+    policy: DuplicatePolicy,
+) -> Result<Vec<OptionContract>, Vec<Issue>> {
+    let mut resolved: Vec<OptionContract> = Vec::with_capacity(options.len());
+    let mut positions = HashMap::new();
+    let mut duplicates = vec![];
+    for o in options.iter() {
+        let key = o.key();
+        let index = match positions.get(&key) {
+            Some(index) => *index,
+            None => {
+                positions.insert(key, resolved.len());
+                resolved.push(*o);
+                continue;
+            }
+        };
+        let kept = resolved[index];
+        let replace = match policy {
+            DuplicatePolicy::Error => {
+                duplicates.push(Issue::DuplicateContract { contract: *o });
+                false
+            }
+            DuplicatePolicy::KeepFirst => false,
+            DuplicatePolicy::KeepFreshest => {
+                (o.quoted_at, o.revision) > (kept.quoted_at, kept.revision)
+            }
+            DuplicatePolicy::KeepTightestSpread => o.ask - o.bid < kept.ask - kept.bid,
+        };
+        if replace {
+            resolved[index] = *o;
+        }
+    }
+    if !duplicates.is_empty() {
+        return Err(duplicates);
+    }
+    return Ok(resolved);
+}
+
+/**
+ * Fails with all of `issues` if any of them is at least as severe as `severity`.
+ */
+pub fn require(issues: Vec<Issue>, severity: Severity) -> Result<(), Vec<Issue>> {
+    if issues.iter().any(|i| i.severity() >= severity) {
+        return Err(issues);
+    }
+    return Ok(());
+}
+
+/**
+ * Like `compute_vix_with_config`, but refuses to compute from quotes with errors, including
+ * forwards more dispersed than `max_forward_dispersion`, as `ForwardDispersion` measures it, if
+ * given.
+ */
+pub fn compute_vix_validated(
+    near_term: &OptionsByExpiryDate,
+    next_term: &OptionsByExpiryDate,
+    near_term_risk_free_rate: f64,
+    next_term_risk_free_rate: f64,
+    now: NaiveDateTime,
+    config: &IndexConfig,
+    max_forward_dispersion: Option<f64>,
+) -> Result<Percentage, Vec<Issue>> {
+    let mut issues = near_term.validate_with(now, config.same_minute);
+    issues.extend(next_term.validate_with(now, config.same_minute));
+    if let Some(max_dispersion) = max_forward_dispersion {
+        issues.extend(near_term.check_forwards(near_term_risk_free_rate, now, max_dispersion));
+        issues.extend(next_term.check_forwards(next_term_risk_free_rate, now, max_dispersion));
+    }
+    require(issues, Severity::Error)?;
+    return Ok(compute_vix_with_config(
+        near_term,
+        next_term,
+        near_term_risk_free_rate,
+        next_term_risk_free_rate,
+        now,
+        config,
+    ));
+}
@@ -0,0 +1,76 @@
+use chrono::prelude::*;
+use options_math::alerts::{Alert, AlertState, Metric::*, Watchlist};
+use options_math::universe::{RelativeValueRow, RelativeValueTable};
+
+fn at(day: u32) -> NaiveDateTime {
+    return NaiveDate::from_ymd_opt(2020, 1, day)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+}
+
+fn table(day: u32, iv_rank: f64, term_slope: Option<f64>) -> RelativeValueTable {
+    return RelativeValueTable {
+        now: at(day),
+        rows: vec![RelativeValueRow {
+            symbol: "SPX".to_string(),
+            index: Some(20.0),
+            iv_rank: Some(iv_rank),
+            term_slope,
+            skew_25: None,
+            vrp: None,
+            liquidity_score: None,
+        }],
+    };
+}
+
+#[test]
+fn test_alert() {
+    let alert = Alert::new("SPX").when(IvRank.gt(80)).and(TermSlope.lt(0.0));
+
+    let event = alert.evaluate(&table(2, 0.9, Some(-1.5))).unwrap();
+    assert_eq!(event.symbol, "SPX");
+    assert_eq!(event.at, at(2));
+    assert_eq!(event.state, AlertState::Firing);
+    assert_eq!(event.readings.len(), 2);
+    assert!((event.readings[0].value.unwrap() - 90.0).abs() < 1e-9);
+    assert_eq!(event.readings[1].value, Some(-1.5));
+
+    assert!(alert.evaluate(&table(2, 0.7, Some(-1.5))).is_none());
+    // a measure that could not be computed does not satisfy its condition
+    assert!(alert.evaluate(&table(2, 0.9, None)).is_none());
+    assert!(Alert::new("NDX")
+        .when(IvRank.gt(80))
+        .evaluate(&table(2, 0.9, None))
+        .is_none());
+    assert!(Alert::new("SPX").evaluate(&table(2, 0.9, None)).is_none());
+}
+
+#[test]
+fn test_watchlist_reports_changes() {
+    let mut watchlist = Watchlist::new();
+    watchlist.add(Alert::new("SPX").when(IvRank.gt(80)).and(TermSlope.lt(0.0)));
+
+    assert!(watchlist.observe(&table(2, 0.5, Some(1.0))).is_empty());
+    let events = watchlist.observe(&table(3, 0.9, Some(-1.0)));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].state, AlertState::Firing);
+    assert_eq!(watchlist.firing().count(), 1);
+
+    // still firing: nothing new to report
+    assert!(watchlist.observe(&table(6, 0.95, Some(-2.0))).is_empty());
+
+    let events = watchlist.observe(&table(7, 0.95, Some(0.5)));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].state, AlertState::Cleared);
+    assert_eq!(events[0].at, at(7));
+    assert_eq!(watchlist.firing().count(), 0);
+
+    watchlist.observe(&table(8, 0.95, Some(-0.5)));
+    let empty = RelativeValueTable {
+        now: at(9),
+        rows: vec![],
+    };
+    let events = watchlist.observe(&empty);
+    assert_eq!(events[0].state, AlertState::Cleared);
+    assert!(events[0].readings.is_empty());
+}
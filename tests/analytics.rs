@@ -0,0 +1,137 @@
+use chrono::prelude::*;
+use options_math::analytics::SmileMarking;
+use options_math::chain::Chain;
+use options_math::rates::YieldCurve;
+use options_math::synthetic::*;
+use options_math::*;
+
+#[test]
+fn test_chain_analytics_recovers_surface() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec::default();
+    let chain = Chain::new(&generate_chain(&spec, now, 1));
+
+    let analytics = chain.analytics(spec.spot, &YieldCurve::flat(spec.risk_free_rate), now);
+    assert_eq!(analytics.expiries.len(), spec.expiries.len());
+
+    for expiry in analytics.expiries.iter() {
+        assert!(
+            expiry.dividend_yield.abs() < 0.01,
+            "{}",
+            expiry.dividend_yield
+        );
+        for c in expiry.contracts.iter() {
+            if (c.moneyness - 1.0).abs() > 0.05 {
+                continue;
+            }
+            let vol = c.implied_volatility.unwrap();
+            assert!((vol - 0.2).abs() < 0.01, "{:?}", c);
+
+            let delta = c.greeks.unwrap().delta;
+            match c.contract.kind() {
+                OptionKind::Call => assert!(delta > 0.0 && delta < 1.0),
+                OptionKind::Put => assert!(delta < 0.0 && delta > -1.0),
+            }
+            assert!(c.liquidity.quoted);
+        }
+    }
+}
+
+#[test]
+fn test_solver_configuration_and_telemetry() {
+    use options_math::math::*;
+
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec {
+        skew: Skew::new(-0.3, 1.0),
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&spec, now, 1));
+    let rates = YieldCurve::flat(spec.risk_free_rate);
+
+    let (fixed, fixed_stats) =
+        chain.analytics_with_solver(spec.spot, &rates, now, &SolverConfig::default());
+    let seeded = SolverConfig {
+        initial_guess: InitialGuess::CorradoMiller,
+        ..SolverConfig::default()
+    };
+    let (corrado, corrado_stats) = chain.analytics_with_solver(spec.spot, &rates, now, &seeded);
+    assert!(fixed_stats.solves > 0);
+    assert_eq!(fixed_stats.solves, corrado_stats.solves);
+    assert_eq!(fixed_stats.not_converged, 0);
+    assert_eq!(corrado_stats.not_converged, 0);
+    assert!(
+        corrado_stats.mean_iterations() < fixed_stats.mean_iterations(),
+        "{:?} {:?}",
+        corrado_stats,
+        fixed_stats
+    );
+    for (a, b) in fixed.expiries[0]
+        .contracts
+        .iter()
+        .zip(corrado.expiries[0].contracts.iter())
+    {
+        match (a.implied_volatility, b.implied_volatility) {
+            // deep in the money the price tolerance allows a little more volatility error
+            (Some(x), Some(y)) => assert!((x - y).abs() < 1e-4, "{:?}", a.contract),
+            (a, b) => assert_eq!(a, b),
+        }
+    }
+
+    // one iteration is not enough from a poor guess
+    let report = implied_volatility_with(
+        OptionKind::Call,
+        black_price(OptionKind::Call, 100.0, 130.0, 0.6, 0.1, 1.0),
+        100.0,
+        130.0,
+        0.1,
+        1.0,
+        &SolverConfig {
+            max_iterations: 1,
+            ..SolverConfig::default()
+        },
+    );
+    assert!(!report.converged);
+    assert_eq!(report.iterations, 1);
+    assert!(report.error > 0.0);
+}
+
+#[test]
+fn test_normal_volatility_analytics() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec::default();
+    let chain = Chain::new(&generate_chain(&spec, now, 1));
+    let rates = YieldCurve::flat(spec.risk_free_rate);
+    let lognormal = chain.analytics(spec.spot, &rates, now);
+    let normal = chain
+        .with_volatility_model(VolatilityModel::Normal)
+        .analytics(spec.spot, &rates, now);
+
+    let expiry = &normal.expiries[0];
+    let atm = expiry
+        .contracts
+        .iter()
+        .zip(lognormal.expiries[0].contracts.iter())
+        .min_by(|a, b| {
+            (a.0.moneyness - 1.0)
+                .abs()
+                .partial_cmp(&(b.0.moneyness - 1.0).abs())
+                .unwrap()
+        })
+        .unwrap();
+    // near the money a normal volatility is the lognormal one times the forward
+    let expected = atm.1.implied_volatility.unwrap() * expiry.forward_price as f64;
+    let vol = atm.0.implied_volatility.unwrap();
+    assert!((vol / expected - 1.0).abs() < 0.02, "{} {}", vol, expected);
+    assert!(atm.0.greeks.is_none());
+    // nor do they mix into the lognormal smile
+    assert!(expiry.smile().is_empty());
+    assert!(expiry.smile_marked(SmileMarking::Blended).is_empty());
+    assert!(!lognormal.expiries[0].smile().is_empty());
+}
@@ -0,0 +1,35 @@
+use options_math::approx::*;
+use options_math::math::black_price;
+use options_math::OptionKind;
+
+#[test]
+fn test_atm_approximations() {
+    let (forward, vol, t) = (100.0, 0.25, 30.0 / 365.0);
+    let call = black_price(OptionKind::Call, forward, forward, vol, t, 1.0);
+    let put = black_price(OptionKind::Put, forward, forward, vol, t, 1.0);
+
+    let straddle = atm_straddle(forward, vol, t);
+    assert!((straddle - (call + put)).abs() / straddle < 0.005);
+    assert!((0.8 * forward * vol * t.sqrt() - straddle).abs() / straddle < 0.01);
+    assert!((straddle_volatility(call + put, forward, t).unwrap() - vol).abs() < 0.002);
+    assert!((brenner_subrahmanyam(call, forward, t).unwrap() - vol).abs() < 0.002);
+    assert_eq!(brenner_subrahmanyam(call, forward, 0.0), None);
+}
+
+#[test]
+fn test_corrado_miller() {
+    let (forward, vol, t, df) = (100.0, 0.3, 0.25, 0.99);
+    for strike in [90.0, 95.0, 100.0, 105.0, 110.0].iter() {
+        for kind in [OptionKind::Call, OptionKind::Put].iter() {
+            let price = black_price(*kind, forward, *strike, vol, t, df);
+            let approx = corrado_miller(*kind, price, forward, *strike, t, df).unwrap();
+            assert!(
+                (approx - vol).abs() < 0.005,
+                "{} {:?} {}",
+                strike,
+                kind,
+                approx
+            );
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use options_math::asian::turnbull_wakeman_price;
+use options_math::montecarlo::*;
+use options_math::*;
+
+#[test]
+fn test_turnbull_wakeman_matches_simulation() {
+    let (r, q, vol, t) = (0.05, 0.02, 0.25, 1.0);
+    let mc = MonteCarlo::new(50_000, 12, 3);
+    for kind in [OptionKind::Call, OptionKind::Put].iter() {
+        for strike in [9_000, 10_000, 11_000].iter() {
+            let asian = ArithmeticAsian::new(*kind, *strike);
+            let estimate = mc.price(&asian, 10_000, r, q, vol, t);
+            let approximate = asian.turnbull_wakeman_price(10_000, r, q, vol, t, 12) as f64;
+            assert!(
+                (estimate.price - approximate).abs() < 4.0 * estimate.standard_error + 3.0,
+                "{:?} {} {:?} {}",
+                kind,
+                strike,
+                estimate,
+                approximate
+            );
+        }
+    }
+}
+
+#[test]
+fn test_continuous_average() {
+    let (r, q, vol, t) = (0.05, 0.02, 0.25, 1.0);
+    for kind in [OptionKind::Call, OptionKind::Put].iter() {
+        // fixing often enough is averaging continuously
+        let continuous = turnbull_wakeman_price(*kind, 10_000, 10_000, r, q, vol, t);
+        let discrete =
+            ArithmeticAsian::new(*kind, 10_000).turnbull_wakeman_price(10_000, r, q, vol, t, 2_000);
+        assert!(
+            (continuous - discrete).abs() <= 2,
+            "{} {}",
+            continuous,
+            discrete
+        );
+
+        // and no carry is handled
+        let flat = turnbull_wakeman_price(*kind, 10_000, 10_000, 0.03, 0.03, vol, t);
+        let near = turnbull_wakeman_price(*kind, 10_000, 10_000, 0.03, 0.03 + 1e-6, vol, t);
+        assert!((flat - near).abs() <= 1, "{} {}", flat, near);
+        let discrete = ArithmeticAsian::new(*kind, 10_000)
+            .turnbull_wakeman_price(10_000, 0.03, 0.03, vol, t, 2_000);
+        assert!((flat - discrete).abs() <= 2, "{} {}", flat, discrete);
+    }
+
+    // at expiration the average is the spot
+    assert_eq!(
+        turnbull_wakeman_price(OptionKind::Call, 10_500, 10_000, r, q, vol, 0.0),
+        500
+    );
+}
@@ -0,0 +1,139 @@
+use chrono::prelude::*;
+use options_math::barrier::*;
+use options_math::montecarlo::*;
+use options_math::pricing::black_scholes_price;
+use options_math::*;
+
+const SPOT: Cents = 10_000;
+
+fn price(kind: OptionKind, barrier: &BarrierSpec, strike: Cents) -> Cents {
+    return barrier_price(kind, barrier, SPOT, strike, 0.05, 0.02, 0.25, 0.5);
+}
+
+#[test]
+fn test_in_out_parity() {
+    for kind in [OptionKind::Call, OptionKind::Put].iter() {
+        let vanilla = black_scholes_price(*kind, SPOT, 10_000, 0.05, 0.02, 0.25, 0.5);
+        for (direction, level) in [
+            (BarrierDirection::Down, 9_000),
+            (BarrierDirection::Up, 11_000),
+        ]
+        .iter()
+        {
+            for strike in [9_500, 10_000, 10_500, 8_500, 11_500].iter() {
+                let vanilla = black_scholes_price(*kind, SPOT, *strike, 0.05, 0.02, 0.25, 0.5);
+                let knock_in = price(
+                    *kind,
+                    &BarrierSpec::new(*direction, Knock::In, *level),
+                    *strike,
+                );
+                let knock_out = price(
+                    *kind,
+                    &BarrierSpec::new(*direction, Knock::Out, *level),
+                    *strike,
+                );
+                assert!(knock_in >= 0 && knock_out >= 0);
+                assert!(
+                    (knock_in + knock_out - vanilla).abs() <= 1,
+                    "{:?} {:?} {} {} {} {}",
+                    kind,
+                    direction,
+                    strike,
+                    knock_in,
+                    knock_out,
+                    vanilla
+                );
+            }
+        }
+
+        // a barrier out of reach never knocks
+        let far = BarrierSpec::new(BarrierDirection::Down, Knock::Out, 100);
+        assert_eq!(price(*kind, &far, 10_000), vanilla);
+        let far = BarrierSpec::new(BarrierDirection::Up, Knock::In, 1_000_000);
+        assert_eq!(price(*kind, &far, 10_000), 0);
+    }
+
+    // already through the barrier
+    let touched = BarrierSpec::new(BarrierDirection::Down, Knock::In, 10_000);
+    assert_eq!(
+        price(OptionKind::Put, &touched, 10_000),
+        black_scholes_price(OptionKind::Put, SPOT, 10_000, 0.05, 0.02, 0.25, 0.5)
+    );
+    let touched = BarrierSpec::new(BarrierDirection::Up, Knock::Out, 9_000).with_rebate(250);
+    assert_eq!(price(OptionKind::Call, &touched, 10_000), 250);
+}
+
+#[test]
+fn test_matches_simulation() {
+    let (r, q, vol, t, steps) = (0.05, 0.02, 0.25, 0.5, 250);
+    let mc = MonteCarlo::new(40_000, steps, 11);
+    // discrete monitoring is continuous monitoring of a barrier shifted away from the spot
+    let shift = (0.5826 * vol * (t / steps as f64).sqrt()).exp();
+    let cases = [
+        (
+            OptionKind::Call,
+            BarrierDirection::Down,
+            Knock::Out,
+            9_000,
+            10_000,
+        ),
+        (
+            OptionKind::Call,
+            BarrierDirection::Up,
+            Knock::Out,
+            12_000,
+            10_000,
+        ),
+        (
+            OptionKind::Put,
+            BarrierDirection::Down,
+            Knock::In,
+            9_000,
+            9_500,
+        ),
+        (
+            OptionKind::Put,
+            BarrierDirection::Up,
+            Knock::In,
+            11_000,
+            10_500,
+        ),
+    ];
+    for (kind, direction, knock, level, strike) in cases.iter() {
+        let barrier = BarrierSpec::new(*direction, *knock, *level);
+        let payoff = |path: &[f64]| -> f64 {
+            let touched = path.iter().any(|p| barrier.is_touched(*p));
+            if touched != (*knock == Knock::In) {
+                return 0.0;
+            }
+            return Vanilla::new(*kind, *strike).payoff(path);
+        };
+        let estimate = mc.price(&payoff, SPOT, r, q, vol, t);
+        let shifted = match direction {
+            BarrierDirection::Down => *level as f64 / shift,
+            BarrierDirection::Up => *level as f64 * shift,
+        };
+        let shifted = BarrierSpec::new(*direction, *knock, shifted.round() as Cents);
+        let exact = barrier_price(*kind, &shifted, SPOT, *strike, r, q, vol, t) as f64;
+        assert!(
+            (estimate.price - exact).abs() < 4.0 * estimate.standard_error + 1.0,
+            "{:?} {:?} {:?} {:?} {}",
+            kind,
+            direction,
+            knock,
+            estimate,
+            exact
+        );
+    }
+
+    let expires_at = NaiveDate::from_ymd_opt(2021, 7, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let now = expires_at - chrono::Duration::days(365);
+    let barrier = BarrierSpec::new(BarrierDirection::Down, Knock::Out, 8_000);
+    let option = BarrierOption::new(OptionKind::Call, 10_000, expires_at, barrier);
+    assert_eq!(
+        option.price(SPOT, r, q, vol, now),
+        barrier_price(OptionKind::Call, &barrier, SPOT, 10_000, r, q, vol, 1.0)
+    );
+}
@@ -0,0 +1,65 @@
+use options_math::basket::*;
+use options_math::montecarlo::*;
+use options_math::pricing::black_scholes_price;
+use options_math::*;
+
+fn correlation(rho: f64) -> Vec<Vec<f64>> {
+    return vec![vec![1.0, rho], vec![rho, 1.0]];
+}
+
+#[test]
+fn test_single_asset_matches_black_scholes() {
+    let mc = MonteCarlo::new(50_000, 1, 7);
+    let asset = Asset::new(10_000, 0.01, 0.2);
+    let basket = Basket::new(OptionKind::Call, vec![1.0], 10_000);
+    let estimate = mc
+        .price_multi_asset(&basket, &[asset], &[vec![1.0]], 0.05, 1.0)
+        .unwrap();
+    let exact = black_scholes_price(OptionKind::Call, 10_000, 10_000, 0.05, 0.01, 0.2, 1.0) as f64;
+    assert!(
+        (estimate.price - exact).abs() < 4.0 * estimate.standard_error,
+        "{:?} {}",
+        estimate,
+        exact
+    );
+}
+
+#[test]
+fn test_correlated_payoffs() {
+    let mc = MonteCarlo::new(20_000, 1, 5);
+    let assets = [Asset::new(10_000, 0.0, 0.2), Asset::new(5_000, 0.02, 0.3)];
+    let (r, t) = (0.03, 1.0);
+
+    // pathwise, the best and the worst are the two assets between them
+    let best = BestOf::new(OptionKind::Call, 1.0, 10_000);
+    let worst = WorstOf::new(OptionKind::Call, 1.0, 10_000);
+    let single = |i: usize| {
+        return move |paths: &[Vec<f64>]| -> f64 {
+            let path = &paths[i];
+            return 10_000.0 * (path[path.len() - 1] / path[0] - 1.0).max(0.0);
+        };
+    };
+    let price = |payoff: &dyn MultiAssetPayoff, rho: f64| -> f64 {
+        return mc
+            .price_multi_asset(payoff, &assets, &correlation(rho), r, t)
+            .unwrap()
+            .price;
+    };
+    let both = price(&best, 0.5) + price(&worst, 0.5);
+    let separately = price(&single(0), 0.5) + price(&single(1), 0.5);
+    assert!((both - separately).abs() < 1e-6, "{} {}", both, separately);
+
+    // diversification makes the worst-of put dearer and the basket cheaper
+    let worst_put = WorstOf::new(OptionKind::Put, 1.0, 10_000);
+    assert!(price(&worst_put, 0.0) > price(&worst_put, 0.9));
+    let basket = Basket::new(OptionKind::Call, vec![1.0, 2.0], 20_000);
+    assert!(price(&basket, 0.0) < price(&basket, 0.9));
+
+    // the matrix must fit the assets and be positive definite
+    assert!(mc
+        .price_multi_asset(&basket, &assets, &[vec![1.0]], r, t)
+        .is_none());
+    assert!(mc
+        .price_multi_asset(&basket, &assets, &correlation(1.5), r, t)
+        .is_none());
+}
@@ -0,0 +1,110 @@
+use chrono::prelude::*;
+use options_math::benchmark::*;
+use options_math::chain::Chain;
+use options_math::math::Rng;
+use options_math::rates::YieldCurve;
+use options_math::synthetic::*;
+use options_math::OptionKind;
+
+fn start() -> NaiveDateTime {
+    return NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+}
+
+/// A chain each day at the given spots, with expiries every 28 days from the
+/// start.
+fn chains(spots: &[i64]) -> Vec<(NaiveDateTime, i64, Chain)> {
+    return spots
+        .iter()
+        .enumerate()
+        .map(|(day, spot)| {
+            let now = start() + chrono::Duration::days(day as i64);
+            let expiries = (1..=6)
+                .map(|m| 28 * m - day as i64)
+                .filter(|days| *days > 0)
+                .map(|days| SyntheticExpiry::new(days, 0.2))
+                .collect();
+            let spec = SurfaceSpec {
+                spot: *spot,
+                expiries,
+                strike_interval: 100,
+                ..SurfaceSpec::default()
+            };
+            return (now, *spot, Chain::new(&generate_chain(&spec, now, 1)));
+        })
+        .collect();
+}
+
+fn snapshots(chains: &[(NaiveDateTime, i64, Chain)]) -> Vec<MarketSnapshot<'_>> {
+    return chains
+        .iter()
+        .map(|(at, spot, chain)| MarketSnapshot::new(*at, *spot, chain))
+        .collect();
+}
+
+#[test]
+fn test_option_write_indices() {
+    let rates = YieldCurve::flat(0.01);
+
+    // in a flat market the writer keeps the premium
+    let flat = chains(&[300_000; 60]);
+    let flat = snapshots(&flat);
+    let bxm = OptionWrite::buy_write().index(&flat, &rates);
+    assert_eq!(bxm.len(), 60);
+    assert_eq!(bxm[0].value, BASE_VALUE);
+    let first = bxm[0].position.unwrap();
+    assert_eq!(first.kind(), OptionKind::Call);
+    assert!(first.strike() >= 300_000);
+    assert!(bxm[59].value > 102.0, "{:?}", bxm[59]);
+    // rolled into the next month at the first expiration
+    assert!(bxm[28].position.unwrap().expires_at() > first.expires_at());
+    assert_eq!(bxm[27].position.unwrap(), first);
+
+    let put = OptionWrite::put_write().index(&flat, &rates);
+    assert_eq!(put[0].position.unwrap().kind(), OptionKind::Put);
+    assert!(put[59].value > 102.0, "{:?}", put[59]);
+
+    // a rally is capped at the strike
+    let rally: Vec<i64> = (0..29).map(|day| 300_000 + 1_500 * day).collect();
+    let rally = chains(&rally);
+    let rally = snapshots(&rally);
+    let bxm = OptionWrite::buy_write().index(&rally, &rates);
+    let underlying = rally[28].spot as f64 / rally[0].spot as f64;
+    assert!(
+        bxm[28].value / BASE_VALUE < underlying - 0.05,
+        "{:?}",
+        bxm[28]
+    );
+    assert!(bxm[28].value > BASE_VALUE);
+}
+
+#[test]
+fn test_vol_target() {
+    let mut rng = Rng::new(3);
+    let mut value = 100.0;
+    let mut series = vec![];
+    for day in 0..500 {
+        // 40% annualized volatility
+        value *= (0.4 / 252f64.sqrt() * rng.next_normal()).exp();
+        series.push((start() + chrono::Duration::days(day), value));
+    }
+    let overlay = vol_target(&series, 0.1, 60, 1.5, &YieldCurve::flat(0.0));
+    assert_eq!(overlay.len(), 500 - 60);
+    assert_eq!(overlay[0].value, BASE_VALUE);
+    assert_eq!(overlay[0].at, series[60].0);
+    for point in overlay.iter() {
+        assert!(point.exposure > 0.15 && point.exposure < 0.4, "{:?}", point);
+    }
+    // a quarter of the exposure moves a quarter as much
+    let (a, b) = (overlay[100], overlay[101]);
+    let underlying = series[161].1 / series[160].1 - 1.0;
+    assert!((b.value / a.value - 1.0 - a.exposure * underlying).abs() < 1e-12);
+
+    // without any movement it is leveraged to the cap
+    let quiet: Vec<(NaiveDateTime, f64)> = series.iter().map(|(at, _)| (*at, 100.0)).collect();
+    let overlay = vol_target(&quiet, 0.1, 20, 1.5, &YieldCurve::flat(0.02));
+    assert!(overlay.iter().all(|p| p.exposure == 1.5));
+    // and pays for the leverage
+    assert!(overlay.last().unwrap().value < BASE_VALUE);
+}
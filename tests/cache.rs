@@ -0,0 +1,50 @@
+use chrono::prelude::*;
+use options_math::cache::ContributionCache;
+use options_math::synthetic::*;
+use options_math::*;
+
+#[test]
+fn test_cached_variance_matches_and_reuses_contributions() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec::default();
+    let mut contracts = generate_chain(&spec, now, 1);
+    let expires_at = contracts[0].expires_at();
+    let r = spec.risk_free_rate;
+
+    let mut cache = ContributionCache::new();
+    let expiry = &group_options_by_expiry(&contracts)[&expires_at];
+    assert_eq!(
+        expiry.variance_cached(r, now, &mut cache),
+        expiry.variance(r, now)
+    );
+    let first_misses = cache.misses();
+    assert!(first_misses > 0);
+    assert_eq!(cache.hits(), 0);
+
+    // one second later, with nothing changed
+    let later = now + chrono::Duration::seconds(1);
+    let v = expiry.variance_cached(r, later, &mut cache);
+    assert_eq!(v, expiry.variance(r, later));
+    assert_eq!(cache.hits(), first_misses);
+    assert_eq!(cache.misses(), first_misses);
+
+    // a single far out-of-the-money quote changes
+    let i = contracts
+        .iter()
+        .position(|o| o.expires_at() == expires_at && o.kind() == OptionKind::Put && o.bid() > 0)
+        .unwrap();
+    contracts[i] = contracts[i].with_quote(contracts[i].bid() + 5, contracts[i].ask() + 5);
+    assert_eq!(contracts[i].revision(), 1);
+    let expiry = &group_options_by_expiry(&contracts)[&expires_at];
+    assert_eq!(
+        expiry.variance_cached(r, later, &mut cache),
+        expiry.variance(r, later)
+    );
+    assert_eq!(cache.misses(), first_misses + 1);
+
+    cache.evict_expired(expires_at);
+    expiry.variance_cached(r, later, &mut cache);
+    assert_eq!(cache.misses(), 2 * first_misses + 1);
+}
@@ -0,0 +1,41 @@
+use chrono::prelude::*;
+use options_math::calendar::*;
+use options_math::chain::Chain;
+use options_math::rates::YieldCurve;
+use options_math::strategy::*;
+use options_math::synthetic::*;
+use options_math::*;
+
+#[test]
+fn test_long_calendar_buys_forward_volatility() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec {
+        expiries: vec![SyntheticExpiry::new(30, 0.2), SyntheticExpiry::new(90, 0.2)],
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&spec, now, 1));
+    let analytics = chain.analytics(spec.spot, &YieldCurve::flat(spec.risk_free_rate), now);
+    let atm_call = |i: usize| -> OptionContract {
+        *chain.expiries()[i]
+            .calls()
+            .iter()
+            .find(|o| o.strike() == 300_000)
+            .unwrap()
+    };
+    let calendar = Strategy::new(vec![Leg::new(atm_call(0), -1), Leg::new(atm_call(1), 1)]);
+
+    let steepen = TermStructureShift::new(0.0, 0.05, 30.0);
+    let flatten = TermStructureShift::new(0.0, -0.05, 30.0);
+    let result = calendar_analytics(&calendar, &analytics, &[steepen, flatten]).unwrap();
+
+    assert_eq!(result.vega_by_expiry.len(), 2);
+    assert!(result.vega_by_expiry[0].1 < 0.0);
+    assert!(result.vega_by_expiry[1].1 > 0.0);
+    assert!(result.net_vega > 0.0);
+    assert!(result.forward_vega > 0.0);
+    assert!((result.forward_volatility.unwrap() - 0.2).abs() < 0.01);
+    assert!(result.scenarios[0].pnl > 0.0);
+    assert!(result.scenarios[1].pnl < 0.0);
+}
@@ -0,0 +1,138 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::*;
+
+fn at(y: i32, m: u32, d: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(y, m, d)
+        .and_then(|d| d.and_hms_opt(16, 15, 0))
+        .unwrap()
+}
+
+#[test]
+fn test_dte_filters() {
+    let now = NaiveDate::from_ymd_opt(2023, 3, 1)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let expirations = [
+        at(2023, 3, 3),
+        at(2023, 3, 17),
+        at(2023, 3, 24),
+        at(2023, 4, 6),
+        at(2023, 4, 21),
+        at(2023, 5, 19),
+    ];
+    let contracts: Vec<OptionContract> = expirations
+        .iter()
+        .map(|e| OptionContract::new(*e, 400_000, OptionKind::Call, 1_000, 1_010))
+        .collect();
+    let chain = Chain::new(&contracts);
+
+    let dates = |expiries: &[OptionsByExpiryDate]| -> Vec<NaiveDateTime> {
+        expiries.iter().map(|e| e.expires_at()).collect()
+    };
+    assert_eq!(
+        dates(chain.expiries_between_dte(16, 51, now)),
+        vec![
+            at(2023, 3, 17),
+            at(2023, 3, 24),
+            at(2023, 4, 6),
+            at(2023, 4, 21)
+        ]
+    );
+    assert!(chain.expiries_between_dte(60, 70, now).is_empty());
+    assert!(chain.expiries_between_dte(30, 20, now).is_empty());
+
+    let monthlies: Vec<NaiveDateTime> = chain.monthlies_only().map(|e| e.expires_at()).collect();
+    assert_eq!(
+        monthlies,
+        vec![at(2023, 3, 17), at(2023, 4, 21), at(2023, 5, 19)]
+    );
+    assert_eq!(
+        chain.front_month(now).map(|e| e.expires_at()),
+        Some(at(2023, 3, 17))
+    );
+    assert_eq!(
+        chain.front_month(at(2023, 3, 17)).map(|e| e.expires_at()),
+        Some(at(2023, 4, 21))
+    );
+}
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_shared_chain() {
+    use options_math::chain::{QuoteUpdate, SharedChain};
+    use options_math::synthetic::*;
+    use std::sync::Arc;
+
+    assert_send_sync::<Chain>();
+    assert_send_sync::<SharedChain>();
+
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec::default();
+    let contracts = generate_chain(&spec, now, 1);
+    let shared = Arc::new(SharedChain::new(Chain::new(&contracts)));
+    let before = shared.snapshot();
+    let expiry = &before.expiries()[0];
+    let put = *expiry
+        .puts()
+        .iter()
+        .find(|o| o.bid() > 0 && o.strike() < spec.spot)
+        .unwrap();
+
+    let rate = spec.risk_free_rate;
+    let readers: Vec<std::thread::JoinHandle<()>> = (0..4)
+        .map(|_| {
+            let shared = Arc::clone(&shared);
+            std::thread::spawn(move || {
+                for _ in 0..50 {
+                    let chain = shared.snapshot();
+                    let variance = chain.expiries()[0].variance(rate, now);
+                    assert!(variance > 0.0);
+                }
+            })
+        })
+        .collect();
+    for i in 1..=50 {
+        shared.apply(&[QuoteUpdate::new(
+            put.expires_at(),
+            put.strike(),
+            OptionKind::Put,
+            put.bid() + i,
+            put.ask() + i,
+        )]);
+    }
+    for reader in readers {
+        reader.join().unwrap();
+    }
+
+    let after = shared.snapshot();
+    let updated = after.expiries()[0]
+        .puts()
+        .iter()
+        .find(|o| o.strike() == put.strike())
+        .copied()
+        .unwrap();
+    assert_eq!(updated.revision(), 50);
+    assert_eq!(updated.bid(), put.bid() + 50);
+    // earlier snapshots are unaffected
+    assert!(before.expiries()[0]
+        .puts()
+        .iter()
+        .all(|o| o.revision() == 0));
+
+    // a new listing creates its expiry
+    let listed_at = at(2020, 6, 19);
+    shared.apply(&[QuoteUpdate::new(
+        listed_at,
+        300_000,
+        OptionKind::Call,
+        1_000,
+        1_050,
+    )]);
+    let chain = shared.snapshot();
+    assert_eq!(chain.expiries().last().unwrap().expires_at(), listed_at);
+    assert_eq!(chain.expiries().last().unwrap().calls().len(), 1);
+}
@@ -0,0 +1,104 @@
+use chrono::prelude::*;
+use options_math::combo::*;
+use options_math::strategy::*;
+use options_math::*;
+
+fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap()
+}
+
+#[test]
+fn test_combo_quotes() {
+    let expires_at = now() + chrono::Duration::days(30);
+    let call = |strike: Cents, bid: Cents, ask: Cents| {
+        OptionContract::new(expires_at, strike, OptionKind::Call, bid, ask)
+    };
+    let put = OptionContract::new(expires_at, 10_000, OptionKind::Put, 380, 420);
+    let straddle = Strategy::new(vec![Leg::new(call(10_000, 380, 420), 1), Leg::new(put, 1)]);
+    let vertical = Strategy::new(vec![
+        Leg::new(call(10_000, 380, 420), 1),
+        Leg::new(call(10_500, 180, 220), -1),
+    ]);
+    assert_eq!(straddle.leg_market(), ComboMarket { bid: 760, ask: 840 });
+    assert_eq!(vertical.leg_market(), ComboMarket { bid: 160, ask: 240 });
+
+    let mut book = ComboBook::new();
+    assert_eq!(book.market(&straddle), None);
+    assert_eq!(straddle.premium_in(&book), straddle.premium());
+
+    // a straddle quoted put first, tighter than its legs
+    book.insert(ComboQuote::new(
+        vec![
+            ComboLeg::new(expires_at, 10_000, OptionKind::Put, 1),
+            ComboLeg::new(expires_at, 10_000, OptionKind::Call, 1),
+        ],
+        790,
+        810,
+    ));
+    assert_eq!(
+        book.market(&straddle),
+        Some(ComboMarket { bid: 790, ask: 810 })
+    );
+    assert_eq!(straddle.premium_in(&book), 800);
+    // ten lots, and selling it
+    let ten = Strategy::new(
+        straddle
+            .legs
+            .iter()
+            .map(|l| Leg::new(l.contract, 10))
+            .collect(),
+    );
+    assert_eq!(
+        book.market(&ten),
+        Some(ComboMarket {
+            bid: 7_900,
+            ask: 8_100
+        })
+    );
+    let short = Strategy::new(
+        straddle
+            .legs
+            .iter()
+            .map(|l| Leg::new(l.contract, -1))
+            .collect(),
+    );
+    assert_eq!(
+        book.market(&short),
+        Some(ComboMarket {
+            bid: -810,
+            ask: -790
+        })
+    );
+    assert_eq!(book.market(&vertical), None);
+
+    // a vertical quoted as the short side, as a credit
+    let stamped = |at| {
+        return ComboQuote::new(
+            vec![
+                ComboLeg::new(expires_at, 10_000, OptionKind::Call, -1),
+                ComboLeg::new(expires_at, 10_500, OptionKind::Call, 1),
+            ],
+            -215,
+            -195,
+        )
+        .with_quoted_at(at);
+    };
+    book.insert(stamped(now()));
+    assert_eq!(
+        book.market(&vertical),
+        Some(ComboMarket { bid: 195, ask: 215 })
+    );
+    assert_eq!(book.len(), 2);
+
+    // later quotes replace earlier ones, not the other way around
+    let later = ComboQuote {
+        bid: -212,
+        ..stamped(now() + chrono::Duration::minutes(1))
+    };
+    book.insert(later);
+    book.insert(stamped(now()));
+    assert_eq!(book.market(&vertical).unwrap().width(), 17);
+    assert_eq!(book.len(), 2);
+}
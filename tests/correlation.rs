@@ -0,0 +1,111 @@
+use chrono::prelude::*;
+use options_math::correlation::*;
+use options_math::math::Rng;
+use options_math::returns::LogReturn;
+
+/// Index returns, and returns of an asset with the given beta plus independent noise.
+fn simulate(n: usize, beta: f64) -> (Vec<LogReturn>, Vec<LogReturn>) {
+    let mut rng = Rng::new(5);
+    let start = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+    let (mut asset, mut index) = (vec![], vec![]);
+    for i in 0..n {
+        let date = start + chrono::Duration::days(i as i64);
+        let market = rng.next_normal() * 0.01;
+        let idiosyncratic = rng.next_normal() * 0.01;
+        index.push(LogReturn {
+            date,
+            value: market,
+            periods: 1,
+        });
+        asset.push(LogReturn {
+            date,
+            value: beta * market + idiosyncratic,
+            periods: 1,
+        });
+    }
+    (asset, index)
+}
+
+#[test]
+fn test_realized_beta_and_correlation() {
+    let (asset, index) = simulate(5_000, 1.5);
+    let c = realized_comovement(&asset, &index).unwrap();
+    assert!((c.beta - 1.5).abs() < 0.05, "{:?}", c);
+    // correlation = 1.5 / sqrt(1.5^2 + 1)
+    assert!((c.correlation - 0.832).abs() < 0.02, "{:?}", c);
+    assert!(
+        (implied_beta(c.correlation, c.asset_volatility, c.index_volatility) - c.beta).abs() < 1e-9
+    );
+
+    let rolling = rolling_comovement(&asset, &index, 63);
+    assert_eq!(rolling.len(), 5_000 - 62);
+    assert_eq!(rolling[0].0, asset[62].date);
+
+    let ewma = ewma_comovement(&asset, &index, 0.97);
+    assert_eq!(ewma.len(), 5_000);
+    assert!((ewma.last().unwrap().1.beta - 1.5).abs() < 0.5);
+
+    // only common dates are used
+    assert_eq!(align(&asset[10..], &index[..20]).len(), 10);
+}
+
+#[test]
+fn test_implied_correlation_term_structure() {
+    use options_math::chain::Chain;
+    use options_math::rates::YieldCurve;
+    use options_math::synthetic::*;
+
+    // two equally weighted stocks at 30 and 40 vol, with the index priced at a correlation of
+    // 0.6 at 30 days and 0.3 at 90 days
+    assert_eq!(implied_correlation(0.3, &[(1.0, 0.3)]), None);
+    let index_vol = |rho: f64| -> f64 {
+        let own = 0.15f64.powi(2) + 0.2f64.powi(2);
+        return (own + rho * (0.35f64.powi(2) - own)).sqrt();
+    };
+    assert!(
+        (implied_correlation(index_vol(0.6), &[(0.5, 0.3), (0.5, 0.4)]).unwrap() - 0.6).abs()
+            < 1e-12
+    );
+
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let analytics = |vols: (f64, f64)| {
+        let spec = SurfaceSpec {
+            expiries: vec![
+                SyntheticExpiry::new(30, vols.0),
+                SyntheticExpiry::new(90, vols.1),
+            ],
+            ..SurfaceSpec::default()
+        };
+        return Chain::new(&generate_chain(&spec, now, 1)).analytics(
+            spec.spot,
+            &YieldCurve::flat(spec.risk_free_rate),
+            now,
+        );
+    };
+    let index = analytics((index_vol(0.6), index_vol(0.3)));
+    let (first, second) = (analytics((0.3, 0.3)), analytics((0.4, 0.4)));
+
+    let term = implied_correlation_term_structure(
+        &index,
+        &[(0.5, &first), (0.5, &second)],
+        &[30.0, 60.0, 90.0],
+    );
+    assert_eq!(term.len(), 3);
+    assert!((term[0].correlation - 0.6).abs() < 0.03, "{:?}", term);
+    assert!((term[2].correlation - 0.3).abs() < 0.03, "{:?}", term);
+    assert!(term[1].correlation < term[0].correlation && term[1].correlation > term[2].correlation);
+    assert!(
+        (term[0].constituent_volatility - 0.35).abs() < 0.01,
+        "{:?}",
+        term
+    );
+
+    // a constituent without a surface leaves nothing to compare
+    let empty = Chain::new(&[]).analytics(300_000, &YieldCurve::flat(0.01), now);
+    assert!(
+        implied_correlation_term_structure(&index, &[(0.5, &first), (0.5, &empty)], &[30.0])
+            .is_empty()
+    );
+}
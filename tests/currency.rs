@@ -0,0 +1,103 @@
+use options_math::currency::*;
+use options_math::greeks::Greeks;
+
+#[test]
+fn test_aggregate_in_reporting_currency() {
+    let mut fx = FxTable::new();
+    fx.set(Currency::EUR, Currency::USD, 1.1);
+    assert_eq!(Currency::new("eur"), Some(Currency::EUR));
+    assert_eq!(Currency::new("EURO"), None);
+
+    let pnl = [
+        Money::new(1_000.0, Currency::USD),
+        Money::new(2_000.0, Currency::EUR),
+    ];
+    let usd = total(&pnl, Currency::USD, &fx).unwrap();
+    assert!((usd.amount - 3_200.0).abs() < 1e-9);
+    let eur = total(&pnl, Currency::EUR, &fx).unwrap();
+    assert!((eur.amount - 2_000.0 - 1_000.0 / 1.1).abs() < 1e-9);
+    assert_eq!(total(&pnl, Currency::JPY, &fx), None);
+
+    let greeks = [
+        (
+            Greeks {
+                delta: 0.5,
+                vega: 10.0,
+                ..Greeks::default()
+            },
+            Money::new(10_000.0, Currency::USD),
+        ),
+        (
+            Greeks {
+                delta: -0.25,
+                vega: 20.0,
+                ..Greeks::default()
+            },
+            Money::new(20_000.0, Currency::EUR),
+        ),
+    ];
+    let usd = total_greeks(&greeks, Currency::USD, &fx).unwrap();
+    // $50 of cash delta against €50
+    assert!((usd.delta + 5.0).abs() < 1e-9);
+    assert!((usd.vega - 32.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_convert_between_minor_units() {
+    let mut fx = FxTable::new();
+    fx.set(Currency::USD, Currency::JPY, 150.0);
+    assert_eq!(Currency::JPY.minor_unit_exponent(), 0);
+    assert_eq!(Currency::USD.minor_unit_exponent(), 2);
+
+    // $10.00 is ¥1,500
+    let yen = Money::new(1_000.0, Currency::USD)
+        .convert(Currency::JPY, &fx)
+        .unwrap();
+    assert!((yen.amount - 1_500.0).abs() < 1e-9);
+    let cents = yen.convert(Currency::USD, &fx).unwrap();
+    assert!((cents.amount - 1_000.0).abs() < 1e-9);
+
+    let pnl = [
+        Money::new(1_000.0, Currency::USD),
+        Money::new(3_000.0, Currency::JPY),
+    ];
+    let usd = total(&pnl, Currency::USD, &fx).unwrap();
+    assert!((usd.amount - 3_000.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_aggregate_greeks_between_minor_units() {
+    let mut fx = FxTable::new();
+    fx.set(Currency::USD, Currency::JPY, 150.0);
+
+    // a $100 underlying, and a ¥30,000 one whose Greeks are in hundreds of yen
+    let greeks = [
+        (
+            Greeks {
+                delta: 0.5,
+                gamma: 0.01,
+                vega: 10.0,
+                ..Greeks::default()
+            },
+            Money::new(10_000.0, Currency::USD),
+        ),
+        (
+            Greeks {
+                delta: -0.5,
+                vega: 20.0,
+                ..Greeks::default()
+            },
+            Money::new(30_000.0, Currency::JPY),
+        ),
+    ];
+    let usd = total_greeks(&greeks, Currency::USD, &fx).unwrap();
+    // $50 of cash delta against ¥15,000, which is $100
+    assert!((usd.delta + 50.0).abs() < 1e-9);
+    assert!((usd.gamma - 100.0).abs() < 1e-9);
+    // $10 and ¥2,000 of vega
+    assert!((usd.vega - 10.0 - 2_000.0 / 150.0).abs() < 1e-9);
+
+    let jpy = total_greeks(&greeks, Currency::JPY, &fx).unwrap();
+    assert!((jpy.vega - 35.0).abs() < 1e-9);
+    assert!((jpy.delta + 75.0).abs() < 1e-9);
+}
@@ -0,0 +1,34 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::density::RiskNeutralDensity;
+use options_math::math::Rng;
+use options_math::rates::YieldCurve;
+use options_math::synthetic::*;
+
+#[test]
+fn test_samples_match_lognormal_for_flat_surface() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec {
+        expiries: vec![SyntheticExpiry::new(60, 0.25)],
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&spec, now, 1));
+    let analytics = chain.analytics(spec.spot, &YieldCurve::flat(spec.risk_free_rate), now);
+    let density = RiskNeutralDensity::from_expiry(&analytics.expiries[0]).unwrap();
+
+    let forward = 100.0 * spec.forward(&spec.expiries[0]);
+    let std_dev = 0.25 * spec.time_to_expiration(&spec.expiries[0]).sqrt();
+    assert!((density.cdf(forward) - 0.5).abs() < 0.05);
+
+    let samples = density.sample(20000, &mut Rng::new(42));
+    let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+    assert!((mean / forward - 1.0).abs() < 0.005, "mean {}", mean);
+
+    let logs: Vec<f64> = samples.iter().map(|s| (s / forward).ln()).collect();
+    let log_mean = logs.iter().sum::<f64>() / logs.len() as f64;
+    let log_std =
+        (logs.iter().map(|l| (l - log_mean).powi(2)).sum::<f64>() / logs.len() as f64).sqrt();
+    assert!((log_std / std_dev - 1.0).abs() < 0.05, "std {}", log_std);
+}
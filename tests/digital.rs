@@ -0,0 +1,121 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::digital::*;
+use options_math::pricing::black_scholes_price;
+use options_math::synthetic::*;
+use options_math::*;
+
+const SPOT: Cents = 10_000;
+
+#[test]
+fn test_digital_prices() {
+    let (r, q, vol, t) = (0.05, 0.02, 0.25, 0.5);
+    for strike in [8_000, 10_000, 12_500].iter() {
+        let price =
+            |kind, payout| DigitalOption::new(kind, *strike, payout).price(SPOT, r, q, vol, t);
+
+        // one of the call and the put pays
+        let cash = DigitalPayout::Cash(100);
+        let total = price(OptionKind::Call, cash) + price(OptionKind::Put, cash);
+        assert!((total - 100.0 * (-r * t).exp()).abs() < 1e-9);
+        let total = price(OptionKind::Call, DigitalPayout::Asset)
+            + price(OptionKind::Put, DigitalPayout::Asset);
+        assert!((total - SPOT as f64 * (-q * t).exp()).abs() < 1e-9);
+
+        // a vanilla call is long the asset and short the strike
+        let vanilla = price(OptionKind::Call, DigitalPayout::Asset)
+            - price(OptionKind::Call, DigitalPayout::Cash(*strike));
+        let exact = black_scholes_price(OptionKind::Call, SPOT, *strike, r, q, vol, t) as f64;
+        assert!((vanilla - exact).abs() <= 0.5, "{} {}", vanilla, exact);
+    }
+
+    let digital = DigitalOption::new(OptionKind::Call, 9_000, DigitalPayout::Cash(100));
+    assert_eq!(digital.price(SPOT, r, q, vol, 0.0), 100.0);
+    assert_eq!(digital.price(8_000, r, q, vol, 0.0), 0.0);
+}
+
+#[test]
+fn test_digital_greeks() {
+    let (r, q, vol, t) = (0.05, 0.02, 0.25, 0.5);
+    let payouts = [DigitalPayout::Cash(100), DigitalPayout::Asset];
+    for kind in [OptionKind::Call, OptionKind::Put].iter() {
+        for payout in payouts.iter() {
+            let digital = DigitalOption::new(*kind, 10_500, *payout);
+            let greeks = digital.greeks(SPOT, r, q, vol, t);
+            // prices in dollars, bumped by a cent of spot and small steps of the rest
+            let dollars = |spot: Cents, r: f64, vol: f64, t: f64| -> f64 {
+                return digital.price(spot, r, q, vol, t) / 100.0;
+            };
+            let delta = (dollars(SPOT + 1, r, vol, t) - dollars(SPOT - 1, r, vol, t)) / 0.02;
+            let gamma = (dollars(SPOT + 1, r, vol, t) - 2.0 * dollars(SPOT, r, vol, t)
+                + dollars(SPOT - 1, r, vol, t))
+                / 1e-4;
+            let vega = (dollars(SPOT, r, vol + 1e-5, t) - dollars(SPOT, r, vol - 1e-5, t)) / 2e-5;
+            let rho = (dollars(SPOT, r + 1e-5, vol, t) - dollars(SPOT, r - 1e-5, vol, t)) / 2e-5;
+            let theta = (dollars(SPOT, r, vol, t - 1e-5) - dollars(SPOT, r, vol, t + 1e-5)) / 2e-5;
+            let close = |analytic: f64, numeric: f64| -> bool {
+                return (analytic - numeric).abs() < 1e-3 * (1.0 + numeric.abs());
+            };
+            assert!(
+                close(greeks.delta, delta),
+                "{:?} {:?} {}",
+                kind,
+                greeks,
+                delta
+            );
+            assert!(
+                close(greeks.gamma, gamma),
+                "{:?} {:?} {}",
+                kind,
+                greeks,
+                gamma
+            );
+            assert!(close(greeks.vega, vega), "{:?} {:?} {}", kind, greeks, vega);
+            assert!(close(greeks.rho, rho), "{:?} {:?} {}", kind, greeks, rho);
+            assert!(
+                close(greeks.theta, theta),
+                "{:?} {:?} {}",
+                kind,
+                greeks,
+                theta
+            );
+        }
+    }
+}
+
+#[test]
+fn test_replication_by_spreads() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec::default();
+    let chain = Chain::new(&generate_chain(&spec, now, 1));
+    let expiry = &chain.expiries()[1];
+    let t = expiry.time_to_expiration(now);
+    let forward = expiry.forward_price(spec.risk_free_rate, now);
+    let q = spec.risk_free_rate - (forward as f64 / spec.spot as f64).ln() / t;
+
+    for kind in [OptionKind::Call, OptionKind::Put].iter() {
+        // between listed strikes, and on one
+        for strike in [297_500, 300_000].iter() {
+            let digital = DigitalOption::new(*kind, *strike, DigitalPayout::Cash(10_000));
+            let replication = expiry.replicate_digital(&digital).unwrap();
+            assert!(replication.long.kind() == *kind && replication.short.kind() == *kind);
+            assert!(replication.long.strike() < *strike || replication.short.strike() < *strike);
+            let exact = digital.price(spec.spot, spec.risk_free_rate, q, 0.2, t);
+            assert!(
+                (replication.price / exact - 1.0).abs() < 0.03,
+                "{:?} {} {:?} {}",
+                kind,
+                strike,
+                replication,
+                exact
+            );
+        }
+    }
+
+    let asset = DigitalOption::new(OptionKind::Call, 300_000, DigitalPayout::Asset);
+    assert!(expiry.replicate_digital(&asset).is_none());
+    let beyond = DigitalOption::new(OptionKind::Call, 10_000_000, DigitalPayout::Cash(100));
+    assert!(expiry.replicate_digital(&beyond).is_none());
+}
@@ -0,0 +1,90 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::dividends::*;
+use options_math::pricing::*;
+use options_math::rates::YieldCurve;
+use options_math::*;
+
+fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2021, 1, 4)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap()
+}
+
+fn schedule() -> DividendSchedule {
+    let day = |days| now() + chrono::Duration::days(days);
+    return DividendSchedule::new(&[(day(130), 100), (day(40), 100), (day(-10), 100)]);
+}
+
+#[test]
+fn test_dividend_schedule() {
+    let schedule = schedule();
+    assert_eq!(
+        schedule.dividends()[0].0,
+        now() - chrono::Duration::days(10)
+    );
+    let expires_at = now() + chrono::Duration::days(91);
+    let pending: Vec<_> = schedule.between(now(), expires_at).collect();
+    assert_eq!(pending.len(), 1);
+
+    let (r, t) = (0.05, 91.0 / 365.0);
+    let pv = schedule.present_value(r, now(), expires_at);
+    assert!((pv - 100.0 * (-r * 40.0 / 365.0f64).exp()).abs() < 1e-9);
+    let forward = schedule.forward_price(10_000, r, now(), expires_at);
+    assert_eq!(forward, ((10_000.0 - pv) * (r * t).exp()).round() as Cents);
+    // the equivalent yield carries spot to the same forward
+    let q = schedule.equivalent_yield(10_000, r, now(), expires_at);
+    assert!((10_000.0 * ((r - q) * t).exp() - forward as f64).abs() < 0.5);
+    assert_eq!(
+        DividendSchedule::default().equivalent_yield(10_000, r, now(), expires_at),
+        0.0
+    );
+
+    // European prices agree with the equivalent yield and with the escrowed lattice
+    let call = OptionContract::new(expires_at, 10_000, OptionKind::Call, 0, 0);
+    let price = call.black_scholes_price_with_dividends(10_000, r, 0.3, &schedule, now());
+    assert!((price - call.black_scholes_price(10_000, r, q, 0.3, now())).abs() <= 1);
+    let lattice = LatticePricer::new(Lattice::Binomial, 500)
+        .with_exercise(Exercise::European)
+        .with_dividends(schedule.lattice_dividends(now(), expires_at));
+    let tree = lattice.price_contract(&call, 10_000, r, 0.3, now());
+    assert!((tree - price).abs() <= 3, "{} {}", tree, price);
+    // the dividend lowers the call
+    assert!(price < call.black_scholes_price(10_000, r, 0.0, 0.3, now()));
+}
+
+#[test]
+fn test_analytics_with_dividends() {
+    let schedule = schedule();
+    let expires_at = now() + chrono::Duration::days(91);
+    let r = 0.05;
+    let options: Vec<OptionContract> = (80..=120)
+        .step_by(5)
+        .flat_map(|strike| {
+            let strike = strike * 100;
+            return [OptionKind::Call, OptionKind::Put].map(|kind| {
+                let contract = OptionContract::new(expires_at, strike, kind, 0, 0);
+                let price =
+                    contract.black_scholes_price_with_dividends(10_000, r, 0.3, &schedule, now());
+                return contract.with_quote(price, price);
+            });
+        })
+        .collect();
+    let chain = Chain::new(&options);
+    let analytics = chain.analytics_with_dividends(10_000, &YieldCurve::flat(r), now(), &schedule);
+    let expiry = &analytics.expiries[0];
+    assert_eq!(
+        expiry.forward_price,
+        chain.expiries()[0].forward_price_with_dividends(10_000, r, now(), &schedule)
+    );
+    for contract in expiry.contracts.iter() {
+        let vol = contract.implied_volatility.unwrap();
+        assert!((vol - 0.3).abs() < 0.005, "{:?}", contract);
+    }
+    let put = expiry
+        .contracts
+        .iter()
+        .find(|c| c.contract.kind() == OptionKind::Put && c.contract.strike() == 10_000)
+        .unwrap();
+    assert!(put.greeks.unwrap().delta < 0.0);
+}
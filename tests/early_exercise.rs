@@ -0,0 +1,78 @@
+use chrono::prelude::*;
+use options_math::early_exercise::*;
+use options_math::synthetic::*;
+use options_math::*;
+
+#[test]
+fn test_puts_carry_early_exercise_premium() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec {
+        spot: 10_000,
+        risk_free_rate: 0.05,
+        expiries: vec![SyntheticExpiry::new(180, 0.25)],
+        strike_interval: 500,
+        tick: 1,
+        spread: SpreadModel::new(0, 0.0),
+        ..SurfaceSpec::default()
+    };
+    let options = generate_chain(&spec, now, 1);
+    let by_expiry = group_options_by_expiry(&options);
+    let options_for_expiry = by_expiry.values().next().unwrap();
+
+    let premiums = early_exercise_premiums(options_for_expiry, spec.spot, spec.risk_free_rate, now);
+    assert!(!premiums.is_empty());
+
+    let (calls, puts): (Vec<&EarlyExercisePremium>, Vec<&EarlyExercisePremium>) = premiums
+        .iter()
+        .partition(|p| p.contract.kind() == OptionKind::Call);
+    // without dividends, American calls are worth the same as European calls
+    for call in calls {
+        assert!(
+            call.premium.abs() < 0.02 * call.european + 2.0,
+            "{:?}",
+            call
+        );
+    }
+    let deepest_put = puts.iter().max_by_key(|p| p.contract.strike()).unwrap();
+    assert!(deepest_put.premium > 5.0, "{:?}", deepest_put);
+}
+
+#[test]
+fn test_early_assignment_candidates() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec {
+        spot: 10_000,
+        risk_free_rate: 0.05,
+        expiries: vec![SyntheticExpiry::new(180, 0.25)],
+        strike_interval: 500,
+        tick: 1,
+        spread: SpreadModel::new(0, 0.0),
+        ..SurfaceSpec::default()
+    };
+    let options = generate_chain(&spec, now, 1);
+    let by_expiry = group_options_by_expiry(&options);
+    let options_for_expiry = by_expiry.values().next().unwrap();
+
+    let candidates =
+        early_assignment_candidates(options_for_expiry, spec.spot, spec.risk_free_rate, now);
+    for candidate in candidates.iter() {
+        // only deep in the money puts, past their critical price
+        assert_eq!(candidate.contract.kind(), OptionKind::Put);
+        assert!(
+            candidate.contract.strike() > spec.spot + 1_000,
+            "{:?}",
+            candidate
+        );
+        assert!(
+            candidate.critical_price >= spec.spot as f64,
+            "{:?}",
+            candidate
+        );
+    }
+    let strikes: Vec<Cents> = candidates.iter().map(|c| c.contract.strike()).collect();
+    assert!(strikes.contains(&15_000), "{:?}", strikes);
+}
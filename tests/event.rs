@@ -0,0 +1,41 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::event::fit_binary_event;
+use options_math::math::black_price;
+use options_math::rates::YieldCurve;
+use options_math::*;
+
+#[test]
+fn test_recovers_binary_outcome() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let expires_at = now + chrono::Duration::days(10);
+    let t = 10.0 / 365.0;
+    let (p, up, down, vol) = (0.4, 11_200.0, 9_200.0, 0.15);
+
+    let mut options = vec![];
+    for strike in (7_000..=14_000).step_by(100) {
+        for kind in [OptionKind::Call, OptionKind::Put].iter() {
+            let price = p * black_price(*kind, up, strike as f64, vol, t, 1.0)
+                + (1.0 - p) * black_price(*kind, down, strike as f64, vol, t, 1.0);
+            let price = price.round() as Cents;
+            if price > 0 {
+                options.push(OptionContract::new(
+                    expires_at,
+                    strike,
+                    *kind,
+                    price,
+                    price + 2,
+                ));
+            }
+        }
+    }
+    let chain = Chain::new(&options);
+    let analytics = chain.analytics(10_000, &YieldCurve::flat(0.0), now);
+
+    let event = fit_binary_event(&analytics.expiries[0]).unwrap();
+    assert!((event.probability - p).abs() < 0.02, "{:?}", event);
+    assert!((event.up_move - 0.12).abs() < 0.01, "{:?}", event);
+    assert!((event.down_move + 0.08).abs() < 0.01, "{:?}", event);
+}
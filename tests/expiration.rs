@@ -0,0 +1,54 @@
+use chrono::prelude::*;
+use options_math::expiration::*;
+use options_math::strategy::*;
+use options_math::*;
+
+#[test]
+fn test_physical_and_cash_settlement() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 17)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let later = now + chrono::Duration::days(28);
+    let strategy = Strategy::new(vec![
+        // assigned: buys 2 shares at 100
+        Leg::new(
+            OptionContract::new(now, 10_000, OptionKind::Put, 200, 200),
+            -2,
+        ),
+        // exercised
+        Leg::new(
+            OptionContract::new(now, 9_000, OptionKind::Call, 600, 600),
+            1,
+        ),
+        // expires worthless
+        Leg::new(OptionContract::new(now, 9_000, OptionKind::Put, 50, 50), 1),
+        Leg::new(
+            OptionContract::new(later, 10_000, OptionKind::Call, 400, 400),
+            1,
+        ),
+    ]);
+
+    let physical = process_expiration(&strategy, 9_500, now);
+    assert_eq!(physical.open_legs.len(), 1);
+    let outcomes: Vec<Outcome> = physical.legs.iter().map(|l| l.outcome).collect();
+    assert_eq!(
+        outcomes,
+        vec![Outcome::Assigned, Outcome::Exercised, Outcome::Expired]
+    );
+    assert_eq!(physical.shares, 3);
+    assert_eq!(physical.cash, -29_000.0);
+    // (2 * -5.00 + 2.00 * 2) + (5.00 - 6.00) + -0.50
+    assert_eq!(physical.realized_pnl, -750.0);
+
+    let cash_settled = Strategy::new(
+        strategy
+            .legs
+            .iter()
+            .map(|l| Leg::new(l.contract.with_settlement(Settlement::Cash), l.quantity))
+            .collect(),
+    );
+    let cash = process_expiration(&cash_settled, 9_500, now);
+    assert_eq!(cash.shares, 0);
+    assert_eq!(cash.cash, -500.0);
+    assert_eq!(cash.realized_pnl, physical.realized_pnl);
+}
@@ -0,0 +1,98 @@
+use chrono::prelude::*;
+use options_math::analytics::ChainAnalytics;
+use options_math::chain::Chain;
+use options_math::explain::*;
+use options_math::rates::YieldCurve;
+use options_math::strategy::{Leg, Strategy};
+use options_math::synthetic::*;
+use options_math::{OptionContract, OptionKind};
+
+fn start() -> NaiveDateTime {
+    return NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+}
+
+/// Quotes without spread `days` after the start, for expiries 30 and 60 days from the start.
+fn snapshot(days: i64, spot: i64, vols: (f64, f64), rate: f64) -> ChainAnalytics {
+    let now = start() + chrono::Duration::days(days);
+    let spec = SurfaceSpec {
+        spot,
+        risk_free_rate: rate,
+        expiries: vec![
+            SyntheticExpiry::new(30 - days, vols.0),
+            SyntheticExpiry::new(60 - days, vols.1),
+        ],
+        tick: 1,
+        spread: SpreadModel::new(0, 0.0),
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&spec, now, 1));
+    return chain.analytics(spot, &YieldCurve::flat(rate), now);
+}
+
+fn contract(
+    analytics: &ChainAnalytics,
+    expiry: usize,
+    kind: OptionKind,
+    strike: i64,
+) -> OptionContract {
+    return analytics.expiries[expiry]
+        .contracts
+        .iter()
+        .find(|c| c.contract.kind() == kind && c.contract.strike() == strike)
+        .unwrap()
+        .contract;
+}
+
+#[test]
+fn test_explain_pnl() {
+    let before = snapshot(0, 300_000, (0.2, 0.2), 0.01);
+    // long the front straddle, short the back one
+    let strategy = Strategy::new(vec![
+        Leg::new(contract(&before, 0, OptionKind::Call, 300_000), 1),
+        Leg::new(contract(&before, 0, OptionKind::Put, 300_000), 1),
+        Leg::new(contract(&before, 1, OptionKind::Call, 300_000), -1),
+    ]);
+
+    // nothing changes but a day passing: theta only
+    let after = snapshot(1, 300_000, (0.2, 0.2), 0.01);
+    let explain = explain_pnl(&strategy, &before, &after);
+    assert!(explain.total < 0.0);
+    assert_eq!(explain.delta, 0.0);
+    assert!(
+        (explain.theta - explain.total).abs() < 0.1 * explain.total.abs() + 2.0,
+        "{:?}",
+        explain
+    );
+
+    // the front volatility rises and the back falls, bucketed by expiry
+    let after = snapshot(1, 303_000, (0.23, 0.19), 0.012);
+    let explain = explain_pnl(&strategy, &before, &after);
+    assert_eq!(explain.vega.len(), 2);
+    assert_eq!(explain.vega[0].0, before.expiries[0].expires_at);
+    assert!(
+        explain.vega[0].1 > 0.0 && explain.vega[1].1 > 0.0,
+        "{:?}",
+        explain
+    );
+    assert!(explain.delta != 0.0 && explain.gamma > 0.0 && explain.rates != 0.0);
+    assert!((explain.explained() + explain.unexplained - explain.total).abs() < 1e-9);
+    assert!(
+        explain.unexplained.abs() < 0.1 * explain.total.abs(),
+        "{:?}",
+        explain
+    );
+}
+
+#[test]
+fn test_explain_expired_legs() {
+    let before = snapshot(29, 300_000, (0.2, 0.2), 0.01);
+    let call = contract(&before, 0, OptionKind::Call, 299_000);
+    let strategy = Strategy::new(vec![Leg::new(call, 2)]);
+    let after = snapshot(31, 305_000, (0.2, 0.2), 0.01);
+    let explain = explain_pnl(&strategy, &before, &after);
+    // settled at 6,000 cents intrinsic per contract
+    assert_eq!(explain.total, 2.0 * (6_000 - call.mark()) as f64);
+    assert!(explain.vega.is_empty());
+}
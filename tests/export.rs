@@ -0,0 +1,68 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::export::CSV_COLUMNS;
+use options_math::rates::YieldCurve;
+use options_math::synthetic::*;
+use options_math::OptionKind;
+
+#[test]
+fn test_write_csv() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec::default();
+    let chain = Chain::new(&generate_chain(&spec, now, 1));
+    let analytics = chain.analytics(spec.spot, &YieldCurve::flat(spec.risk_free_rate), now);
+
+    let csv = analytics.to_csv();
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    assert_eq!(
+        reader.headers().unwrap().iter().collect::<Vec<_>>(),
+        CSV_COLUMNS.to_vec()
+    );
+    let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+    let contracts: Vec<_> = analytics
+        .expiries
+        .iter()
+        .flat_map(|e| e.contracts.iter())
+        .collect();
+    assert_eq!(rows.len(), contracts.len());
+
+    for (row, c) in rows.iter().zip(contracts) {
+        assert_eq!(row.len(), CSV_COLUMNS.len());
+        assert_eq!(row[3].parse::<i64>().unwrap(), c.contract.strike());
+        match c.implied_volatility {
+            Some(vol) => {
+                assert_eq!(row[10].parse::<f64>().unwrap(), vol);
+                assert_eq!(row[11].parse::<f64>().unwrap(), c.greeks.unwrap().delta);
+            }
+            None => assert_eq!(&row[10], ""),
+        }
+        assert_eq!(row[19].parse::<bool>().unwrap(), c.liquidity.quoted);
+        let score = row[20].parse::<f64>().unwrap();
+        assert_eq!(score, c.liquidity.score());
+        assert!((0.0..=1.0).contains(&score));
+        if !c.liquidity.quoted {
+            assert_eq!(score, 0.0);
+        }
+        match c.early_exercise_premium {
+            Some(premium) => assert_eq!(row[21].parse::<i64>().unwrap(), premium),
+            None => assert_eq!(&row[21], ""),
+        }
+    }
+    // with a positive rate, puts deep enough in the money are worth exercising early
+    let put_premium = analytics
+        .expiries
+        .iter()
+        .flat_map(|e| e.contracts.iter())
+        .filter(|c| c.contract.kind() == OptionKind::Put)
+        .filter_map(|c| c.early_exercise_premium)
+        .max();
+    assert!(put_premium.unwrap() > 0);
+    assert!(rows[0][0].starts_with(
+        &analytics.expiries[0]
+            .expires_at
+            .format("%Y-%m-%d")
+            .to_string()
+    ));
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "high-precision")]
+
+use chrono::prelude::*;
+use options_math::math::extended::Extended;
+use options_math::math::Summation;
+use options_math::*;
+
+#[test]
+fn test_extended_arithmetic() {
+    let tiny = (Extended::new(1.0) + 1e-20) - Extended::new(1.0);
+    assert_eq!(tiny.to_f64(), 1e-20);
+
+    let two = Extended::new(2.0);
+    let root = two.sqrt();
+    assert!((root * root - two).abs().to_f64() < 1e-30);
+
+    let third = Extended::new(1.0) / 3.0;
+    assert!((third * 3.0 - Extended::new(1.0)).abs().to_f64() < 1e-30);
+
+    // ten thousand terms each too small to survive being added to one in f64
+    let values = || std::iter::once(1.0).chain(std::iter::repeat_n(1e-17, 10_000));
+    assert_eq!(Summation::Naive.sum(values()), 1.0);
+    assert_eq!(Summation::Extended.sum(values()), 1.0 + 1e-13);
+}
+
+#[test]
+fn test_extended_index() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let spec = synthetic::SurfaceSpec {
+        strike_interval: 5,
+        ..synthetic::SurfaceSpec::default()
+    };
+    let options = synthetic::generate_chain(&spec, now, 1);
+    let chain = chain::Chain::new(&options);
+    let (near, next) = (&chain.expiries()[0], &chain.expiries()[1]);
+    let naive = compute_vix_with_config(near, next, 0.01, 0.01, now, &IndexConfig::default());
+    let extended = compute_vix_with_config(
+        near,
+        next,
+        0.01,
+        0.01,
+        now,
+        &IndexConfig {
+            summation: Summation::Extended,
+            ..IndexConfig::default()
+        },
+    );
+    assert!(naive.is_finite());
+    assert!((naive - extended).abs() < 1e-10);
+}
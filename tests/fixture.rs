@@ -0,0 +1,45 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::fixture::*;
+use options_math::io::jsonl::Record;
+use options_math::*;
+
+#[test]
+fn test_anonymize() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let spec = synthetic::SurfaceSpec::default();
+    let options = synthetic::generate_chain(&spec, now, 1);
+    let anonymizer = Anonymizer::random(7);
+    assert!(anonymizer.scale >= 0.5 && anonymizer.scale <= 2.0);
+    assert!(anonymizer.shift_weeks <= -52 && anonymizer.shift_weeks > -520);
+
+    let fixture = anonymizer.anonymize(&options, spec.spot, now);
+    assert_eq!(fixture.at.weekday(), now.weekday());
+    assert_eq!(fixture.spot, anonymizer.price(spec.spot));
+    let (original, anonymized) = (Chain::new(&options), &fixture.chain);
+    assert_eq!(anonymized.expiries().len(), original.expiries().len());
+    for (a, o) in anonymized.expiries().iter().zip(original.expiries().iter()) {
+        assert_eq!(a.expires_at() - fixture.at, o.expires_at() - now);
+        assert_eq!(a.calls().len(), o.calls().len());
+        for (a, o) in a.calls().iter().zip(o.calls().iter()) {
+            assert_eq!(a.strike(), anonymizer.price(o.strike()));
+            assert!(a.bid() <= a.ask());
+            assert_eq!(a.bid() == 0, o.bid() == 0);
+        }
+    }
+
+    // the index barely moves
+    let index = |chain: &Chain, at| {
+        let expiries = chain.expiries();
+        return compute_vix(&expiries[0], &expiries[1], 0.01, 0.01, at);
+    };
+    assert!((index(anonymized, fixture.at) - index(&original, now)).abs() < 0.2);
+
+    // deterministic, and survives a round trip through JSON Lines
+    let again = anonymizer.anonymize(&options, spec.spot, now);
+    assert_eq!(again.record().to_json(), fixture.record().to_json());
+    let line = fixture.record().to_json();
+    assert_eq!(Record::from_json(&line).unwrap().to_json(), line);
+}
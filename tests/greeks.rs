@@ -0,0 +1,125 @@
+use chrono::prelude::*;
+use options_math::greeks::*;
+use options_math::*;
+
+#[test]
+fn test_greeks() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let spec = synthetic::SurfaceSpec::default();
+    let chain = chain::Chain::new(&synthetic::generate_chain(&spec, now, 1));
+    let expiry = &chain.expiries()[0];
+    let t = 23.0 / 365.0;
+
+    let greeks = expiry.greeks(spec.spot, 0.01, 0.0, 0.2, now);
+    assert_eq!(greeks.len(), expiry.calls().len() + expiry.puts().len());
+    for (contract, greeks) in greeks.iter() {
+        let expected = black_scholes_greeks(
+            contract.kind(),
+            3000.0,
+            contract.strike() as f64 / 100.0,
+            0.01,
+            0.0,
+            0.2,
+            t,
+        );
+        assert_eq!(*greeks, expected);
+        assert_eq!(contract.greeks(spec.spot, 0.01, 0.0, 0.2, now), expected);
+        match contract.kind() {
+            OptionKind::Call => assert!(greeks.delta >= 0.0 && greeks.rho >= 0.0),
+            OptionKind::Put => assert!(greeks.delta <= 0.0 && greeks.rho <= 0.0),
+        }
+    }
+
+    // put-call parity: call delta less put delta is one at every strike
+    let (call, put) = (expiry.calls()[10], expiry.puts()[10]);
+    assert_eq!(call.strike(), put.strike());
+    let call = call.greeks(spec.spot, 0.01, 0.0, 0.2, now);
+    let put = put.greeks(spec.spot, 0.01, 0.0, 0.2, now);
+    assert!((call.delta - put.delta - 1.0).abs() < 1e-12);
+    assert!((call.gamma - put.gamma).abs() < 1e-12);
+}
+
+#[test]
+fn test_second_order_greeks() {
+    let h = 1e-5;
+    for kind in [OptionKind::Call, OptionKind::Put].iter() {
+        for (strike, q) in [(90.0, 0.0), (100.0, 0.02), (115.0, 0.03)].iter() {
+            let greeks =
+                |vol: f64, t: f64| black_scholes_greeks(*kind, 100.0, *strike, 0.05, *q, vol, t);
+            let second =
+                black_scholes_second_order_greeks(*kind, 100.0, *strike, 0.05, *q, 0.25, 0.5);
+            let vanna = (greeks(0.25 + h, 0.5).delta - greeks(0.25 - h, 0.5).delta) / (2.0 * h);
+            let volga = (greeks(0.25 + h, 0.5).vega - greeks(0.25 - h, 0.5).vega) / (2.0 * h);
+            // delta after time passes, i.e. with less time left
+            let charm = (greeks(0.25, 0.5 - h).delta - greeks(0.25, 0.5 + h).delta) / (2.0 * h);
+            assert!(
+                (second.vanna - vanna).abs() < 1e-6,
+                "{:?} {}",
+                second,
+                vanna
+            );
+            assert!(
+                (second.volga - volga).abs() < 1e-4,
+                "{:?} {}",
+                second,
+                volga
+            );
+            assert!(
+                (second.charm - charm).abs() < 1e-6,
+                "{:?} {}",
+                second,
+                charm
+            );
+        }
+    }
+    assert_eq!(
+        black_scholes_second_order_greeks(OptionKind::Call, 100.0, 100.0, 0.05, 0.0, 0.25, 0.0),
+        greeks::SecondOrderGreeks::default()
+    );
+}
+
+#[test]
+fn test_third_order_greeks() {
+    let h = 1e-4;
+    for (strike, q) in [(90.0, 0.0), (100.0, 0.02), (115.0, 0.03)].iter() {
+        let at = |spot: f64, vol: f64, t: f64| {
+            black_scholes_greeks_to(
+                GreeksDepth::Second,
+                OptionKind::Call,
+                spot,
+                *strike,
+                0.05,
+                *q,
+                vol,
+                t,
+            )
+        };
+        let third = black_scholes_third_order_greeks(100.0, *strike, 0.05, *q, 0.25, 0.5);
+        let gamma = |spot: f64, vol: f64, t: f64| at(spot, vol, t).first.gamma;
+        let volga = |vol: f64| at(100.0, vol, 0.5).second.unwrap().volga;
+        let speed = (gamma(100.0 + h, 0.25, 0.5) - gamma(100.0 - h, 0.25, 0.5)) / (2.0 * h);
+        let zomma = (gamma(100.0, 0.25 + h, 0.5) - gamma(100.0, 0.25 - h, 0.5)) / (2.0 * h);
+        let color = (gamma(100.0, 0.25, 0.5 - h) - gamma(100.0, 0.25, 0.5 + h)) / (2.0 * h);
+        let ultima = (volga(0.25 + h) - volga(0.25 - h)) / (2.0 * h);
+        assert!((third.speed - speed).abs() < 1e-6, "{:?} {}", third, speed);
+        assert!((third.zomma - zomma).abs() < 1e-6, "{:?} {}", third, zomma);
+        assert!((third.color - color).abs() < 1e-6, "{:?} {}", third, color);
+        assert!(
+            (third.ultima - ultima).abs() < 1e-3,
+            "{:?} {}",
+            third,
+            ultima
+        );
+    }
+
+    // only the orders asked for are computed
+    let report =
+        |depth| black_scholes_greeks_to(depth, OptionKind::Put, 100.0, 95.0, 0.05, 0.0, 0.2, 0.25);
+    assert!(report(GreeksDepth::First).second.is_none());
+    assert!(report(GreeksDepth::Second).third.is_none());
+    let full = report(GreeksDepth::Third);
+    assert_eq!(full.first, report(GreeksDepth::First).first);
+    assert!(full.second.is_some() && full.third.is_some());
+}
@@ -0,0 +1,29 @@
+use options_math::greeks::black_scholes_greeks;
+use options_math::hedging::*;
+use options_math::math::Rng;
+use options_math::OptionKind;
+
+#[test]
+fn test_negative_spot_vol_correlation_lowers_delta() {
+    let greeks = black_scholes_greeks(OptionKind::Call, 100.0, 100.0, 0.01, 0.0, 0.2, 0.25);
+    let sensitivity = SpotVolSensitivity::from_correlation(-0.7, 0.8, 0.2);
+    let delta = minimum_variance_delta(&greeks, 100.0, sensitivity);
+    assert!(delta < greeks.delta);
+    assert!((delta - (greeks.delta + greeks.vega * -2.8 / 100.0)).abs() < 1e-12);
+}
+
+#[test]
+fn test_estimate_sensitivity_from_history() {
+    let mut rng = Rng::new(1);
+    let (mut spot, mut vol) = (100.0, 0.2);
+    let (mut spots, mut vols) = (vec![spot], vec![vol]);
+    for _ in 0..2000 {
+        let ret = 0.01 * rng.next_normal();
+        spot *= f64::exp(ret);
+        vol += -1.5 * ret + 0.001 * rng.next_normal();
+        spots.push(spot);
+        vols.push(vol);
+    }
+    let sensitivity = SpotVolSensitivity::estimate(&spots, &vols).unwrap();
+    assert!((sensitivity.0 + 1.5).abs() < 0.05, "{:?}", sensitivity);
+}
@@ -0,0 +1,150 @@
+use chrono::prelude::*;
+use options_math::holidays::*;
+use options_math::*;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn test_nyse_holidays() {
+    assert_eq!(
+        Nyse::holidays(2023),
+        vec![
+            date(2023, 1, 2),
+            date(2023, 1, 16),
+            date(2023, 2, 20),
+            date(2023, 4, 7),
+            date(2023, 5, 29),
+            date(2023, 6, 19),
+            date(2023, 7, 4),
+            date(2023, 9, 4),
+            date(2023, 11, 23),
+            date(2023, 12, 25),
+        ]
+    );
+    // New Year's Day on a Saturday is not observed
+    assert!(Nyse.is_trading_day(date(2021, 12, 31)));
+    assert!(!Nyse.is_trading_day(date(2020, 4, 10)));
+}
+
+#[test]
+fn test_expiration_fridays_and_trading_minutes() {
+    // Good Friday moves the expiration to Thursday
+    assert_eq!(
+        Cboe.next_expiration_friday(date(2023, 4, 3)),
+        date(2023, 4, 6)
+    );
+    assert_eq!(
+        Cboe.next_expiration_friday(date(2023, 4, 7)),
+        date(2023, 4, 14)
+    );
+    assert_eq!(
+        Nyse.next_expiration_friday(date(2023, 4, 14)),
+        date(2023, 4, 14)
+    );
+
+    // Friday 15:00 to Monday 10:00
+    let from = date(2023, 4, 14).and_hms_opt(15, 0, 0).unwrap();
+    let to = date(2023, 4, 17).and_hms_opt(10, 0, 0).unwrap();
+    assert_eq!(Nyse.trading_minutes_between(from, to), 90);
+    assert_eq!(Cboe.trading_minutes_between(from, to), 105);
+    // day after Thanksgiving closes early
+    let friday = date(2023, 11, 24);
+    assert_eq!(
+        Nyse.trading_minutes_between(
+            friday.and_hms_opt(0, 0, 0).unwrap(),
+            friday.and_hms_opt(23, 0, 0).unwrap()
+        ),
+        210
+    );
+
+    let contracts = [OptionContract::new(to, 10_000, OptionKind::Call, 100, 110)];
+    let t = group_options_by_expiry(&contracts)[&to].trading_time_to_expiration(from, &Nyse);
+    assert!((t - 90.0 / (252.0 * 390.0)).abs() < 1e-12);
+}
+
+#[test]
+fn test_registry() {
+    let mut registry = CalendarRegistry::default();
+    assert!(registry.get("CBOE").is_some());
+    assert!(registry.get("XETR").is_none());
+
+    let open = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    let close = NaiveTime::from_hms_opt(17, 30, 0).unwrap();
+    registry.register(
+        "XETR",
+        ListedHolidays::new(&[date(2023, 5, 1)], open, close),
+    );
+    let xetra = registry.get("XETR").unwrap();
+    assert!(!xetra.is_trading_day(date(2023, 5, 1)));
+    assert!(xetra.is_trading_day(date(2023, 4, 7)));
+    assert_eq!(
+        xetra.trading_minutes_between(
+            date(2023, 5, 1).and_hms_opt(0, 0, 0).unwrap(),
+            date(2023, 5, 3).and_hms_opt(0, 0, 0).unwrap()
+        ),
+        510
+    );
+}
+
+#[test]
+fn test_eurex() {
+    for holiday in [
+        date(2023, 4, 7),
+        date(2023, 4, 10),
+        date(2023, 5, 1),
+        date(2023, 12, 25),
+        date(2023, 12, 26),
+    ]
+    .iter()
+    {
+        assert!(!Eurex.is_trading_day(*holiday), "{}", holiday);
+    }
+    // US holidays trade in Frankfurt
+    assert!(Eurex.is_trading_day(date(2023, 7, 4)));
+    assert_eq!(
+        Eurex.trading_minutes_between(
+            date(2023, 4, 6).and_hms_opt(17, 0, 0).unwrap(),
+            date(2023, 4, 11).and_hms_opt(10, 0, 0).unwrap()
+        ),
+        90
+    );
+    assert!(CalendarRegistry::default().get("EUREX").is_some());
+}
+
+#[test]
+fn test_apac_calendars() {
+    let holidays = Jpx::holidays(2024);
+    for holiday in [
+        date(2024, 1, 2),
+        date(2024, 1, 8),
+        // National Foundation Day falls on a Sunday
+        date(2024, 2, 12),
+        date(2024, 3, 20),
+        date(2024, 5, 6),
+        date(2024, 9, 23),
+        date(2024, 11, 4),
+    ]
+    .iter()
+    {
+        assert!(holidays.contains(holiday), "{}", holiday);
+    }
+    // between Respect for the Aged Day and the autumnal equinox
+    assert!(Jpx::holidays(2026).contains(&date(2026, 9, 22)));
+    assert!(Jpx.is_trading_day(date(2024, 7, 4)));
+    assert_eq!(
+        Jpx.trading_minutes_between(
+            date(2024, 3, 19).and_hms_opt(15, 0, 0).unwrap(),
+            date(2024, 3, 21).and_hms_opt(10, 0, 0).unwrap()
+        ),
+        90
+    );
+
+    let chuseok = [date(2024, 9, 16), date(2024, 9, 17), date(2024, 9, 18)];
+    let krx = Krx::new(&chuseok);
+    assert!(!krx.is_trading_day(date(2024, 9, 17)));
+    assert!(!krx.is_trading_day(date(2024, 10, 3)));
+    assert!(krx.is_trading_day(date(2024, 9, 19)));
+    assert!(CalendarRegistry::default().get("KRX").is_none());
+}
@@ -0,0 +1,67 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::holidays::Cboe;
+use options_math::interpolation::*;
+use options_math::synthetic::*;
+use options_math::*;
+
+fn trading_minutes(inputs: &WeightingInputs) -> InterpolationWeights {
+    return InterpolationWeights::trading_minutes(&Cboe, inputs);
+}
+
+fn calendar_minutes(inputs: &WeightingInputs) -> InterpolationWeights {
+    return InterpolationWeights::calendar_minutes(inputs);
+}
+
+#[test]
+fn test_interpolation_weights() {
+    let listed = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec::default();
+    let chain = Chain::new(&generate_chain(&spec, listed, 1));
+    let (near, next) = (&chain.expiries()[0], &chain.expiries()[1]);
+    // a day after listing, when the target is no longer midway between the terms
+    let open = listed + chrono::Duration::days(1);
+    let r = spec.risk_free_rate;
+    let estimate = |weighting| {
+        let config = IndexConfig {
+            weighting,
+            ..IndexConfig::default()
+        };
+        return compute_vix_estimate(near, next, r, r, open, &config);
+    };
+
+    let default = estimate(Weighting::CalendarMinutes);
+    let weights = default.weights.unwrap();
+    let n_t1 = near.minutes_to_expiration(open);
+    let n_t2 = next.minutes_to_expiration(open);
+    let n_target = (30 * 24 * 60) as f64;
+    assert!((weights.near - (n_t2 - n_target) / (n_t2 - n_t1)).abs() < 1e-12);
+    assert!((weights.near + weights.next - 1.0).abs() < 1e-12);
+    assert_eq!(
+        default.value,
+        compute_vix(near, next, r, r, open),
+        "the default is the VIX weighting"
+    );
+
+    // a custom function reproducing the default gives the same index
+    let custom = estimate(Weighting::Custom(calendar_minutes));
+    assert_eq!(custom.weights, default.weights);
+    assert_eq!(custom.value, default.value);
+
+    // trading time moves weight between the terms, but still sums to one
+    let trading = estimate(Weighting::Custom(trading_minutes));
+    let weights = trading.weights.unwrap();
+    assert!((weights.near + weights.next - 1.0).abs() < 1e-12);
+    assert!((weights.near - default.weights.unwrap().near).abs() > 1e-6);
+    assert!(trading.value.is_finite() && trading.value != default.value);
+}
+
+#[test]
+fn test_linear_weights() {
+    let weights = InterpolationWeights::linear(10.0, 40.0, 30.0);
+    assert_eq!(weights, InterpolationWeights::new(1.0 / 3.0, 2.0 / 3.0));
+    // outside the terms one weight is negative
+    assert!(InterpolationWeights::linear(10.0, 20.0, 30.0).near < 0.0);
+}
@@ -0,0 +1,48 @@
+use chrono::prelude::*;
+use options_math::invariants::*;
+use options_math::synthetic::*;
+use options_math::*;
+
+fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap()
+}
+
+#[test]
+fn test_synthetic_chains_have_no_violations() {
+    for seed in 0..5 {
+        let spec = SurfaceSpec {
+            skew: Skew::new(-0.1 * seed as f64, 0.2 * seed as f64),
+            missing_quote_probability: 0.05,
+            ..SurfaceSpec::default()
+        };
+        let options = generate_chain(&spec, now(), seed);
+        for options_for_expiry in group_options_by_expiry(&options).values() {
+            let violations = check(options_for_expiry, spec.risk_free_rate, now());
+            assert_eq!(violations, vec![], "seed {}", seed);
+        }
+    }
+}
+
+#[test]
+fn test_crossed_call_is_reported() {
+    let expires_at = now() + chrono::Duration::days(30);
+    let options = vec![
+        OptionContract::new(expires_at, 9000, OptionKind::Call, 1000, 1020),
+        OptionContract::new(expires_at, 10000, OptionKind::Call, 1100, 1120),
+        OptionContract::new(expires_at, 11000, OptionKind::Call, 100, 120),
+    ];
+    let by_expiry = group_options_by_expiry(&options);
+
+    let violations = check_monotonicity(&by_expiry[&expires_at]);
+    assert_eq!(
+        violations,
+        vec![Violation::NotMonotone {
+            kind: OptionKind::Call,
+            lower_strike: 9000,
+            higher_strike: 10000,
+        }]
+    );
+    assert_eq!(check_butterflies(&by_expiry[&expires_at]).len(), 1);
+}
@@ -0,0 +1,63 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::inverse::*;
+use options_math::methodology::Preset;
+use options_math::rates::YieldCurve;
+use options_math::*;
+
+fn now() -> NaiveDateTime {
+    return NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(8, 0, 0))
+        .unwrap();
+}
+
+#[test]
+fn test_inverse_options() {
+    let expires_at = now() + chrono::Duration::days(30);
+    let call = InverseOptionContract::new(expires_at, 4_000_000, OptionKind::Call, 0.02, 0.03);
+    assert_eq!(call.mark(), 0.025);
+    assert_eq!(call.payoff(5_000_000), 0.2);
+    assert_eq!(call.payoff(3_000_000), 0.0);
+    assert!((call.delta(0.5) - 0.475).abs() < 1e-12);
+
+    let linear = call.to_linear(4_000_000);
+    assert_eq!((linear.bid(), linear.ask()), (80_000, 120_000));
+    assert_eq!(linear.settlement(), Settlement::Inverse);
+    let back = InverseOptionContract::from_linear(linear, 4_000_000);
+    assert!((back.bid - call.bid).abs() < 1e-15);
+
+    // settled in coin, but worth the intrinsic value in cash
+    let strategy = strategy::Strategy::new(vec![strategy::Leg::new(linear, 1)]);
+    let result = expiration::process_expiration(&strategy, 5_000_000, expires_at);
+    assert_eq!(result.cash, 1_000_000.0);
+    assert_eq!(result.shares, 0);
+}
+
+#[test]
+fn test_dvol() {
+    let spec = synthetic::SurfaceSpec {
+        risk_free_rate: 0.0,
+        ..synthetic::SurfaceSpec::default()
+    };
+    let options = synthetic::generate_chain(&spec, now(), 1);
+    let underlying: Vec<(NaiveDateTime, Cents)> = spec
+        .expiries
+        .iter()
+        .map(|e| (now() + chrono::Duration::days(e.days), spec.spot))
+        .collect();
+    let inverse: Vec<InverseOptionContract> = options
+        .iter()
+        .map(|o| InverseOptionContract::from_linear(*o, spec.spot))
+        .collect();
+    assert_eq!(linear_contracts(&inverse, &underlying).len(), options.len());
+    assert!(linear_contracts(&inverse, &underlying[..1])
+        .iter()
+        .all(|o| o.expires_at() == underlying[0].0));
+
+    let expected = Preset::DeribitDvol
+        .methodology()
+        .compute(&Chain::new(&options), &YieldCurve::flat(0.0), now())
+        .unwrap();
+    assert_eq!(dvol(&inverse, &underlying, now()), Some(expected));
+    assert!((expected - 20.0).abs() < 1.0);
+}
@@ -0,0 +1,140 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::currency::Currency;
+use options_math::io::jsonl::*;
+use options_math::synthetic::*;
+use options_math::Settlement;
+use std::io::Cursor;
+
+#[test]
+fn test_round_trip() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec::default();
+    let chain = Chain::new(&generate_chain(&spec, now, 1))
+        .with_settlement(Settlement::Cash)
+        .with_currency(Currency::EUR);
+
+    let mut writer = JsonlWriter::new(vec![]);
+    writer.write_snapshot(now, &chain).unwrap();
+    writer.write_index(now, "VIX \"test\"", 13.25).unwrap();
+    writer
+        .write_index(now + chrono::Duration::minutes(1), "VIX", f64::NAN)
+        .unwrap();
+    let mut buf = writer.into_inner();
+    buf.extend_from_slice(b"\n");
+    assert_eq!(buf.iter().filter(|b| **b == b'\n').count(), 4);
+
+    let records: Vec<Record> = JsonlReader::new(Cursor::new(buf))
+        .map(|r| r.unwrap())
+        .collect();
+    assert_eq!(records.len(), 3);
+    match &records[0] {
+        Record::Snapshot { at, chain: read } => {
+            assert_eq!(*at, now);
+            assert_eq!(read.expiries().len(), chain.expiries().len());
+            for (a, b) in read.expiries().iter().zip(chain.expiries()) {
+                assert_eq!(a.calls(), b.calls());
+                assert_eq!(a.puts(), b.puts());
+            }
+        }
+        other => panic!("{:?}", other),
+    }
+    match &records[1] {
+        Record::Index { name, value, .. } => {
+            assert_eq!(name, "VIX \"test\"");
+            assert_eq!(*value, 13.25);
+        }
+        other => panic!("{:?}", other),
+    }
+    match &records[2] {
+        Record::Index { value, .. } => assert!(value.is_nan()),
+        other => panic!("{:?}", other),
+    }
+
+    assert!(Record::from_json("{\"type\":\"index\"}").is_err());
+    assert!(Record::from_json("not json").is_err());
+}
+
+#[test]
+fn test_append_to_file() {
+    let path = std::env::temp_dir().join(format!("options-math-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let at = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    for value in [12.0, 13.0].iter() {
+        // reopened each time, as a restarted collector would
+        JsonlWriter::append(&path)
+            .unwrap()
+            .write_index(at, "VIX", *value)
+            .unwrap();
+    }
+    let values: Vec<f64> = JsonlReader::open(&path)
+        .unwrap()
+        .map(|r| match r.unwrap() {
+            Record::Index { value, .. } => value,
+            other => panic!("{:?}", other),
+        })
+        .collect();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(values, vec![12.0, 13.0]);
+}
+
+#[test]
+fn test_quote_times_and_revisions() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let contracts: Vec<_> = generate_chain(&SurfaceSpec::default(), now, 1)
+        .into_iter()
+        .enumerate()
+        .map(|(i, o)| {
+            let o = o
+                .with_quote(o.bid(), o.ask() + 5)
+                .with_quote(o.bid(), o.ask());
+            return if i % 2 == 0 {
+                o.with_quoted_at(now - chrono::Duration::seconds(i as i64))
+            } else {
+                o
+            };
+        })
+        .collect();
+    let chain = Chain::new(&contracts);
+    let line = Record::Snapshot { at: now, chain }.to_json();
+    match Record::from_json(&line).unwrap() {
+        Record::Snapshot { chain: read, .. } => {
+            let read: Vec<_> = read
+                .expiries()
+                .iter()
+                .flat_map(|e| {
+                    e.calls()
+                        .iter()
+                        .chain(e.puts().iter())
+                        .copied()
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            assert_eq!(read.len(), contracts.len());
+            for o in read.iter() {
+                assert_eq!(o.revision(), 2);
+                assert!(contracts.contains(o));
+            }
+            assert!(read.iter().any(|o| o.quoted_at().is_some()));
+            assert!(read.iter().any(|o| o.quoted_at().is_none()));
+        }
+        other => panic!("{:?}", other),
+    }
+
+    // lines written before quote times and revisions were recorded
+    let old = "{\"type\":\"snapshot\",\"at\":\"2020-01-02T09:30:00\",\"contracts\":[{\"expires_at\":\"2020-01-17T09:30:00\",\"strike\":320000,\"kind\":\"call\",\"bid\":410,\"ask\":420,\"settlement\":\"cash\",\"currency\":\"USD\"}]}";
+    match Record::from_json(old).unwrap() {
+        Record::Snapshot { chain, .. } => {
+            let call = chain.expiries()[0].calls()[0];
+            assert_eq!(call.revision(), 0);
+            assert_eq!(call.quoted_at(), None);
+        }
+        other => panic!("{:?}", other),
+    }
+}
@@ -0,0 +1,104 @@
+use options_math::math::linalg::*;
+use options_math::math::Rng;
+
+fn multiply(lower: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = lower.len();
+    return (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| (0..n).map(|k| lower[i][k] * lower[j][k]).sum())
+                .collect()
+        })
+        .collect();
+}
+
+#[test]
+fn test_cholesky_and_eigen() {
+    let matrix = vec![
+        vec![1.0, 0.5, 0.2],
+        vec![0.5, 1.0, 0.3],
+        vec![0.2, 0.3, 1.0],
+    ];
+    let lower = cholesky(&matrix).unwrap();
+    assert_eq!(lower[0][1], 0.0);
+    for (row, expected) in multiply(&lower).iter().zip(matrix.iter()) {
+        for (x, y) in row.iter().zip(expected.iter()) {
+            assert!((x - y).abs() < 1e-12);
+        }
+    }
+
+    let (values, vectors) = symmetric_eigen(&matrix);
+    assert!(values.windows(2).all(|w| w[0] <= w[1]));
+    assert!((values.iter().sum::<f64>() - 3.0).abs() < 1e-10);
+    for (value, vector) in values.iter().zip(vectors.iter()) {
+        for i in 0..3 {
+            let product: f64 = (0..3).map(|j| matrix[i][j] * vector[j]).sum();
+            assert!((product - value * vector[i]).abs() < 1e-10);
+        }
+    }
+
+    assert!(cholesky(&[vec![1.0, 2.0], vec![2.0, 1.0]]).is_none());
+    assert!(cholesky(&[vec![1.0, 0.0]]).is_none());
+}
+
+#[test]
+fn test_repairing_correlations() {
+    assert!(validate_correlation(&[vec![1.0, 0.3], vec![0.3, 1.0]], 1e-9).is_empty());
+    assert_eq!(
+        validate_correlation(&[vec![1.0, 0.3], vec![0.2, 1.1]], 1e-9),
+        vec![
+            CorrelationIssue::NotSymmetric { row: 0, column: 1 },
+            CorrelationIssue::DiagonalNotOne { index: 1 },
+            CorrelationIssue::OutOfRange { row: 1, column: 1 },
+        ]
+    );
+
+    // pairwise estimates no three variables can have
+    let inconsistent = vec![
+        vec![1.0, 0.9, 0.7],
+        vec![0.9, 1.0, -0.4],
+        vec![0.7, -0.4, 1.0],
+    ];
+    let issues = validate_correlation(&inconsistent, 1e-9);
+    assert!(matches!(
+        issues[..],
+        [CorrelationIssue::NotPositiveSemidefinite { min_eigenvalue }] if min_eigenvalue < 0.0
+    ));
+    assert!(CorrelatedNormals::new(&inconsistent).is_none());
+
+    let repaired = nearest_correlation(&inconsistent, 1e-8);
+    assert!(
+        validate_correlation(&repaired, 1e-9).is_empty(),
+        "{:?}",
+        repaired
+    );
+    assert!(cholesky(&repaired).is_some());
+    // the nearest such matrix moves every entry only a little
+    for i in 0..3 {
+        for j in 0..3 {
+            assert!((repaired[i][j] - inconsistent[i][j]).abs() < 0.3);
+        }
+    }
+    // and a valid matrix is already nearest itself
+    let valid = vec![vec![1.0, 0.3], vec![0.3, 1.0]];
+    let same = nearest_correlation(&valid, 1e-8);
+    assert!((same[0][1] - 0.3).abs() < 1e-10);
+}
+
+#[test]
+fn test_correlated_normals() {
+    let normals = CorrelatedNormals::new(&[vec![1.0, -0.6], vec![-0.6, 1.0]]).unwrap();
+    assert_eq!(normals.dimension(), 2);
+    let mut rng = Rng::new(3);
+    let n = 100_000;
+    let (mut xx, mut yy, mut xy) = (0.0, 0.0, 0.0);
+    for _ in 0..n {
+        let z = normals.sample(&mut rng);
+        xx += z[0] * z[0];
+        yy += z[1] * z[1];
+        xy += z[0] * z[1];
+    }
+    let correlation = xy / (xx * yy).sqrt();
+    assert!((correlation + 0.6).abs() < 0.01, "{}", correlation);
+    assert!((yy / n as f64 - 1.0).abs() < 0.02);
+}
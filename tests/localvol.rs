@@ -0,0 +1,94 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::localvol::*;
+use options_math::montecarlo::*;
+use options_math::rates::YieldCurve;
+use options_math::synthetic::*;
+use options_math::*;
+
+fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap()
+}
+
+fn surface(spec: &SurfaceSpec) -> ImpliedSurface {
+    let chain = Chain::new(&generate_chain(spec, now(), 1));
+    let analytics = chain.analytics(spec.spot, &YieldCurve::flat(spec.risk_free_rate), now());
+    return ImpliedSurface::from_analytics(&analytics).unwrap();
+}
+
+#[test]
+fn test_flat_surface() {
+    let spec = SurfaceSpec::default();
+    let surface = surface(&spec);
+    assert_eq!(surface.slices.len(), 2);
+    let t = surface.slices[1].time_to_expiration;
+    assert!((surface.implied_volatility(spec.spot, t) - 0.2).abs() < 0.005);
+
+    // local volatility of a flat surface is the implied volatility, inside and outside the slices
+    let prices = [280_000.0, 300_000.0, 320_000.0];
+    let times = [0.01, 0.08, 0.2];
+    for row in surface.local_volatility_grid(&prices, &times).iter() {
+        for vol in row.iter() {
+            assert!((vol - 0.2).abs() < 0.01, "{}", vol);
+        }
+    }
+}
+
+#[test]
+fn test_skewed_surface() {
+    let spec = SurfaceSpec {
+        skew: Skew::new(-0.4, 0.0),
+        ..SurfaceSpec::default()
+    };
+    let surface = surface(&spec);
+    let t = surface.slices[1].time_to_expiration;
+
+    // local volatility steepens the implied skew
+    let (low, high) = (
+        surface.local_volatility(280_000.0, t),
+        surface.local_volatility(320_000.0, t),
+    );
+    let implied = (
+        surface.implied_volatility(280_000, t),
+        surface.implied_volatility(320_000, t),
+    );
+    assert!(low > high);
+    assert!(
+        low - high > implied.0 - implied.1,
+        "{} {} {:?}",
+        low,
+        high,
+        implied
+    );
+
+    // simulating with it reprices an out-of-the-money put
+    let strike = 285_000;
+    let r = spec.risk_free_rate;
+    let q = -(surface.slices[1].forward_price / spec.spot as f64).ln() / t + r;
+    let estimate = MonteCarlo::new(20_000, 50, 7).price_with_local_volatility(
+        &Vanilla::new(OptionKind::Put, strike),
+        spec.spot,
+        r,
+        q,
+        |price, time| surface.local_volatility(price, time),
+        t,
+    );
+    let vol = surface.implied_volatility(strike, t);
+    let forward = surface.slices[1].forward_price;
+    let expected = math::black_price(
+        OptionKind::Put,
+        forward,
+        strike as f64,
+        vol,
+        t,
+        (-r * t).exp(),
+    );
+    assert!(
+        (estimate.price - expected).abs() < 4.0 * estimate.standard_error,
+        "{:?} {}",
+        estimate,
+        expected
+    );
+}
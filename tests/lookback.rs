@@ -0,0 +1,109 @@
+use chrono::prelude::*;
+use options_math::lookback::*;
+use options_math::montecarlo::*;
+use options_math::*;
+
+fn expires_at() -> NaiveDateTime {
+    return NaiveDate::from_ymd_opt(2021, 7, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+}
+
+#[test]
+fn test_lookback_prices() {
+    let (r, q, vol, t) = (0.05, 0.02, 0.25, 0.5);
+    let price = |kind, strike, extreme| {
+        return lookback_price(kind, strike, 10_000, extreme, r, q, vol, t);
+    };
+    let forward_leg = 10_000.0 * (-q * t).exp();
+
+    // a fixed strike inside the running range is a floating strike plus a forward
+    let floating_put = price(OptionKind::Put, LookbackStrike::Floating, 11_000);
+    let fixed_call = price(OptionKind::Call, LookbackStrike::Fixed(9_500), 11_000);
+    let expected = floating_put as f64 + forward_leg - 9_500.0 * (-r * t).exp();
+    assert!(
+        (fixed_call as f64 - expected).abs() <= 1.0,
+        "{} {}",
+        fixed_call,
+        expected
+    );
+    let floating_call = price(OptionKind::Call, LookbackStrike::Floating, 9_000);
+    let fixed_put = price(OptionKind::Put, LookbackStrike::Fixed(10_500), 9_000);
+    let expected = floating_call as f64 - forward_leg + 10_500.0 * (-r * t).exp();
+    assert!(
+        (fixed_put as f64 - expected).abs() <= 1.0,
+        "{} {}",
+        fixed_put,
+        expected
+    );
+
+    // looking back is worth more than a vanilla option
+    let vanilla = pricing::black_scholes_price(OptionKind::Call, 10_000, 10_000, r, q, vol, t);
+    assert!(price(OptionKind::Call, LookbackStrike::Floating, 10_000) > vanilla);
+    assert!(price(OptionKind::Call, LookbackStrike::Fixed(10_000), 10_000) > vanilla);
+
+    // no carry is handled, and at expiration the payoff is known
+    let flat = lookback_price(
+        OptionKind::Put,
+        LookbackStrike::Floating,
+        10_000,
+        10_000,
+        0.03,
+        0.03,
+        vol,
+        t,
+    );
+    let near = lookback_price(
+        OptionKind::Put,
+        LookbackStrike::Floating,
+        10_000,
+        10_000,
+        0.03,
+        0.0301,
+        vol,
+        t,
+    );
+    assert!((flat - near).abs() <= 2, "{} {}", flat, near);
+    assert_eq!(
+        lookback_price(
+            OptionKind::Put,
+            LookbackStrike::Fixed(10_500),
+            10_000,
+            9_000,
+            r,
+            q,
+            vol,
+            0.0
+        ),
+        1_500
+    );
+}
+
+#[test]
+fn test_lookback_matches_simulation() {
+    let (r, q, vol) = (0.05, 0.02, 0.25);
+    let now = expires_at() - chrono::Duration::days(365);
+    let mc = MonteCarlo::new(4_000, 1_000, 9);
+    let strikes = [
+        LookbackStrike::Floating,
+        LookbackStrike::Fixed(10_000),
+        LookbackStrike::Fixed(11_000),
+    ];
+    for kind in [OptionKind::Call, OptionKind::Put].iter() {
+        for strike in strikes.iter() {
+            let lookback = Lookback::new(*kind, *strike, expires_at());
+            let exact = lookback.price(10_000, 10_000, r, q, vol, now) as f64;
+            let estimate = mc.price(&lookback, 10_000, r, q, vol, 1.0);
+            // monitoring a thousand times a year still misses some of the extremes
+            assert!(estimate.price < exact, "{:?} {:?}", estimate, exact);
+            assert!(
+                estimate.price > exact * 0.96 - 4.0 * estimate.standard_error,
+                "{:?} {:?} {:?} {}",
+                kind,
+                strike,
+                estimate,
+                exact
+            );
+        }
+    }
+}
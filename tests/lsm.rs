@@ -0,0 +1,117 @@
+use options_math::lsm::*;
+use options_math::math::linalg::least_squares;
+use options_math::models::Heston;
+use options_math::montecarlo::*;
+use options_math::pricing::*;
+use options_math::OptionKind;
+
+#[test]
+fn test_least_squares() {
+    // y = 1 + 2x exactly
+    let rows: Vec<Vec<f64>> = (0..5).map(|x| vec![1.0, x as f64]).collect();
+    let y: Vec<f64> = (0..5).map(|x| 1.0 + 2.0 * x as f64).collect();
+    let b = least_squares(&rows, &y).unwrap();
+    assert!(
+        (b[0] - 1.0).abs() < 1e-9 && (b[1] - 2.0).abs() < 1e-9,
+        "{:?}",
+        b
+    );
+    assert_eq!(least_squares(&[], &[]), None);
+
+    assert_eq!(Basis::Polynomial(2).evaluate(3.0), vec![1.0, 3.0, 9.0]);
+    // L_2(x) = (x² - 4x + 2) / 2
+    assert_eq!(Basis::Laguerre(2).evaluate(3.0), vec![1.0, -2.0, -0.5]);
+    assert_eq!(Basis::Laguerre(0).evaluate(3.0), vec![1.0]);
+}
+
+#[test]
+fn test_american_put() {
+    // Longstaff and Schwartz's first example: S = 36, K = 40, r = 6%, σ = 20%, one year
+    let mc = MonteCarlo::new(20_000, 50, 7);
+    let put = Vanilla::new(OptionKind::Put, 4_000);
+    let gbm = GeometricBrownianMotion::new(0.2);
+    let american =
+        mc.price_early_exercise(&put, &gbm, 3_600, 0.06, 0.0, 1.0, &LeastSquares::default());
+    let tree = BinomialTree::new(1000).price(OptionKind::Put, 3_600, 4_000, 0.06, 0.0, 0.2, 1.0);
+    assert!(
+        (american.price - tree as f64).abs() < 4.0 * american.standard_error + 2.0,
+        "{:?} {}",
+        american,
+        tree
+    );
+
+    // polynomial regressions get there as well
+    let polynomial = LeastSquares::new(Basis::Polynomial(3), ExerciseSchedule::American);
+    let estimate = mc.price_early_exercise(&put, &gbm, 3_600, 0.06, 0.0, 1.0, &polynomial);
+    assert!(
+        (estimate.price - american.price).abs() < 3.0,
+        "{:?} {:?}",
+        estimate,
+        american
+    );
+
+    // a Bermudan with no early dates is European, and one with a few is in between
+    let european = mc.price_early_exercise(
+        &put,
+        &gbm,
+        3_600,
+        0.06,
+        0.0,
+        1.0,
+        &LeastSquares::new(Basis::default(), ExerciseSchedule::Bermudan(vec![])),
+    );
+    let simulated = mc.price_with_dynamics(&put, &gbm, 3_600, 0.06, 0.0, 1.0);
+    assert!((european.price - simulated.price).abs() < 1e-9);
+    let exact = black_scholes_price(OptionKind::Put, 3_600, 4_000, 0.06, 0.0, 0.2, 1.0);
+    assert!(
+        (european.price - exact as f64).abs() < 3.0 * european.standard_error,
+        "{:?} {}",
+        european,
+        exact
+    );
+    let bermudan = mc.price_early_exercise(
+        &put,
+        &gbm,
+        3_600,
+        0.06,
+        0.0,
+        1.0,
+        &LeastSquares::new(
+            Basis::default(),
+            ExerciseSchedule::Bermudan(vec![0.25, 0.5, 0.75]),
+        ),
+    );
+    assert!(european.price < bermudan.price && bermudan.price < american.price + 1.0);
+
+    // deep in the money it is exercised right away
+    let deep = mc.price_early_exercise(&put, &gbm, 2_000, 0.06, 0.0, 1.0, &LeastSquares::default());
+    assert_eq!(deep.price, 2_000.0);
+    assert_eq!(deep.standard_error, 0.0);
+}
+
+#[test]
+fn test_heston_american_put() {
+    let heston = Heston::new(0.04, 2.0, 0.04, 0.4, -0.7);
+    let mc = MonteCarlo::new(20_000, 50, 11);
+    let put = Vanilla::new(OptionKind::Put, 10_000);
+    let (r, t) = (0.05, 0.5);
+
+    let european = mc.price_with_dynamics(&put, &heston, 10_000, r, 0.0, t);
+    let forward = 10_000.0 * (r * t).exp();
+    let exact = heston.price(OptionKind::Put, forward, 10_000.0, t, (-r * t).exp());
+    assert!(
+        (european.price - exact).abs() < 3.0 * european.standard_error + 2.0,
+        "{:?} {}",
+        european,
+        exact
+    );
+
+    let american =
+        mc.price_early_exercise(&put, &heston, 10_000, r, 0.0, t, &LeastSquares::default());
+    assert!(
+        american.price > european.price + 5.0,
+        "{:?} {:?}",
+        american,
+        european
+    );
+}
@@ -0,0 +1,219 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::methodology::*;
+use options_math::rates::YieldCurve;
+use options_math::series::compute_vix_series;
+use options_math::synthetic::*;
+use options_math::*;
+
+fn now() -> NaiveDateTime {
+    return NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+}
+
+#[test]
+fn test_presets() {
+    let spec = SurfaceSpec {
+        expiries: vec![
+            SyntheticExpiry::new(7, 0.2),
+            SyntheticExpiry::new(14, 0.2),
+            SyntheticExpiry::new(23, 0.2),
+            SyntheticExpiry::new(37, 0.2),
+        ],
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&spec, now(), 1));
+    let rates = YieldCurve::flat(spec.risk_free_rate);
+
+    let vix = Preset::CboeVix.methodology();
+    let untruncated = Methodology {
+        index: IndexConfig::default(),
+        ..vix
+    };
+    let series = compute_vix_series(&[(now(), &chain)], &rates, &IndexConfig::default());
+    assert_eq!(untruncated.compute(&chain, &rates, now()), series[0].value);
+    assert!((vix.compute(&chain, &rates, now()).unwrap() - 20.0).abs() < 1.0);
+
+    let vix9d = Preset::CboeVix9d.methodology();
+    let (near, next) = vix9d.select_terms(&chain, now()).unwrap();
+    assert_eq!((near.expires_at() - now()).num_days(), 7);
+    assert_eq!((next.expires_at() - now()).num_days(), 14);
+    assert!((vix9d.compute(&chain, &rates, now()).unwrap() - 20.0).abs() < 1.0);
+
+    // without discounting the index only moves a little
+    let dvol = Preset::DeribitDvol.methodology();
+    assert_eq!(dvol.calendar, None);
+    let zero_rates = Methodology {
+        rates: RateSource::Curve,
+        ..dvol
+    };
+    let difference = dvol.compute(&chain, &rates, now()).unwrap()
+        - zero_rates
+            .compute(&chain, &YieldCurve::flat(0.0), now())
+            .unwrap();
+    assert!(difference.abs() < 1e-12);
+
+    let vstoxx = Preset::Vstoxx.methodology();
+    assert_eq!(vstoxx.currency, currency::Currency::EUR);
+    assert_eq!(
+        vstoxx.expiration(NaiveDate::from_ymd_opt(2020, 1, 17).unwrap()),
+        NaiveDate::from_ymd_opt(2020, 1, 17)
+            .and_then(|d| d.and_hms_opt(12, 0, 0))
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_consecutive_zero_bid_truncation() {
+    let expires_at = now() + chrono::Duration::days(30);
+    let quotes = |put_bids: &[(Cents, Cents)]| -> OptionsByExpiryDate {
+        let mut options = vec![];
+        for (strike, put_bid) in put_bids.iter() {
+            let call_bid = (10_000 - strike).max(0) + 100;
+            options.push(OptionContract::new(
+                expires_at,
+                *strike,
+                OptionKind::Call,
+                call_bid,
+                call_bid + 10,
+            ));
+            options.push(OptionContract::new(
+                expires_at,
+                *strike,
+                OptionKind::Put,
+                *put_bid,
+                put_bid + 10,
+            ));
+        }
+        return group_options_by_expiry(&options)
+            .remove(&expires_at)
+            .unwrap();
+    };
+    let truncated = IndexConfig {
+        truncation: Truncation::ConsecutiveZeroBids,
+        ..IndexConfig::default()
+    };
+    let variances = |expiry: &OptionsByExpiryDate| -> (f64, f64) {
+        return (
+            expiry.variance_with_config(0.0, now(), &IndexConfig::default()),
+            expiry.variance_with_config(0.0, now(), &truncated),
+        );
+    };
+
+    // a quoted strike beyond two zero bids is excluded
+    let expiry = quotes(&[
+        (6_000, 2),
+        (7_000, 5),
+        (8_000, 0),
+        (8_500, 0),
+        (9_000, 30),
+        (9_500, 60),
+        (10_000, 100),
+    ]);
+    let (all, cboe) = variances(&expiry);
+    assert!(cboe < all, "{} {}", cboe, all);
+
+    // a single zero bid does not stop the walk
+    let expiry = quotes(&[
+        (6_000, 2),
+        (7_000, 5),
+        (8_000, 10),
+        (8_500, 0),
+        (9_000, 30),
+        (9_500, 60),
+        (10_000, 100),
+    ]);
+    let (all, cboe) = variances(&expiry);
+    assert_eq!(cboe, all);
+}
+
+#[test]
+fn test_vstoxx_sub_indices() {
+    let spec = SurfaceSpec {
+        expiries: vec![
+            SyntheticExpiry::new(16, 0.2),
+            SyntheticExpiry::new(44, 0.22),
+            SyntheticExpiry::new(72, 0.24),
+        ],
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&spec, now(), 1));
+    let euro_rates = YieldCurve::new(&[(0.0, 0.035), (90.0, 0.038)]);
+    let vstoxx = Preset::Vstoxx.methodology();
+
+    let sub_indices = vstoxx.sub_indices(&chain, &euro_rates, now());
+    assert_eq!(sub_indices.len(), 3);
+    for (sub_index, expiry) in sub_indices.iter().zip(spec.expiries.iter()) {
+        assert!(
+            (sub_index.value - expiry.atm_vol * 100.0).abs() < 1.0,
+            "{:?}",
+            sub_index
+        );
+    }
+    let index = vstoxx.compute(&chain, &euro_rates, now()).unwrap();
+    let interpolated = vstoxx.interpolate(&sub_indices[0], &sub_indices[1]);
+    assert!(
+        (index - interpolated).abs() < 1e-9,
+        "{} {}",
+        index,
+        interpolated
+    );
+
+    // the March 2023 expiry settles at noon in Frankfurt
+    let registry = holidays::CalendarRegistry::default();
+    assert_eq!(
+        vstoxx.monthly_expiration(2023, 3, &registry),
+        NaiveDate::from_ymd_opt(2023, 3, 17).and_then(|d| d.and_hms_opt(12, 0, 0))
+    );
+    // Good Friday moves the April 2025 expiration a day earlier
+    assert_eq!(
+        vstoxx.monthly_expiration(2025, 4, &registry),
+        NaiveDate::from_ymd_opt(2025, 4, 17).and_then(|d| d.and_hms_opt(12, 0, 0))
+    );
+}
+
+#[test]
+fn test_apac_settlement_times() {
+    let mut registry = holidays::CalendarRegistry::default();
+    let nikkei = Preset::NikkeiVi.methodology();
+    // SQ on the second Friday, at the open in Tokyo
+    assert_eq!(
+        nikkei.monthly_expiration(2024, 3, &registry),
+        NaiveDate::from_ymd_opt(2024, 3, 8).and_then(|d| d.and_hms_opt(9, 0, 0))
+    );
+
+    let vkospi = Preset::Vkospi.methodology();
+    assert_eq!(vkospi.monthly_expiration(2024, 3, &registry), None);
+    registry.register("KRX", holidays::Krx::new(&[]));
+    assert_eq!(
+        vkospi.monthly_expiration(2024, 3, &registry),
+        NaiveDate::from_ymd_opt(2024, 3, 14).and_then(|d| d.and_hms_opt(15, 20, 0))
+    );
+
+    // DVOL settles on the last Friday of the month in UTC
+    assert_eq!(
+        Preset::DeribitDvol
+            .methodology()
+            .monthly_expiration(2024, 3, &registry),
+        NaiveDate::from_ymd_opt(2024, 3, 29).and_then(|d| d.and_hms_opt(8, 0, 0))
+    );
+
+    // midnight UTC is already 9:00 in Tokyo
+    let utc = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+    let tokyo = NaiveDate::from_ymd_opt(2024, 3, 1)
+        .and_then(|d| d.and_hms_opt(9, 0, 0))
+        .unwrap();
+    assert_eq!(nikkei.local_time(utc), Some(tokyo));
+    assert_eq!(Preset::CboeVix.methodology().local_time(utc), None);
+
+    let spec = SurfaceSpec {
+        spot: 3_900_000,
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&spec, tokyo, 1));
+    let rates = YieldCurve::flat(0.001);
+    let index = nikkei.compute_at(&chain, &rates, utc).unwrap();
+    assert_eq!(Some(index), nikkei.compute(&chain, &rates, tokyo));
+    assert_ne!(Some(index), nikkei.compute(&chain, &rates, utc.naive_utc()));
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "metrics")]
+
+use chrono::prelude::*;
+use options_math::chain::{Chain, QuoteUpdate, SharedChain};
+use options_math::metrics::*;
+use options_math::rates::YieldCurve;
+use options_math::synthetic::*;
+use options_math::*;
+
+#[test]
+fn test_histogram() {
+    static BOUNDS: [f64; 3] = [1.0, 2.0, 5.0];
+    let histogram = Histogram::new(&BOUNDS);
+    for value in [0.5, 1.0, 1.5, 4.0, 10.0].iter() {
+        histogram.observe(*value);
+    }
+    assert_eq!(
+        histogram.cumulative(),
+        vec![(1.0, 2), (2.0, 3), (5.0, 4), (f64::INFINITY, 5)]
+    );
+    assert_eq!(histogram.sum(), 17.0);
+}
+
+#[test]
+fn test_live_path_records_metrics() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec::default();
+    let contracts = generate_chain(&spec, now, 1);
+    let shared = SharedChain::new(Chain::new(&contracts));
+
+    let metrics = global();
+    let (updates, computed, latency) = (
+        metrics.quote_updates.get(),
+        metrics.chains_processed.get(),
+        metrics.computation_seconds.count(),
+    );
+    let c = contracts[0];
+    shared.apply(&[
+        QuoteUpdate::new(c.expires_at(), c.strike(), c.kind(), c.bid(), c.ask() + 1),
+        QuoteUpdate::new(c.expires_at(), c.strike(), c.kind(), c.bid(), c.ask() + 2),
+    ]);
+    assert!(metrics.quote_updates.get() >= updates + 2);
+
+    let chain = shared.snapshot();
+    let expiries = chain.expiries();
+    compute_vix_estimate(
+        &expiries[0],
+        &expiries[1],
+        0.01,
+        0.01,
+        now,
+        &IndexConfig::default(),
+    );
+    assert!(metrics.chains_processed.get() > computed);
+    assert!(metrics.computation_seconds.count() > latency);
+
+    let (computed, dropped) = (
+        metrics.chains_processed.get(),
+        metrics.strikes_dropped.get(),
+    );
+    let config = IndexConfig {
+        truncation: Truncation::ConsecutiveZeroBids,
+        ..IndexConfig::default()
+    };
+    let series = options_math::series::compute_vix_series(
+        &[(now, &*chain)],
+        &YieldCurve::flat(spec.risk_free_rate),
+        &config,
+    );
+    let estimate = series[0].estimate.unwrap();
+    assert!(metrics.chains_processed.get() > computed);
+    assert!(
+        metrics.strikes_dropped.get()
+            >= dropped
+                + (estimate.near_term.strikes_dropped + estimate.next_term.strikes_dropped) as u64
+    );
+    chain.analytics(spec.spot, &YieldCurve::flat(spec.risk_free_rate), now);
+
+    let text = metrics.render();
+    assert!(text.contains("# TYPE options_math_chains_processed_total counter"));
+    assert!(text.contains("options_math_computation_seconds_bucket{le=\"+Inf\"}"));
+    assert!(text.contains("options_math_solver_failures_total"));
+}
@@ -0,0 +1,352 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::fourier::*;
+use options_math::math::complex::Complex;
+use options_math::models::*;
+use options_math::rates::YieldCurve;
+use options_math::synthetic::*;
+use options_math::OptionKind;
+
+fn heston() -> Heston {
+    return Heston::new(0.04, 1.5, 0.05, 0.6, -0.7);
+}
+
+#[test]
+fn test_heston_characteristic_function() {
+    let model = heston();
+    // the forward is the expected terminal price
+    let at_forward = model.characteristic(Complex::new(0.0, -1.0), 0.5);
+    assert!((at_forward.re - 1.0).abs() < 1e-12 && at_forward.im.abs() < 1e-12);
+
+    let strikes = [80.0, 100.0, 120.0];
+    let fft = CarrMadan::default().prices(&model, OptionKind::Call, 100.0, &strikes, 0.5, 0.99);
+    for (strike, price) in strikes.iter().zip(fft.iter()) {
+        let expected = fourier_price(&model, OptionKind::Call, 100.0, *strike, 0.5, 0.99);
+        assert!(
+            (price - expected).abs() < 1e-3,
+            "{} {} {}",
+            strike,
+            price,
+            expected
+        );
+    }
+
+    // negative correlation skews the smile down
+    let vols = model.volatilities(&[0.8, 1.0, 1.2], 0.5);
+    assert!(vols[0].unwrap() > vols[1].unwrap() && vols[1].unwrap() > vols[2].unwrap());
+}
+
+#[test]
+fn test_variance_gamma() {
+    let model = VarianceGamma::new(0.2, 0.3, -0.15);
+    let at_forward = model.characteristic(Complex::new(0.0, -1.0), 0.5);
+    assert!((at_forward.re - 1.0).abs() < 1e-12 && at_forward.im.abs() < 1e-12);
+
+    let strikes = [80.0, 100.0, 120.0];
+    let fft = CarrMadan::default().prices(&model, OptionKind::Put, 100.0, &strikes, 0.5, 0.99);
+    for (strike, price) in strikes.iter().zip(fft.iter()) {
+        let expected = model.price(OptionKind::Put, 100.0, *strike, 0.5, 0.99);
+        assert!(
+            (price - expected).abs() < 1e-3,
+            "{} {} {}",
+            strike,
+            price,
+            expected
+        );
+    }
+
+    // negative drift on the gamma clock skews the smile down
+    let vols = model.volatilities(&[0.8, 1.0, 1.2], 0.5);
+    assert!(vols[0].unwrap() > vols[1].unwrap() && vols[1].unwrap() > vols[2].unwrap());
+
+    let moneyness: Vec<f64> = (0..21).map(|i| 0.8 + 0.02 * i as f64).collect();
+    let smile: Vec<(f64, f64)> = moneyness
+        .iter()
+        .zip(model.volatilities(&moneyness, 0.5))
+        .map(|(m, v)| (*m, v.unwrap()))
+        .collect();
+    let fit = ModelFit::of(&VarianceGamma::calibrate(&smile, 0.5).unwrap(), &smile, 0.5);
+    assert!(fit.rmse < 1e-3, "{} {:?}", fit.rmse, fit.parameters);
+}
+
+#[test]
+fn test_fit_variance_gamma() {
+    // symmetric: no skew, and kurtosis 3ν / t
+    let symmetric = VarianceGamma::new(0.2, 0.3, 0.0);
+    assert!((symmetric.variance_rate() - 0.04).abs() < 1e-15);
+    assert_eq!(symmetric.skewness(0.5), 0.0);
+    assert!((symmetric.excess_kurtosis(0.5) - 1.8).abs() < 1e-12);
+    assert!(VarianceGamma::new(0.2, 0.3, -0.2).skewness(0.5) < 0.0);
+
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec {
+        skew: Skew::new(-0.3, 1.5),
+        strike_range: 3.0,
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&spec, now, 1));
+    let analytics = chain.analytics(spec.spot, &YieldCurve::flat(spec.risk_free_rate), now);
+    let expiry = &analytics.expiries[0];
+    let fit = fit_variance_gamma(expiry).unwrap();
+    let flat = ModelFit::of(
+        &FlatVolatility::calibrate(&expiry.smile()).unwrap(),
+        &expiry.smile(),
+        expiry.time_to_expiration,
+    );
+    assert!(
+        fit.fit.rmse < 0.5 * flat.rmse,
+        "{} {}",
+        fit.fit.rmse,
+        flat.rmse
+    );
+    assert!(
+        fit.skewness < 0.0 && fit.excess_kurtosis > 0.0,
+        "{:?}",
+        fit.model
+    );
+}
+
+#[test]
+fn test_price_expiry() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec {
+        strike_interval: 100,
+        ..SurfaceSpec::default()
+    };
+    let options = generate_chain(&spec, now, 1);
+    let by_expiry = options_math::group_options_by_expiry(&options);
+    let expiry = by_expiry.values().next().unwrap();
+    let (forward, t, discount) = (spec.spot as f64, 23.0 / 365.0, 0.999);
+
+    // hundreds of strikes from one transform, each within a cent of its own integral
+    let model = heston();
+    // a finer strike grid than the default, since the expiry is only weeks away
+    let pricer = CarrMadan {
+        points: 16_384,
+        ..CarrMadan::default()
+    };
+    let prices = pricer.price_expiry(&model, expiry, forward, t, discount);
+    assert_eq!(prices.len(), expiry.calls().len() + expiry.puts().len());
+    assert!(prices.len() > 200, "{}", prices.len());
+    for (contract, price) in prices.iter() {
+        let expected = model.price(
+            contract.kind(),
+            forward,
+            contract.strike() as f64,
+            t,
+            discount,
+        );
+        assert!(
+            (price - expected).abs() < 1.0,
+            "{:?} {} {}",
+            contract,
+            price,
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_calibration_recovers_generating_model() {
+    let t = 0.5;
+    let moneyness: Vec<f64> = (0..21).map(|i| 0.8 + 0.02 * i as f64).collect();
+
+    let svi = Svi::new(0.01, 0.1, -0.4, 0.02, 0.15);
+    let smile: Vec<(f64, f64)> = moneyness
+        .iter()
+        .zip(svi.volatilities(&moneyness, t))
+        .map(|(m, v)| (*m, v.unwrap()))
+        .collect();
+    let fit = ModelFit::of(&Svi::calibrate(&smile, t).unwrap(), &smile, t);
+    assert!(fit.rmse < 1e-4, "{:?}", fit.parameters);
+
+    let smile: Vec<(f64, f64)> = moneyness
+        .iter()
+        .zip(heston().volatilities(&moneyness, t))
+        .map(|(m, v)| (*m, v.unwrap()))
+        .collect();
+    let fit = ModelFit::of(&Heston::calibrate(&smile, t).unwrap(), &smile, t);
+    assert!(fit.rmse < 1e-3, "{} {:?}", fit.rmse, fit.parameters);
+}
+
+#[test]
+fn test_compare_models() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec {
+        skew: Skew::new(-0.3, 1.0),
+        strike_range: 3.0,
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&spec, now, 1));
+    let analytics = chain.analytics(spec.spot, &YieldCurve::flat(spec.risk_free_rate), now);
+
+    let report = compare_models(&analytics.expiries[1], 1.0).unwrap();
+    let names: Vec<&str> = report.fits.iter().map(|f| f.name).collect();
+    assert_eq!(names, vec!["Black-Scholes", "SVI", "SABR", "Heston"]);
+    let flat = &report.fits[0];
+    for fit in report.fits.iter().skip(1) {
+        assert!(
+            fit.rmse < flat.rmse,
+            "{} {} {}",
+            fit.name,
+            fit.rmse,
+            flat.rmse
+        );
+        assert!(fit.wing_rmse.unwrap() < flat.wing_rmse.unwrap());
+        assert_eq!(fit.fitted.len(), analytics.expiries[1].smile().len());
+        assert!(!fit.density.is_empty());
+    }
+    assert_ne!(report.best().unwrap().name, "Black-Scholes");
+    assert!(report.fits[1].density.iter().all(|(_, d)| *d > -1e-6));
+}
+
+#[test]
+fn test_heston_pricing_and_variance() {
+    use options_math::math::black_price;
+
+    // with almost no volatility of variance Heston is Black–Scholes
+    let flat = Heston::new(0.04, 2.0, 0.04, 1e-4, 0.0);
+    for strike in [80.0, 100.0, 125.0].iter() {
+        let price = flat.price(OptionKind::Put, 100.0, *strike, 1.0, 0.95);
+        let expected = black_price(OptionKind::Put, 100.0, *strike, 0.2, 1.0, 0.95);
+        assert!((price - expected).abs() < 1e-4, "{} {}", price, expected);
+    }
+    assert!((flat.expected_variance(1.0) - 0.04).abs() < 1e-12);
+
+    // variance drifts from v0 towards theta
+    let model = heston();
+    assert!((model.expected_variance(0.0) - model.v0).abs() < 1e-12);
+    assert!(model.expected_variance(0.5) > model.v0);
+    assert!(model.expected_variance(50.0) < model.theta);
+    assert!((model.expected_variance(1e6) - model.theta).abs() < 1e-6);
+
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec {
+        strike_range: 3.0,
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&spec, now, 1));
+    let analytics = chain.analytics(spec.spot, &YieldCurve::flat(spec.risk_free_rate), now);
+    let fit = fit_heston(&chain.expiries()[1], &analytics.expiries[1], now).unwrap();
+    assert!(fit.fit.rmse < 0.005, "{:?}", fit.fit.parameters);
+    assert!((fit.model_variance - 0.04).abs() < 0.004, "{:?}", fit);
+    assert!(fit.variance_spread().abs() < 0.004, "{:?}", fit);
+}
+
+#[test]
+fn test_sabr_slice() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec {
+        skew: Skew::new(-0.3, 1.0),
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&spec, now, 1));
+    let expiry = &chain.expiries()[1];
+
+    let fixed = SabrSlice::calibrate(expiry, spec.risk_free_rate, now, Some(1.0)).unwrap();
+    assert_eq!(fixed.model.beta, 1.0);
+    let free = SabrSlice::calibrate(expiry, spec.risk_free_rate, now, None).unwrap();
+    assert!(free.model.beta >= 0.0 && free.model.beta <= 1.0);
+    assert!(
+        free.rmse <= fixed.rmse + 1e-4,
+        "{} {}",
+        free.rmse,
+        fixed.rmse
+    );
+    assert!(free.rmse < 0.02, "{:?}", free);
+
+    // an unlisted strike between two listed ones prices between them
+    let calls = expiry.calls();
+    let (low, high) = calls
+        .windows(2)
+        .map(|w| (w[0], w[1]))
+        .find(|(a, _)| a.strike() >= free.forward_price)
+        .unwrap();
+    let between = (low.strike() + high.strike()) / 2;
+    let price = free.price(OptionKind::Call, between);
+    assert!(price < free.price(OptionKind::Call, low.strike()));
+    assert!(price > free.price(OptionKind::Call, high.strike()));
+    // and the smile is skewed like the chain
+    assert!(free.volatility(low.strike() * 9 / 10) > free.volatility(low.strike()));
+}
+
+#[test]
+fn test_jump_diffusion() {
+    use options_math::math::black_price;
+
+    let merton = JumpDiffusion::merton(0.15, 2.0, -0.1, 0.1);
+    let kou = JumpDiffusion::new(
+        0.15,
+        2.0,
+        JumpSize::DoubleExponential {
+            up_probability: 0.3,
+            up_rate: 20.0,
+            down_rate: 10.0,
+        },
+    );
+    for model in [merton, kou].iter() {
+        let at_forward = model.characteristic(Complex::new(0.0, -1.0), 0.5);
+        assert!((at_forward.re - 1.0).abs() < 1e-12 && at_forward.im.abs() < 1e-12);
+        // downward jumps skew the smile down
+        let vols = model.volatilities(&[0.8, 1.0, 1.2], 0.25);
+        assert!(vols[0].unwrap() > vols[1].unwrap(), "{:?}", vols);
+    }
+
+    // without jumps it is Black–Scholes
+    let diffusion = JumpDiffusion::merton(0.2, 0.0, -0.1, 0.1);
+    let price = diffusion.price(OptionKind::Put, 100.0, 90.0, 0.5, 0.98);
+    let expected = black_price(OptionKind::Put, 100.0, 90.0, 0.2, 0.5, 0.98);
+    assert!((price - expected).abs() < 1e-6, "{} {}", price, expected);
+
+    // Merton's series: Black prices conditional on the number of jumps, weighted by their
+    // Poisson probabilities
+    let (forward, t, df): (f64, f64, f64) = (100.0, 0.5, 0.98);
+    let (lambda, mean, delta) = (2.0, -0.1, 0.1);
+    let kappa = (mean + delta * delta / 2.0f64).exp() - 1.0;
+    for strike in [80.0, 100.0, 120.0].iter() {
+        let mut expected = 0.0;
+        let mut probability = (-lambda * t).exp();
+        for n in 0..40 {
+            if n > 0 {
+                probability *= lambda * t / n as f64;
+            }
+            let n = n as f64;
+            let vol = (0.15f64.powi(2) + n * delta * delta / t).sqrt();
+            let f_n = forward * (n * (mean + delta * delta / 2.0) - lambda * kappa * t).exp();
+            expected += probability * black_price(OptionKind::Call, f_n, *strike, vol, t, df);
+        }
+        let price = merton.price(OptionKind::Call, forward, *strike, t, df);
+        assert!(
+            (price - expected).abs() < 1e-6,
+            "{} {} {}",
+            strike,
+            price,
+            expected
+        );
+    }
+    assert!((merton.total_variance_rate() - (0.0225 + 2.0 * 0.02)).abs() < 1e-12);
+
+    let moneyness: Vec<f64> = (0..21).map(|i| 0.8 + 0.02 * i as f64).collect();
+    let smile: Vec<(f64, f64)> = moneyness
+        .iter()
+        .zip(merton.volatilities(&moneyness, 0.25))
+        .map(|(m, v)| (*m, v.unwrap()))
+        .collect();
+    let fit = ModelFit::of(
+        &JumpDiffusion::calibrate(&smile, 0.25).unwrap(),
+        &smile,
+        0.25,
+    );
+    assert_eq!(fit.name, "Merton");
+    assert!(fit.rmse < 1e-3, "{} {:?}", fit.rmse, fit.parameters);
+}
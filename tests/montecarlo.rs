@@ -0,0 +1,90 @@
+use chrono::prelude::*;
+use options_math::montecarlo::*;
+use options_math::pricing::black_scholes_price;
+use options_math::*;
+
+#[test]
+fn test_vanilla_matches_black_scholes() {
+    let mc = MonteCarlo::new(50_000, 1, 7);
+    for kind in [OptionKind::Call, OptionKind::Put].iter() {
+        let estimate = mc.price(&Vanilla::new(*kind, 10_000), 10_000, 0.05, 0.01, 0.2, 1.0);
+        let exact = black_scholes_price(*kind, 10_000, 10_000, 0.05, 0.01, 0.2, 1.0) as f64;
+        assert!(
+            (estimate.price - exact).abs() < 4.0 * estimate.standard_error,
+            "{:?} {}",
+            estimate,
+            exact
+        );
+    }
+
+    // the same seed gives the same price
+    let payoff = Vanilla::new(OptionKind::Call, 10_500);
+    assert_eq!(
+        mc.price(&payoff, 10_000, 0.05, 0.0, 0.2, 1.0),
+        mc.price(&payoff, 10_000, 0.05, 0.0, 0.2, 1.0)
+    );
+    assert_ne!(
+        mc.price(&payoff, 10_000, 0.05, 0.0, 0.2, 1.0),
+        MonteCarlo::new(50_000, 1, 8).price(&payoff, 10_000, 0.05, 0.0, 0.2, 1.0)
+    );
+
+    let now = NaiveDate::from_ymd_opt(2021, 1, 4)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let contract = OptionContract::new(
+        now + chrono::Duration::days(365),
+        10_000,
+        OptionKind::Call,
+        1_000,
+        1_100,
+    );
+    assert_eq!(
+        mc.price_contract(&contract, 10_000, 0.05, 0.01, 0.2, now),
+        mc.price(
+            &Vanilla::new(OptionKind::Call, 10_000),
+            10_000,
+            0.05,
+            0.01,
+            0.2,
+            1.0
+        )
+    );
+}
+
+#[test]
+fn test_path_dependent_payoffs() {
+    let mc = MonteCarlo::new(20_000, 50, 1);
+    let vanilla = mc.price(
+        &Vanilla::new(OptionKind::Call, 10_000),
+        10_000,
+        0.05,
+        0.0,
+        0.2,
+        1.0,
+    );
+    // averaging dampens volatility, so the Asian option is cheaper
+    let asian = mc.price(
+        &ArithmeticAsian::new(OptionKind::Call, 10_000),
+        10_000,
+        0.05,
+        0.0,
+        0.2,
+        1.0,
+    );
+    assert!(
+        asian.price < vanilla.price * 0.7,
+        "{:?} {:?}",
+        asian,
+        vanilla
+    );
+
+    // closures are payoffs too: an up-and-out call can only be worth less
+    let knock_out = |path: &[f64]| -> f64 {
+        if path.iter().any(|p| *p >= 12_000.0) {
+            return 0.0;
+        }
+        return (path[path.len() - 1] - 10_000.0).max(0.0);
+    };
+    let barrier = mc.price(&knock_out, 10_000, 0.05, 0.0, 0.2, 1.0);
+    assert!(barrier.price > 0.0 && barrier.price < vanilla.price);
+}
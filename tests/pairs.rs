@@ -0,0 +1,82 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::pairs::*;
+use options_math::rates::YieldCurve;
+use options_math::series::IndexPoint;
+use options_math::synthetic::*;
+
+fn at(minutes: i64) -> NaiveDateTime {
+    return NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap()
+        + chrono::Duration::minutes(minutes);
+}
+
+fn point(minutes: i64, value: Option<f64>) -> IndexPoint {
+    return IndexPoint {
+        at: at(minutes),
+        value,
+        estimate: None,
+    };
+}
+
+#[test]
+fn test_index_spread() {
+    let first = vec![
+        point(0, Some(20.0)),
+        point(1, Some(21.0)),
+        point(2, None),
+        point(3, Some(22.0)),
+        point(4, Some(30.0)),
+    ];
+    let second = vec![
+        point(1, Some(15.0)),
+        point(2, Some(15.0)),
+        point(3, Some(16.0)),
+        point(4, Some(16.0)),
+        point(5, Some(16.0)),
+    ];
+    let pairs = align_indices(&first, &second);
+    assert_eq!(
+        pairs.iter().map(|p| p.at).collect::<Vec<_>>(),
+        vec![at(1), at(3), at(4)]
+    );
+    assert_eq!(pairs[0].spread(), 6.0);
+    assert_eq!(pairs[1].ratio(), 22.0 / 16.0);
+
+    // the spread jumped from 6 to 14 at the last point
+    let z = rolling_spread_zscore(&pairs, 3);
+    assert_eq!(z.len(), 1);
+    let (mean, variance) = (
+        26.0 / 3.0,
+        ((8.0f64 / 3.0).powi(2) * 2.0 + (16.0f64 / 3.0).powi(2)) / 2.0,
+    );
+    assert_eq!(z[0].0, at(4));
+    assert!((z[0].1 - (14.0 - mean) / variance.sqrt()).abs() < 1e-12);
+
+    // a spread that never moves has no z-score
+    assert!(rolling_spread_zscore(&pairs[..2], 2).is_empty());
+}
+
+#[test]
+fn test_surface_spread() {
+    let now = at(0);
+    let rates = YieldCurve::flat(0.01);
+    let analytics = |atm_vol: f64| {
+        let spec = SurfaceSpec {
+            expiries: vec![
+                SyntheticExpiry::new(23, atm_vol),
+                SyntheticExpiry::new(37, atm_vol),
+            ],
+            ..SurfaceSpec::default()
+        };
+        let chain = Chain::new(&generate_chain(&spec, now, 1));
+        return chain.analytics(spec.spot, &rates, now);
+    };
+    let spreads = surface_spread(&analytics(0.3), &analytics(0.2), &[30.0, 90.0]);
+    assert_eq!(spreads.len(), 2);
+    for tenor in spreads.iter() {
+        assert!((tenor.spread() - 10.0).abs() < 0.5, "{:?}", tenor);
+        assert!((tenor.ratio() - 1.5).abs() < 0.05, "{:?}", tenor);
+    }
+}
@@ -0,0 +1,121 @@
+use chrono::prelude::*;
+use options_math::greeks::black_scholes_greeks;
+use options_math::pricer::*;
+use options_math::pricing::{AmericanApproximation, BinomialTree};
+use options_math::*;
+
+fn now() -> NaiveDateTime {
+    return NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+}
+
+/**
+ * Black–Scholes with the default finite-difference Greeks.
+ */
+struct Differenced;
+
+impl Pricer for Differenced {
+    fn price(&self, contract: &OptionContract, market: &Market, volatility: f64) -> Cents {
+        return BlackScholes.price(contract, market, volatility);
+    }
+}
+
+#[test]
+fn test_pricers() {
+    let expires_at = now() + chrono::Duration::days(182);
+    let put = OptionContract::new(expires_at, 11_000, OptionKind::Put, 0, 0);
+    let american = put.with_exercise_style(ExerciseStyle::American);
+    let market = Market::new(10_000, 0.05, 0.0, now());
+
+    let expected = put.black_scholes_price(10_000, 0.05, 0.0, 0.3, now());
+    assert_eq!(BlackScholes.price(&put, &market, 0.3), expected);
+    assert_eq!(
+        Black76.price(&put, &market, 0.3),
+        put.black76_price(10_000, 0.05, 0.3, now())
+    );
+
+    // models swap without touching the call site
+    let pricers: Vec<Box<dyn Pricer>> = vec![
+        Box::new(BlackScholes),
+        Box::new(BinomialTree::default()),
+        Box::new(AmericanApproximation::BaroneAdesiWhaley),
+        Box::new(ByExerciseStyle::default()),
+    ];
+    let prices: Vec<Cents> = pricers
+        .iter()
+        .map(|p| p.price(&american, &market, 0.3))
+        .collect();
+    assert!(prices[1] > expected);
+    assert!((prices[2] - prices[1]).abs() <= 15);
+    assert_eq!(prices[3], prices[2]);
+
+    // the tree honours the exercise style, and so does the dispatch
+    let tree = BinomialTree::new(500);
+    assert!((Pricer::price(&tree, &put, &market, 0.3) - expected).abs() <= 1);
+    assert_eq!(
+        ByExerciseStyle::default().price(&put, &market, 0.3),
+        expected
+    );
+
+    // differences of prices in whole cents need a large underlying for a stable gamma
+    let put = OptionContract::new(expires_at, 330_000, OptionKind::Put, 0, 0);
+    let market = Market::new(300_000, 0.05, 0.0, now());
+    let t = 182.0 / 365.0;
+    let analytic = black_scholes_greeks(OptionKind::Put, 3000.0, 3300.0, 0.05, 0.0, 0.3, t);
+    assert_eq!(BlackScholes.greeks(&put, &market, 0.3), analytic);
+    let numeric = Differenced.greeks(&put, &market, 0.3);
+    let close = |a: f64, b: f64| (a - b).abs() < 0.05 * b.abs();
+    assert!(close(numeric.delta, analytic.delta));
+    assert!(close(numeric.gamma, analytic.gamma));
+    assert!(close(numeric.vega, analytic.vega));
+    assert!(close(numeric.theta, analytic.theta));
+    assert!(close(numeric.rho, analytic.rho));
+
+    // a tree's gamma oscillates as the spot moves between its nodes, but not its delta
+    let numeric = tree.greeks(&put, &market, 0.3);
+    assert!(close(numeric.delta, analytic.delta));
+    assert!(close(numeric.vega, analytic.vega));
+
+    // Black-76 on a futures price is Black–Scholes on an asset yielding the rate
+    let futures = Market::new(300_000, 0.05, 0.05, now());
+    let black = Black76.greeks(&put, &market, 0.3);
+    let numeric = BinomialTree::new(500).greeks(&put, &futures, 0.3);
+    assert!((black.delta - numeric.delta).abs() < 0.01);
+    let price = Black76.price(&put, &market, 0.3) as f64 / 100.0;
+    assert!((black.rho + t * price).abs() < 1e-9);
+}
+
+#[test]
+fn test_bumped_greeks() {
+    let expires_at = now() + chrono::Duration::days(182);
+    let put = OptionContract::new(expires_at, 330_000, OptionKind::Put, 0, 0);
+    let market = Market::new(300_000, 0.05, 0.0, now());
+    let analytic = BlackScholes.greeks(&put, &market, 0.3);
+    let close = |a: f64, b: f64| (a - b).abs() < 0.05 * b.abs();
+
+    let bumped = Bumped::new(BlackScholes, Bumps::default());
+    assert_eq!(
+        bumped.price(&put, &market, 0.3),
+        BlackScholes.price(&put, &market, 0.3)
+    );
+    assert_eq!(
+        bumped.greeks(&put, &market, 0.3),
+        Differenced.greeks(&put, &market, 0.3)
+    );
+
+    // bumping the spot across several nodes smooths out a tree's gamma
+    let tree = BinomialTree::new(500);
+    let wide = Bumps {
+        spot: 0.05,
+        time: chrono::Duration::days(5),
+        ..Bumps::default()
+    };
+    let numeric = Bumped::new(tree, wide).greeks(&put, &market, 0.3);
+    assert!(close(numeric.delta, analytic.delta));
+    assert!(close(numeric.gamma, analytic.gamma));
+    assert!(close(numeric.vega, analytic.vega));
+    assert!(close(numeric.theta, analytic.theta));
+    assert!(close(numeric.rho, analytic.rho));
+    assert_eq!(wide.greeks(&tree, &put, &market, 0.3), numeric);
+}
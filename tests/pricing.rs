@@ -0,0 +1,446 @@
+use chrono::prelude::*;
+use options_math::pricing::*;
+use options_math::*;
+
+#[test]
+fn test_black_scholes_price() {
+    // Hull's textbook example values
+    assert_eq!(
+        black_scholes_price(OptionKind::Call, 10_000, 10_000, 0.05, 0.0, 0.2, 1.0),
+        1045
+    );
+    assert_eq!(
+        black_scholes_price(OptionKind::Put, 10_000, 10_000, 0.05, 0.0, 0.2, 1.0),
+        557
+    );
+    // a dividend yield lowers calls and raises puts
+    assert!(black_scholes_price(OptionKind::Call, 10_000, 10_000, 0.05, 0.03, 0.2, 1.0) < 1045);
+    assert!(black_scholes_price(OptionKind::Put, 10_000, 10_000, 0.05, 0.03, 0.2, 1.0) > 557);
+    assert_eq!(
+        black_scholes_price(OptionKind::Put, 10_000, 12_000, 0.05, 0.0, 0.2, 0.0),
+        2_000
+    );
+
+    let now = NaiveDate::from_ymd_opt(2021, 1, 4)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let contract = OptionContract::new(
+        now + chrono::Duration::days(365),
+        10_000,
+        OptionKind::Call,
+        1_000,
+        1_100,
+    );
+    assert_eq!(
+        contract.black_scholes_price(10_000, 0.05, 0.0, 0.2, now),
+        1045
+    );
+    assert_eq!(
+        contract.black_scholes_price(10_000, 0.05, 0.0, 0.2, now + chrono::Duration::days(400)),
+        0
+    );
+}
+
+#[test]
+fn test_binomial_tree() {
+    let tree = BinomialTree::default();
+    // without dividends an American call is worth the European
+    let call = tree.price(OptionKind::Call, 10_000, 10_000, 0.05, 0.0, 0.2, 1.0);
+    assert!((call - 1045).abs() <= 1, "{}", call);
+    // an American put is worth more than the European
+    let put = tree.price(OptionKind::Put, 10_000, 10_000, 0.05, 0.0, 0.2, 1.0);
+    assert!(put > 557 + 20, "{}", put);
+    // deep in the money it is exercised immediately
+    assert_eq!(
+        tree.price(OptionKind::Put, 5_000, 10_000, 0.05, 0.0, 0.2, 1.0),
+        5_000
+    );
+    // more steps converge
+    let coarse = BinomialTree::new(10).price(OptionKind::Put, 10_000, 10_000, 0.05, 0.0, 0.2, 1.0);
+    let fine = BinomialTree::new(1000).price(OptionKind::Put, 10_000, 10_000, 0.05, 0.0, 0.2, 1.0);
+    assert!((fine - put).abs() < (coarse - put).abs() + 1);
+    assert!((fine - 609).abs() <= 1, "{}", fine);
+
+    let now = NaiveDate::from_ymd_opt(2021, 1, 4)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let contract = OptionContract::new(
+        now + chrono::Duration::days(365),
+        10_000,
+        OptionKind::Put,
+        600,
+        620,
+    );
+    assert_eq!(
+        tree.price_contract(&contract, 10_000, 0.05, 0.0, 0.2, now),
+        put
+    );
+}
+
+#[test]
+fn test_trinomial_tree() {
+    let trinomial = LatticePricer::new(Lattice::Trinomial, 200);
+    let put = trinomial.price(OptionKind::Put, 10_000, 10_000, 0.05, 0.2, 1.0);
+    assert!((put - 609).abs() <= 1, "{}", put);
+    let european = trinomial.clone().with_exercise(Exercise::European).price(
+        OptionKind::Put,
+        10_000,
+        10_000,
+        0.05,
+        0.2,
+        1.0,
+    );
+    assert!((european - 557).abs() <= 1, "{}", european);
+
+    // the trinomial tree gets closer with fewer steps
+    let binomial = LatticePricer::new(Lattice::Binomial, 50)
+        .with_exercise(Exercise::European)
+        .price(OptionKind::Call, 10_000, 10_500, 0.05, 0.2, 1.0);
+    let trinomial = LatticePricer::new(Lattice::Trinomial, 50)
+        .with_exercise(Exercise::European)
+        .price(OptionKind::Call, 10_000, 10_500, 0.05, 0.2, 1.0);
+    let exact = black_scholes_price(OptionKind::Call, 10_000, 10_500, 0.05, 0.0, 0.2, 1.0);
+    assert!((trinomial - exact).abs() <= (binomial - exact).abs());
+}
+
+#[test]
+fn test_lattice_dividends() {
+    let pricer = LatticePricer::new(Lattice::Trinomial, 200);
+    assert_eq!(
+        pricer.clone().with_dividends(Dividends::Yield(0.0)).price(
+            OptionKind::Put,
+            10_000,
+            10_000,
+            0.05,
+            0.2,
+            1.0
+        ),
+        pricer
+            .clone()
+            .with_dividends(Dividends::Cash(vec![]))
+            .price(OptionKind::Put, 10_000, 10_000, 0.05, 0.2, 1.0)
+    );
+
+    // a large dividend makes it worth exercising a call just before it is paid
+    let dividends = Dividends::Cash(vec![(0.5, 1_000)]);
+    let american = pricer.clone().with_dividends(dividends.clone()).price(
+        OptionKind::Call,
+        10_000,
+        9_000,
+        0.05,
+        0.2,
+        1.0,
+    );
+    let european = pricer
+        .with_exercise(Exercise::European)
+        .with_dividends(dividends)
+        .price(OptionKind::Call, 10_000, 9_000, 0.05, 0.2, 1.0);
+    assert!(american > european + 50, "{} {}", american, european);
+
+    // the binomial tree keeps pricing through the same lattice
+    assert_eq!(
+        BinomialTree::new(100).price(OptionKind::Put, 10_000, 10_000, 0.05, 0.02, 0.2, 1.0),
+        LatticePricer::new(Lattice::Binomial, 100)
+            .with_dividends(Dividends::Yield(0.02))
+            .price(OptionKind::Put, 10_000, 10_000, 0.05, 0.2, 1.0)
+    );
+}
+
+#[test]
+fn test_exercise_boundary() {
+    let pricer = LatticePricer::new(Lattice::Binomial, 500);
+    let boundary = pricer.exercise_boundary(OptionKind::Put, 10_000, 10_000, 0.05, 0.2, 1.0);
+    assert!(!boundary.points.is_empty());
+    // the critical price rises toward the strike as expiration nears
+    let now = boundary.critical_price(0.0).unwrap();
+    let near_expiry = boundary.critical_price(0.99).unwrap();
+    assert!(now > 7_500.0 && now < 9_000.0, "{}", now);
+    assert!(
+        near_expiry > 9_500.0 && near_expiry < 10_000.0,
+        "{}",
+        near_expiry
+    );
+    assert!(boundary.points.windows(2).all(|w| w[0].0 < w[1].0));
+
+    // past the boundary the put is worth its intrinsic value, and above it more
+    let deep = (now - 200.0) as Cents;
+    assert!(boundary.should_exercise(deep, 0.0));
+    assert_eq!(
+        pricer.price(OptionKind::Put, deep, 10_000, 0.05, 0.2, 1.0),
+        10_000 - deep
+    );
+    let shallow = (now + 200.0) as Cents;
+    assert!(!boundary.should_exercise(shallow, 0.0));
+    assert!(pricer.price(OptionKind::Put, shallow, 10_000, 0.05, 0.2, 1.0) > 10_000 - shallow);
+
+    // calls without dividends and European options are never exercised early
+    let call = pricer.exercise_boundary(OptionKind::Call, 10_000, 10_000, 0.05, 0.2, 1.0);
+    assert!(call.points.is_empty());
+    assert_eq!(call.critical_price(0.5), None);
+    assert!(!call.should_exercise(20_000, 0.5));
+    let european = pricer
+        .clone()
+        .with_exercise(Exercise::European)
+        .exercise_boundary(OptionKind::Put, 10_000, 10_000, 0.05, 0.2, 1.0);
+    assert!(european.points.is_empty());
+
+    // a call with a large dividend is exercised only just before it is paid
+    let call = pricer
+        .with_dividends(Dividends::Cash(vec![(0.5, 1_000)]))
+        .exercise_boundary(OptionKind::Call, 10_000, 9_000, 0.05, 0.2, 1.0);
+    assert!(!call.points.is_empty());
+    assert!(
+        call.points.iter().all(|(t, _)| *t < 0.5 && *t > 0.45),
+        "{:?}",
+        call
+    );
+}
+
+#[test]
+fn test_normal_model() {
+    use options_math::math::*;
+
+    // at the money the Bachelier price is σ√t/√(2π)
+    let atm = bachelier_price(OptionKind::Call, 100.0, 100.0, 20.0, 1.0, 1.0);
+    assert!((atm - 20.0 / (2.0 * std::f64::consts::PI).sqrt()).abs() < 1e-6);
+    // negative forwards and strikes are fine
+    let put = bachelier_price(OptionKind::Put, -0.5, 0.25, 0.8, 2.0, 0.97);
+    let call = bachelier_price(OptionKind::Call, -0.5, 0.25, 0.8, 2.0, 0.97);
+    assert!((call - put - 0.97 * (-0.5 - 0.25)).abs() < 1e-12);
+    let vol = implied_normal_volatility(OptionKind::Put, put, -0.5, 0.25, 2.0, 0.97).unwrap();
+    assert!((vol - 0.8).abs() < 1e-8, "{}", vol);
+    for strike in [60.0, 100.0, 180.0].iter() {
+        let price = bachelier_price(OptionKind::Call, 100.0, *strike, 35.0, 0.5, 0.99);
+        let vol = implied_normal_volatility(OptionKind::Call, price, 100.0, *strike, 0.5, 0.99);
+        assert!((vol.unwrap() - 35.0).abs() < 1e-6, "{} {:?}", strike, vol);
+    }
+    assert_eq!(
+        implied_normal_volatility(OptionKind::Call, 10.0, 100.0, 90.0, 0.5, 1.0),
+        None
+    );
+
+    // contracts pick their model
+    let now = NaiveDate::from_ymd_opt(2021, 1, 4)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let contract = OptionContract::new(
+        now + chrono::Duration::days(365),
+        10_000,
+        OptionKind::Call,
+        700,
+        800,
+    );
+    assert_eq!(
+        contract.model_price(10_000, 0.05, 0.0, 0.2, now),
+        contract.black_scholes_price(10_000, 0.05, 0.0, 0.2, now)
+    );
+    let normal = contract.with_volatility_model(VolatilityModel::Normal);
+    assert_eq!(normal.volatility_model(), VolatilityModel::Normal);
+    assert_eq!(
+        normal.model_price(10_000, 0.05, 0.0, 2_000.0, now),
+        normal_price(OptionKind::Call, 10_000, 10_000, 0.05, 0.0, 2_000.0, 1.0)
+    );
+}
+
+#[test]
+fn test_barone_adesi_whaley() {
+    let tree = BinomialTree::new(1000);
+    for (kind, spot, strike, r, q, vol, t) in [
+        (OptionKind::Put, 10_000, 10_000, 0.05, 0.0, 0.2, 1.0),
+        (OptionKind::Put, 9_000, 10_000, 0.08, 0.0, 0.3, 0.25),
+        (OptionKind::Put, 11_000, 10_000, 0.05, 0.02, 0.25, 0.5),
+        (OptionKind::Call, 10_000, 10_000, 0.03, 0.08, 0.2, 0.5),
+        (OptionKind::Call, 11_000, 10_000, 0.03, 0.1, 0.3, 0.25),
+    ]
+    .iter()
+    {
+        let approx = barone_adesi_whaley_price(*kind, *spot, *strike, *r, *q, *vol, *t);
+        let exact = tree.price(*kind, *spot, *strike, *r, *q, *vol, *t);
+        assert!(
+            (approx - exact).abs() <= 10,
+            "{:?} {} {}: {} {}",
+            kind,
+            spot,
+            strike,
+            approx,
+            exact
+        );
+        assert!(approx >= black_scholes_price(*kind, *spot, *strike, *r, *q, *vol, *t));
+    }
+
+    // without dividends the call is European
+    assert_eq!(
+        barone_adesi_whaley_price(OptionKind::Call, 10_000, 10_000, 0.05, 0.0, 0.2, 1.0),
+        1045
+    );
+    // deep in the money it is exercised
+    assert_eq!(
+        barone_adesi_whaley_price(OptionKind::Put, 5_000, 10_000, 0.05, 0.0, 0.2, 1.0),
+        5_000
+    );
+}
+
+#[test]
+fn test_bjerksund_stensland() {
+    use options_math::math::*;
+
+    // independent normals factor; perfectly correlated ones reduce to the smaller tail
+    assert!((bivariate_norm_cdf(0.3, -0.4, 0.0) - norm_cdf(0.3) * norm_cdf(-0.4)).abs() < 1e-14);
+    assert!((bivariate_norm_cdf(0.0, 0.0, 0.5) - 1.0 / 3.0).abs() < 1e-14);
+    assert!((bivariate_norm_cdf(0.5, 1.0, 0.999_999) - norm_cdf(0.5)).abs() < 1e-4);
+    for rho in [-0.95, -0.5, 0.2, 0.8, 0.95].iter() {
+        // P(X < x, Y < y) + P(X < x, Y >= y) = P(X < x)
+        let total = bivariate_norm_cdf(0.7, -0.2, *rho) + bivariate_norm_cdf(0.7, 0.2, -*rho);
+        assert!((total - norm_cdf(0.7)).abs() < 1e-12, "{}", rho);
+    }
+
+    let tree = BinomialTree::new(2000);
+    for (kind, spot, strike, r, q, vol, t) in [
+        (OptionKind::Put, 10_000, 10_000, 0.05, 0.0, 0.2, 1.0),
+        (OptionKind::Put, 10_000, 11_000, 0.06, 0.0, 0.3, 3.0),
+        (OptionKind::Put, 9_000, 10_000, 0.08, 0.02, 0.25, 5.0),
+        (OptionKind::Call, 10_000, 10_000, 0.03, 0.08, 0.2, 0.5),
+        (OptionKind::Call, 11_000, 10_000, 0.03, 0.1, 0.3, 3.0),
+    ]
+    .iter()
+    {
+        let exact = tree.price(*kind, *spot, *strike, *r, *q, *vol, *t);
+        let approx = bjerksund_stensland_price(*kind, *spot, *strike, *r, *q, *vol, *t);
+        assert!(
+            (approx - exact).abs() <= 10,
+            "{:?} {} {}: {} {}",
+            kind,
+            spot,
+            t,
+            approx,
+            exact
+        );
+        assert_eq!(
+            AmericanApproximation::BjerksundStensland
+                .price(*kind, *spot, *strike, *r, *q, *vol, *t),
+            approx
+        );
+    }
+
+    // without dividends the call is European, and deep in the money the put is exercised
+    assert_eq!(
+        bjerksund_stensland_price(OptionKind::Call, 10_000, 10_000, 0.05, 0.0, 0.2, 1.0),
+        1045
+    );
+    assert_eq!(
+        bjerksund_stensland_price(OptionKind::Put, 5_000, 10_000, 0.05, 0.0, 0.2, 1.0),
+        5_000
+    );
+}
+
+#[test]
+fn test_black76() {
+    // Hull's example of a put on a futures price of 20 struck at 20
+    assert_eq!(
+        black76_price(OptionKind::Put, 2_000, 2_000, 0.09, 0.25, 4.0 / 12.0),
+        112
+    );
+    // Black-Scholes with the futures price as spot and a dividend yield equal to the rate
+    for kind in [OptionKind::Call, OptionKind::Put].iter() {
+        assert_eq!(
+            black76_price(*kind, 7_500, 8_000, 0.04, 0.35, 0.5),
+            black_scholes_price(*kind, 7_500, 8_000, 0.04, 0.04, 0.35, 0.5)
+        );
+    }
+
+    // quotes on a futures priced at 75.00 imply that forward for the expiry
+    let now = NaiveDate::from_ymd_opt(2021, 1, 4)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let expires_at = now + chrono::Duration::days(60);
+    let t = 60.0 / 365.0;
+    let options: Vec<OptionContract> = (60..=90)
+        .step_by(5)
+        .flat_map(|strike| {
+            let strike = strike * 100;
+            return [OptionKind::Call, OptionKind::Put].map(|kind| {
+                let price = black76_price(kind, 7_500, strike, 0.03, 0.3, t);
+                return OptionContract::new(expires_at, strike, kind, price - 1, price + 1);
+            });
+        })
+        .collect();
+    let chain = chain::Chain::new(&options);
+    let expiry = &chain.expiries()[0];
+    assert!((expiry.forward_price(0.03, now) - 7_500).abs() <= 1);
+    let price = expiry.black76_price(OptionKind::Call, 8_200, 0.03, 0.3, now);
+    assert!((price - black76_price(OptionKind::Call, 7_500, 8_200, 0.03, 0.3, t)).abs() <= 1);
+    assert_eq!(
+        options[0].black76_price(7_500, 0.03, 0.3, now),
+        black76_price(OptionKind::Call, 7_500, 6_000, 0.03, 0.3, t)
+    );
+}
+
+#[test]
+fn test_garman_kohlhagen() {
+    let market = FxMarket::new(1.085, 0.05, 0.035);
+    let (strike, vol, t) = (1.1, 0.08, 0.5);
+    let call = garman_kohlhagen_price(OptionKind::Call, 1.085, strike, 0.05, 0.035, vol, t);
+    let put = market.price(OptionKind::Put, strike, vol, t);
+    // put-call parity with both currencies' discount factors
+    let parity = 1.085 * (-0.035 * t).exp() - strike * (-0.05 * t).exp();
+    assert!((call - put - parity).abs() < 1e-12);
+    // the foreign rate plays the dividend yield
+    assert_eq!(
+        (call * 1e6).round() as Cents,
+        black_scholes_price(OptionKind::Call, 1_085_000, 1_100_000, 0.05, 0.035, vol, t)
+    );
+
+    let delta = |kind, convention| market.delta(kind, strike, vol, t, convention);
+    let foreign_discount = (-0.035 * t).exp();
+    let spot = delta(OptionKind::Call, DeltaConvention::Spot);
+    assert!(
+        (spot - delta(OptionKind::Call, DeltaConvention::Forward) * foreign_discount).abs() < 1e-12
+    );
+    assert!(
+        (spot - delta(OptionKind::Put, DeltaConvention::Spot) - foreign_discount).abs() < 1e-12
+    );
+    // premium adjustment takes out the premium, as a fraction of spot
+    let adjusted = delta(OptionKind::Call, DeltaConvention::PremiumAdjustedSpot);
+    assert!((adjusted - (spot - call / 1.085)).abs() < 1e-12);
+    let adjusted_put = delta(OptionKind::Put, DeltaConvention::PremiumAdjustedSpot);
+    assert!(
+        (adjusted_put - (delta(OptionKind::Put, DeltaConvention::Spot) - put / 1.085)).abs()
+            < 1e-12
+    );
+
+    for convention in [
+        DeltaConvention::Spot,
+        DeltaConvention::Forward,
+        DeltaConvention::PremiumAdjustedSpot,
+        DeltaConvention::PremiumAdjustedForward,
+    ]
+    .iter()
+    {
+        for (kind, target) in [(OptionKind::Call, 0.25), (OptionKind::Put, -0.25)].iter() {
+            let strike = market
+                .strike_for_delta(*kind, *target, vol, t, *convention)
+                .unwrap();
+            let delta = market.delta(*kind, strike, vol, t, *convention);
+            assert!((delta - target).abs() < 1e-9, "{:?} {:?}", convention, kind);
+        }
+    }
+    // 25-delta calls are above the forward and puts below it
+    let forward = market.forward(t);
+    assert!(
+        market
+            .strike_for_delta(OptionKind::Call, 0.25, vol, t, DeltaConvention::Spot)
+            .unwrap()
+            > forward
+    );
+    // premium-adjusted call deltas never reach one
+    assert_eq!(
+        market.strike_for_delta(
+            OptionKind::Call,
+            0.99,
+            vol,
+            t,
+            DeltaConvention::PremiumAdjustedForward
+        ),
+        None
+    );
+}
@@ -0,0 +1,64 @@
+use chrono::prelude::*;
+use options_math::publish::*;
+
+fn at() -> NaiveDateTime {
+    return NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+}
+
+#[test]
+fn test_publish_to_memory() {
+    let mut metadata = Metadata::new();
+    metadata.insert("near_term".to_string(), "2020-01-24".to_string());
+
+    let mut published: Vec<Publication> = vec![];
+    published.publish("VIX", at(), 13.25, &metadata).unwrap();
+    published
+        .publish("VIX9D", at(), f64::NAN, &Metadata::new())
+        .unwrap();
+    assert_eq!(published.len(), 2);
+    assert_eq!(published[0].metadata, metadata);
+    assert_eq!(
+        published[0].to_json(),
+        "{\"symbol\":\"VIX\",\"timestamp\":\"2020-01-02T09:30:00\",\"value\":13.25,\"metadata\":{\"near_term\":\"2020-01-24\"}}"
+    );
+    assert!(published[1].to_json().contains("\"value\":null"));
+}
+
+#[cfg(feature = "redis")]
+#[test]
+fn test_publish_to_redis() {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = std::thread::spawn(move || -> Vec<String> {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let count: usize = line.trim()[1..].parse().unwrap();
+        let mut args = vec![];
+        for _ in 0..count {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+            let len: usize = line.trim()[1..].parse().unwrap();
+            let mut arg = vec![0; len + 2];
+            reader.read_exact(&mut arg).unwrap();
+            args.push(String::from_utf8(arg[..len].to_vec()).unwrap());
+        }
+        (&stream).write_all(b":1\r\n").unwrap();
+        return args;
+    });
+
+    let mut publisher = RedisPublisher::connect(address, "index:").unwrap();
+    publisher
+        .publish("VIX", at(), 13.25, &Metadata::new())
+        .unwrap();
+    let args = server.join().unwrap();
+    assert_eq!(args[0], "PUBLISH");
+    assert_eq!(args[1], "index:VIX");
+    assert!(args[2].contains("\"value\":13.25"));
+}
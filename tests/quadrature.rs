@@ -0,0 +1,117 @@
+use options_math::fourier::*;
+use options_math::math::black_price;
+use options_math::math::quadrature::*;
+use options_math::models::Heston;
+use options_math::OptionKind;
+
+#[test]
+fn test_gauss_lobatto() {
+    let sin = gauss_lobatto(|x: f64| x.sin(), 0.0, std::f64::consts::PI, 1e-12);
+    assert!((sin - 2.0).abs() < 1e-12, "{}", sin);
+    // a sharp peak needs the adaptivity
+    let peak = gauss_lobatto(|x: f64| 1.0 / (1e-4 + x * x), -1.0, 1.0, 1e-10);
+    let exact = 2.0 * 100.0 * (100.0f64).atan();
+    assert!((peak - exact).abs() / exact < 1e-9, "{} {}", peak, exact);
+    let gaussian = gauss_lobatto_semi_infinite(|x: f64| (-x * x).exp(), 0.0, 1e-12);
+    assert!((gaussian - std::f64::consts::PI.sqrt() / 2.0).abs() < 1e-10);
+}
+
+#[test]
+fn test_gauss_laguerre() {
+    let rule = GaussLaguerre::new(32);
+    assert_eq!(rule.nodes().len(), 32);
+    assert!((rule.weights().iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    // ∫ x^5 e^{-x} = 5!
+    assert!((rule.integrate_weighted(|x| x.powi(5)) - 120.0).abs() < 1e-9);
+    let decaying = rule.integrate(|x: f64| (-2.0 * x).exp() * (3.0 * x).cos());
+    assert!((decaying - 2.0 / 13.0).abs() < 1e-6, "{}", decaying);
+}
+
+#[test]
+fn test_fourier_price_matches_black() {
+    let model = Lognormal::new(0.25);
+    for strike in [70.0, 90.0, 100.0, 110.0, 140.0].iter() {
+        for kind in [OptionKind::Call, OptionKind::Put].iter() {
+            let expected = black_price(*kind, 100.0, *strike, 0.25, 0.5, 0.98);
+            let price = fourier_price(&model, *kind, 100.0, *strike, 0.5, 0.98);
+            assert!(
+                (price - expected).abs() < 1e-8,
+                "{} {:?} {} {}",
+                strike,
+                kind,
+                price,
+                expected
+            );
+        }
+    }
+}
+
+#[test]
+fn test_carr_madan_matches_black() {
+    let model = Lognormal::new(0.3);
+    let pricer = CarrMadan::default();
+    let grid = pricer.call_prices(&model, 100.0, 1.0, 0.95);
+    assert_eq!(grid.len(), pricer.points);
+    for (strike, price) in grid.iter().filter(|(k, _)| *k > 50.0 && *k < 200.0) {
+        let expected = black_price(OptionKind::Call, 100.0, *strike, 0.3, 1.0, 0.95);
+        assert!(
+            (price - expected).abs() < 1e-6,
+            "{} {} {}",
+            strike,
+            price,
+            expected
+        );
+    }
+
+    let strikes = [60.0, 95.0, 100.0, 105.0, 150.0];
+    let puts = pricer.prices(&model, OptionKind::Put, 100.0, &strikes, 1.0, 0.95);
+    for (strike, price) in strikes.iter().zip(puts.iter()) {
+        let expected = fourier_price(&model, OptionKind::Put, 100.0, *strike, 1.0, 0.95);
+        assert!(
+            (price - expected).abs() < 1e-3,
+            "{} {} {}",
+            strike,
+            price,
+            expected
+        );
+    }
+    assert!(pricer.prices(&model, OptionKind::Call, 100.0, &[1e-12], 1.0, 0.95)[0].is_nan());
+}
+
+#[test]
+fn test_cos_method() {
+    let pricer = Cos::default();
+    let model = Lognormal::new(0.3);
+    for t in [0.02, 0.5, 3.0].iter() {
+        for strike in [50.0, 90.0, 100.0, 110.0, 200.0].iter() {
+            for kind in [OptionKind::Call, OptionKind::Put].iter() {
+                let price = pricer.price(&model, *kind, 100.0, *strike, *t, 0.95);
+                let expected = black_price(*kind, 100.0, *strike, 0.3, *t, 0.95);
+                assert!(
+                    (price - expected).abs() < 1e-7,
+                    "{} {} {:?} {} {}",
+                    t,
+                    strike,
+                    kind,
+                    price,
+                    expected
+                );
+            }
+        }
+    }
+
+    // agrees with the integral under a model with a skew
+    let heston = Heston::new(0.04, 1.5, 0.05, 0.6, -0.7);
+    let strikes = [70.0, 100.0, 130.0];
+    let prices = pricer.prices(&heston, OptionKind::Put, 100.0, &strikes, 0.5, 0.99);
+    for (strike, price) in strikes.iter().zip(prices.iter()) {
+        let expected = fourier_price(&heston, OptionKind::Put, 100.0, *strike, 0.5, 0.99);
+        assert!(
+            (price - expected).abs() < 1e-6,
+            "{} {} {}",
+            strike,
+            price,
+            expected
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::methodology::Preset;
+use options_math::rates::YieldCurve;
+use options_math::series::*;
+use options_math::sparse::*;
+use options_math::synthetic::*;
+use options_math::*;
+
+#[test]
+fn test_index_quality_metadata() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec::default();
+    let chain = Chain::new(&generate_chain(&spec, now, 1));
+    let rates = YieldCurve::flat(spec.risk_free_rate);
+    let config = IndexConfig::default();
+
+    let (near, next) = select_terms(&chain, now, &config).unwrap();
+    let estimate = compute_vix_estimate(near, next, 0.01, 0.01, now, &config);
+    assert_eq!(
+        estimate.value,
+        compute_vix_with_config(near, next, 0.01, 0.01, now, &config)
+    );
+    for term in [estimate.near_term, estimate.next_term].iter() {
+        assert!(term.strikes > 100, "{:?}", term);
+        assert!(term.lowest_strike.unwrap() < spec.spot);
+        assert!(term.highest_strike.unwrap() > spec.spot);
+        assert!(term.spreads.mean_relative_spread > 0.0);
+        assert!(term.spreads.max_spread >= spec.spread.fixed);
+        assert_eq!(term.fallback, None);
+    }
+    assert!(!estimate.low_quality());
+    assert_eq!(
+        estimate.strikes(),
+        estimate.near_term.strikes + estimate.next_term.strikes
+    );
+
+    // series points carry the estimate behind each value
+    let series = compute_vix_series(&[(now, &chain)], &rates, &config);
+    assert_eq!(series[0].estimate, Some(estimate));
+
+    // the Cboe truncation never sums more strikes
+    let vix = Preset::CboeVix
+        .methodology()
+        .estimate(&chain, &rates, now)
+        .unwrap();
+    assert!(vix.near_term.strikes <= estimate.near_term.strikes);
+
+    let demanding = IndexConfig {
+        sparse: Some(SparseChain::new(10_000, Fallback::Flag)),
+        ..IndexConfig::default()
+    };
+    let flagged = compute_vix_estimate(near, next, 0.01, 0.01, now, &demanding);
+    assert!(flagged.low_quality() && flagged.fallback_applied());
+    assert_eq!(flagged.value, estimate.value);
+}
@@ -0,0 +1,53 @@
+use chrono::prelude::*;
+use options_math::math::Rng;
+use options_math::realized::*;
+use options_math::returns::LogReturn;
+
+fn simulated_returns(n: usize, vols: &[f64]) -> Vec<LogReturn> {
+    let mut rng = Rng::new(11);
+    let start = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+    (0..n)
+        .map(|i| LogReturn {
+            date: start + chrono::Duration::days(i as i64),
+            value: rng.next_normal() * vols[i * vols.len() / n] / TRADING_DAYS.sqrt(),
+            periods: 1,
+        })
+        .collect()
+}
+
+#[test]
+fn test_realized_volatility() {
+    let returns = simulated_returns(5_000, &[0.2]);
+    let vol = realized_volatility(&returns).unwrap();
+    assert!((vol - 0.2).abs() < 0.01, "{}", vol);
+    assert_eq!(realized_volatility(&returns[..1]), None);
+}
+
+#[test]
+fn test_vol_cone() {
+    // a calm year followed by a turbulent one
+    let returns = simulated_returns(504, &[0.1, 0.3]);
+    let cone = vol_cone(&returns, &CONE_HORIZONS, &[0.1, 0.5, 0.9]);
+    assert_eq!(cone.len(), CONE_HORIZONS.len());
+
+    for band in cone.iter() {
+        let values: Vec<f64> = band.percentiles.iter().map(|p| p.1).collect();
+        assert!(
+            values[0] <= values[1] && values[1] <= values[2],
+            "{:?}",
+            band
+        );
+    }
+    // short horizons see both regimes, the longest mostly averages them
+    let short = &cone[0];
+    let long = cone.last().unwrap();
+    assert!(
+        short.percentiles[2].1 - short.percentiles[0].1
+            > long.percentiles[2].1 - long.percentiles[0].1
+    );
+    assert!(short.rank(0.35) > 0.8);
+    assert!(short.rank(0.05) < 0.05);
+    assert!((long.current - 0.3).abs() < 0.05, "{:?}", long.current);
+
+    assert!(vol_cone(&returns[..20], &[21], &[0.5]).is_empty());
+}
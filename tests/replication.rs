@@ -0,0 +1,120 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::synthetic::*;
+
+fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap()
+}
+
+#[test]
+fn test_static_replication() {
+    let spec = SurfaceSpec::default();
+    let chain = Chain::new(&generate_chain(&spec, now(), 1));
+    let expiry = &chain.expiries()[1];
+    let r = spec.risk_free_rate;
+    let t = expiry.time_to_expiration(now());
+    let discount = (-r * t).exp();
+
+    // the underlying itself is a forward: no options needed
+    let linear = expiry.replicate(|s| s, r, now()).unwrap();
+    assert!(linear.weights.iter().all(|(_, w)| w.abs() < 1e-6));
+    assert!((linear.price - discount * linear.forward_price as f64).abs() < 1e-6);
+
+    // the variance swap's log payoff prices the flat surface's variance
+    let forward = linear.forward_price as f64;
+    let log = expiry
+        .replicate(
+            |s| 2.0 / t * (s / forward - 1.0 - (s / forward).ln()),
+            r,
+            now(),
+        )
+        .unwrap();
+    assert_eq!(log.expansion_strike, linear.expansion_strike);
+    let variance = log.price / discount;
+    assert!((variance / 0.04 - 1.0).abs() < 0.01, "{}", variance);
+
+    // the second moment of a flat lognormal surface
+    let square = expiry.replicate(|s| s * s, r, now()).unwrap();
+    let expected = discount * forward * forward * (0.04 * t).exp();
+    assert!(
+        (square.price / expected - 1.0).abs() < 1e-3,
+        "{} {}",
+        square.price,
+        expected
+    );
+    // convex payoffs hold no option short
+    assert!(square.weights.iter().all(|(_, w)| *w >= 0.0));
+}
+
+#[test]
+fn test_moment_swaps() {
+    let spec = SurfaceSpec::default();
+    let chain = Chain::new(&generate_chain(&spec, now(), 1));
+    let expiry = &chain.expiries()[1];
+    let r = spec.risk_free_rate;
+    let t = expiry.time_to_expiration(now());
+    let discount = (-r * t).exp();
+
+    let power = |n: f64| expiry.power_price(n, r, now()).unwrap();
+    assert!((power(1.0) / discount - 1.0).abs() < 1e-6);
+    assert!((power(2.0) / discount - (0.04 * t).exp()).abs() < 1e-3);
+
+    // log returns of a flat surface are normal
+    let moments = expiry.implied_moments(r, now()).unwrap();
+    assert!((moments.mean + 0.02 * t).abs() < 1e-4, "{:?}", moments);
+    assert!((moments.variance() / (0.04 * t) - 1.0).abs() < 0.01);
+    assert!(moments.skewness().abs() < 0.05, "{}", moments.skewness());
+    assert!(
+        (moments.kurtosis() - 3.0).abs() < 0.1,
+        "{}",
+        moments.kurtosis()
+    );
+
+    // a downward sloping smile fattens the left tail
+    let skewed = SurfaceSpec {
+        skew: Skew::new(-0.4, 0.5),
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&skewed, now(), 1));
+    let moments = chain.expiries()[1].implied_moments(r, now()).unwrap();
+    assert!(moments.skewness() < -0.1, "{}", moments.skewness());
+    assert!(moments.kurtosis() > 3.0, "{}", moments.kurtosis());
+}
+
+#[test]
+fn test_gamma_swap() {
+    let spec = SurfaceSpec::default();
+    let chain = Chain::new(&generate_chain(&spec, now(), 1));
+    let expiry = &chain.expiries()[1];
+    let r = spec.risk_free_rate;
+    let t = expiry.time_to_expiration(now());
+    let discount = (-r * t).exp();
+
+    // price weighting makes no difference without a skew
+    let gamma = expiry.gamma_variance(r, now());
+    let variance = expiry.variance(r, now());
+    assert!(
+        (gamma / variance - 1.0).abs() < 1e-3,
+        "{} {}",
+        gamma,
+        variance
+    );
+
+    // replicating the gamma swap's payoff directly gives the flat surface's variance
+    let forward = expiry.forward_price(r, now()) as f64;
+    let replicated = expiry
+        .replicate(|s| 2.0 / t * (s / forward) * (s / forward).ln(), r, now())
+        .unwrap();
+    assert!((replicated.price / discount / 0.04 - 1.0).abs() < 0.01);
+
+    // and it leans less on the expensive downside than variance does
+    let skewed = SurfaceSpec {
+        skew: Skew::new(-0.4, 0.0),
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&skewed, now(), 1));
+    let expiry = &chain.expiries()[1];
+    assert!(expiry.gamma_variance(r, now()) < expiry.variance(r, now()));
+}
@@ -0,0 +1,81 @@
+use chrono::prelude::*;
+use options_math::analytics::SmileMarking;
+use options_math::chain::Chain;
+use options_math::rates::YieldCurve;
+use options_math::resample::*;
+use options_math::synthetic::*;
+use options_math::*;
+
+#[test]
+fn test_resample_onto_moneyness_grid() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec {
+        skew: Skew::new(-0.3, 0.0),
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&spec, now, 1));
+    let analytics = chain.analytics(spec.spot, &YieldCurve::flat(spec.risk_free_rate), now);
+
+    let resampled = resample(&analytics, &MoneynessGrid::default());
+    assert_eq!(resampled.len(), 2);
+    for (expiry, synthetic_expiry) in resampled.iter().zip(spec.expiries.iter()) {
+        assert_eq!(expiry.points.len(), 41);
+        let forward = spec.forward(synthetic_expiry);
+        for point in expiry.points.iter() {
+            let vol = match point.implied_volatility {
+                Some(vol) => vol,
+                None => continue,
+            };
+            let expected = spec.volatility(synthetic_expiry, point.moneyness * forward);
+            assert!((vol - expected).abs() < 0.01, "{:?}", point);
+            assert!(point.call.unwrap() >= 0.0 && point.put.unwrap() >= 0.0);
+        }
+        let atm = &expiry.points[20];
+        assert!((atm.moneyness - 1.0).abs() < 1e-9);
+        assert!(atm.implied_volatility.is_some());
+    }
+}
+
+#[test]
+fn test_blended_marking_dampens_a_noisy_quote() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec::default();
+    let noisy_strike = 297_500;
+    let options: Vec<OptionContract> = generate_chain(&spec, now, 1)
+        .into_iter()
+        .map(|o| {
+            if o.strike() == noisy_strike && o.kind() == OptionKind::Put {
+                // a stale put quote, well above the surface
+                return OptionContract::new(
+                    o.expires_at(),
+                    o.strike(),
+                    o.kind(),
+                    o.bid() + 300,
+                    o.ask() + 300,
+                );
+            }
+            o
+        })
+        .collect();
+    let chain = Chain::new(&options);
+    let analytics = chain.analytics(spec.spot, &YieldCurve::flat(spec.risk_free_rate), now);
+
+    for expiry in analytics.expiries.iter() {
+        let moneyness = noisy_strike as f64 / expiry.forward_price as f64;
+        let vol_at = |marking: SmileMarking| -> f64 {
+            expiry
+                .smile_marked(marking)
+                .into_iter()
+                .find(|p| (p.0 - moneyness).abs() < 1e-9)
+                .unwrap()
+                .1
+        };
+        let blended_error = (vol_at(SmileMarking::Blended) - 0.2).abs();
+        let otm_error = (vol_at(SmileMarking::OutOfTheMoney) - 0.2).abs();
+        assert!(blended_error < otm_error, "{} {}", blended_error, otm_error);
+    }
+}
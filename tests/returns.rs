@@ -0,0 +1,55 @@
+use chrono::prelude::*;
+use options_math::holidays::Nyse;
+use options_math::returns::*;
+
+fn date(m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2023, m, d).unwrap()
+}
+
+#[test]
+fn test_log_returns() {
+    let prices = [
+        Observation::new(date(6, 26), 10_000),
+        Observation::new(date(6, 27), 10_100),
+        // 2-for-1 split effective June 28
+        Observation::new(date(6, 28), 5_100),
+        // $1 dividend, ex-date June 29
+        Observation::new(date(6, 29), 5_000),
+        // missing June 30 and July 3, and July 4 is a holiday
+        Observation::new(date(7, 5), 5_200),
+        Observation::new(date(7, 6), 0),
+        Observation::new(date(7, 7), 5_300),
+    ];
+    let actions = [
+        CorporateAction::Split {
+            ex_date: date(6, 28),
+            ratio: 2.0,
+        },
+        CorporateAction::Dividend {
+            ex_date: date(6, 29),
+            amount: 100,
+        },
+    ];
+
+    let returns = log_returns(&prices, &actions, &Nyse, GapPolicy::Include);
+    let values: Vec<f64> = returns.iter().map(|r| r.value).collect();
+    let expected = [
+        (10_100.0f64 / 10_000.0).ln(),
+        (10_200.0f64 / 10_100.0).ln(),
+        (5_100.0f64 / 5_100.0).ln(),
+        (5_200.0f64 / 5_000.0).ln(),
+    ];
+    assert_eq!(values.len(), expected.len());
+    for (v, e) in values.iter().zip(expected.iter()) {
+        assert!((v - e).abs() < 1e-12, "{} {}", v, e);
+    }
+    assert_eq!(returns[3].periods, 3);
+
+    let skipped = log_returns(&prices, &actions, &Nyse, GapPolicy::Skip);
+    assert_eq!(skipped.len(), 3);
+    let scaled = log_returns(&prices, &actions, &Nyse, GapPolicy::Scale);
+    assert!((scaled[3].value - expected[3] / 3f64.sqrt()).abs() < 1e-12);
+    assert_eq!(scaled[3].periods, 1);
+
+    assert_eq!(simple_log_returns(&[100.0, 0.0, 100.0]), vec![None, None]);
+}
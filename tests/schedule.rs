@@ -0,0 +1,75 @@
+use chrono::prelude::*;
+use options_math::holidays::*;
+use options_math::schedule::*;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn test_monthly_and_quarterly_expirations() {
+    assert_eq!(monthly_expiration(2023, 3, &Cboe), date(2023, 3, 17));
+    // Good Friday 2022 was the third Friday of April
+    assert_eq!(monthly_expiration(2022, 4, &Cboe), date(2022, 4, 14));
+    // Juneteenth observed on Friday, June 18 2027
+    assert_eq!(monthly_expiration(2027, 6, &Cboe), date(2027, 6, 17));
+    assert_eq!(quarterly_expiration(2023, 9, &Cboe), date(2023, 9, 29));
+
+    assert_eq!(
+        classify(date(2022, 4, 14), &Cboe),
+        Some(ExpirationCycle::Monthly)
+    );
+    assert_eq!(
+        classify(date(2023, 3, 31), &Cboe),
+        Some(ExpirationCycle::Quarterly)
+    );
+    assert_eq!(
+        classify(date(2023, 3, 24), &Cboe),
+        Some(ExpirationCycle::Weekly)
+    );
+    assert_eq!(classify(date(2023, 3, 23), &Cboe), None);
+}
+
+#[test]
+fn test_expirations() {
+    let listed = expirations(
+        date(2023, 3, 1),
+        date(2023, 4, 30),
+        &[
+            ExpirationCycle::Monthly,
+            ExpirationCycle::Quarterly,
+            ExpirationCycle::Weekly,
+        ],
+        &Cboe,
+    );
+    let dates: Vec<(NaiveDate, ExpirationCycle)> =
+        listed.iter().map(|e| (e.date, e.cycle)).collect();
+    assert_eq!(
+        dates,
+        vec![
+            (date(2023, 3, 3), ExpirationCycle::Weekly),
+            (date(2023, 3, 10), ExpirationCycle::Weekly),
+            (date(2023, 3, 17), ExpirationCycle::Monthly),
+            (date(2023, 3, 24), ExpirationCycle::Weekly),
+            (date(2023, 3, 31), ExpirationCycle::Quarterly),
+            (date(2023, 4, 6), ExpirationCycle::Weekly),
+            (date(2023, 4, 14), ExpirationCycle::Weekly),
+            (date(2023, 4, 21), ExpirationCycle::Monthly),
+            (date(2023, 4, 28), ExpirationCycle::Weekly),
+        ]
+    );
+    assert_eq!(
+        listed[0].at_close(&Cboe),
+        date(2023, 3, 3).and_hms_opt(16, 15, 0).unwrap()
+    );
+
+    let leaps = expirations(
+        date(2023, 3, 1),
+        date(2026, 1, 31),
+        &[ExpirationCycle::Leaps],
+        &Cboe,
+    );
+    // January 2024 is within a year of listing
+    let dates: Vec<NaiveDate> = leaps.iter().map(|e| e.date).collect();
+    assert_eq!(dates, vec![date(2025, 1, 17), date(2026, 1, 16)]);
+}
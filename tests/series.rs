@@ -0,0 +1,233 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::rates::YieldCurve;
+use options_math::series::*;
+use options_math::synthetic::*;
+use options_math::*;
+
+#[test]
+fn test_vix_series() {
+    let open = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec::default();
+    let chain = Chain::new(&generate_chain(&spec, open, 1));
+    let config = IndexConfig::default();
+
+    let snapshots: Vec<(NaiveDateTime, &Chain)> = (0..390)
+        .map(|minute| (open + chrono::Duration::minutes(minute), &chain))
+        .collect();
+    let series = compute_vix_series(&snapshots, &YieldCurve::flat(spec.risk_free_rate), &config);
+    assert_eq!(series.len(), 390);
+    for point in series.iter() {
+        let (near, next) = select_terms(&chain, point.at, &config).unwrap();
+        let expected = compute_vix_with_config(
+            near,
+            next,
+            spec.risk_free_rate,
+            spec.risk_free_rate,
+            point.at,
+            &config,
+        );
+        assert_eq!(point.value, Some(expected));
+    }
+    assert!(
+        (series[0].value.unwrap() - 20.0).abs() < 1.0,
+        "{:?}",
+        series[0]
+    );
+
+    // once both expiries are within 30 days there is nothing to interpolate between
+    let later = open + chrono::Duration::days(8);
+    let series = compute_vix_series(&[(later, &chain)], &YieldCurve::flat(0.01), &config);
+    assert_eq!(series[0].value, None);
+}
+
+#[test]
+fn test_smoothing_and_outliers() {
+    let open = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let raw = [20.0, 20.2, 19.9, 35.0, 20.1, 20.0, 25.0, 25.1, 24.9, 25.0];
+    let series: Vec<IndexPoint> = raw
+        .iter()
+        .enumerate()
+        .map(|(i, v)| IndexPoint {
+            at: open + chrono::Duration::seconds(i as i64),
+            value: Some(*v),
+            estimate: None,
+        })
+        .collect();
+    let values = |config| -> Vec<f64> {
+        smooth(&series, config)
+            .iter()
+            .map(|p| p.value.unwrap())
+            .collect()
+    };
+
+    assert_eq!(values(SmoothingConfig::default()), raw.to_vec());
+
+    // the single-tick spike is dropped, the level shift gets through after a few ticks
+    let rejecting = SmoothingConfig {
+        smoothing: Smoothing::None,
+        outliers: Some(OutlierRejection::new(5, 0.1)),
+    };
+    assert_eq!(
+        values(rejecting),
+        vec![20.0, 20.2, 19.9, 19.9, 20.1, 20.0, 20.0, 20.0, 24.9, 25.0]
+    );
+
+    let median = values(SmoothingConfig {
+        smoothing: Smoothing::Median { window: 3 },
+        outliers: None,
+    });
+    assert_eq!(median[3], 20.2);
+    assert_eq!(median[4], 20.1);
+
+    let ewma = values(SmoothingConfig {
+        smoothing: Smoothing::Ewma { alpha: 0.5 },
+        outliers: None,
+    });
+    assert_eq!(ewma[1], 20.1);
+    assert!((ewma[3] - 27.5).abs() < 0.1);
+
+    let mut smoother = IndexSmoother::new(SmoothingConfig::default());
+    smoother.push(series[0]);
+    let missing = smoother.push(IndexPoint {
+        at: open,
+        value: None,
+        estimate: None,
+    });
+    assert_eq!(missing.value, Some(20.0));
+}
+
+#[test]
+fn test_missing_term_policies() {
+    let open = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec::default();
+    let chain = Chain::new(&generate_chain(&spec, open, 1));
+    let rates = YieldCurve::flat(spec.risk_free_rate);
+    // both expiries are within 30 days
+    let later = open + chrono::Duration::days(8);
+    let with = |missing_term| IndexConfig {
+        missing_term,
+        ..IndexConfig::default()
+    };
+
+    let single = compute_vix_series(&[(later, &chain)], &rates, &with(MissingTerm::SingleExpiry));
+    let estimate = single[0].estimate.unwrap();
+    assert_eq!(estimate.term_fallback, Some(MissingTerm::SingleExpiry));
+    let last = chain.expiries().last().unwrap();
+    let expected = last.variance(spec.risk_free_rate, later).sqrt() * 100.0;
+    assert!((single[0].value.unwrap() - expected).abs() < 1e-9);
+
+    let extrapolated =
+        compute_vix_series(&[(later, &chain)], &rates, &with(MissingTerm::Extrapolate));
+    let estimate = extrapolated[0].estimate.unwrap();
+    assert_eq!(estimate.term_fallback, Some(MissingTerm::Extrapolate));
+    assert!((estimate.value - 20.0).abs() < 5.0, "{:?}", estimate);
+
+    // an interpolated value is not flagged
+    let normal = compute_vix_series(&[(open, &chain)], &rates, &with(MissingTerm::Extrapolate));
+    assert_eq!(normal[0].estimate.unwrap().term_fallback, None);
+
+    // a next term without quotes falls back to the near term
+    let near = &chain.expiries()[0];
+    let unquoted = Chain::new(
+        &chain.expiries()[1]
+            .calls()
+            .iter()
+            .chain(chain.expiries()[1].puts())
+            .map(|o| o.with_quote(0, 0))
+            .collect::<Vec<_>>(),
+    );
+    let next = &unquoted.expiries()[0];
+    let estimate = compute_vix_estimate(
+        near,
+        next,
+        spec.risk_free_rate,
+        spec.risk_free_rate,
+        open,
+        &with(MissingTerm::SingleExpiry),
+    );
+    assert_eq!(estimate.term_fallback, Some(MissingTerm::SingleExpiry));
+    assert_eq!(estimate.next_term.strikes, 0);
+    assert!(
+        (estimate.value - near.variance(spec.risk_free_rate, open).sqrt() * 100.0).abs() < 1e-9
+    );
+    assert!(compute_vix_estimate(
+        near,
+        next,
+        spec.risk_free_rate,
+        spec.risk_free_rate,
+        open,
+        &IndexConfig::default(),
+    )
+    .value
+    .is_nan());
+}
+
+#[test]
+fn test_rolls() {
+    let open = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(10, 0, 0))
+        .unwrap();
+    let spec = SurfaceSpec::default();
+    let chain = Chain::new(&generate_chain(&spec, open, 1));
+    // a day later an expiry with a richer volatility is listed between the terms
+    let listed = SurfaceSpec {
+        expiries: vec![
+            SyntheticExpiry::new(23, 0.2),
+            SyntheticExpiry::new(33, 0.3),
+            SyntheticExpiry::new(37, 0.2),
+        ],
+        ..SurfaceSpec::default()
+    };
+    let listed = Chain::new(&generate_chain(&listed, open, 1));
+    let rates = YieldCurve::flat(spec.risk_free_rate);
+    let config = IndexConfig::default();
+    let snapshots: Vec<(NaiveDateTime, &Chain)> = (0..48)
+        .map(|hour| {
+            let at = open + chrono::Duration::hours(hour);
+            return (at, if hour < 24 { &chain } else { &listed });
+        })
+        .collect();
+
+    let naive = compute_vix_series_with_rolls(&snapshots, &rates, &config, &RollConfig::default());
+    assert_eq!(
+        naive.points,
+        compute_vix_series(&snapshots, &rates, &config),
+        "without a blend the series is unchanged"
+    );
+    assert_eq!(naive.rolls.len(), 1);
+    let roll = naive.rolls[0];
+    assert_eq!(roll.at, open + chrono::Duration::hours(24));
+    assert_eq!(roll.from.next, open + chrono::Duration::days(37));
+    assert_eq!(roll.to.next, open + chrono::Duration::days(33));
+    assert_eq!(roll.from.near, roll.to.near);
+    assert_eq!(roll.value, naive.points[24].value);
+    assert!(roll.jump().unwrap() > 1.0, "{:?}", roll);
+
+    let blend = chrono::Duration::hours(6);
+    let blended = compute_vix_series_with_rolls(
+        &snapshots,
+        &rates,
+        &config,
+        &RollConfig { blend: Some(blend) },
+    );
+    assert_eq!(blended.rolls, naive.rolls);
+    // the blend starts from the previous terms and moves steadily to the new ones
+    assert_eq!(blended.points[24].value, roll.previous_value);
+    for hour in 25..30 {
+        let (previous, value) = (blended.points[hour - 1].value, blended.points[hour].value);
+        assert!(value > previous && value < naive.points[hour].value);
+    }
+    for (point, raw) in blended.points.iter().zip(naive.points.iter()) {
+        if point.at < roll.at || point.at - roll.at >= blend {
+            assert_eq!(point.value, raw.value);
+        }
+        assert_eq!(point.estimate, raw.estimate);
+    }
+}
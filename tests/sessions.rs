@@ -0,0 +1,87 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::holidays::Nyse;
+use options_math::sessions::*;
+use options_math::*;
+
+fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+    return NaiveDate::from_ymd_opt(y, m, d)
+        .and_then(|d| d.and_hms_opt(h, min, 0))
+        .unwrap();
+}
+
+#[test]
+fn test_sessions() {
+    let spx = TradingHours::spx();
+
+    let regular = spx.session_at(at(2023, 7, 11, 10, 0)).unwrap();
+    assert_eq!(regular.session, Session::Regular);
+    assert_eq!(regular.end, at(2023, 7, 11, 16, 15));
+    assert_eq!(regular.minutes_remaining(at(2023, 7, 11, 10, 0)), 375);
+
+    // global trading hours run overnight into the next trading day
+    let overnight = spx.session_at(at(2023, 7, 10, 21, 0)).unwrap();
+    assert_eq!(overnight.session, Session::Extended);
+    assert_eq!(overnight.start, at(2023, 7, 10, 20, 15));
+    assert_eq!(overnight.end, at(2023, 7, 11, 9, 15));
+    assert_eq!(
+        spx.session_at(at(2023, 7, 11, 8, 0)).unwrap().start,
+        at(2023, 7, 10, 20, 15)
+    );
+    assert!(
+        spx.is_open(at(2023, 7, 16, 21, 0)),
+        "Sunday evening trades for Monday"
+    );
+
+    // closed between sessions, into weekends and holidays, and after early closes
+    assert!(!spx.is_open(at(2023, 7, 11, 9, 20)));
+    assert!(!spx.is_open(at(2023, 7, 11, 16, 30)));
+    assert!(!spx.is_open(at(2023, 7, 14, 21, 0)));
+    assert!(!spx.is_open(at(2023, 7, 3, 21, 0)));
+    assert!(!spx.is_open(at(2023, 7, 3, 14, 0)));
+    assert!(spx.is_regular(at(2023, 7, 3, 13, 0)));
+
+    // a same-day extended session
+    let equity = TradingHours::new(Nyse).with_extended(
+        NaiveTime::from_hms_opt(4, 0, 0).unwrap(),
+        NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+    );
+    assert_eq!(
+        equity.session_at(at(2023, 7, 11, 7, 0)).unwrap().session,
+        Session::Extended
+    );
+    assert!(!equity.is_open(at(2023, 7, 11, 3, 0)));
+    assert!(!equity.is_open(at(2023, 7, 15, 7, 0)));
+
+    let registry = SessionRegistry::default();
+    assert!(registry
+        .get("SPX")
+        .unwrap()
+        .is_regular(at(2023, 7, 11, 16, 0)));
+    assert!(registry.get("ES").is_none());
+}
+
+#[test]
+fn test_quote_status() {
+    let spx = TradingHours::spx();
+    let expires_at = at(2023, 8, 18, 9, 30);
+    let firm = OptionContract::new(expires_at, 450_000, OptionKind::Call, 1_000, 1_020)
+        .with_quoted_at(at(2023, 7, 11, 15, 59));
+    let overnight = OptionContract::new(expires_at, 450_000, OptionKind::Put, 900, 980)
+        .with_quoted_at(at(2023, 7, 11, 2, 0));
+    let unstamped = OptionContract::new(expires_at, 455_000, OptionKind::Call, 800, 820);
+
+    let now = at(2023, 7, 11, 16, 0);
+    assert_eq!(spx.quote_status(&firm, now), QuoteStatus::Firm);
+    assert_eq!(spx.quote_status(&overnight, now), QuoteStatus::Indicative);
+    assert_eq!(spx.quote_status(&unstamped, now), QuoteStatus::Firm);
+    let evening = at(2023, 7, 11, 21, 0);
+    assert_eq!(
+        spx.quote_status(&unstamped, evening),
+        QuoteStatus::Indicative
+    );
+
+    let chain = Chain::new(&[firm, overnight, unstamped]);
+    assert_eq!(spx.indicative_quotes(&chain, now), vec![overnight]);
+    assert_eq!(spx.indicative_quotes(&chain, evening).len(), 2);
+}
@@ -0,0 +1,81 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::rates::YieldCurve;
+use options_math::skew::*;
+use options_math::synthetic::*;
+
+fn rows(spec: &SurfaceSpec) -> Vec<SkewRow> {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let chain = Chain::new(&generate_chain(spec, now, 1));
+    let analytics = chain.analytics(spec.spot, &YieldCurve::flat(spec.risk_free_rate), now);
+    skew_term_structure(&analytics)
+}
+
+#[test]
+fn test_flat_surface_has_no_skew() {
+    let rows = rows(&SurfaceSpec::default());
+    assert_eq!(rows.len(), 8);
+    for row in rows {
+        assert!(row.value.abs() < 0.01, "{:?}", row);
+    }
+}
+
+#[test]
+fn test_downward_skew_has_negative_risk_reversals() {
+    let rows = rows(&SurfaceSpec {
+        skew: Skew::new(-0.4, 0.0),
+        ..SurfaceSpec::default()
+    });
+    let risk_reversals: Vec<&SkewRow> = rows
+        .iter()
+        .filter(|r| {
+            r.metric == SkewMetric::RiskReversal25 || r.metric == SkewMetric::RiskReversal10
+        })
+        .collect();
+    assert_eq!(risk_reversals.len(), 4);
+    for row in risk_reversals {
+        assert!(row.value < -0.01, "{:?}", row);
+    }
+}
+
+#[test]
+fn test_strike_at_delta() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let spec = SurfaceSpec {
+        skew: Skew::new(-0.4, 0.0),
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&spec, now, 1));
+    let analytics = chain.analytics(spec.spot, &YieldCurve::flat(spec.risk_free_rate), now);
+    let expiry = &analytics.expiries[1];
+    let forward = expiry.forward_price as f64;
+
+    let call = strike_at_delta(expiry, options_math::OptionKind::Call, 0.25).unwrap();
+    let put = strike_at_delta(expiry, options_math::OptionKind::Put, -0.25).unwrap();
+    assert!(
+        put < forward && forward < call,
+        "{} {} {}",
+        put,
+        forward,
+        call
+    );
+
+    // the volatility at those strikes matches the volatility interpolated at that delta
+    let expected = spec.volatility(&spec.expiries[1], put / 100.0);
+    let at_delta = vol_at_delta(expiry, options_math::OptionKind::Put, -0.25).unwrap();
+    assert!(
+        (expected - at_delta).abs() < 0.005,
+        "{} {}",
+        expected,
+        at_delta
+    );
+
+    assert_eq!(
+        strike_at_delta(expiry, options_math::OptionKind::Call, 1.5),
+        None
+    );
+}
@@ -0,0 +1,30 @@
+use options_math::math::solve::*;
+
+#[test]
+fn test_root_finders_agree() {
+    let f = |x: f64| x.powi(3) - 2.0 * x - 5.0;
+    let root = 2.094_551_481_542_326_5;
+
+    let b = bisection(f, 2.0, 3.0, 1e-12, 200).unwrap();
+    assert!(b.converged && (b.x - root).abs() < 1e-11);
+
+    let r = brent(f, 2.0, 3.0, 1e-12, 100).unwrap();
+    assert!(r.converged && (r.x - root).abs() < 1e-11);
+    assert!(r.iterations < b.iterations);
+
+    let n = newton(|x| (f(x), 3.0 * x * x - 2.0), 2.5, 2.0, 3.0, 1e-12, 50);
+    assert!(n.converged && (n.x - root).abs() < 1e-11);
+    assert_eq!(n.bisection_steps, 0);
+
+    // plain Newton diverges on arctan from this far out
+    let g = |x: f64| (x.atan(), 1.0 / (1.0 + x * x));
+    let safeguarded = newton(g, 4.0, -10.0, 10.0, 1e-12, 200);
+    assert!(safeguarded.converged && safeguarded.bisection_steps > 0);
+    assert!(safeguarded.x.abs() < 1e-11);
+
+    let fixed = fixed_point(|x: f64| x.cos(), 1.0, 1e-12, 200);
+    assert!(fixed.converged && (fixed.x - 0.739_085_133_215_160_6).abs() < 1e-11);
+
+    assert_eq!(bisection(f, 3.0, 4.0, 1e-12, 100), None);
+    assert_eq!(brent(f, 3.0, 4.0, 1e-12, 100), None);
+}
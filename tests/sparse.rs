@@ -0,0 +1,103 @@
+use chrono::prelude::*;
+use options_math::sparse::*;
+use options_math::synthetic::*;
+use options_math::*;
+
+fn now() -> NaiveDateTime {
+    return NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+}
+
+fn expiry(options: &[OptionContract]) -> OptionsByExpiryDate {
+    let expires_at = options[0].expires_at();
+    return group_options_by_expiry(options)
+        .remove(&expires_at)
+        .unwrap();
+}
+
+fn config(fallback: Fallback) -> IndexConfig {
+    return IndexConfig {
+        sparse: Some(SparseChain::new(30, fallback)),
+        ..IndexConfig::default()
+    };
+}
+
+#[test]
+fn test_sparse_chain_fallbacks() {
+    let spec = SurfaceSpec::default();
+    let mut options = generate_chain(&spec, now(), 1);
+    let expires_at = options[0].expires_at();
+    options.retain(|o| o.expires_at() == expires_at);
+    let full = expiry(&options).variance(0.01, now());
+
+    // only a few strikes around the money
+    options.retain(|o| (o.strike() - spec.spot).abs() <= 5_000);
+    let thin = expiry(&options);
+    let plain = thin.variance_estimate(0.01, now(), &IndexConfig::default());
+    assert_eq!(plain.strikes, 21);
+    assert_eq!(plain.fallback, None);
+    assert!(!plain.low_quality);
+    assert!(plain.variance < 0.5 * full, "{} {}", plain.variance, full);
+
+    let flagged = thin.variance_estimate(0.01, now(), &config(Fallback::Flag));
+    assert_eq!(flagged.variance, plain.variance);
+    assert_eq!(flagged.fallback, Some(Fallback::Flag));
+    assert!(flagged.low_quality);
+
+    let wings = thin.variance_estimate(0.01, now(), &config(Fallback::ExtrapolateWings));
+    assert_eq!(wings.fallback, Some(Fallback::ExtrapolateWings));
+    assert!(
+        (wings.variance - full).abs() < 0.1 * full,
+        "{} {}",
+        wings.variance,
+        full
+    );
+    assert_eq!(
+        thin.variance_with_config(0.01, now(), &config(Fallback::ExtrapolateWings)),
+        wings.variance
+    );
+
+    // enough strikes, so no fallback
+    let enough = IndexConfig {
+        sparse: Some(SparseChain::new(10, Fallback::ExtrapolateWings)),
+        ..IndexConfig::default()
+    };
+    assert_eq!(thin.variance_estimate(0.01, now(), &enough), plain);
+}
+
+#[test]
+fn test_include_zero_bids() {
+    let spec = SurfaceSpec::default();
+    let mut options = generate_chain(&spec, now(), 1);
+    let expires_at = options[0].expires_at();
+    options.retain(|o| o.expires_at() == expires_at && (o.strike() - spec.spot).abs() <= 5_000);
+    // every other put below the money loses its bid
+    for o in options.iter_mut() {
+        if o.kind() == OptionKind::Put && o.strike() < spec.spot && o.strike() % 1_000 == 0 {
+            *o = o.with_quote(0, o.ask());
+        }
+    }
+    let thin = expiry(&options);
+    let plain = thin.variance_estimate(0.01, now(), &IndexConfig::default());
+    let widened = thin.variance_estimate(0.01, now(), &config(Fallback::IncludeZeroBids));
+    assert_eq!(plain.strikes, 16);
+    assert_eq!(widened.strikes, 21);
+    assert_eq!(widened.fallback, Some(Fallback::IncludeZeroBids));
+    assert!(widened.low_quality);
+    assert_ne!(widened.variance, plain.variance);
+
+    // a configured truncation still applies: two zero bids in a row at 297,000 and 296,500
+    for o in options.iter_mut() {
+        if o.kind() == OptionKind::Put && o.strike() == 296_500 {
+            *o = o.with_quote(0, o.ask());
+        }
+    }
+    let truncated = IndexConfig {
+        truncation: Truncation::ConsecutiveZeroBids,
+        ..config(Fallback::IncludeZeroBids)
+    };
+    let widened = expiry(&options).variance_estimate(0.01, now(), &truncated);
+    assert_eq!(widened.fallback, Some(Fallback::IncludeZeroBids));
+    assert_eq!(widened.lowest_strike, Some(297_000));
+}
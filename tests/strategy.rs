@@ -0,0 +1,165 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::rates::YieldCurve;
+use options_math::strategy::*;
+use options_math::synthetic::*;
+use options_math::*;
+
+fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap()
+}
+
+#[test]
+fn test_profit_intervals() {
+    let expires_at = now() + chrono::Duration::days(30);
+    let call = |strike: Cents, mark: Cents| {
+        OptionContract::new(expires_at, strike, OptionKind::Call, mark, mark)
+    };
+    let put = |strike: Cents, mark: Cents| {
+        OptionContract::new(expires_at, strike, OptionKind::Put, mark, mark)
+    };
+
+    let long_call = Strategy::new(vec![Leg::new(call(10_000, 500), 1)]);
+    assert_eq!(
+        long_call.profit_intervals(),
+        vec![(10_500.0, f64::INFINITY)]
+    );
+
+    let short_put = Strategy::new(vec![Leg::new(put(10_000, 300), -1)]);
+    assert_eq!(short_put.profit_intervals(), vec![(9_700.0, f64::INFINITY)]);
+
+    let short_strangle = Strategy::new(vec![
+        Leg::new(put(9_000, 200), -1),
+        Leg::new(call(11_000, 200), -1),
+    ]);
+    assert_eq!(short_strangle.profit_intervals(), vec![(8_600.0, 11_400.0)]);
+
+    let long_straddle = Strategy::new(vec![
+        Leg::new(put(10_000, 400), 1),
+        Leg::new(call(10_000, 400), 1),
+    ]);
+    assert_eq!(
+        long_straddle.profit_intervals(),
+        vec![(0.0, 9_200.0), (10_800.0, f64::INFINITY)]
+    );
+}
+
+fn short_put_probability_of_profit(skew: Skew) -> ProbabilityOfProfit {
+    let spec = SurfaceSpec {
+        skew,
+        ..SurfaceSpec::default()
+    };
+    let chain = Chain::new(&generate_chain(&spec, now(), 1));
+    let analytics = chain.analytics(spec.spot, &YieldCurve::flat(spec.risk_free_rate), now());
+    let expiry = &analytics.expiries[1];
+
+    let put = expiry
+        .contracts
+        .iter()
+        .find(|c| c.contract.kind() == OptionKind::Put && c.contract.strike() == 285_000)
+        .unwrap()
+        .contract;
+    Strategy::new(vec![Leg::new(put, -1)])
+        .probability_of_profit(expiry)
+        .unwrap()
+}
+
+#[test]
+fn test_smile_probability_of_profit() {
+    let flat = short_put_probability_of_profit(Skew::default());
+    assert!(flat.lognormal > 0.5 && flat.lognormal < 1.0, "{:?}", flat);
+    assert!((flat.smile - flat.lognormal).abs() < 0.005, "{:?}", flat);
+
+    let skewed = short_put_probability_of_profit(Skew::new(-0.5, 2.0));
+    assert!(
+        (skewed.smile - skewed.lognormal).abs() > 0.01,
+        "{:?}",
+        skewed
+    );
+}
+
+#[test]
+fn test_ratio_spread_risk_profile() {
+    let expires_at = now() + chrono::Duration::days(30);
+    let call = |strike: Cents, mark: Cents| {
+        OptionContract::new(expires_at, strike, OptionKind::Call, mark, mark)
+    };
+
+    // long 1 100 call, short 2 110 calls, for a credit
+    let ratio = Strategy::new(vec![
+        Leg::new(call(10_000, 500), 1),
+        Leg::new(call(11_000, 300), -2),
+    ]);
+    let profile = ratio.risk_profile();
+    assert_eq!(profile.upside_slope, -1.0);
+    assert_eq!(profile.pnl_at_zero, 100.0);
+    assert_eq!(profile.max_profit, Some(1_100.0));
+    assert_eq!(profile.max_loss, None);
+    assert_eq!(profile.unlimited_risk_above, Some(11_000.0));
+    assert_eq!(profile.naked_calls, 1);
+    assert_eq!(profile.naked_puts, 0);
+    assert_eq!(ratio.profit_intervals(), vec![(0.0, 12_100.0)]);
+
+    // one 110 call is naked: 3.00 + max(20% * 100 - 10, 10% * 100)
+    assert_eq!(ratio.naked_margin(10_000), 300.0 + 1_000.0);
+    let index_ratio = Strategy::new(
+        ratio
+            .legs
+            .iter()
+            .map(|l| Leg::new(l.contract.with_settlement(Settlement::Cash), l.quantity))
+            .collect(),
+    );
+    // 15% for index options: 3.00 + max(15% * 105 - 5, 10% * 105)
+    assert_eq!(index_ratio.naked_margin(10_500), 300.0 + 1_075.0);
+    assert_eq!(ratio.naked_margin(10_500), 300.0 + 1_600.0);
+
+    // the backspread is the mirror image
+    let backspread = Strategy::new(vec![
+        Leg::new(call(10_000, 500), -1),
+        Leg::new(call(11_000, 300), 2),
+    ]);
+    let profile = backspread.risk_profile();
+    assert_eq!(profile.max_profit, None);
+    assert_eq!(profile.max_loss, Some(1_100.0));
+    assert_eq!(profile.unlimited_risk_above, None);
+    assert_eq!(backspread.naked_margin(10_000), 0.0);
+}
+
+#[test]
+fn test_remark_by_key() {
+    use options_math::chain::{QuoteUpdate, SharedChain};
+
+    let expires_at = now() + chrono::Duration::days(30);
+    let options = [
+        OptionContract::new(expires_at, 10_000, OptionKind::Call, 300, 310),
+        OptionContract::new(expires_at, 10_500, OptionKind::Call, 100, 110),
+        OptionContract::new(expires_at, 9_500, OptionKind::Put, 120, 130),
+    ];
+    let shared = SharedChain::new(Chain::new(&options));
+    let long = ContractKey::new(expires_at, 10_000, OptionKind::Call);
+    let short = ContractKey::new(expires_at, 10_500, OptionKind::Call);
+    let unlisted = ContractKey::new(expires_at, 11_000, OptionKind::Call);
+    assert_eq!(
+        Strategy::from_keys(&[(long, 1), (unlisted, -1)], &shared.snapshot()).unwrap_err(),
+        vec![unlisted]
+    );
+
+    let mut spread = Strategy::from_keys(&[(long, 1), (short, -1)], &shared.snapshot()).unwrap();
+    assert_eq!(spread.premium(), 305 - 105);
+    assert_eq!(spread.legs[0].key(), long);
+
+    shared.apply(&[
+        QuoteUpdate::new(expires_at, 10_000, OptionKind::Call, 400, 410),
+        QuoteUpdate::new(expires_at, 10_500, OptionKind::Call, 150, 160),
+    ]);
+    assert!(spread.remark(&shared.snapshot()).is_empty());
+    assert_eq!(spread.premium(), 405 - 155);
+    assert_eq!(spread.legs[0].contract.revision(), 1);
+
+    // a contract delisted from the chain keeps its last quote
+    let delisted = Chain::new(&options[..1]);
+    assert_eq!(spread.remark(&delisted), vec![short]);
+    assert_eq!(spread.premium(), 305 - 155);
+}
@@ -0,0 +1,63 @@
+use chrono::prelude::*;
+use options_math::synthetic::*;
+use options_math::*;
+
+fn now() -> NaiveDateTime {
+    return NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+}
+
+fn sorted_expiries(options: &[OptionContract]) -> Vec<OptionsByExpiryDate> {
+    let by_expiry = group_options_by_expiry(options);
+    let mut dates: Vec<NaiveDateTime> = by_expiry.keys().copied().collect();
+    dates.sort();
+    return dates.iter().map(|d| by_expiry[d].clone()).collect();
+}
+
+#[test]
+fn test_generation_is_deterministic() {
+    let spec = SurfaceSpec {
+        missing_quote_probability: 0.1,
+        ..SurfaceSpec::default()
+    };
+    let a = generate_chain(&spec, now(), 7);
+    let b = generate_chain(&spec, now(), 7);
+    assert_eq!(format!("{:?}", a), format!("{:?}", b));
+}
+
+#[test]
+fn test_flat_surface_vix_matches_volatility() {
+    let spec = SurfaceSpec::default();
+    let options = generate_chain(&spec, now(), 1);
+    let expiries = sorted_expiries(&options);
+
+    let vix = compute_vix(
+        &expiries[0],
+        &expiries[1],
+        spec.risk_free_rate,
+        spec.risk_free_rate,
+        now(),
+    );
+    assert!((vix - 20.0).abs() < 0.5, "vix was {}", vix);
+}
+
+#[test]
+fn test_variance_matches_ground_truth_with_skew() {
+    let spec = SurfaceSpec {
+        skew: Skew::new(-0.3, 0.5),
+        ..SurfaceSpec::default()
+    };
+    let options = generate_chain(&spec, now(), 3);
+
+    for (expiry, options_for_expiry) in spec.expiries.iter().zip(sorted_expiries(&options)) {
+        let expected = spec.expected_variance(expiry);
+        let variance = options_for_expiry.variance(spec.risk_free_rate, now());
+        assert!(
+            (variance.sqrt() - expected.sqrt()).abs() < 0.01,
+            "variance was {}, expected {}",
+            variance,
+            expected
+        );
+    }
+}
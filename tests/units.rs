@@ -0,0 +1,102 @@
+use chrono::prelude::*;
+use options_math::synthetic::*;
+use options_math::units::*;
+use options_math::OptionContract;
+
+fn now() -> NaiveDateTime {
+    return NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+}
+
+fn scaled(options: &[OptionContract], strikes: f64, prices: f64) -> Vec<OptionContract> {
+    return options
+        .iter()
+        .map(|o| {
+            let scale = |v: i64, by: f64| (v as f64 * by).round() as i64;
+            OptionContract::new(
+                o.expires_at(),
+                scale(o.strike(), strikes),
+                o.kind(),
+                scale(o.bid(), prices),
+                scale(o.ask(), prices),
+            )
+        })
+        .collect();
+}
+
+#[test]
+fn test_chain_units() {
+    let spec = SurfaceSpec::default();
+    let options = generate_chain(&spec, now(), 1);
+    assert_eq!(check_strikes(&options, spec.spot), None);
+    assert_eq!(check_prices(&options, spec.spot, now()), None);
+    let (normalized, issues) =
+        normalize_chain(&options, spec.spot, now(), UnitPolicy::Correct).unwrap();
+    assert!(issues.is_empty());
+    assert_eq!(normalized, options);
+
+    // strikes converted to cents twice
+    let twice = scaled(&options, 100.0, 1.0);
+    assert_eq!(
+        check_strikes(&twice, spec.spot),
+        Some(UnitIssue::Strikes { factor: 100.0 })
+    );
+    let (normalized, issues) =
+        normalize_chain(&twice, spec.spot, now(), UnitPolicy::Correct).unwrap();
+    assert_eq!(issues, vec![UnitIssue::Strikes { factor: 100.0 }]);
+    assert_eq!(normalized, options);
+
+    // strikes and prices left in dollars
+    let dollars = scaled(&options, 0.01, 0.01);
+    let (normalized, issues) =
+        normalize_chain(&dollars, spec.spot, now(), UnitPolicy::Correct).unwrap();
+    assert_eq!(
+        issues,
+        vec![
+            UnitIssue::Strikes { factor: 0.01 },
+            UnitIssue::Prices { factor: 0.01 }
+        ]
+    );
+    assert_eq!(normalized[0].strike(), options[0].strike());
+    assert_eq!(
+        normalize_chain(&dollars, spec.spot, now(), UnitPolicy::Reject),
+        Err(issues)
+    );
+
+    // prices converted twice
+    let prices = scaled(&options, 1.0, 100.0);
+    assert_eq!(
+        check_prices(&prices, spec.spot, now()),
+        Some(UnitIssue::Prices { factor: 100.0 })
+    );
+}
+
+#[test]
+fn test_scalar_units() {
+    assert_eq!(
+        normalize_volatility(0.25, UnitPolicy::Reject),
+        Ok((0.25, None))
+    );
+    assert_eq!(
+        normalize_volatility(25.0, UnitPolicy::Correct),
+        Ok((0.25, Some(UnitIssue::PercentVolatility { value: 25.0 })))
+    );
+    assert_eq!(
+        normalize_volatility(25.0, UnitPolicy::Reject),
+        Err(UnitIssue::PercentVolatility { value: 25.0 })
+    );
+
+    assert_eq!(
+        normalize_rate(-0.005, UnitPolicy::Reject),
+        Ok((-0.005, None))
+    );
+    assert_eq!(
+        normalize_rate(5.25, UnitPolicy::Correct),
+        Ok((0.0525, Some(UnitIssue::PercentRate { value: 5.25 })))
+    );
+    assert_eq!(
+        normalize_rate(4.0, UnitPolicy::Reject),
+        Err(UnitIssue::PercentRate { value: 4.0 })
+    );
+}
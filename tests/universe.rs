@@ -0,0 +1,88 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::rates::YieldCurve;
+use options_math::realized::TRADING_DAYS;
+use options_math::returns::LogReturn;
+use options_math::synthetic::*;
+use options_math::universe::*;
+
+fn now() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap()
+}
+
+/// Daily returns alternating in sign, realizing `volatility`.
+fn returns(volatility: f64) -> Vec<LogReturn> {
+    let size = volatility / TRADING_DAYS.sqrt();
+    return (0..42)
+        .map(|i| LogReturn {
+            date: now().date() - chrono::Duration::days(42 - i),
+            value: if i % 2 == 0 { size } else { -size },
+            periods: 1,
+        })
+        .collect();
+}
+
+fn underlying(spec: &SurfaceSpec, realized: f64, history: &[f64]) -> Underlying {
+    let chain = Chain::new(&generate_chain(spec, now(), 1));
+    return Underlying::new(spec.spot, chain, returns(realized), history.to_vec());
+}
+
+#[test]
+fn test_relative_value() {
+    let expiries = vec![
+        SyntheticExpiry::new(23, 0.2),
+        SyntheticExpiry::new(37, 0.2),
+        SyntheticExpiry::new(93, 0.2),
+    ];
+    let flat = SurfaceSpec {
+        expiries: expiries.clone(),
+        ..SurfaceSpec::default()
+    };
+    let skewed = SurfaceSpec {
+        expiries: vec![
+            SyntheticExpiry::new(23, 0.3),
+            SyntheticExpiry::new(37, 0.3),
+            SyntheticExpiry::new(93, 0.25),
+        ],
+        skew: Skew::new(-0.4, 0.0),
+        ..SurfaceSpec::default()
+    };
+
+    let mut universe = Universe::new();
+    universe.insert("FLAT", underlying(&flat, 0.15, &[10.0, 30.0]));
+    universe.insert("BACK", underlying(&skewed, 0.35, &[]));
+    assert_eq!(universe.symbols().collect::<Vec<_>>(), vec!["BACK", "FLAT"]);
+
+    let rates = YieldCurve::flat(flat.risk_free_rate);
+    let table = universe.relative_value(&rates, now());
+    assert_eq!(table.rows.len(), 2);
+    let (back, flat) = (&table.rows[0], &table.rows[1]);
+    assert_eq!(flat.symbol, "FLAT");
+
+    let index = flat.index.unwrap();
+    assert!((index - 20.0).abs() < 0.5, "{}", index);
+    assert!((flat.iv_rank.unwrap() - (index - 10.0) / 20.0).abs() < 1e-12);
+    assert!(flat.term_slope.unwrap().abs() < 0.5, "{:?}", flat);
+    assert!(flat.skew_25.unwrap().abs() < 0.5, "{:?}", flat);
+    assert!(
+        (flat.vrp.unwrap() - (index - 15.0)).abs() < 0.5,
+        "{:?}",
+        flat
+    );
+    let liquidity = flat.liquidity_score.unwrap();
+    assert!(liquidity > 0.0 && liquidity <= 1.0);
+
+    // inverted, put-skewed, and cheaper than realized, but without history to rank against
+    assert!(back.term_slope.unwrap() < -2.0, "{:?}", back);
+    assert!(back.skew_25.unwrap() < -1.0, "{:?}", back);
+    assert!(back.vrp.unwrap() < 0.0, "{:?}", back);
+    assert_eq!(back.iv_rank, None);
+
+    let csv = table.to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], RELATIVE_VALUE_COLUMNS.join(","));
+    assert!(lines[1].starts_with("BACK,") && lines[1].contains(",,"));
+    assert_eq!(lines[2].split(',').count(), RELATIVE_VALUE_COLUMNS.len());
+}
@@ -0,0 +1,230 @@
+use chrono::prelude::*;
+use options_math::chain::Chain;
+use options_math::validation::*;
+use options_math::*;
+
+#[test]
+fn test_validate() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let near = now + chrono::Duration::days(23);
+    let next = now + chrono::Duration::days(37);
+    let expired = now - chrono::Duration::days(1);
+    let quotes = [
+        OptionContract::new(near, 10_000, OptionKind::Call, 500, 520),
+        OptionContract::new(near, 10_000, OptionKind::Put, 480, 500),
+        OptionContract::new(next, 10_000, OptionKind::Call, 700, 720),
+        OptionContract::new(next, 10_000, OptionKind::Put, 680, 700),
+    ];
+    let chain = Chain::new(&quotes);
+    assert!(chain.validate(now).is_empty());
+    let ok = compute_vix_validated(
+        &chain.expiries()[0],
+        &chain.expiries()[1],
+        0.01,
+        0.01,
+        now,
+        &IndexConfig::default(),
+        None,
+    );
+    assert!(ok.is_ok());
+
+    let bad = [
+        OptionContract::new(near, 10_000, OptionKind::Call, 500, 520),
+        OptionContract::new(near, 10_000, OptionKind::Call, 505, 515),
+        OptionContract::new(near, 11_000, OptionKind::Call, 300, 250),
+        OptionContract::new(near, 0, OptionKind::Put, 0, 5),
+        OptionContract::new(near, 9_000, OptionKind::Put, -5, 10),
+        OptionContract::new(expired, 10_000, OptionKind::Put, 100, 110),
+    ];
+    let issues = Chain::new(&bad).validate(now);
+    assert_eq!(
+        issues,
+        vec![
+            Issue::Expired {
+                expires_at: expired
+            },
+            Issue::DuplicateContract { contract: bad[1] },
+            Issue::CrossedQuote { contract: bad[2] },
+            Issue::NonPositiveStrike { contract: bad[3] },
+            Issue::NegativePrice { contract: bad[4] },
+        ]
+    );
+    assert_eq!(issues[1].severity(), Severity::Warning);
+    assert!(require(issues.clone(), Severity::Error).is_err());
+    assert!(require(vec![issues[1].clone()], Severity::Error).is_ok());
+
+    let chain = Chain::new(&bad);
+    let rejected = compute_vix_validated(
+        &chain.expiries()[0],
+        &chain.expiries()[1],
+        0.01,
+        0.01,
+        now,
+        &IndexConfig::default(),
+        None,
+    );
+    assert_eq!(rejected.unwrap_err().len(), 5);
+}
+
+#[test]
+fn test_duplicate_policy() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(9, 30, 0))
+        .unwrap();
+    let expires_at = now + chrono::Duration::days(30);
+    let contract = |bid, ask, minute| {
+        OptionContract::new(expires_at, 10_000, OptionKind::Call, bid, ask)
+            .with_quoted_at(now + chrono::Duration::minutes(minute))
+    };
+    let put = OptionContract::new(expires_at, 10_000, OptionKind::Put, 400, 420);
+    let quotes = [
+        contract(500, 540, 0),
+        put,
+        contract(505, 515, 1),
+        contract(490, 530, 2),
+    ];
+
+    let resolve = |policy| resolve_duplicates(&quotes, policy);
+    assert_eq!(
+        resolve(DuplicatePolicy::KeepFirst).unwrap(),
+        vec![quotes[0], put]
+    );
+    assert_eq!(
+        resolve(DuplicatePolicy::KeepFreshest).unwrap(),
+        vec![quotes[3], put]
+    );
+    assert_eq!(
+        resolve(DuplicatePolicy::KeepTightestSpread).unwrap(),
+        vec![quotes[2], put]
+    );
+    assert_eq!(
+        resolve(DuplicatePolicy::Error).unwrap_err(),
+        vec![
+            Issue::DuplicateContract {
+                contract: quotes[2]
+            },
+            Issue::DuplicateContract {
+                contract: quotes[3]
+            },
+        ]
+    );
+
+    let chain = Chain::with_duplicate_policy(&quotes, DuplicatePolicy::KeepTightestSpread).unwrap();
+    assert!(chain.validate(now).is_empty());
+    assert_eq!(chain.expiries()[0].calls(), &[quotes[2]]);
+}
+
+#[test]
+fn test_expired_and_same_minute_expiries() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(15, 59, 30))
+        .unwrap();
+    let expiry = |expires_at| {
+        vec![
+            OptionContract::new(expires_at, 10_000, OptionKind::Call, 500, 520),
+            OptionContract::new(expires_at, 10_000, OptionKind::Put, 480, 500),
+            OptionContract::new(expires_at, 10_500, OptionKind::Call, 300, 320),
+            OptionContract::new(expires_at, 10_500, OptionKind::Put, 780, 800),
+        ]
+    };
+    let expired = now - chrono::Duration::days(1);
+    let same_minute = now + chrono::Duration::seconds(30);
+    let live = now + chrono::Duration::days(30);
+    let quotes: Vec<OptionContract> = [expired, same_minute, live]
+        .iter()
+        .flat_map(|e| expiry(*e))
+        .collect();
+    let chain = Chain::new(&quotes);
+
+    let (unexpired, excluded) = chain.unexpired(now, SameMinuteExpiry::Expired);
+    assert_eq!(unexpired.len(), 1);
+    assert_eq!(
+        excluded,
+        vec![
+            Issue::Expired {
+                expires_at: expired
+            },
+            Issue::Expired {
+                expires_at: same_minute
+            },
+        ]
+    );
+    let (unexpired, excluded) = chain.unexpired(now, SameMinuteExpiry::OneMinute);
+    assert_eq!(unexpired.len(), 2);
+    assert_eq!(excluded.len(), 1);
+
+    let config = IndexConfig::default();
+    assert!(chain.expiries()[0]
+        .variance_with_config(0.01, now, &config)
+        .is_nan());
+    assert!(chain.expiries()[1]
+        .variance_with_config(0.01, now, &config)
+        .is_nan());
+    let one_minute = IndexConfig {
+        same_minute: SameMinuteExpiry::OneMinute,
+        ..IndexConfig::default()
+    };
+    assert!(chain.expiries()[1]
+        .variance_with_config(0.01, now, &one_minute)
+        .is_finite());
+
+    let near = &chain.expiries()[1];
+    let next = &chain.expiries()[2];
+    assert_eq!(
+        compute_vix_validated(near, next, 0.01, 0.01, now, &config, None).unwrap_err(),
+        vec![Issue::Expired {
+            expires_at: same_minute
+        }]
+    );
+    assert!(compute_vix_validated(near, next, 0.01, 0.01, now, &one_minute, None).is_ok());
+}
+
+#[test]
+fn test_forward_dispersion() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let spec = synthetic::SurfaceSpec {
+        spread: synthetic::SpreadModel::new(0, 0.0),
+        tick: 1,
+        ..synthetic::SurfaceSpec::default()
+    };
+    let options = synthetic::generate_chain(&spec, now, 1);
+    let chain = Chain::new(&options);
+    let (near, next) = (&chain.expiries()[0], &chain.expiries()[1]);
+    let consistent = near.forward_dispersion(0.01, now).unwrap();
+    assert!(consistent.forwards.len() > near.calls().len() / 2);
+    let forward = 300_000.0 * (0.01f64 * 23.0 / 365.0).exp();
+    assert!((consistent.median - forward).abs() < 2.0);
+    assert!(consistent.dispersion < 1e-5);
+    assert_eq!(near.check_forwards(0.01, now, 1e-4), None);
+
+    // puts quoted off a stale smile, mispriced more the further they are from the money
+    let stale: Vec<OptionContract> = options
+        .iter()
+        .map(|o| match o.kind() {
+            OptionKind::Put if o.expires_at() == near.expires_at() && o.bid() > 0 => {
+                let error = (o.strike() - spec.spot).abs() / 20;
+                o.with_quote(o.bid() + error, o.ask() + error)
+            }
+            _ => *o,
+        })
+        .collect();
+    let stale = Chain::new(&stale);
+    let stale_near = &stale.expiries()[0];
+    let dispersion = stale_near.forward_dispersion(0.01, now).unwrap().dispersion;
+    assert!(dispersion > 1e-3);
+
+    let config = IndexConfig::default();
+    assert!(compute_vix_validated(near, next, 0.01, 0.01, now, &config, Some(1e-4)).is_ok());
+    assert!(compute_vix_validated(stale_near, next, 0.01, 0.01, now, &config, None).is_ok());
+    assert_eq!(
+        compute_vix_validated(stale_near, next, 0.01, 0.01, now, &config, Some(1e-4)).unwrap_err(),
+        vec![Issue::InconsistentForwards {
+            expires_at: near.expires_at(),
+            dispersion,
+        }]
+    );
+}
@@ -1,3 +1,12 @@
+// `test_vix` is kept as originally written, which predates these lints and deprecations
+#![allow(
+    dead_code,
+    deprecated,
+    clippy::get_first,
+    clippy::map_clone,
+    clippy::single_match
+)]
+
 use chrono::prelude::*;
 use options_math::*;
 use serde::Deserialize;
@@ -72,3 +81,302 @@ fn test_vix() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_clamp_to_bounds() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let expires_at = now + chrono::Duration::days(30);
+    let options = [
+        OptionContract::new(expires_at, 9000, OptionKind::Call, 1000, 1020),
+        OptionContract::new(expires_at, 10000, OptionKind::Call, 300, 320),
+        OptionContract::new(expires_at, 11000, OptionKind::Call, 50, 60),
+        // above the discounted strike
+        OptionContract::new(expires_at, 9000, OptionKind::Put, 9500, 9600),
+        OptionContract::new(expires_at, 10000, OptionKind::Put, 300, 320),
+        // below intrinsic value
+        OptionContract::new(expires_at, 11000, OptionKind::Put, 400, 420),
+    ];
+    let by_expiry = group_options_by_expiry(&options[..]);
+    let options_for_expiry = &by_expiry[&expires_at];
+
+    let (clamped, report) = options_for_expiry.clamp_to_bounds(0.01, now);
+    assert_eq!(report.len(), 2);
+    assert!(report[0].clamped_mark <= 9000);
+    assert!(report[1].clamped_mark >= 999);
+
+    let config = IndexConfig {
+        clamp_to_bounds: true,
+        ..IndexConfig::default()
+    };
+    assert_eq!(
+        options_for_expiry.variance_with_config(0.01, now, &config),
+        clamped.variance(0.01, now)
+    );
+    assert_eq!(clamped.clamp_to_bounds(0.01, now).1.len(), 0);
+}
+
+#[test]
+fn test_variance_ignores_quote_order() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let spec = synthetic::SurfaceSpec::default();
+    let mut options = synthetic::generate_chain(&spec, now, 1);
+    let expires_at = options[0].expires_at();
+    options.retain(|o| o.expires_at() == expires_at);
+    let sorted = group_options_by_expiry(&options)[&expires_at].variance(0.01, now);
+
+    options.reverse();
+    let reversed = group_options_by_expiry(&options)[&expires_at].variance(0.01, now);
+    assert_eq!(sorted, reversed);
+    assert!(sorted > 0.0);
+}
+
+#[test]
+fn test_index_is_independent_of_input_order() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let spec = synthetic::SurfaceSpec::default();
+    let mut options = synthetic::generate_chain(&spec, now, 7);
+    let vix = |options: &[OptionContract]| -> f64 {
+        let chain = chain::Chain::new(options);
+        let (near, next) = (&chain.expiries()[0], &chain.expiries()[1]);
+        compute_vix(near, next, 0.01, 0.01, now)
+    };
+    let expected = vix(&options);
+
+    // shuffle, interleaving the expiries
+    let mut rng = math::Rng::new(3);
+    for _ in 0..5 {
+        for i in (1..options.len()).rev() {
+            let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+            options.swap(i, j);
+        }
+        assert_eq!(vix(&options).to_bits(), expected.to_bits());
+    }
+}
+
+#[test]
+fn test_compensated_summation() {
+    use options_math::math::Summation;
+
+    let values = || std::iter::once(1.0).chain(std::iter::repeat_n(1e-16, 10_000));
+    assert_eq!(Summation::Naive.sum(values()), 1.0);
+    assert!((Summation::Compensated.sum(values()) - (1.0 + 1e-12)).abs() < 1e-15);
+    // at least as accurate with or without the high-precision feature
+    assert!((Summation::Extended.sum(values()) - (1.0 + 1e-12)).abs() < 1e-15);
+    assert_eq!(Summation::Compensated.mean(vec![1.0, 2.0, 6.0]), Some(3.0));
+    assert_eq!(Summation::Naive.mean(vec![]), None);
+
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let spec = synthetic::SurfaceSpec {
+        strike_interval: 5,
+        ..synthetic::SurfaceSpec::default()
+    };
+    let options = synthetic::generate_chain(&spec, now, 1);
+    let chain = chain::Chain::new(&options);
+    let expiry = &chain.expiries()[0];
+    let naive = expiry.variance(0.01, now);
+    let compensated = expiry.variance_with_config(
+        0.01,
+        now,
+        &IndexConfig {
+            summation: Summation::Compensated,
+            ..IndexConfig::default()
+        },
+    );
+    assert!((naive - compensated).abs() < 1e-12);
+}
+
+#[test]
+fn test_irregular_strike_intervals() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let expires_at = now + chrono::Duration::days(30);
+    let mut options = vec![];
+    // 2.5-point strikes near the money, 5-point further out
+    for strike in [
+        9_000, 9_250, 9_500, 9_750, 10_000, 10_500, 11_000, 11_500, 12_000,
+    ]
+    .iter()
+    {
+        let call = (10_000 - strike).max(0) + 150;
+        let put = (strike - 10_000).max(0) + 150;
+        // the 105 put has no bid
+        let put_bid = if *strike == 10_500 { 0 } else { put };
+        options.push(OptionContract::new(
+            expires_at,
+            *strike,
+            OptionKind::Call,
+            call,
+            call + 10,
+        ));
+        options.push(OptionContract::new(
+            expires_at,
+            *strike,
+            OptionKind::Put,
+            put_bid,
+            put + 10,
+        ));
+    }
+    let expiry = &group_options_by_expiry(&options)[&expires_at];
+
+    let span = expiry.strike_intervals(StrikeIntervals::Span);
+    let grid = expiry.strike_intervals(StrikeIntervals::ListedGrid);
+    let at = |intervals: &[StrikeInterval], strike: Cents| -> StrikeInterval {
+        return *intervals.iter().find(|i| i.strike == strike).unwrap();
+    };
+    assert_eq!(span.len(), 8);
+    assert_eq!(at(&span, 9_750).delta_k, 250);
+    assert_eq!(at(&span, 10_000).delta_k, 625);
+    assert_eq!(at(&span, 10_000).skipped, 1);
+    assert_eq!(at(&span, 11_000).delta_k, 750);
+    assert_eq!(at(&grid, 10_000).delta_k, 375);
+    assert_eq!(at(&grid, 11_000).delta_k, 500);
+    assert_eq!(at(&grid, 9_000).delta_k, 250);
+    assert_eq!(at(&grid, 12_000).delta_k, 500);
+    assert_eq!(at(&span, 9_000).delta_k, 0);
+
+    let listed = IndexConfig {
+        intervals: StrikeIntervals::ListedGrid,
+        ..IndexConfig::default()
+    };
+    let variance = expiry.variance_with_config(0.01, now, &listed);
+    assert!(variance.is_finite() && variance > 0.0);
+    assert_ne!(variance, expiry.variance(0.01, now));
+}
+
+#[test]
+fn test_truncated_strike_intervals() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let expires_at = now + chrono::Duration::days(30);
+    let mut options = vec![];
+    // two zero bids in a row at 82.50 and 80 truncate the puts below 85
+    for strike in (6_000..=12_000).step_by(500).chain(Some(8_250)) {
+        let call = (10_000 - strike).max(0) + 150;
+        let put = (strike - 10_000).max(0) + 150;
+        let put_bid = if strike == 8_250 || strike == 8_000 {
+            0
+        } else {
+            put
+        };
+        options.push(OptionContract::new(
+            expires_at,
+            strike,
+            OptionKind::Call,
+            call,
+            call + 10,
+        ));
+        options.push(OptionContract::new(
+            expires_at,
+            strike,
+            OptionKind::Put,
+            put_bid,
+            put + 10,
+        ));
+    }
+    let variance = |options: &[OptionContract],
+                    intervals: StrikeIntervals,
+                    end_intervals: EndIntervals| {
+        let config = IndexConfig {
+            truncation: Truncation::ConsecutiveZeroBids,
+            intervals,
+            end_intervals,
+            ..IndexConfig::default()
+        };
+        return group_options_by_expiry(options)[&expires_at].variance_estimate(0.01, now, &config);
+    };
+    // the strikes beyond the truncation do not widen the interval of the last one summed
+    let inside: Vec<OptionContract> = options
+        .iter()
+        .filter(|o| o.strike() >= 8_500)
+        .copied()
+        .collect();
+    for intervals in [StrikeIntervals::Span, StrikeIntervals::ListedGrid].iter() {
+        let truncated = variance(&options, *intervals, EndIntervals::Summed);
+        assert_eq!(truncated.lowest_strike, Some(8_500));
+        assert!(truncated.strikes_dropped > 0);
+        assert_eq!(
+            truncated.variance,
+            variance(&inside, *intervals, EndIntervals::Summed).variance
+        );
+        // by default the interval of 85 still reaches down to 82.50
+        assert_ne!(
+            variance(&options, *intervals, EndIntervals::Quoted).variance,
+            truncated.variance
+        );
+    }
+}
+
+#[test]
+fn test_zero_bid_policy() {
+    let now = NaiveDate::from_ymd_opt(2020, 1, 2)
+        .and_then(|d| d.and_hms_opt(16, 0, 0))
+        .unwrap();
+    let expires_at = now + chrono::Duration::days(30);
+    let mut options = vec![];
+    // offered but not bid at 85 and from 70 to 60; at 55 the put is bid but the call is not
+    for (strike, put_bid) in [
+        (5_500, 10),
+        (6_000, 0),
+        (6_500, 0),
+        (7_000, 0),
+        (8_000, 20),
+        (8_500, 0),
+        (9_000, 30),
+        (9_500, 60),
+        (10_000, 100),
+    ]
+    .iter()
+    {
+        let call_bid = if *strike == 5_500 {
+            0
+        } else {
+            (10_000 - strike).max(0) + 100
+        };
+        options.push(OptionContract::new(
+            expires_at,
+            *strike,
+            OptionKind::Call,
+            call_bid,
+            call_bid + 10,
+        ));
+        options.push(OptionContract::new(
+            expires_at,
+            *strike,
+            OptionKind::Put,
+            *put_bid,
+            put_bid + 10,
+        ));
+    }
+    let expiry = group_options_by_expiry(&options)
+        .remove(&expires_at)
+        .unwrap();
+    let estimate = |zero_bids: ZeroBidPolicy| {
+        let config = IndexConfig {
+            zero_bids,
+            ..IndexConfig::default()
+        };
+        return expiry.variance_estimate(0.0, now, &config);
+    };
+
+    let drop = estimate(ZeroBidPolicy::Drop);
+    let inside = estimate(ZeroBidPolicy::InsideTruncation);
+    let ask_only = estimate(ZeroBidPolicy::AskOnlyMark);
+    assert_eq!(drop.strikes, 4);
+    // 85 and 70 are kept, being above 65, where the second zero bid in a row is found; 55 is
+    // kept because its put, the contract summed, is bid
+    assert_eq!(inside.strikes, 7);
+    assert_eq!(inside.lowest_strike, Some(5_500));
+    assert_eq!(inside.strikes_dropped, 2);
+    assert_eq!(ask_only.strikes, 9);
+    assert!(drop.variance < inside.variance && inside.variance < ask_only.variance);
+}